@@ -159,15 +159,6 @@ pub(crate) fn tag_prefix_mismatch_error(tag: &str, prefix: &str) -> Error {
 
 /// The lowercased host of a URL, for auth-origin comparison. Parses with `http::Uri` (always
 /// available, no `url` crate needed). Returns `None` when the URL has no host.
-#[cfg_attr(
-    not(any(
-        feature = "github",
-        feature = "gitlab",
-        feature = "gitea",
-        feature = "gitee"
-    )),
-    allow(dead_code)
-)]
 pub(crate) fn host_of(url: &str) -> Option<String> {
     url.parse::<http::Uri>().ok()?.host().map(|h| {
         h.trim_start_matches('[')
@@ -193,6 +184,30 @@ pub(crate) struct RequestConfig {
     pub(crate) retry_base_delay: Duration,
     /// Cap on the exponential retry backoff delay. Defaults to ~3.2s (100ms << 5).
     pub(crate) retry_max_delay: Duration,
+    /// Longest server-requested wait (`Retry-After` / rate-limit reset) the retry loop will sleep
+    /// through before retrying a rate-limited request; a longer one returns `Error::RateLimited`
+    /// immediately. Defaults to 60s.
+    pub(crate) max_rate_limit_wait: Duration,
+    /// The last rate-limit state reported by a response to this config's requests. Shared (not
+    /// copied) between clones so the `Update` a builder produced sees what its requests observed;
+    /// reset by [`build_client`](Self::build_client) so each built `Update` starts empty.
+    pub(crate) rate_limit: std::sync::Arc<std::sync::Mutex<Option<crate::RateLimit>>>,
+    /// Directory caching release-listing pages for conditional requests (`If-None-Match` /
+    /// `If-Modified-Since`). `None` (the default) disables the cache.
+    pub(crate) cache_dir: Option<PathBuf>,
+    /// `(url_prefix, mirror_prefix)` rewrite rules, in the order they were added. An asset download
+    /// URL starting with `url_prefix` gains a mirror with that prefix replaced by `mirror_prefix`
+    /// (see [`mirror_urls`](Self::mirror_urls)).
+    pub(crate) mirror_rewrites: Vec<(String, String)>,
+    /// Upper bound on concurrent `Range` requests the asset download is split into. `1` (the
+    /// default) downloads with a single request.
+    pub(crate) parallel_chunks: usize,
+    /// Cap on the asset download rate in bytes per second. `None` (the default) does not throttle.
+    pub(crate) max_bandwidth: Option<u64>,
+    /// Which redirects every request follows. Defaults to [`RedirectPolicy::new`](crate::RedirectPolicy::new).
+    pub(crate) redirect_policy: crate::RedirectPolicy,
+    /// Called with each redirect a request follows (listing and download).
+    pub(crate) on_redirect: Option<crate::redirect::RedirectCallback>,
     /// The backend's authorization scheme for rendering [`auth_token`](Self::auth_token) into the
     /// `Authorization` header. Per-backend default (github/gitea `Token`, gitlab `Bearer`).
     pub(crate) auth_scheme: AuthScheme,
@@ -219,6 +234,18 @@ pub(crate) struct RequestConfig {
     /// (invalid cert bytes or a client-build failure). Deferred like [`header_error`](Self::header_error)
     /// and surfaced from [`check`](Self::check) as an `Error::InvalidCertificate`.
     pub(crate) cert_error: Option<String>,
+    /// Which root CA set the crate-built ureq client trusts beneath
+    /// [`root_certificates`](Self::root_certificates). A non-default choice is materialized by
+    /// [`build_client`](Self::build_client) like the certificates.
+    pub(crate) root_store: crate::RootStore,
+    /// Explicit proxy to bake into the HTTP client the crate builds when no client was injected.
+    /// Materialized alongside [`root_certificates`](Self::root_certificates) by
+    /// [`build_client`](Self::build_client).
+    pub(crate) proxy: Option<crate::Proxy>,
+    /// First error produced applying [`proxy`](Self::proxy) (a malformed URL or credentials the
+    /// client cannot represent). Deferred like [`cert_error`](Self::cert_error) and surfaced from
+    /// [`check`](Self::check) as an `Error::InvalidProxy`.
+    pub(crate) proxy_error: Option<String>,
     /// The host of the backend's configured API base (e.g. `api.github.com`, `gitlab.com`, the
     /// gitea host). The derived [`auth_token`](Self::auth_token) is only attached to a request whose
     /// host matches this (or an [`auth_hosts`](Self::auth_hosts) entry), so a server-supplied asset
@@ -237,6 +264,8 @@ pub(crate) struct RequestConfig {
 pub(crate) const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
 /// Default cap on the exponential retry backoff (100ms << 5 == 3200ms).
 pub(crate) const DEFAULT_RETRY_MAX_DELAY: Duration = Duration::from_millis(3200);
+/// Default cap on a server-requested rate-limit wait the retry loop sleeps through.
+pub(crate) const DEFAULT_MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
 
 impl Default for RequestConfig {
     fn default() -> Self {
@@ -246,6 +275,14 @@ impl Default for RequestConfig {
             retries: 0,
             retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
             retry_max_delay: DEFAULT_RETRY_MAX_DELAY,
+            max_rate_limit_wait: DEFAULT_MAX_RATE_LIMIT_WAIT,
+            rate_limit: Default::default(),
+            cache_dir: None,
+            mirror_rewrites: Vec::new(),
+            parallel_chunks: 1,
+            max_bandwidth: None,
+            redirect_policy: crate::RedirectPolicy::new(),
+            on_redirect: None,
             auth_scheme: AuthScheme::default(),
             auth_token: None,
             client: None,
@@ -254,6 +291,9 @@ impl Default for RequestConfig {
             header_error: None,
             root_certificates: Vec::new(),
             cert_error: None,
+            root_store: crate::RootStore::default(),
+            proxy: None,
+            proxy_error: None,
             auth_base_host: None,
             auth_hosts: Vec::new(),
             allow_insecure_auth: false,
@@ -269,6 +309,17 @@ impl std::fmt::Debug for RequestConfig {
             .field("retries", &self.retries)
             .field("retry_base_delay", &self.retry_base_delay)
             .field("retry_max_delay", &self.retry_max_delay)
+            .field("max_rate_limit_wait", &self.max_rate_limit_wait)
+            .field("rate_limit", &self.last_rate_limit())
+            .field("cache_dir", &self.cache_dir)
+            .field("mirror_rewrites", &self.mirror_rewrites)
+            .field("parallel_chunks", &self.parallel_chunks)
+            .field("max_bandwidth", &self.max_bandwidth)
+            .field("redirect_policy", &self.redirect_policy)
+            .field(
+                "on_redirect",
+                &self.on_redirect.as_ref().map(|_| "<callback>"),
+            )
             .field("auth_scheme", &self.auth_scheme)
             .field("auth_token", &self.auth_token.as_ref().map(|_| "<token>"))
             .field("client", &self.client.as_ref().map(|_| "<http_client>"));
@@ -283,11 +334,73 @@ impl std::fmt::Debug for RequestConfig {
                 &format_args!("<{} root_certificates>", self.root_certificates.len()),
             )
             .field("cert_error", &self.cert_error)
+            .field("root_store", &self.root_store)
+            .field("proxy", &self.proxy)
+            .field("proxy_error", &self.proxy_error)
             .finish()
     }
 }
 
 impl RequestConfig {
+    /// The mirror URLs the configured rewrite rules derive from an asset's `download_url`, in rule
+    /// order.
+    pub(crate) fn mirror_urls<'a>(
+        &'a self,
+        download_url: &'a str,
+    ) -> impl Iterator<Item = String> + 'a {
+        self.mirror_rewrites
+            .iter()
+            .filter_map(move |(prefix, mirror)| {
+                download_url
+                    .strip_prefix(prefix.as_str())
+                    .map(|rest| format!("{mirror}{rest}"))
+            })
+    }
+
+    /// Record the rate-limit headers of an API response (or of a rate-limit rejection) as the last
+    /// observed state, passing the result through. A response without such headers leaves the
+    /// previous state in place.
+    pub(crate) fn observe_rate_limit(
+        &self,
+        result: crate::Result<Box<dyn crate::http_client::HttpResponse>>,
+    ) -> crate::Result<Box<dyn crate::http_client::HttpResponse>> {
+        self.record_rate_limit(match &result {
+            Ok(resp) => crate::RateLimit::from_headers(resp.headers()),
+            Err(e) => e.rate_limit(),
+        });
+        result
+    }
+
+    /// Async sibling of [`observe_rate_limit`](Self::observe_rate_limit).
+    #[cfg(feature = "async")]
+    pub(crate) fn observe_rate_limit_async(
+        &self,
+        result: crate::Result<Box<dyn crate::http_client::AsyncHttpResponse>>,
+    ) -> crate::Result<Box<dyn crate::http_client::AsyncHttpResponse>> {
+        self.record_rate_limit(match &result {
+            Ok(resp) => crate::RateLimit::from_headers(resp.headers()),
+            Err(e) => e.rate_limit(),
+        });
+        result
+    }
+
+    fn record_rate_limit(&self, observed: Option<crate::RateLimit>) {
+        if let Some(observed) = observed {
+            *self
+                .rate_limit
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(observed);
+        }
+    }
+
+    /// The last rate-limit state recorded by [`observe_rate_limit`](Self::observe_rate_limit).
+    pub(crate) fn last_rate_limit(&self) -> Option<crate::RateLimit> {
+        *self
+            .rate_limit
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
     /// Insert an extra request header from `TryInto<HeaderName>` / `TryInto<HeaderValue>` args. A
     /// conversion failure is recorded in [`header_error`](Self::header_error) (first one wins) and
     /// surfaced later by [`check`](Self::check); the header is simply not inserted.
@@ -403,45 +516,65 @@ impl RequestConfig {
         uri.scheme_str() == Some("https") || is_loopback || self.allow_insecure_auth
     }
 
-    /// Materialize a pre-configured HTTP client from `root_certificates` if set and no client was
-    /// injected. On success, stores the client in `self.client` (and the async sibling). On failure,
-    /// records the error in `self.cert_error` (first error wins, mirroring `header_error`).
+    /// Materialize a pre-configured HTTP client from `root_certificates`, a non-default
+    /// `root_store` and/or `proxy` if set and no client was injected. On success, stores the client in `self.client` (and the async
+    /// sibling). On failure, records the error in `self.cert_error` or `self.proxy_error` (first
+    /// error wins, mirroring `header_error`).
     ///
-    /// Each client slot is materialized independently: the sync client is built from the certs only
-    /// when the sync slot is empty, and the async client only when the async slot is empty. So
-    /// injecting a client for one transport does not drop the custom roots for the other (the
-    /// injected client owns its own TLS; the auto-built one still trusts the certs). A cert-build
-    /// failure for a slot that will actually be built is recorded in `cert_error`.
+    /// Each client slot is materialized independently: the sync client is built only when the sync
+    /// slot is empty, and the async client only when the async slot is empty. So injecting a client
+    /// for one transport does not drop the custom roots or proxy for the other (the injected client
+    /// owns its own TLS and proxy; the auto-built one still applies them). A build failure for a
+    /// slot that will actually be built is recorded.
     pub(crate) fn build_client(&mut self) {
-        if self.root_certificates.is_empty() {
+        // Every `build()` passes through here: give the built value its own rate-limit state
+        // rather than sharing the builder's.
+        self.rate_limit = Default::default();
+        if self.root_certificates.is_empty()
+            && self.proxy.is_none()
+            && self.root_store == crate::RootStore::default()
+        {
             return;
         }
         if self.client.is_none() {
-            match crate::http_client::client_with_root_certs(&self.root_certificates) {
+            match crate::http_client::configured_client(
+                &self.root_certificates,
+                self.proxy.as_ref(),
+                self.root_store,
+            ) {
                 Ok(c) => self.client = Some(c),
-                Err(e) => {
-                    if self.cert_error.is_none() {
-                        self.cert_error = Some(e.to_string());
-                    }
-                }
+                Err(e) => self.record_client_error(e),
             }
         }
         #[cfg(feature = "async")]
         if self.async_client.is_none() {
-            match crate::http_client::async_client_with_root_certs(&self.root_certificates) {
+            match crate::http_client::async_configured_client(
+                &self.root_certificates,
+                self.proxy.as_ref(),
+            ) {
                 Ok(c) => self.async_client = Some(c),
-                Err(e) => {
-                    if self.cert_error.is_none() {
-                        self.cert_error = Some(e.to_string());
-                    }
-                }
+                Err(e) => self.record_client_error(e),
             }
         }
     }
 
+    /// Record a [`build_client`](Self::build_client) failure in `cert_error` or `proxy_error`,
+    /// keeping the first error of each kind.
+    fn record_client_error(&mut self, err: crate::http_client::ClientConfigError) {
+        use crate::http_client::ClientConfigError;
+        let (slot, source) = match err {
+            ClientConfigError::Certificate(source) => (&mut self.cert_error, source),
+            ClientConfigError::Proxy(source) => (&mut self.proxy_error, source),
+        };
+        if slot.is_none() {
+            *slot = Some(source.to_string());
+        }
+    }
+
     /// Surface any deferred config error: a `request_header` conversion failure as
     /// `Error::InvalidHeader` (checked first, so it takes precedence), then a root-certificate /
-    /// client-build failure as `Error::InvalidCertificate`.
+    /// client-build failure as `Error::InvalidCertificate`, then a proxy failure as
+    /// `Error::InvalidProxy`.
     pub(crate) fn check(&self) -> Result<()> {
         if let Some(msg) = &self.header_error {
             return Err(Error::InvalidHeader {
@@ -453,6 +586,11 @@ impl RequestConfig {
                 source: Box::new(crate::errors::MessageError(msg.clone())),
             });
         }
+        if let Some(msg) = &self.proxy_error {
+            return Err(Error::InvalidProxy {
+                source: Box::new(crate::errors::MessageError(msg.clone())),
+            });
+        }
         Ok(())
     }
 }
@@ -469,11 +607,51 @@ pub(crate) struct CommonBuilderConfig {
     /// with [`Error::InstallPathNotWritable`] on a definite permission refusal. Default `false`;
     /// set via `check_install_path_writable(true)`.
     pub check_install_path_writable: bool,
+    /// Opt-in preflight: refuse an install path owned by a package manager with
+    /// [`Error::PackageManaged`]. Default `false`; set via `check_package_manager(true)`.
+    pub check_package_manager: bool,
+    /// Opt-in preflight: refuse an update the temp or install filesystem has no room for with
+    /// [`Error::InsufficientSpace`]. Default `false`; set via `check_free_space(true)`.
+    pub check_free_space: bool,
+    /// What an asset is assumed to unpack to, as a multiple of its size, for the free-space
+    /// preflight. Default `3.0`; set via `free_space_expansion_factor`.
+    pub free_space_expansion_factor: f64,
+    /// Download and extract under the install directory rather than the system temp dir.
+    /// Default `false`; set via `temp_dir_on_install_fs(true)`.
+    pub temp_dir_on_install_fs: bool,
+    /// What extracting the downloaded archive may unpack. Default: no limits; set via
+    /// `extract_limits`.
+    pub extract_limits: crate::ExtractLimits,
+    /// Where to write an install receipt after a successful update, if anywhere.
+    pub install_receipt: Option<PathBuf>,
+    /// Carry the installed binary's owner, mode and xattrs over to its replacement. Default
+    /// `false`; set via `preserve_metadata(true)`.
+    #[cfg(feature = "preserve-metadata")]
+    pub preserve_metadata: bool,
+    /// Retry a replace refused for lack of permission through this tool. Default `None`; set via
+    /// `elevate(..)`.
+    #[cfg(feature = "elevate")]
+    pub elevate: Option<crate::elevate::Elevator>,
+    /// Where `stage()` stores a verified update for `staged::ApplyStaged`. Default `None`; set
+    /// via `staging_dir(path)`.
+    #[cfg(feature = "stage")]
+    pub staging_dir: Option<PathBuf>,
     pub bin_path_in_archive: Option<String>,
     /// `true` when `bin_path_in_archive` was auto-derived from `bin_name` (not set explicitly by
     /// the user). Used by `bin_name` to re-derive when called again, while leaving an explicitly
     /// set value untouched.
     pub(crate) bin_path_in_archive_auto: bool,
+    /// The bundle root directory inside the archive. Setting it selects bundle mode: the whole
+    /// tree is extracted and swapped in for `bundle_install_path`.
+    pub bundle_root_in_archive: Option<String>,
+    /// The installed bundle directory to replace in bundle mode. Defaults on macOS to the
+    /// nearest `.app` ancestor of the current executable; required elsewhere.
+    pub bundle_install_path: Option<PathBuf>,
+    /// Extra `(path_in_archive, install_path)` files installed alongside the binary, in one
+    /// transaction with it. Appended to via `install_file`.
+    pub install_files: Vec<(String, PathBuf)>,
+    /// Install into a versioned side-by-side layout instead of replacing `bin_install_path`.
+    pub versioned_install: Option<crate::VersionedInstall>,
     pub show_download_progress: bool,
     pub show_output: bool,
     pub no_confirm: bool,
@@ -495,6 +673,7 @@ pub(crate) struct CommonBuilderConfig {
     pub auth_scheme: AuthScheme,
     pub progress_callback: Option<crate::ProgressCallback>,
     pub verify: Option<crate::VerifyCallback>,
+    pub self_test: Option<crate::self_test::SelfTest>,
     pub asset_matcher: Option<crate::AssetMatcher>,
     #[cfg(feature = "checksums")]
     pub checksum: Option<crate::Checksum>,
@@ -504,6 +683,14 @@ pub(crate) struct CommonBuilderConfig {
     pub verify_release_digest: bool,
     #[cfg(feature = "signatures")]
     pub verifying_keys: Vec<[u8; zipsign_api::PUBLIC_KEY_LENGTH]>,
+    #[cfg(feature = "signatures")]
+    pub trusted_keys: Vec<crate::keys::TrustedKey>,
+    #[cfg(feature = "signatures")]
+    pub revocation_list_asset: Option<String>,
+    #[cfg(feature = "signatures")]
+    pub key_set_asset: Option<String>,
+    #[cfg(feature = "signatures")]
+    pub key_store_path: Option<PathBuf>,
 }
 
 impl Default for CommonBuilderConfig {
@@ -515,8 +702,24 @@ impl Default for CommonBuilderConfig {
             bin_name: None,
             bin_install_path: None,
             check_install_path_writable: false,
+            check_package_manager: false,
+            check_free_space: false,
+            free_space_expansion_factor: crate::free_space::DEFAULT_EXPANSION_FACTOR,
+            temp_dir_on_install_fs: false,
+            extract_limits: crate::ExtractLimits::default(),
+            install_receipt: None,
+            #[cfg(feature = "preserve-metadata")]
+            preserve_metadata: false,
+            #[cfg(feature = "elevate")]
+            elevate: None,
+            #[cfg(feature = "stage")]
+            staging_dir: None,
             bin_path_in_archive: None,
             bin_path_in_archive_auto: false,
+            bundle_root_in_archive: None,
+            bundle_install_path: None,
+            install_files: vec![],
+            versioned_install: None,
             show_download_progress: false,
             show_output: true,
             no_confirm: false,
@@ -533,6 +736,7 @@ impl Default for CommonBuilderConfig {
             auth_scheme: AuthScheme::default(),
             progress_callback: None,
             verify: None,
+            self_test: None,
             asset_matcher: None,
             #[cfg(feature = "checksums")]
             checksum: None,
@@ -540,16 +744,52 @@ impl Default for CommonBuilderConfig {
             verify_release_digest: true,
             #[cfg(feature = "signatures")]
             verifying_keys: vec![],
+            #[cfg(feature = "signatures")]
+            trusted_keys: vec![],
+            #[cfg(feature = "signatures")]
+            revocation_list_asset: None,
+            #[cfg(feature = "signatures")]
+            key_set_asset: None,
+            #[cfg(feature = "signatures")]
+            key_store_path: None,
         }
     }
 }
 
 impl CommonBuilderConfig {
+    /// The setting that selects an install other than the plain single-binary replace, if any.
+    /// `staging_dir` comes last: `ApplyStaged` installs a staged update with a plain replace, so
+    /// settings it does not carry over conflict with it too.
+    #[cfg(any(feature = "preserve-metadata", feature = "elevate", feature = "stage"))]
+    fn single_binary_conflict(&self) -> Option<&'static str> {
+        if self.bundle_root_in_archive.is_some() {
+            Some("bundle_root_in_archive")
+        } else if self.versioned_install.is_some() {
+            Some("versioned_install")
+        } else if !self.install_files.is_empty() {
+            Some("install_file")
+        } else {
+            #[cfg(feature = "stage")]
+            if self.staging_dir.is_some() {
+                return Some("staging_dir");
+            }
+            None
+        }
+    }
+
     /// Validate the common options and resolve defaults, producing a [`CommonConfig`].
     ///
     /// `target` defaults to the crate's build target; `bin_install_path` defaults to the
     /// current executable. `current_version`, `bin_name`, and `bin_path_in_archive` are
     /// required (the last is set automatically by the `bin_name` setter).
+    ///
+    /// Bundle mode (`bundle_root_in_archive`) rejects an explicit `bin_install_path` or
+    /// `bin_path_in_archive` with [`Error::ConflictingConfig`], and resolves
+    /// `bundle_install_path` (see [`default_bundle_install_path`](crate::update::default_bundle_install_path)).
+    /// A `versioned_install` layout likewise rejects an explicit `bin_install_path`, bundle mode,
+    /// and extra `install_file`s, and `preserve_metadata`, `elevate` and `staging_dir` reject all
+    /// three of those modes. `preserve_metadata`, `elevate` and `install_receipt` also reject
+    /// `staging_dir`, since a staged update is applied without them.
     pub(crate) fn build(&self) -> Result<CommonConfig> {
         // Resolve the auth scheme/token into the request config so the shared header-derivation
         // (`apply_auth`) can apply it on both the listing and download paths.
@@ -560,6 +800,100 @@ impl CommonBuilderConfig {
         // injected), then surface any deferred header/cert error as a config error.
         request.build_client();
         request.check()?;
+        // A delivered key set is only useful if it can be remembered for the next run.
+        #[cfg(feature = "signatures")]
+        if self.key_set_asset.is_some() && self.key_store_path.is_none() {
+            return Err(Error::MissingField {
+                field: "key_store_path",
+            });
+        }
+        if self.versioned_install.is_some() {
+            let conflict = if self.bin_install_path.is_some() {
+                Some("bin_install_path")
+            } else if self.bundle_root_in_archive.is_some() {
+                Some("bundle_root_in_archive")
+            } else if !self.install_files.is_empty() {
+                Some("install_file")
+            } else {
+                None
+            };
+            if let Some(field) = conflict {
+                return Err(Error::ConflictingConfig {
+                    field,
+                    conflicts_with: "versioned_install",
+                });
+            }
+        }
+        // Metadata preservation, elevation and staging only cover the single-binary install.
+        #[cfg(feature = "preserve-metadata")]
+        if self.preserve_metadata
+            && let Some(conflicts_with) = self.single_binary_conflict()
+        {
+            return Err(Error::ConflictingConfig {
+                field: "preserve_metadata",
+                conflicts_with,
+            });
+        }
+        #[cfg(feature = "elevate")]
+        if self.elevate.is_some()
+            && let Some(conflicts_with) = self.single_binary_conflict()
+        {
+            return Err(Error::ConflictingConfig {
+                field: "elevate",
+                conflicts_with,
+            });
+        }
+        #[cfg(feature = "stage")]
+        if self.staging_dir.is_some() {
+            if let Some(conflicts_with) = self
+                .single_binary_conflict()
+                .filter(|&conflict| conflict != "staging_dir")
+            {
+                return Err(Error::ConflictingConfig {
+                    field: "staging_dir",
+                    conflicts_with,
+                });
+            }
+            if self.install_receipt.is_some() {
+                return Err(Error::ConflictingConfig {
+                    field: "install_receipt",
+                    conflicts_with: "staging_dir",
+                });
+            }
+        }
+        let bundle_install_path = match &self.bundle_root_in_archive {
+            Some(_) => {
+                if self.bin_install_path.is_some() {
+                    return Err(Error::ConflictingConfig {
+                        field: "bin_install_path",
+                        conflicts_with: "bundle_root_in_archive",
+                    });
+                }
+                // An auto-derived `bin_path_in_archive` (from `bin_name`) is simply unused.
+                if self.bin_path_in_archive.is_some() && !self.bin_path_in_archive_auto {
+                    return Err(Error::ConflictingConfig {
+                        field: "bin_path_in_archive",
+                        conflicts_with: "bundle_root_in_archive",
+                    });
+                }
+                if !self.install_files.is_empty() {
+                    return Err(Error::ConflictingConfig {
+                        field: "install_file",
+                        conflicts_with: "bundle_root_in_archive",
+                    });
+                }
+                Some(match &self.bundle_install_path {
+                    Some(p) => p.clone(),
+                    None => crate::update::default_bundle_install_path()?,
+                })
+            }
+            None if self.bundle_install_path.is_some() => {
+                return Err(Error::MissingField {
+                    field: "bundle_root_in_archive",
+                });
+            }
+            None => None,
+        };
         Ok(CommonConfig {
             request,
             target: self
@@ -581,12 +915,28 @@ impl CommonBuilderConfig {
                 None => std::env::current_exe()?,
             },
             check_install_path_writable: self.check_install_path_writable,
+            check_package_manager: self.check_package_manager,
+            check_free_space: self.check_free_space,
+            free_space_expansion_factor: self.free_space_expansion_factor,
+            temp_dir_on_install_fs: self.temp_dir_on_install_fs,
+            extract_limits: self.extract_limits,
+            install_receipt: self.install_receipt.clone(),
+            #[cfg(feature = "preserve-metadata")]
+            preserve_metadata: self.preserve_metadata,
+            #[cfg(feature = "elevate")]
+            elevate: self.elevate,
+            #[cfg(feature = "stage")]
+            staging_dir: self.staging_dir.clone(),
             bin_path_in_archive: self
                 .bin_path_in_archive
                 .clone()
                 .ok_or(Error::MissingField {
                     field: "bin_path_in_archive",
                 })?,
+            bundle_root_in_archive: self.bundle_root_in_archive.clone(),
+            bundle_install_path,
+            install_files: self.install_files.clone(),
+            versioned_install: self.versioned_install.clone(),
             show_download_progress: self.show_download_progress,
             show_output: self.show_output,
             no_confirm: self.no_confirm,
@@ -598,6 +948,7 @@ impl CommonBuilderConfig {
             progress_chars: self.progress_chars.clone(),
             progress_callback: self.progress_callback.clone(),
             verify: self.verify.clone(),
+            self_test: self.self_test.clone(),
             asset_matcher: self.asset_matcher.clone(),
             #[cfg(feature = "checksums")]
             checksum: self.checksum.clone(),
@@ -605,6 +956,14 @@ impl CommonBuilderConfig {
             verify_release_digest: self.verify_release_digest,
             #[cfg(feature = "signatures")]
             verifying_keys: self.verifying_keys.clone(),
+            #[cfg(feature = "signatures")]
+            trusted_keys: self.trusted_keys.clone(),
+            #[cfg(feature = "signatures")]
+            revocation_list_asset: self.revocation_list_asset.clone(),
+            #[cfg(feature = "signatures")]
+            key_set_asset: self.key_set_asset.clone(),
+            #[cfg(feature = "signatures")]
+            key_store_path: self.key_store_path.clone(),
         })
     }
 }
@@ -622,7 +981,36 @@ pub(crate) struct CommonConfig {
     pub bin_install_path: PathBuf,
     /// Opt-in preflight writability probe of `bin_install_path` (default `false`).
     pub check_install_path_writable: bool,
+    /// Opt-in preflight refusing a package-managed install path (default `false`).
+    pub check_package_manager: bool,
+    /// Opt-in free-space preflight (default `false`).
+    pub check_free_space: bool,
+    /// The free-space preflight's expansion factor (default `3.0`).
+    pub free_space_expansion_factor: f64,
+    /// Download and extract under the install directory (default `false`).
+    pub temp_dir_on_install_fs: bool,
+    /// What extracting the downloaded archive may unpack (default: no limits).
+    pub extract_limits: crate::ExtractLimits,
+    /// Where to write an install receipt after a successful update.
+    pub install_receipt: Option<PathBuf>,
+    /// Carry the installed binary's metadata over to its replacement (default `false`).
+    #[cfg(feature = "preserve-metadata")]
+    pub preserve_metadata: bool,
+    /// The tool a replace refused for lack of permission is retried through (default `None`).
+    #[cfg(feature = "elevate")]
+    pub elevate: Option<crate::elevate::Elevator>,
+    /// Where `stage()` stores a verified update.
+    #[cfg(feature = "stage")]
+    pub staging_dir: Option<PathBuf>,
     pub bin_path_in_archive: String,
+    /// Bundle mode: the bundle root inside the archive (`None` for a single-binary install).
+    pub bundle_root_in_archive: Option<String>,
+    /// Bundle mode: the resolved installed bundle directory (`Some` exactly in bundle mode).
+    pub bundle_install_path: Option<PathBuf>,
+    /// Extra `(path_in_archive, install_path)` files installed with the binary.
+    pub install_files: Vec<(String, PathBuf)>,
+    /// The versioned side-by-side layout to install into, if any.
+    pub versioned_install: Option<crate::VersionedInstall>,
     pub show_download_progress: bool,
     pub show_output: bool,
     pub no_confirm: bool,
@@ -634,6 +1022,7 @@ pub(crate) struct CommonConfig {
     pub progress_chars: String,
     pub progress_callback: Option<crate::ProgressCallback>,
     pub verify: Option<crate::VerifyCallback>,
+    pub self_test: Option<crate::self_test::SelfTest>,
     pub asset_matcher: Option<crate::AssetMatcher>,
     #[cfg(feature = "checksums")]
     pub checksum: Option<crate::Checksum>,
@@ -641,6 +1030,14 @@ pub(crate) struct CommonConfig {
     pub verify_release_digest: bool,
     #[cfg(feature = "signatures")]
     pub verifying_keys: Vec<[u8; zipsign_api::PUBLIC_KEY_LENGTH]>,
+    #[cfg(feature = "signatures")]
+    pub trusted_keys: Vec<crate::keys::TrustedKey>,
+    #[cfg(feature = "signatures")]
+    pub revocation_list_asset: Option<String>,
+    #[cfg(feature = "signatures")]
+    pub key_set_asset: Option<String>,
+    #[cfg(feature = "signatures")]
+    pub key_store_path: Option<PathBuf>,
 }
 
 #[cfg(test)]
@@ -939,7 +1336,7 @@ mod tests {
     }
 
     // The bad-cert path only records an error when a real client backend can attempt (and reject)
-    // the parse. With neither client feature, `client_with_root_certs` returns the
+    // the parse. With neither client feature, `configured_client` returns the
     // "no HTTP client feature enabled" error instead, which still populates `cert_error`.
     #[cfg(any(feature = "reqwest", feature = "ureq"))]
     #[test]
@@ -947,7 +1344,7 @@ mod tests {
         // A malformed cert with no injected client: `build_client` asks the active backend to build
         // a client, the parse fails, and the error is recorded in `cert_error`. The two backends
         // reject different malformed inputs, so the bad bytes are selected to match the same backend
-        // `client_with_root_certs` dispatches to (reqwest preferred when both features are on):
+        // `configured_client` dispatches to (reqwest preferred when both features are on):
         //   - reqwest validates at client-build time, accepting PEM framing but rejecting a body that
         //     decodes to non-X.509-DER bytes (base64 of "not a valid cert").
         //   - ureq validates the PEM framing in `from_pem` (deferring DER), so it rejects bytes that
@@ -992,6 +1389,52 @@ mod tests {
         }
     }
 
+    // A non-default root store alone is enough to materialize a client (reqwest ignores the choice;
+    // a ureq-only build needs `native-certs` for `System`).
+    #[cfg(any(feature = "reqwest", all(feature = "ureq", feature = "native-certs")))]
+    #[test]
+    fn build_client_materializes_a_client_for_a_non_default_root_store() {
+        let mut req = RequestConfig {
+            root_store: crate::RootStore::System,
+            ..Default::default()
+        };
+        req.build_client();
+        assert!(
+            req.client.is_some(),
+            "a System root store must build a client"
+        );
+        assert!(req.check().is_ok());
+    }
+
+    #[cfg(any(feature = "reqwest", feature = "ureq"))]
+    #[test]
+    fn build_client_applies_proxy_and_records_bad_proxy_as_proxy_error() {
+        // A valid proxy with no injected client materializes a client; a malformed proxy URL is
+        // recorded in `proxy_error` (not `cert_error`) and surfaces as `Error::InvalidProxy`.
+        let mut req = RequestConfig {
+            proxy: Some(crate::Proxy::new("http://127.0.0.1:3128").basic_auth("bot", "pw")),
+            ..Default::default()
+        };
+        req.build_client();
+        assert!(req.client.is_some(), "a valid proxy must build a client");
+        assert!(req.check().is_ok());
+
+        let mut req = RequestConfig {
+            proxy: Some(crate::Proxy::new("ftp://proxy:21")),
+            ..Default::default()
+        };
+        req.build_client();
+        assert!(req.client.is_none(), "a bad proxy must not leave a client");
+        assert!(req.cert_error.is_none(), "a bad proxy is not a cert error");
+        match req
+            .check()
+            .expect_err("proxy_error must surface from check()")
+        {
+            crate::errors::Error::InvalidProxy { .. } => {}
+            other => panic!("expected Error::InvalidProxy, got {:?}", other),
+        }
+    }
+
     #[test]
     fn check_surfaces_header_error_before_cert_error() {
         // When BOTH a header error and a cert error are present, `check()` must report the header
@@ -1050,6 +1493,242 @@ mod tests {
         }
     }
 
+    // Bundle mode with every required field and an explicit install path.
+    fn bundle_builder() -> CommonBuilderConfig {
+        CommonBuilderConfig {
+            current_version: Some("0.1.0".to_string()),
+            bin_name: Some("app".to_string()),
+            bin_path_in_archive: Some("app".to_string()),
+            bin_path_in_archive_auto: true,
+            bundle_root_in_archive: Some("app-{{ version }}".to_string()),
+            bundle_install_path: Some("/opt/app".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn build_resolves_bundle_mode_ignoring_the_auto_derived_bin_path() {
+        let config = bundle_builder().build().unwrap();
+        assert_eq!(
+            config.bundle_root_in_archive.as_deref(),
+            Some("app-{{ version }}")
+        );
+        assert_eq!(
+            config.bundle_install_path.as_deref(),
+            Some(std::path::Path::new("/opt/app"))
+        );
+    }
+
+    #[test]
+    fn build_rejects_bundle_mode_with_an_explicit_install_target() {
+        let conflict = |builder: CommonBuilderConfig| match builder.build().unwrap_err() {
+            crate::errors::Error::ConflictingConfig {
+                field,
+                conflicts_with,
+            } => {
+                assert_eq!(conflicts_with, "bundle_root_in_archive");
+                field
+            }
+            other => panic!("expected Error::ConflictingConfig, got {:?}", other),
+        };
+        let install_path = CommonBuilderConfig {
+            bin_install_path: Some("/usr/local/bin/app".into()),
+            ..bundle_builder()
+        };
+        assert_eq!(conflict(install_path), "bin_install_path");
+        let archive_path = CommonBuilderConfig {
+            bin_path_in_archive_auto: false,
+            ..bundle_builder()
+        };
+        assert_eq!(conflict(archive_path), "bin_path_in_archive");
+        let extra_file = CommonBuilderConfig {
+            install_files: vec![("app.1".to_string(), "/usr/share/man/man1/app.1".into())],
+            ..bundle_builder()
+        };
+        assert_eq!(conflict(extra_file), "install_file");
+    }
+
+    #[test]
+    fn build_requires_bundle_root_for_a_bundle_install_path() {
+        let builder = CommonBuilderConfig {
+            bundle_root_in_archive: None,
+            ..bundle_builder()
+        };
+        match builder.build().unwrap_err() {
+            crate::errors::Error::MissingField { field } => {
+                assert_eq!(field, "bundle_root_in_archive")
+            }
+            other => panic!("expected Error::MissingField, got {:?}", other),
+        }
+    }
+
+    // Outside macOS there is no `.app` default to fall back to.
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn build_requires_bundle_install_path_off_macos() {
+        let builder = CommonBuilderConfig {
+            bundle_install_path: None,
+            ..bundle_builder()
+        };
+        match builder.build().unwrap_err() {
+            crate::errors::Error::MissingField { field } => {
+                assert_eq!(field, "bundle_install_path")
+            }
+            other => panic!("expected Error::MissingField, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn build_rejects_versioned_install_with_another_install_target() {
+        let versioned = || CommonBuilderConfig {
+            current_version: Some("0.1.0".to_string()),
+            bin_name: Some("app".to_string()),
+            bin_path_in_archive: Some("app".to_string()),
+            versioned_install: Some(crate::VersionedInstall::new("/opt/app")),
+            ..Default::default()
+        };
+        assert!(versioned().build().unwrap().versioned_install.is_some());
+        let conflict = |builder: CommonBuilderConfig| match builder.build().unwrap_err() {
+            crate::errors::Error::ConflictingConfig {
+                field,
+                conflicts_with,
+            } => {
+                assert_eq!(conflicts_with, "versioned_install");
+                field
+            }
+            other => panic!("expected Error::ConflictingConfig, got {:?}", other),
+        };
+        let install_path = CommonBuilderConfig {
+            bin_install_path: Some("/usr/local/bin/app".into()),
+            ..versioned()
+        };
+        assert_eq!(conflict(install_path), "bin_install_path");
+        let bundle = CommonBuilderConfig {
+            bundle_root_in_archive: Some("app".to_string()),
+            bundle_install_path: Some("/opt/app".into()),
+            ..versioned()
+        };
+        assert_eq!(conflict(bundle), "bundle_root_in_archive");
+        let extra_file = CommonBuilderConfig {
+            install_files: vec![("app.1".to_string(), "/usr/share/man/man1/app.1".into())],
+            ..versioned()
+        };
+        assert_eq!(conflict(extra_file), "install_file");
+    }
+
+    #[cfg(feature = "preserve-metadata")]
+    #[test]
+    fn build_rejects_preserve_metadata_outside_the_single_binary_install() {
+        let preserving = || CommonBuilderConfig {
+            current_version: Some("0.1.0".to_string()),
+            bin_name: Some("app".to_string()),
+            bin_path_in_archive: Some("app".to_string()),
+            bin_path_in_archive_auto: true,
+            preserve_metadata: true,
+            ..Default::default()
+        };
+        assert!(preserving().build().unwrap().preserve_metadata);
+        let conflict = |builder: CommonBuilderConfig| match builder.build().unwrap_err() {
+            crate::errors::Error::ConflictingConfig {
+                field,
+                conflicts_with,
+            } => {
+                assert_eq!(field, "preserve_metadata");
+                conflicts_with
+            }
+            other => panic!("expected Error::ConflictingConfig, got {:?}", other),
+        };
+        let bundle = CommonBuilderConfig {
+            bundle_root_in_archive: Some("app".to_string()),
+            bundle_install_path: Some("/opt/app".into()),
+            ..preserving()
+        };
+        assert_eq!(conflict(bundle), "bundle_root_in_archive");
+        let versioned = CommonBuilderConfig {
+            versioned_install: Some(crate::VersionedInstall::new("/opt/app")),
+            ..preserving()
+        };
+        assert_eq!(conflict(versioned), "versioned_install");
+        let extra_file = CommonBuilderConfig {
+            install_files: vec![("app.1".to_string(), "/usr/share/man/man1/app.1".into())],
+            ..preserving()
+        };
+        assert_eq!(conflict(extra_file), "install_file");
+    }
+
+    #[cfg(feature = "elevate")]
+    #[test]
+    fn build_rejects_elevate_outside_the_single_binary_install() {
+        let elevating = || CommonBuilderConfig {
+            current_version: Some("0.1.0".to_string()),
+            bin_name: Some("app".to_string()),
+            bin_path_in_archive: Some("app".to_string()),
+            bin_path_in_archive_auto: true,
+            elevate: Some(crate::elevate::Elevator::Sudo),
+            ..Default::default()
+        };
+        assert_eq!(
+            elevating().build().unwrap().elevate,
+            Some(crate::elevate::Elevator::Sudo)
+        );
+        let versioned = CommonBuilderConfig {
+            versioned_install: Some(crate::VersionedInstall::new("/opt/app")),
+            ..elevating()
+        };
+        match versioned.build().unwrap_err() {
+            crate::errors::Error::ConflictingConfig {
+                field,
+                conflicts_with,
+            } => assert_eq!((field, conflicts_with), ("elevate", "versioned_install")),
+            other => panic!("expected Error::ConflictingConfig, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "stage")]
+    #[test]
+    fn build_rejects_settings_a_staged_update_cannot_carry() {
+        let staging = || CommonBuilderConfig {
+            current_version: Some("0.1.0".to_string()),
+            bin_name: Some("app".to_string()),
+            bin_path_in_archive: Some("app".to_string()),
+            bin_path_in_archive_auto: true,
+            staging_dir: Some("/var/lib/app/update".into()),
+            ..Default::default()
+        };
+        assert!(staging().build().is_ok());
+        let conflict = |builder: CommonBuilderConfig| match builder.build().unwrap_err() {
+            crate::errors::Error::ConflictingConfig {
+                field,
+                conflicts_with,
+            } => {
+                assert_eq!(conflicts_with, "staging_dir");
+                field
+            }
+            other => panic!("expected Error::ConflictingConfig, got {:?}", other),
+        };
+        let receipt = CommonBuilderConfig {
+            install_receipt: Some("/var/lib/app/receipt.json".into()),
+            ..staging()
+        };
+        assert_eq!(conflict(receipt), "install_receipt");
+        #[cfg(feature = "preserve-metadata")]
+        assert_eq!(
+            conflict(CommonBuilderConfig {
+                preserve_metadata: true,
+                ..staging()
+            }),
+            "preserve_metadata"
+        );
+        #[cfg(feature = "elevate")]
+        assert_eq!(
+            conflict(CommonBuilderConfig {
+                elevate: Some(crate::elevate::Elevator::Sudo),
+                ..staging()
+            }),
+            "elevate"
+        );
+    }
+
     // --- apply_auth: auth-header derivation --------------------------------------------------
 
     #[test]
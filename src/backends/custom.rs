@@ -338,6 +338,14 @@ impl<S: crate::update::AsyncReleaseSource> AsyncUpdate<S> {
         crate::update::AsyncReleaseUpdate::update_extended_async(self).await
     }
 
+    /// Download and verify the latest release, then store it in the `staging_dir` for
+    /// [`staged::ApplyStaged`](crate::staged::ApplyStaged) instead of installing it. See
+    /// [`AsyncReleaseUpdate::stage_async`](crate::AsyncReleaseUpdate::stage_async).
+    #[cfg(feature = "stage")]
+    pub async fn stage_async(&self) -> Result<Option<Release>> {
+        crate::update::AsyncReleaseUpdate::stage_async(self).await
+    }
+
     /// Fetch the single newest release from the source. See
     /// [`AsyncReleaseUpdate::get_latest_release_async`](crate::AsyncReleaseUpdate::get_latest_release_async).
     pub async fn get_latest_release_async(&self) -> Result<Releases> {
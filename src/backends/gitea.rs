@@ -18,6 +18,8 @@ use serde::Deserialize;
 struct AssetDto {
     name: Option<String>,
     browser_download_url: Option<String>,
+    /// Size in bytes, for the free-space preflight.
+    size: Option<u64>,
 }
 
 impl AssetDto {
@@ -28,7 +30,11 @@ impl AssetDto {
         let name = self
             .name
             .ok_or_else(|| Error::missing_asset_field("name"))?;
-        Ok(ReleaseAsset::new(name, download_url))
+        let asset = ReleaseAsset::new(name, download_url);
+        Ok(match self.size {
+            Some(size) => asset.with_size(size),
+            None => asset,
+        })
     }
 }
 
@@ -22,6 +22,8 @@ struct AssetDto {
     /// Content digest in `algorithm:hex` form (e.g. `sha256:2cf24d…`); github publishes one per
     /// asset since mid-2025. Optional so older payloads (and enterprise instances) still parse.
     digest: Option<String>,
+    /// Size in bytes, for the free-space preflight.
+    size: Option<u64>,
 }
 
 impl AssetDto {
@@ -30,11 +32,14 @@ impl AssetDto {
         let name = self
             .name
             .ok_or_else(|| Error::missing_asset_field("name"))?;
-        let asset = ReleaseAsset::new(name, download_url);
-        Ok(match self.digest {
-            Some(digest) => asset.with_digest(digest),
-            None => asset,
-        })
+        let mut asset = ReleaseAsset::new(name, download_url);
+        if let Some(digest) = self.digest {
+            asset = asset.with_digest(digest);
+        }
+        if let Some(size) = self.size {
+            asset = asset.with_size(size);
+        }
+        Ok(asset)
     }
 }
 
@@ -1150,8 +1155,8 @@ mod tests {
     fn github_dto_parses_sample_payload_through_getters() {
         // A realistic github release object (tag, name, created_at, body, two assets) must parse
         // via the private `ReleaseDto` into a public `Release` whose getters return the expected
-        // values: the leading `v` is stripped from the version, the asset `url`/`name`/`digest`
-        // map across (a missing `digest` maps to `None`), and the body is carried.
+        // values: the leading `v` is stripped from the version, the asset `url`/`name`/`digest`/
+        // `size` map across (a missing `digest` maps to `None`), and the body is carried.
         let body = r#"{
             "tag_name": "v4.5.6",
             "name": "Release 4.5.6",
@@ -1159,7 +1164,8 @@ mod tests {
             "body": "the release notes",
             "assets": [
                 { "name": "app-x86_64-unknown-linux-gnu.tar.gz", "url": "https://api/asset/1",
-                  "digest": "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824" },
+                  "digest": "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+                  "size": 4096 },
                 { "name": "app-aarch64-apple-darwin.tar.gz", "url": "https://api/asset/2" }
             ]
         }"#;
@@ -1190,6 +1196,8 @@ mod tests {
             Some("sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"),
             "the API's per-asset digest field is carried onto the asset"
         );
+        assert_eq!(rel.assets()[0].size(), Some(4096));
+        assert_eq!(rel.assets()[1].size(), None);
         assert_eq!(
             rel.assets()[1].digest(),
             None,
@@ -1730,6 +1738,21 @@ mod tests {
         assert_eq!(upd.request_headers().get("x-typed").unwrap(), "v");
     }
 
+    // The backend name recorded in install receipts comes from the module the accessor macro is
+    // invoked in.
+    #[test]
+    fn backend_is_named_after_its_module() {
+        use crate::update::UpdateInternals;
+        let upd = super::Update::configure()
+            .repo_owner("o")
+            .repo_name("r")
+            .bin_name("app")
+            .current_version("0.1.0")
+            .build()
+            .unwrap();
+        assert_eq!(upd.backend(), "github");
+    }
+
     #[test]
     fn api_headers_override_uses_github_user_agent() {
         // The `{api_headers}` override arm of `impl_update_config_accessors!` must wire github's
@@ -0,0 +1,144 @@
+/*!
+On-disk cache of release-listing pages for conditional requests.
+
+When a cache directory is configured (`cache_dir` on a builder), [`run_paginated`] and
+[`run_paginated_async`] keep each listing page's body and response headers here, keyed by URL.
+The next request for the page sends the stored `ETag` / `Last-Modified` back as `If-None-Match` /
+`If-Modified-Since`, and a `304 Not Modified` reuses the stored page instead of a fresh download.
+GitHub does not count a 304 against the rate limit.
+
+Each entry is one file: a single JSON line (the URL and the response headers), then the raw body.
+Entries are written through a temporary file and renamed into place, so a concurrent reader never
+sees a partial entry. The cache is best effort: an unreadable entry is a miss, and a failed write
+is logged and otherwise ignored.
+
+[`run_paginated`]: super::run_paginated
+[`run_paginated_async`]: super::run_paginated_async
+*/
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::http_client::{HeaderMap, header};
+
+/// A listing page restored from the cache.
+pub(crate) struct CachedPage {
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Vec<u8>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EntryMeta {
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+impl CachedPage {
+    /// Add the validators of the cached response (`If-None-Match` from `ETag`, `If-Modified-Since`
+    /// from `Last-Modified`) to a request for the same page.
+    pub(crate) fn add_validators(&self, request: &mut HeaderMap) {
+        if let Some(etag) = self.headers.get(header::ETAG) {
+            request.insert(header::IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(modified) = self.headers.get(header::LAST_MODIFIED) {
+            request.insert(header::IF_MODIFIED_SINCE, modified.clone());
+        }
+    }
+}
+
+/// The cached page for `url`, if the directory holds an intact entry for it.
+pub(crate) fn load(dir: &Path, url: &str) -> Option<CachedPage> {
+    let bytes = std::fs::read(entry_path(dir, url)).ok()?;
+    let split = bytes.iter().position(|b| *b == b'\n')?;
+    let meta: EntryMeta = serde_json::from_slice(&bytes[..split]).ok()?;
+    // The file name is a hash of the URL; a colliding entry belongs to another page.
+    if meta.url != url {
+        return None;
+    }
+    let mut headers = HeaderMap::new();
+    for (name, value) in meta.headers {
+        let name = header::HeaderName::from_bytes(name.as_bytes()).ok()?;
+        headers.append(name, value.parse().ok()?);
+    }
+    Some(CachedPage {
+        headers,
+        body: bytes[split + 1..].to_vec(),
+    })
+}
+
+/// Cache a freshly downloaded page, if its response carries a validator to revalidate it with.
+pub(crate) fn store(dir: &Path, url: &str, headers: &HeaderMap, body: &[u8]) {
+    if !headers.contains_key(header::ETAG) && !headers.contains_key(header::LAST_MODIFIED) {
+        return;
+    }
+    if let Err(e) = write_entry(dir, url, headers, body) {
+        log::warn!(
+            "self_update: could not cache the listing page for {} in {}: {e}",
+            crate::errors::redact_url(url),
+            dir.display()
+        );
+    }
+}
+
+fn write_entry(dir: &Path, url: &str, headers: &HeaderMap, body: &[u8]) -> std::io::Result<()> {
+    let meta = EntryMeta {
+        url: url.to_owned(),
+        headers: headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect(),
+    };
+    std::fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    serde_json::to_writer(&mut tmp, &meta)?;
+    tmp.write_all(b"\n")?;
+    tmp.write_all(body)?;
+    tmp.flush()?;
+    tmp.persist(entry_path(dir, url)).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// `<dir>/<fnv1a-64 of the URL>.page`: a stable, filesystem-safe name for any URL.
+fn entry_path(dir: &Path, url: &str) -> PathBuf {
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    dir.join(format!("{hash:016x}.page"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_and_restores_a_page_with_its_headers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = "https://api.github.com/repos/o/r/releases?per_page=100";
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ETAG, "W/\"abc\"".parse().unwrap());
+        headers.insert(
+            header::LINK,
+            "<https://api.github.com/x?page=2>; rel=\"next\""
+                .parse()
+                .unwrap(),
+        );
+        store(dir.path(), url, &headers, b"[1,2]\n[3]");
+
+        let page = load(dir.path(), url).expect("cached");
+        assert_eq!(page.body, b"[1,2]\n[3]");
+        assert_eq!(page.headers.get(header::LINK), headers.get(header::LINK));
+        let mut request = HeaderMap::new();
+        page.add_validators(&mut request);
+        assert_eq!(request.get(header::IF_NONE_MATCH).unwrap(), "W/\"abc\"");
+        assert!(request.get(header::IF_MODIFIED_SINCE).is_none());
+
+        assert!(load(dir.path(), "https://api.github.com/other").is_none());
+    }
+
+    #[test]
+    fn a_response_without_validators_is_not_cached() {
+        let dir = tempfile::TempDir::new().unwrap();
+        store(dir.path(), "https://example.com/", &HeaderMap::new(), b"[]");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
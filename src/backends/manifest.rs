@@ -29,7 +29,8 @@ println!("update status: `{}`", status.version());
 # Manifest schema
 
 The manifest is a JSON object with a `schema` version and a list of `releases`. Unknown fields are
-ignored (forward compatibility), and `date`, `notes_url`, and per-asset `digest` are optional.
+ignored (forward compatibility), and `date`, `notes_url`, and per-asset `digest` and `mirrors` are
+optional.
 
 ```json
 {
@@ -43,7 +44,10 @@ ignored (forward compatibility), and `date`, `notes_url`, and per-asset `digest`
         {
           "name": "app-1.2.3-x86_64-unknown-linux-gnu.tar.gz",
           "url": "app-1.2.3-x86_64-unknown-linux-gnu.tar.gz",
-          "digest": "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+          "digest": "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+          "mirrors": [
+            "https://eu.example.net/app-1.2.3-x86_64-unknown-linux-gnu.tar.gz"
+          ]
         }
       ]
     }
@@ -65,6 +69,9 @@ ignored (forward compatibility), and `date`, `notes_url`, and per-asset `digest`
 - `digest` (in `algorithm:hex` form, e.g. `sha256:...`) is mapped onto the asset and, with the
   `checksums` feature, verified against the downloaded artifact before installing (see
   `verify_release_digest` on the builder).
+- `mirrors` lists alternate URLs for the asset, resolved like `url`. The download tries them in
+  order when the primary URL cannot be reached or answers with a 5xx status (see
+  [`ReleaseAsset::with_mirrors`]).
 
 # Async
 
@@ -115,6 +122,10 @@ struct ManifestAsset {
     url: String,
     #[serde(default)]
     digest: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    mirrors: Vec<String>,
 }
 
 /// Resolve an asset URL against the manifest URL.
@@ -174,6 +185,17 @@ pub fn parse_manifest(body: &str, manifest_url: &str) -> Result<Vec<Release>> {
             if let Some(digest) = &asset.digest {
                 release_asset = release_asset.with_digest(&**digest);
             }
+            if let Some(size) = asset.size {
+                release_asset = release_asset.with_size(size);
+            }
+            if !asset.mirrors.is_empty() {
+                release_asset = release_asset.with_mirrors(
+                    asset
+                        .mirrors
+                        .iter()
+                        .map(|mirror| resolve_asset_url(manifest_url, mirror)),
+                );
+            }
             builder.asset(release_asset);
         }
         match builder.build() {
@@ -556,6 +578,34 @@ mod tests {
         assert_eq!(releases[0].assets()[0].digest(), None);
     }
 
+    #[test]
+    fn parse_manifest_size_mapped_through_to_asset() {
+        let body = r#"{ "schema": 1, "releases": [
+            { "version": "1.0.0", "assets": [
+                { "name": "app.tar.gz", "url": "app.tar.gz", "size": 12345 },
+                { "name": "app.zip", "url": "app.zip" } ] } ] }"#;
+        let releases = parse_manifest(body, MANIFEST_URL).unwrap();
+        assert_eq!(releases[0].assets()[0].size(), Some(12345));
+        assert_eq!(releases[0].assets()[1].size(), None);
+    }
+
+    #[test]
+    fn parse_manifest_mirrors_resolved_in_order() {
+        let body = r#"{ "schema": 1, "releases": [
+            { "version": "1.0.0", "assets": [ { "name": "app.tar.gz", "url": "app.tar.gz",
+                "mirrors": [ "https://eu.example.net/app.tar.gz", "backup/app.tar.gz" ] } ] } ] }"#;
+        let releases = parse_manifest(body, MANIFEST_URL).unwrap();
+        let asset = &releases[0].assets()[0];
+        assert_eq!(
+            asset.mirrors(),
+            [
+                "https://eu.example.net/app.tar.gz".to_string(),
+                resolve_asset_url(MANIFEST_URL, "backup/app.tar.gz"),
+            ],
+            "mirrors keep their order and resolve relative URLs like `url`"
+        );
+    }
+
     #[test]
     fn parse_manifest_date_and_notes_url_mapped() {
         let body = r#"{ "schema": 1, "releases": [
@@ -590,7 +640,7 @@ mod tests {
             "generator": "some-tool",
             "releases": [
                 { "version": "1.2.3", "channel": "stable", "assets": [
-                    { "name": "app.tar.gz", "url": "app.tar.gz", "size": 12345 } ] } ]
+                    { "name": "app.tar.gz", "url": "app.tar.gz", "arch": "x86_64" } ] } ]
         }"#;
         let releases = parse_manifest(body, MANIFEST_URL).unwrap();
         assert_eq!(releases.len(), 1);
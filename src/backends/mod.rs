@@ -4,6 +4,7 @@ Collection of modules supporting various release distribution backends
 
 use crate::errors::{Error, Result};
 use crate::http_client;
+use crate::redirect;
 
 pub(crate) mod common;
 pub mod custom;
@@ -15,6 +16,7 @@ pub mod gitee;
 pub mod github;
 #[cfg(feature = "gitlab")]
 pub mod gitlab;
+mod http_cache;
 #[cfg(feature = "manifest")]
 pub mod manifest;
 #[cfg(feature = "s3")]
@@ -165,12 +167,54 @@ impl<T> Page<T> {
     }
 }
 
+/// With a cache directory configured, load the cached copy of the page at `url` and add its
+/// validators to the page's request `headers`, so an unchanged page comes back as a 304.
+#[cfg_attr(
+    not(any(
+        feature = "github",
+        feature = "gitlab",
+        feature = "gitea",
+        feature = "gitee",
+        feature = "s3"
+    )),
+    allow(dead_code)
+)]
+fn cached_page(
+    url: &str,
+    headers: &mut http_client::HeaderMap,
+    config: &common::RequestConfig,
+) -> Option<http_cache::CachedPage> {
+    let page = http_cache::load(config.cache_dir.as_deref()?, url)?;
+    page.add_validators(headers);
+    Some(page)
+}
+
+#[cfg_attr(
+    not(any(
+        feature = "github",
+        feature = "gitlab",
+        feature = "gitea",
+        feature = "gitee",
+        feature = "s3"
+    )),
+    allow(dead_code)
+)]
+fn listing_body_too_large() -> Error {
+    Error::InvalidResponse {
+        source: Box::new(crate::errors::MessageError(format!(
+            "listing page body exceeded the {MAX_LISTING_BODY_BYTES}-byte cap; \
+             real release listing pages are much smaller"
+        ))),
+    }
+}
+
 /// Drive a sans-io [`PageRequest`] chain to completion over the sync transport.
 ///
 /// Loops: send the request via [`send`] (reusing its retry/backoff machinery), read the body bytes
 /// once, call `parse`, extend the accumulator, then stop if `page.stop`, `page.next` is `None`, or
 /// the [`MAX_RELEASE_PAGES`] bound is reached (logging a warning if a further page was still
-/// advertised at the bound).
+/// advertised at the bound). With a `cache_dir`, each request is conditional on the cached copy of
+/// its page (see [`http_cache`]), and a 304 parses the cached body and headers instead.
 #[cfg_attr(
     not(any(
         feature = "github",
@@ -191,29 +235,34 @@ pub(crate) fn run_paginated<T>(
     while let Some(request) = next {
         let PageRequest {
             url,
-            headers,
+            mut headers,
             parse,
         } = request;
-        let resp = send(&url, headers, config)?;
-        let resp_headers = resp.headers().clone();
-        let mut body = Vec::new();
-        let reader = resp.body();
-        // S5: cap the per-page body size so a malicious or misconfigured endpoint cannot force
-        // unbounded memory use. Read one byte past the cap to distinguish "exactly at the cap"
-        // (fine) from "over the cap" (error).
-        let mut limited = {
-            use std::io::Read as _;
-            reader.take((MAX_LISTING_BODY_BYTES + 1) as u64)
+        let cached = cached_page(&url, &mut headers, config);
+        let (body, resp_headers) = match (send(&url, headers, config), cached) {
+            (Ok(resp), _) => {
+                let resp_headers = resp.headers().clone();
+                let mut body = Vec::new();
+                let reader = resp.body();
+                // S5: cap the per-page body size so a malicious or misconfigured endpoint cannot
+                // force unbounded memory use. Read one byte past the cap to distinguish "exactly at
+                // the cap" (fine) from "over the cap" (error).
+                let mut limited = {
+                    use std::io::Read as _;
+                    reader.take((MAX_LISTING_BODY_BYTES + 1) as u64)
+                };
+                std::io::Read::read_to_end(&mut limited, &mut body)?;
+                if body.len() > MAX_LISTING_BODY_BYTES {
+                    return Err(listing_body_too_large());
+                }
+                if let Some(dir) = &config.cache_dir {
+                    http_cache::store(dir, &url, &resp_headers, &body);
+                }
+                (body, resp_headers)
+            }
+            (Err(e), Some(page)) if e.http_status() == Some(304) => (page.body, page.headers),
+            (Err(e), _) => return Err(e),
         };
-        std::io::Read::read_to_end(&mut limited, &mut body)?;
-        if body.len() > MAX_LISTING_BODY_BYTES {
-            return Err(Error::InvalidResponse {
-                source: Box::new(crate::errors::MessageError(format!(
-                    "listing page body exceeded the {MAX_LISTING_BODY_BYTES}-byte cap; \
-                     real release listing pages are much smaller"
-                ))),
-            });
-        }
         let page = parse(&body, &resp_headers)?;
         out.extend(page.items);
         pages += 1;
@@ -260,27 +309,32 @@ pub(crate) async fn run_paginated_async<T>(
     while let Some(request) = next {
         let PageRequest {
             url,
-            headers,
+            mut headers,
             parse,
         } = request;
-        let resp = send_async(&url, headers, config).await?;
-        let resp_headers = resp.headers().clone();
-        // Drain the streamed body into a single buffer (one full read, honoring the I7 intent of
-        // not double-buffering: the bytes stream feeds the buffer directly).
-        let mut stream = resp.bytes_stream();
-        let mut body = Vec::new();
-        while let Some(chunk) = stream.next().await {
-            body.extend_from_slice(&chunk?);
-            // S5: cap the per-page body size (same bound as the sync driver).
-            if body.len() > MAX_LISTING_BODY_BYTES {
-                return Err(Error::InvalidResponse {
-                    source: Box::new(crate::errors::MessageError(format!(
-                        "listing page body exceeded the {MAX_LISTING_BODY_BYTES}-byte cap; \
-                         real release listing pages are much smaller"
-                    ))),
-                });
+        let cached = cached_page(&url, &mut headers, config);
+        let (body, resp_headers) = match (send_async(&url, headers, config).await, cached) {
+            (Ok(resp), _) => {
+                let resp_headers = resp.headers().clone();
+                // Drain the streamed body into a single buffer (one full read, honoring the I7
+                // intent of not double-buffering: the bytes stream feeds the buffer directly).
+                let mut stream = resp.bytes_stream();
+                let mut body = Vec::new();
+                while let Some(chunk) = stream.next().await {
+                    body.extend_from_slice(&chunk?);
+                    // S5: cap the per-page body size (same bound as the sync driver).
+                    if body.len() > MAX_LISTING_BODY_BYTES {
+                        return Err(listing_body_too_large());
+                    }
+                }
+                if let Some(dir) = &config.cache_dir {
+                    http_cache::store(dir, &url, &resp_headers, &body);
+                }
+                (body, resp_headers)
             }
-        }
+            (Err(e), Some(page)) if e.http_status() == Some(304) => (page.body, page.headers),
+            (Err(e), _) => return Err(e),
+        };
         let page = parse(&body, &resp_headers)?;
         out.extend(page.items);
         pages += 1;
@@ -357,14 +411,46 @@ pub(crate) fn retry_backoff_ms(
     doubled.min(max_ms)
 }
 
+/// Delay in milliseconds before retrying after `err`, or `None` to stop retrying. A
+/// [`RateLimited`](Error::RateLimited) error waits for what the server asked for (`Retry-After`, or
+/// until the quota resets) instead of the exponential backoff, unless that exceeds
+/// `max_rate_limit_wait`: a retry before then would only be rejected again, so the error is
+/// returned straight away. A 3xx (a 304, or a redirect that could not be followed) and a redirect
+/// the policy refused are never retried. Every other error (and a rate limit without a hint) uses
+/// [`retry_backoff_ms`].
+pub(crate) fn retry_delay_ms(
+    err: &Error,
+    attempt: u32,
+    base: std::time::Duration,
+    max: std::time::Duration,
+    max_rate_limit_wait: std::time::Duration,
+) -> Option<u64> {
+    // A 304 answers a conditional request: the caller reuses its cached copy, nothing to retry. A
+    // redirect that was not followed would only be refused the same way again.
+    if err
+        .http_status()
+        .is_some_and(|status| (300..400).contains(&status))
+        || matches!(err, Error::RedirectRejected { .. })
+    {
+        return None;
+    }
+    match err.rate_limit().and_then(|r| r.wait()) {
+        Some(wait) if wait > max_rate_limit_wait => None,
+        Some(wait) => Some(u64::try_from(wait.as_millis()).unwrap_or(u64::MAX)),
+        None => Some(retry_backoff_ms(attempt, base, max)),
+    }
+}
+
 /// Run `attempt` until it succeeds or the retry budget is spent, invoking `on_retry(err, backoff)`
 /// (which logs the failure and sleeps) between tries. With `retries == 0` the attempt runs exactly
-/// once. The transport and the sleep are injected so the retry/backoff control flow can be
+/// once. The delay comes from [`retry_delay_ms`], so a rate-limited attempt waits for the server's
+/// hint. The transport and the sleep are injected so the retry/backoff control flow can be
 /// unit-tested without real requests or real delays.
 pub(crate) fn retry<R>(
     retries: u32,
     base: std::time::Duration,
     max: std::time::Duration,
+    max_rate_limit_wait: std::time::Duration,
     mut attempt: impl FnMut() -> Result<R>,
     mut on_retry: impl FnMut(&Error, u64),
 ) -> Result<R> {
@@ -376,7 +462,11 @@ pub(crate) fn retry<R>(
                 if attempts >= retries {
                     return Err(e);
                 }
-                on_retry(&e, retry_backoff_ms(attempts, base, max));
+                let Some(delay) = retry_delay_ms(&e, attempts, base, max, max_rate_limit_wait)
+                else {
+                    return Err(e);
+                };
+                on_retry(&e, delay);
                 attempts += 1;
             }
         }
@@ -391,6 +481,7 @@ pub(crate) async fn retry_async<R, A, Fut, S, SFut>(
     retries: u32,
     base: std::time::Duration,
     max: std::time::Duration,
+    max_rate_limit_wait: std::time::Duration,
     mut attempt: A,
     mut log_retry: impl FnMut(&Error, u64),
     mut sleep: S,
@@ -409,7 +500,10 @@ where
                 if attempts >= retries {
                     return Err(e);
                 }
-                let backoff = retry_backoff_ms(attempts, base, max);
+                let Some(backoff) = retry_delay_ms(&e, attempts, base, max, max_rate_limit_wait)
+                else {
+                    return Err(e);
+                };
                 log_retry(&e, backoff);
                 sleep(backoff).await;
                 attempts += 1;
@@ -463,7 +557,16 @@ pub(crate) fn send(
         config.retries,
         config.retry_base_delay,
         config.retry_max_delay,
-        || client.get(url, &base, config.timeout),
+        config.max_rate_limit_wait,
+        || {
+            config.observe_rate_limit(redirect::follow(
+                &config.redirect_policy,
+                config.on_redirect.as_ref(),
+                url,
+                &base,
+                |url, headers| client.get(url, headers, config.timeout),
+            ))
+        },
         |e, backoff| {
             // S1: redact presigned-URL credentials before logging.
             let safe_url = crate::errors::redact_url(url);
@@ -512,7 +615,19 @@ pub(crate) async fn send_async(
         config.retries,
         config.retry_base_delay,
         config.retry_max_delay,
-        || client.get(url, &base, config.timeout),
+        config.max_rate_limit_wait,
+        || async {
+            config.observe_rate_limit_async(
+                redirect::follow_async(
+                    &config.redirect_policy,
+                    config.on_redirect.as_ref(),
+                    url,
+                    &base,
+                    |url, headers| async move { client.get(&url, &headers, config.timeout).await },
+                )
+                .await,
+            )
+        },
         |e, backoff| {
             // S1: redact presigned-URL credentials before logging.
             let safe_url = crate::errors::redact_url(url);
@@ -737,6 +852,48 @@ mod test {
     // (body-drain via bytes_stream + async early-stop), mirroring the sync tests.
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn run_paginated_revalidates_cached_pages_and_reuses_them_on_304() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = requests.clone();
+        std::thread::spawn(move || {
+            for _ in 0..2 {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let req = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+                let out = if req.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n"
+                } else {
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 3\r\n\
+                     Connection: close\r\n\r\n1,2"
+                };
+                sink.lock().unwrap().push(req);
+                let _ = stream.write_all(out.as_bytes());
+            }
+        });
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = RequestConfig {
+            cache_dir: Some(dir.path().join("listings")),
+            // A 304 is an answer, not a failure: it must not consume the retry budget.
+            retries: 2,
+            ..Default::default()
+        };
+        let url = format!("{base}/releases");
+        let first = crate::backends::run_paginated(int_page(url.clone()), &config).unwrap();
+        let second = crate::backends::run_paginated(int_page(url), &config).unwrap();
+        assert_eq!(first, vec![1, 2]);
+        assert_eq!(second, first, "the 304 is served from the cached page");
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     async fn run_paginated_async_accumulates_pages() {
@@ -903,6 +1060,7 @@ mod test {
             3,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 Ok(7)
@@ -925,6 +1083,7 @@ mod test {
             0,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 Err(Error::HttpStatus {
@@ -950,6 +1109,7 @@ mod test {
             2,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 Err(Error::HttpStatus {
@@ -976,6 +1136,7 @@ mod test {
             5,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 if calls.get() < 3 {
@@ -1051,6 +1212,7 @@ mod test {
             1,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 Err(Error::HttpStatus {
@@ -1080,6 +1242,7 @@ mod test {
             6,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 Err(Error::HttpStatus {
@@ -1106,6 +1269,7 @@ mod test {
             2,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 async {
@@ -1136,6 +1300,7 @@ mod test {
             5,
             crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
             crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
             || {
                 calls.set(calls.get() + 1);
                 let done = calls.get() >= 3;
@@ -1159,6 +1324,97 @@ mod test {
         assert_eq!(*backoffs.borrow(), vec![100, 200]);
     }
 
+    fn rate_limited(retry_after: u64) -> crate::errors::Error {
+        crate::errors::Error::RateLimited {
+            status: 429,
+            url: "u".into(),
+            reset_at: None,
+            retry_after: Some(std::time::Duration::from_secs(retry_after)),
+            limit: None,
+            remaining: Some(0),
+        }
+    }
+
+    #[test]
+    fn retry_waits_for_the_rate_limit_hint_instead_of_backoff() {
+        use crate::backends::retry;
+        use std::cell::{Cell, RefCell};
+        let calls = Cell::new(0u32);
+        let backoffs = RefCell::new(Vec::<u64>::new());
+        let res: crate::errors::Result<i32> = retry(
+            3,
+            crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
+            crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            crate::backends::common::DEFAULT_MAX_RATE_LIMIT_WAIT,
+            || {
+                calls.set(calls.get() + 1);
+                if calls.get() == 1 {
+                    Err(rate_limited(7))
+                } else {
+                    Ok(1)
+                }
+            },
+            |_e, b| backoffs.borrow_mut().push(b),
+        );
+        assert_eq!(res.unwrap(), 1);
+        // Retry-After: 7 -> 7000ms, not the 100ms exponential step (and not clamped to its cap).
+        assert_eq!(*backoffs.borrow(), vec![7000]);
+    }
+
+    #[test]
+    fn retry_gives_up_when_the_rate_limit_wait_exceeds_the_cap() {
+        use crate::backends::retry;
+        use crate::errors::Error;
+        use std::cell::{Cell, RefCell};
+        let calls = Cell::new(0u32);
+        let backoffs = RefCell::new(Vec::<u64>::new());
+        let res: crate::errors::Result<i32> = retry(
+            3,
+            crate::backends::common::DEFAULT_RETRY_BASE_DELAY,
+            crate::backends::common::DEFAULT_RETRY_MAX_DELAY,
+            std::time::Duration::from_secs(30),
+            || {
+                calls.set(calls.get() + 1);
+                Err(rate_limited(3600))
+            },
+            |_e, b| backoffs.borrow_mut().push(b),
+        );
+        assert!(matches!(res, Err(Error::RateLimited { .. })));
+        // An hour-long wait is over the 30s cap: fail at once rather than burn the budget.
+        assert_eq!(calls.get(), 1);
+        assert!(backoffs.borrow().is_empty());
+    }
+
+    #[test]
+    fn send_retries_a_rate_limit_and_records_the_last_observed_state() {
+        use crate::backends::common::RequestConfig;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}/", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for head in [
+                "429 Too Many Requests\r\nRetry-After: 0\r\nX-RateLimit-Remaining: 0",
+                "200 OK\r\nX-RateLimit-Limit: 5000\r\nX-RateLimit-Remaining: 4999",
+            ] {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                let out =
+                    format!("HTTP/1.1 {head}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                let _ = stream.write_all(out.as_bytes());
+            }
+        });
+        let config = RequestConfig {
+            retries: 1,
+            ..Default::default()
+        };
+        crate::backends::send(&base, HeaderMap::new(), &config).unwrap();
+        let observed = config.last_rate_limit().expect("rate limit recorded");
+        assert_eq!(observed.limit, Some(5000));
+        assert_eq!(observed.remaining, Some(4999));
+    }
+
     // -----------------------------------------------------------------------
     // send: the listing path applies the derived auth header AND honors a user
     // AUTHORIZATION override (B5), captured off a loopback TCP stub.
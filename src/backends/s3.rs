@@ -1582,6 +1582,7 @@ fn parse_s3_response<R: std::io::BufRead>(
         Contents,
         Key,
         LastModified,
+        Size,
         IsTruncated,
         NextContinuationToken,
         Other,
@@ -1589,6 +1590,8 @@ fn parse_s3_response<R: std::io::BufRead>(
 
     let mut current_tag = Tag::Other;
     let mut current_release: Option<Release> = None;
+    // The current `<Contents>` object's `<Size>`, which may come before or after its `<Key>`.
+    let mut current_size: Option<u64> = None;
     let mut is_truncated = false;
     let mut next_continuation_token: Option<String> = None;
     // A user-supplied `asset_key_pattern` (compiled once at `build()`) replaces the default
@@ -1608,9 +1611,11 @@ fn parse_s3_response<R: std::io::BufRead>(
                         add_to_releases_list(&mut releases, release);
                     }
                     current_release = None;
+                    current_size = None;
                 }
                 b"Key" => current_tag = Tag::Key,
                 b"LastModified" => current_tag = Tag::LastModified,
+                b"Size" => current_tag = Tag::Size,
                 b"IsTruncated" => current_tag = Tag::IsTruncated,
                 b"NextContinuationToken" => current_tag = Tag::NextContinuationToken,
                 _ => current_tag = Tag::Other,
@@ -1654,8 +1659,9 @@ fn parse_s3_response<R: std::io::BufRead>(
                                         signature_ttl.as_secs(),
                                     )?;
 
-                                    release.assets =
-                                        vec![ReleaseAsset::new(exe_name, download_url)];
+                                    let mut asset = ReleaseAsset::new(exe_name, download_url);
+                                    asset.size = current_size;
+                                    release.assets = vec![asset];
                                     debug!("Matched release: {:?}", release);
                                 }
                             } else {
@@ -1666,6 +1672,15 @@ fn parse_s3_response<R: std::io::BufRead>(
                             let release = current_release.get_or_insert(Release::default());
                             release.date = std::sync::Arc::from(txt);
                         }
+                        Tag::Size => {
+                            current_size = txt.parse().ok();
+                            if let Some(asset) = current_release
+                                .as_mut()
+                                .and_then(|release| release.assets.first_mut())
+                            {
+                                asset.size = current_size;
+                            }
+                        }
                         Tag::IsTruncated => {
                             is_truncated = txt.eq_ignore_ascii_case("true");
                         }
@@ -1850,6 +1865,37 @@ mod tests {
         assert_eq!(rel.date(), "2024-01-01T00:00:00.000Z");
     }
 
+    #[test]
+    fn parse_s3_response_carries_the_object_size() {
+        // `<Size>` is read whether the listing puts it after the `<Key>` (as S3 does) or before.
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <ListBucketResult xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+            <Contents><Key>myapp-1.2.3-x86_64-linux</Key><Size>4096</Size></Contents>\
+            <Contents><Size>512</Size><Key>myapp-1.2.4-x86_64-linux</Key></Contents>\
+            <Contents><Key>myapp-1.2.5-x86_64-linux</Key></Contents>\
+            </ListBucketResult>";
+        let releases = parse_s3_response(
+            xml.as_bytes(),
+            "https://bucket/",
+            #[cfg(feature = "s3-auth")]
+            &None,
+            #[cfg(feature = "s3-auth")]
+            &None,
+        )
+        .unwrap();
+        let size_of = |version: &str| {
+            releases
+                .iter()
+                .find(|r| r.version() == version)
+                .unwrap()
+                .assets[0]
+                .size()
+        };
+        assert_eq!(size_of("1.2.3"), Some(4096));
+        assert_eq!(size_of("1.2.4"), Some(512));
+        assert_eq!(size_of("1.2.5"), None);
+    }
+
     #[test]
     fn parse_s3_response_v_prefix_stripped() {
         // A `v`-prefixed version tag (e.g. "myapp-v2.0.0-arm-linux") must have the `v` stripped
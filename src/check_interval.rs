@@ -71,6 +71,11 @@ impl UpdateCheckGuard {
         }
     }
 
+    /// The stamp file recording the last check.
+    pub fn stamp_path(&self) -> &Path {
+        &self.stamp_path
+    }
+
     /// Whether an update check is due: `true` when the stamp file is missing, holds an unparseable
     /// timestamp, is dated in the future, or is at least `interval` old. See the
     /// [module docs](crate::check_interval) for the exact rules. Returns `Err` only on a genuine IO
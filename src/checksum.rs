@@ -7,17 +7,19 @@ file — against the downloaded file before it is installed.
 */
 #![cfg(feature = "checksums")]
 
-use std::io::Read;
+use std::io::{self, Read, Write};
+use std::mem::Discriminant;
 use std::path::Path;
 
-use sha2::{Digest, Sha256, Sha512};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 
 use crate::errors::*;
 
 /// An expected checksum for a downloaded release artifact, tagged with its hash algorithm.
 ///
 /// The variant selects the algorithm; the contained `String` is the expected digest, hex
-/// encoded (case-insensitive, surrounding whitespace ignored). Pass one to
+/// encoded (case-insensitive, surrounding whitespace ignored). `Blake3` additionally needs the
+/// `checksums-blake3` feature. Pass one to
 /// `Update::configure().verify_checksum(..)`; the download is rejected before installation
 /// if it does not match.
 ///
@@ -31,17 +33,29 @@ use crate::errors::*;
 pub enum Checksum {
     /// Expected SHA-256 digest, hex encoded.
     Sha256(String),
+    /// Expected SHA-384 digest, hex encoded.
+    Sha384(String),
     /// Expected SHA-512 digest, hex encoded.
     Sha512(String),
+    /// Expected BLAKE3 digest (the default 32-byte output), hex encoded. Requires the
+    /// `checksums-blake3` feature.
+    #[cfg(feature = "checksums-blake3")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checksums-blake3")))]
+    Blake3(String),
 }
 
 impl Checksum {
-    /// Parse an `algorithm:hex` digest string (e.g. `sha256:2cf24d…`, the form GitHub's release
-    /// API publishes per asset) into a `Checksum`.
+    /// Parse a digest string into a `Checksum`. Two forms are accepted:
     ///
-    /// Supported algorithms are `sha256` and `sha512` (case-insensitive, surrounding whitespace
-    /// ignored). Any other prefix — or a string with no `:` separator — is rejected with an error
-    /// naming the digest, rather than silently skipping verification.
+    /// - `algorithm:hex` (e.g. `sha256:2cf24d…`, the form GitHub's release API publishes per
+    ///   asset), with algorithm `sha256`, `sha384`, `sha512`, or `blake3` (the last with the
+    ///   `checksums-blake3` feature);
+    /// - a [Subresource Integrity](https://www.w3.org/TR/SRI/) string `algorithm-base64` (e.g.
+    ///   `sha384-oqVuAfXR…`), with algorithm `sha256`, `sha384`, or `sha512`.
+    ///
+    /// The algorithm is case-insensitive and surrounding whitespace is ignored. Any other
+    /// algorithm, a string in neither form, or SRI base64 that does not decode is rejected with an
+    /// error naming the digest, rather than silently skipping verification.
     ///
     /// ```
     /// use self_update::Checksum;
@@ -49,19 +63,45 @@ impl Checksum {
     ///     "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
     /// ).unwrap();
     /// assert!(matches!(c, Checksum::Sha256(_)));
+    /// let c = Checksum::parse_digest(
+    ///     "sha384-WeF0h3dEjGnea4ANejO7+5/xtGPkQ1TDVTvNucZm+pASWjx5+QOXvfX2oT3oKGhP",
+    /// ).unwrap();
+    /// assert!(matches!(c, Checksum::Sha384(_)));
     /// assert!(Checksum::parse_digest("md5:abc123").is_err());
     /// ```
     pub fn parse_digest(digest: &str) -> Result<Self> {
         let digest = digest.trim();
         let unsupported = || {
             Error::invalid_response(format!(
-                "unsupported asset digest `{digest}` (expected `sha256:<hex>` or `sha512:<hex>`)"
+                "unsupported asset digest `{digest}` (expected `<algorithm>:<hex>` or SRI \
+                 `<algorithm>-<base64>` with a sha256/sha384/sha512{} algorithm)",
+                if cfg!(feature = "checksums-blake3") {
+                    "/blake3"
+                } else {
+                    ""
+                }
             ))
         };
-        let (algorithm, hex) = digest.split_once(':').ok_or_else(unsupported)?;
-        match algorithm.trim().to_ascii_lowercase().as_str() {
-            "sha256" => Ok(Checksum::Sha256(hex.to_string())),
-            "sha512" => Ok(Checksum::Sha512(hex.to_string())),
+        if let Some((algorithm, hex)) = digest.split_once(':') {
+            let hex = hex.to_string();
+            return match algorithm.trim().to_ascii_lowercase().as_str() {
+                "sha256" => Ok(Checksum::Sha256(hex)),
+                "sha384" => Ok(Checksum::Sha384(hex)),
+                "sha512" => Ok(Checksum::Sha512(hex)),
+                #[cfg(feature = "checksums-blake3")]
+                "blake3" => Ok(Checksum::Blake3(hex)),
+                _ => Err(unsupported()),
+            };
+        }
+        // SRI: `<algorithm>-<base64>`. The base64 is converted to the hex every variant holds.
+        let (algorithm, b64) = digest.split_once('-').ok_or_else(unsupported)?;
+        let hex = base64_decode(b64)
+            .map(|bytes| hex_encode(&bytes))
+            .ok_or_else(unsupported)?;
+        match algorithm.to_ascii_lowercase().as_str() {
+            "sha256" => Ok(Checksum::Sha256(hex)),
+            "sha384" => Ok(Checksum::Sha384(hex)),
+            "sha512" => Ok(Checksum::Sha512(hex)),
             _ => Err(unsupported()),
         }
     }
@@ -69,46 +109,245 @@ impl Checksum {
     /// The expected digest, hex encoded.
     fn expected(&self) -> &str {
         match self {
-            Checksum::Sha256(hex) | Checksum::Sha512(hex) => hex,
+            Checksum::Sha256(hex) | Checksum::Sha384(hex) | Checksum::Sha512(hex) => hex,
+            #[cfg(feature = "checksums-blake3")]
+            Checksum::Blake3(hex) => hex,
         }
     }
 
-    /// Hash the file at `path` with this checksum's algorithm and return the hex digest.
-    fn hash_file(&self, path: &Path) -> Result<String> {
+    /// A fresh running hash for this checksum's algorithm.
+    fn hasher(&self) -> Hasher {
         match self {
-            Checksum::Sha256(_) => hash_file::<Sha256>(path),
-            Checksum::Sha512(_) => hash_file::<Sha512>(path),
+            Checksum::Sha256(_) => Hasher::Sha256(Sha256::new()),
+            Checksum::Sha384(_) => Hasher::Sha384(Sha384::new()),
+            Checksum::Sha512(_) => Hasher::Sha512(Sha512::new()),
+            #[cfg(feature = "checksums-blake3")]
+            Checksum::Blake3(_) => Hasher::Blake3(Box::default()),
         }
     }
 
-    /// Verify that the file at `path` matches this checksum, returning an error on mismatch.
-    pub(crate) fn verify(&self, path: &Path) -> Result<()> {
+    /// Compare `computed` (lowercase hex) against this checksum's expected digest.
+    fn check(&self, computed: String) -> Result<()> {
         let expected = self.expected().trim().to_lowercase();
-        let actual = self.hash_file(path)?;
-        if actual.eq_ignore_ascii_case(&expected) {
+        if computed.eq_ignore_ascii_case(&expected) {
             Ok(())
         } else {
-            Err(Error::ChecksumMismatch {
-                expected,
-                computed: actual,
-            })
+            Err(Error::ChecksumMismatch { expected, computed })
+        }
+    }
+
+    /// Verify that the file at `path` matches this checksum, returning
+    /// [`Error::ChecksumMismatch`] if it does not. To check several checksums, use
+    /// [`verify_all`](Self::verify_all), which reads the file only once.
+    pub fn verify(&self, path: &Path) -> Result<()> {
+        Checksum::verify_all(path, std::slice::from_ref(self))
+    }
+
+    /// Verify the file at `path` against every checksum in `checksums`, reading it once.
+    ///
+    /// Each distinct algorithm is computed a single time in the same pass, so a forge digest, a
+    /// pinned checksum, and any number of entries from sums files cost one read of the file. The
+    /// first checksum (in slice order) that does not match is returned as
+    /// [`Error::ChecksumMismatch`]. An empty slice is `Ok` without opening the file.
+    pub fn verify_all(path: &Path, checksums: &[Checksum]) -> Result<()> {
+        if checksums.is_empty() {
+            return Ok(());
         }
+        let mut hashers = Hashers::new(checksums);
+        for_each_chunk(path, |chunk| hashers.update(chunk))?;
+        hashers.verify(checksums)
+    }
+}
+
+/// A [`Write`] adapter that hashes everything written through it, so a download is verified
+/// against any number of [`Checksum`]s while it is being written rather than by re-reading the
+/// file afterwards.
+///
+/// Each distinct algorithm is computed once however many checksums use it. Call
+/// [`finish`](Self::finish) once the download is complete to compare the digests and get the
+/// inner writer back.
+///
+/// ```
+/// # fn run() -> self_update::errors::Result<()> {
+/// use self_update::{Checksum, ChecksumWriter};
+/// let mut out = ChecksumWriter::new(
+///     Vec::new(),
+///     vec![Checksum::Sha256(
+///         "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".to_string(),
+///     )],
+/// );
+/// // e.g. `self_update::Download::from_url(url).download_to(&mut out)?`
+/// std::io::Write::write_all(&mut out, b"hello")?;
+/// let bytes = out.finish()?;
+/// assert_eq!(bytes, b"hello");
+/// # Ok(())
+/// # }
+/// # run().unwrap();
+/// ```
+pub struct ChecksumWriter<W> {
+    inner: W,
+    checksums: Vec<Checksum>,
+    hashers: Hashers,
+}
+
+impl<W> ChecksumWriter<W> {
+    /// Wrap `inner`, hashing written bytes for each of `checksums`.
+    pub fn new(inner: W, checksums: impl Into<Vec<Checksum>>) -> Self {
+        let checksums = checksums.into();
+        let hashers = Hashers::new(&checksums);
+        Self {
+            inner,
+            checksums,
+            hashers,
+        }
+    }
+
+    /// Verify the bytes written so far against every checksum, returning the inner writer on
+    /// success and the first mismatch ([`Error::ChecksumMismatch`]) otherwise. The inner writer is
+    /// not flushed.
+    pub fn finish(self) -> Result<W> {
+        self.hashers.verify(&self.checksums)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hashers.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
     }
 }
 
-/// Stream the file through digest `D` and return its lowercase hex digest.
-fn hash_file<D: Digest>(path: &Path) -> Result<String> {
+impl<W: std::fmt::Debug> std::fmt::Debug for ChecksumWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChecksumWriter")
+            .field("inner", &self.inner)
+            .field("checksums", &self.checksums)
+            .finish()
+    }
+}
+
+/// A running hash for one algorithm.
+enum Hasher {
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+    // Boxed: the BLAKE3 state is several times larger than the sha2 ones.
+    #[cfg(feature = "checksums-blake3")]
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha384(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+            #[cfg(feature = "checksums-blake3")]
+            Hasher::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    /// The lowercase hex digest.
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Sha256(h) => hex_encode(&h.finalize()),
+            Hasher::Sha384(h) => hex_encode(&h.finalize()),
+            Hasher::Sha512(h) => hex_encode(&h.finalize()),
+            #[cfg(feature = "checksums-blake3")]
+            Hasher::Blake3(h) => h.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+/// One [`Hasher`] per distinct algorithm among a set of checksums, keyed by the `Checksum` variant.
+struct Hashers(Vec<(Discriminant<Checksum>, Hasher)>);
+
+impl Hashers {
+    fn new(checksums: &[Checksum]) -> Self {
+        let mut hashers: Vec<(Discriminant<Checksum>, Hasher)> = Vec::new();
+        for checksum in checksums {
+            let kind = std::mem::discriminant(checksum);
+            if !hashers.iter().any(|(k, _)| *k == kind) {
+                hashers.push((kind, checksum.hasher()));
+            }
+        }
+        Self(hashers)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for (_, hasher) in &mut self.0 {
+            hasher.update(data);
+        }
+    }
+
+    /// Finalize every hash and check each of `checksums` (the set this was built from) in order.
+    fn verify(self, checksums: &[Checksum]) -> Result<()> {
+        let digests: Vec<_> = self
+            .0
+            .into_iter()
+            .map(|(kind, hasher)| (kind, hasher.finalize()))
+            .collect();
+        for checksum in checksums {
+            let kind = std::mem::discriminant(checksum);
+            let (_, computed) = digests
+                .iter()
+                .find(|(k, _)| *k == kind)
+                .expect("a hasher exists for every checksum kind");
+            checksum.check(computed.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the file at `path` in fixed-size chunks, handing each to `f`.
+fn for_each_chunk(path: &Path, mut f: impl FnMut(&[u8])) -> Result<()> {
     let mut file = std::fs::File::open(path)?;
-    let mut hasher = D::new();
     let mut buf = [0u8; 8192];
     loop {
         let n = file.read(&mut buf)?;
         if n == 0 {
-            break;
+            return Ok(());
         }
-        hasher.update(&buf[..n]);
+        f(&buf[..n]);
     }
-    Ok(hex_encode(&hasher.finalize()))
+}
+
+/// Decode standard (RFC 4648) base64, as used by SRI strings. Padding is optional; any other
+/// character, or a dangling single sextet, yields `None`.
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        Some(match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        } as u32)
+    }
+    let input = input.trim_end_matches('=').as_bytes();
+    if input.is_empty() || input.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    for chunk in input.chunks(4) {
+        let mut acc = 0u32;
+        for &c in chunk {
+            acc = (acc << 6) | value(c)?;
+        }
+        acc <<= 6 * (4 - chunk.len() as u32);
+        let bytes = acc.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
 }
 
 fn hex_encode(bytes: &[u8]) -> String {
@@ -120,6 +359,19 @@ fn hex_encode(bytes: &[u8]) -> String {
     out
 }
 
+/// The hex SHA-256 digest of the file at `path`, as recorded in an install receipt.
+pub(crate) fn sha256_file_hex(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for_each_chunk(path, |chunk| hasher.update(chunk))?;
+    Ok(hex_encode(&hasher.finalize()))
+}
+
+/// The hex SHA-256 digest of `bytes`, as the local release server publishes it per asset.
+#[cfg(feature = "test-util")]
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    hex_encode(&Sha256::digest(bytes))
+}
+
 #[cfg(test)]
 mod tests {
     use super::Checksum;
@@ -152,6 +404,69 @@ mod tests {
             .unwrap();
     }
 
+    // SHA-384 hashes files, and `parse_digest` takes it both as `sha384:<hex>` and as an SRI
+    // `sha384-<base64>` string (here the SRI form of the same digest, as `openssl dgst -sha384
+    // -binary | base64` prints it).
+    #[test]
+    fn sha384_verifies_in_hex_and_sri_form() {
+        let (_dir, path) = write_tmp(b"hello");
+        // `printf hello | sha384sum`
+        let hex = "59e1748777448c69de6b800d7a33bbfb9ff1b463e44354c3553bcdb9c666fa90125a3c79f90397bdf5f6a13de828684f";
+        Checksum::Sha384(hex.to_string()).verify(&path).unwrap();
+        Checksum::parse_digest(&format!("sha384:{hex}"))
+            .unwrap()
+            .verify(&path)
+            .unwrap();
+
+        let sri = "sha384-WeF0h3dEjGnea4ANejO7+5/xtGPkQ1TDVTvNucZm+pASWjx5+QOXvfX2oT3oKGhP";
+        let parsed = Checksum::parse_digest(sri).unwrap();
+        assert!(matches!(&parsed, Checksum::Sha384(h) if h == hex));
+        parsed.verify(&path).unwrap();
+    }
+
+    // The other SRI algorithms decode to the same hex as their `algorithm:hex` form, padding
+    // included; malformed base64 and non-SRI algorithms are rejected.
+    #[test]
+    fn parse_digest_accepts_sri_sha256_and_sha512() {
+        let (_dir, path) = write_tmp(b"hello");
+        // `printf hello | openssl dgst -sha256 -binary | base64`
+        Checksum::parse_digest("sha256-LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=")
+            .unwrap()
+            .verify(&path)
+            .unwrap();
+        let sha512 = Checksum::parse_digest(
+            "sha512-m3HSJL1i83hdltRq0+o9czGb+8KJDKra4t/3JRlnPKcjI8PZm6XBHXx6zG4UuMXaDEZjR1wuXDre9G9zvN7AQw==",
+        )
+        .unwrap();
+        assert!(matches!(sha512, Checksum::Sha512(_)));
+        sha512.verify(&path).unwrap();
+
+        for bad in [
+            "sha384-not*base64",
+            "md5-XUFAKrxLKna5cZ2REBfFkg==",
+            "sha256-A",
+        ] {
+            assert!(
+                Checksum::parse_digest(bad).is_err(),
+                "{bad:?} must be rejected"
+            );
+        }
+    }
+
+    // BLAKE3 (`checksums-blake3`) hashes files and parses from `blake3:<hex>`.
+    #[cfg(feature = "checksums-blake3")]
+    #[test]
+    fn blake3_matches_known_digest() {
+        let (_dir, path) = write_tmp(b"hello");
+        // `printf hello | b3sum`
+        let hex = "ea8f163db38682925e4491c5e58d4bb3506ef8c14eb78a86e908c5624a67200f";
+        Checksum::Blake3(hex.to_string()).verify(&path).unwrap();
+        let parsed = Checksum::parse_digest(&format!("BLAKE3:{hex}")).unwrap();
+        assert!(matches!(parsed, Checksum::Blake3(_)));
+        parsed.verify(&path).unwrap();
+        assert!(Checksum::Blake3("00".repeat(32)).verify(&path).is_err());
+    }
+
     // An unknown algorithm or a string without the `:` separator is rejected with
     // `Error::InvalidResponse`, and the message names the offending digest.
     #[test]
@@ -255,4 +570,62 @@ mod tests {
             shown
         );
     }
+
+    // `verify_all` checks several checksums (two of the same algorithm, plus a different one)
+    // in one pass; the first mismatch in slice order is the one reported, and an empty set never
+    // touches the file.
+    #[test]
+    fn verify_all_checks_every_checksum_in_one_pass() {
+        let (_dir, path) = write_tmp(b"hello");
+        let sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let sha512 = "9b71d224bd62f3785d96d46ad3ea3d73319bfbc2890caadae2dff72519673ca72323c3d99ba5c11d7c7acc6e14b8c5da0c4663475c2e5c3adef46f73bcdec043";
+        let good = [
+            Checksum::Sha256(sha256.to_string()),
+            Checksum::Sha512(sha512.to_string()),
+            Checksum::Sha256(sha256.to_uppercase()),
+        ];
+        Checksum::verify_all(&path, &good).unwrap();
+
+        let bad = [
+            Checksum::Sha512(sha512.to_string()),
+            Checksum::Sha256("11".repeat(32)),
+            Checksum::Sha256("22".repeat(32)),
+        ];
+        match Checksum::verify_all(&path, &bad).unwrap_err() {
+            crate::errors::Error::ChecksumMismatch { expected, computed } => {
+                assert_eq!(expected, "11".repeat(32));
+                assert_eq!(computed, sha256);
+            }
+            other => panic!("expected ChecksumMismatch, got {other:?}"),
+        }
+
+        Checksum::verify_all(&path.with_file_name("missing"), &[]).unwrap();
+    }
+
+    // `ChecksumWriter` passes bytes through to the inner writer and verifies them on `finish`,
+    // without the data ever being re-read.
+    #[test]
+    fn checksum_writer_verifies_while_writing() {
+        use std::io::Write;
+        let sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let sha384 = "59e1748777448c69de6b800d7a33bbfb9ff1b463e44354c3553bcdb9c666fa90125a3c79f90397bdf5f6a13de828684f";
+
+        let mut out = super::ChecksumWriter::new(
+            Vec::new(),
+            [
+                Checksum::Sha256(sha256.to_string()),
+                Checksum::Sha384(sha384.to_string()),
+            ],
+        );
+        out.write_all(b"hel").unwrap();
+        out.write_all(b"lo").unwrap();
+        assert_eq!(out.finish().unwrap(), b"hello");
+
+        let mut out = super::ChecksumWriter::new(Vec::new(), [Checksum::Sha256("00".repeat(32))]);
+        out.write_all(b"hello").unwrap();
+        assert!(matches!(
+            out.finish(),
+            Err(crate::errors::Error::ChecksumMismatch { .. })
+        ));
+    }
 }
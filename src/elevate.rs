@@ -0,0 +1,314 @@
+/*!
+Finishing a system-wide install with elevated privileges (`elevate(..)`, `elevate` feature).
+
+Updating a binary in a root-owned directory such as `/usr/local/bin` fails at the replace step
+with [`Error::InstallPathNotWritable`], after everything has already been downloaded. With an
+[`Elevator`] configured, that one step is retried with privileges instead: the release is still
+downloaded, checked and verified as the normal user, and only the replacement is handed to a
+re-invocation of the current executable through `sudo`, `doas` or `pkexec`:
+
+```text
+sudo /usr/local/bin/app --self-update-elevated-install <staged file> <install path> <sha256>
+```
+
+The privileged process copies the staged file into a temporary file beside the install path,
+where the unprivileged user can no longer change it, checks the copy against the SHA-256 computed
+before elevating, keeps the replaced binary's owner and mode, and renames it into place. Back in
+the unprivileged process, the installed file is hashed once more so a re-invocation that did not
+perform the install is reported rather than taken for success.
+
+**The application has to cooperate:** call [`handle_elevated_install`] first thing in `main`,
+before parsing arguments, so the re-invoked executable performs the install and exits instead of
+running the application as root.
+
+```rust,no_run
+// The first statement of `main`, before the application parses its arguments:
+self_update::elevate::handle_elevated_install();
+```
+*/
+
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::checksum::sha256_file_hex;
+use crate::errors::*;
+
+/// The first argument that marks a re-invocation as the privileged install step.
+pub const ELEVATED_INSTALL_ARG: &str = "--self-update-elevated-install";
+
+/// The tool used to run the privileged install step. Set via the `elevate` builder setter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Elevator {
+    /// The first of `sudo`, `doas` and `pkexec` found on `PATH`; `pkexec` is tried first when
+    /// stdin is not a terminal, since it can prompt through a graphical agent.
+    #[default]
+    Auto,
+    /// `sudo`.
+    Sudo,
+    /// `doas`.
+    Doas,
+    /// polkit's `pkexec`.
+    Pkexec,
+}
+
+impl Elevator {
+    /// The program names to look for, in order.
+    fn candidates(self, interactive: bool) -> &'static [&'static str] {
+        match self {
+            Elevator::Auto if interactive => &["sudo", "doas", "pkexec"],
+            Elevator::Auto => &["pkexec", "sudo", "doas"],
+            Elevator::Sudo => &["sudo"],
+            Elevator::Doas => &["doas"],
+            Elevator::Pkexec => &["pkexec"],
+        }
+    }
+
+    /// The first candidate found in one of the `search_path` directories.
+    fn find(self, interactive: bool, search_path: &OsStr) -> Option<PathBuf> {
+        self.candidates(interactive).iter().find_map(|name| {
+            std::env::split_paths(search_path)
+                .map(|dir| dir.join(name))
+                .find(|candidate| candidate.is_file())
+        })
+    }
+}
+
+/// Perform the privileged install step if this process is the re-invocation made for one, then
+/// exit; otherwise return immediately. Call it first thing in `main` when `elevate` is set (see
+/// the [module docs](crate::elevate)).
+///
+/// The process exits `0` once the new binary is in place, `1` (with the error on stderr) when
+/// the install failed, and `2` on malformed arguments.
+pub fn handle_elevated_install() {
+    let mut args = std::env::args_os().skip(1);
+    if args.next().as_deref() != Some(OsStr::new(ELEVATED_INSTALL_ARG)) {
+        return;
+    }
+    let code = match (args.next(), args.next(), args.next(), args.next()) {
+        (Some(staged), Some(dest), Some(sha256), None) => {
+            match install_verified(
+                Path::new(&staged),
+                Path::new(&dest),
+                &sha256.to_string_lossy(),
+            ) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    1
+                }
+            }
+        }
+        _ => {
+            eprintln!(
+                "usage: {} <staged file> <install path> <sha256>",
+                ELEVATED_INSTALL_ARG
+            );
+            2
+        }
+    };
+    std::process::exit(code);
+}
+
+/// Resolve the `elevator` to a program on `PATH`, failing with [`Error::ElevationFailed`] for
+/// `bin_install_path` when there is none.
+pub(crate) fn find_tool(elevator: Elevator, bin_install_path: &Path) -> Result<PathBuf> {
+    let search_path = std::env::var_os("PATH").unwrap_or_default();
+    elevator
+        .find(io::stdin().is_terminal(), &search_path)
+        .ok_or_else(|| Error::ElevationFailed {
+            path: bin_install_path.to_path_buf(),
+            reason: format!(
+                "none of {} found on PATH",
+                elevator.candidates(true).join(", ")
+            ),
+        })
+}
+
+/// Install `new_exe` at `bin_install_path` by re-invoking the current executable through `tool`.
+/// Like the unprivileged install, the running executable itself is replaced through its resolved
+/// path rather than a symlink to it.
+pub(crate) fn install_elevated(tool: &Path, new_exe: &Path, bin_install_path: &Path) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let dest = if crate::update::same_file(bin_install_path, &exe) {
+        exe.as_path()
+    } else {
+        bin_install_path
+    };
+    run_elevated(tool, &exe, new_exe, dest, bin_install_path)
+}
+
+/// Run `tool exe ELEVATED_INSTALL_ARG new_exe dest <sha256>` and check that `dest` now holds
+/// `new_exe`. Errors name `bin_install_path`, the path the caller configured.
+fn run_elevated(
+    tool: &Path,
+    exe: &Path,
+    new_exe: &Path,
+    dest: &Path,
+    bin_install_path: &Path,
+) -> Result<()> {
+    let failed = |reason: String| Error::ElevationFailed {
+        path: bin_install_path.to_path_buf(),
+        reason,
+    };
+    let sha256 = sha256_file_hex(new_exe)?;
+    let status = Command::new(tool)
+        .arg(exe)
+        .arg(ELEVATED_INSTALL_ARG)
+        .arg(new_exe)
+        .arg(dest)
+        .arg(OsString::from(&sha256))
+        .status()
+        .map_err(|e| failed(format!("could not run {}: {}", tool.display(), e)))?;
+    if !status.success() {
+        return Err(failed(format!("{} exited with {}", tool.display(), status)));
+    }
+    match sha256_file_hex(dest) {
+        Ok(installed) if installed == sha256 => Ok(()),
+        // Nothing more to check when the installed binary is not readable to this user.
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::PermissionDenied => Ok(()),
+        _ => Err(failed(format!(
+            "the new binary was not installed; does main() call \
+             self_update::elevate::handle_elevated_install() before anything else? ({} exited \
+             with {})",
+            tool.display(),
+            status
+        ))),
+    }
+}
+
+/// The privileged half: copy `staged` beside `dest`, check the copy's SHA-256 against `sha256`,
+/// carry over `dest`'s owner and mode (a new install gets `0755`), and rename it into place.
+/// A mismatch is [`Error::ChecksumMismatch`] and leaves `dest` untouched.
+fn install_verified(staged: &Path, dest: &Path, sha256: &str) -> Result<()> {
+    let mut copy = tempfile::Builder::new()
+        .prefix(".self_update-elevated")
+        .tempfile_in(crate::update::install_parent(dest))?;
+    io::copy(&mut fs::File::open(staged)?, copy.as_file_mut())?;
+    // Hash the copy, not `staged`: only the copy is out of the unprivileged user's reach.
+    let computed = sha256_file_hex(copy.path())?;
+    if !computed.eq_ignore_ascii_case(sha256.trim()) {
+        return Err(Error::ChecksumMismatch {
+            expected: sha256.trim().to_ascii_lowercase(),
+            computed,
+        });
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+        let mode = match fs::metadata(dest) {
+            Ok(meta) => {
+                // `chown` clears setuid/setgid bits, so the owner goes first.
+                let staged = copy.as_file().metadata()?;
+                if (staged.uid(), staged.gid()) != (meta.uid(), meta.gid()) {
+                    std::os::unix::fs::chown(copy.path(), Some(meta.uid()), Some(meta.gid()))?;
+                }
+                meta.mode() & 0o7777
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0o755,
+            Err(e) => return Err(e.into()),
+        };
+        fs::set_permissions(copy.path(), fs::Permissions::from_mode(mode))?;
+    }
+    copy.persist(dest).map_err(|e| Error::Io(e.error))?;
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn auto_prefers_pkexec_only_without_a_terminal() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["sudo", "pkexec"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let search_path = dir.path().as_os_str();
+
+        assert_eq!(
+            Elevator::Auto.find(true, search_path),
+            Some(dir.path().join("sudo"))
+        );
+        assert_eq!(
+            Elevator::Auto.find(false, search_path),
+            Some(dir.path().join("pkexec"))
+        );
+        assert_eq!(Elevator::Doas.find(true, search_path), None);
+    }
+
+    #[test]
+    fn install_verified_replaces_keeping_the_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let staged = dir.path().join("staged");
+        fs::write(&staged, "new").unwrap();
+        let dest = dir.path().join("app");
+        fs::write(&dest, "old").unwrap();
+        fs::set_permissions(&dest, fs::Permissions::from_mode(0o750)).unwrap();
+
+        install_verified(&staged, &dest, &sha256_file_hex(&staged).unwrap()).unwrap();
+
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+        assert_eq!(
+            fs::metadata(&dest).unwrap().permissions().mode() & 0o7777,
+            0o750
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn install_verified_rejects_a_changed_staged_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let staged = dir.path().join("staged");
+        fs::write(&staged, "new").unwrap();
+        let expected = sha256_file_hex(&staged).unwrap();
+        fs::write(&staged, "tampered").unwrap();
+        let dest = dir.path().join("app");
+        fs::write(&dest, "old").unwrap();
+
+        match install_verified(&staged, &dest, &expected).unwrap_err() {
+            Error::ChecksumMismatch { expected: e, .. } => assert_eq!(e, expected),
+            other => panic!("expected Error::ChecksumMismatch, got {:?}", other),
+        }
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "old");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    // `sh` stands in for the elevation tool, running `script` in place of the re-invoked
+    // executable with the same arguments (`$1` is `ELEVATED_INSTALL_ARG`).
+    fn run_with_script(script: &str) -> (tempfile::TempDir, Result<()>) {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("exe.sh");
+        fs::write(&exe, script).unwrap();
+        let new_exe = dir.path().join("new");
+        fs::write(&new_exe, "new").unwrap();
+        let dest = dir.path().join("app");
+        fs::write(&dest, "old").unwrap();
+        let res = run_elevated(Path::new("/bin/sh"), &exe, &new_exe, &dest, &dest);
+        (dir, res)
+    }
+
+    #[test]
+    fn run_elevated_passes_the_staged_file_destination_and_hash() {
+        let (dir, res) = run_with_script(
+            "[ \"$1\" = --self-update-elevated-install ] && [ -n \"$4\" ] && cp \"$2\" \"$3\"\n",
+        );
+        res.unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join("app")).unwrap(), "new");
+    }
+
+    #[test]
+    fn run_elevated_fails_when_the_tool_fails_or_installs_nothing() {
+        for script in ["exit 1\n", "exit 0\n"] {
+            let (dir, res) = run_with_script(script);
+            match res.unwrap_err() {
+                Error::ElevationFailed { path, .. } => assert_eq!(path, dir.path().join("app")),
+                other => panic!("expected Error::ElevationFailed, got {:?}", other),
+            }
+        }
+    }
+}
@@ -88,6 +88,54 @@ pub enum Error {
         /// The URL whose response was this status.
         url: String,
     },
+    /// A request was rejected by the host's rate limit: HTTP 429, or a 403 whose rate-limit headers
+    /// show an exhausted quota or carry `Retry-After` (GitHub).
+    ///
+    /// Returned once the retry budget is spent, or straight away when the server asks for a longer
+    /// wait than `max_rate_limit_wait` allows. The remaining fields mirror the response's
+    /// [`RateLimit`](crate::RateLimit) headers and are `None` when the host did not send them.
+    #[non_exhaustive]
+    RateLimited {
+        /// The HTTP status code (429 or 403).
+        status: u16,
+        /// The URL whose response was rate limited.
+        url: String,
+        /// When the host's rate-limit window resets.
+        reset_at: Option<std::time::SystemTime>,
+        /// How long the host asked the client to wait (`Retry-After`).
+        retry_after: Option<std::time::Duration>,
+        /// The request quota for the current window.
+        limit: Option<u64>,
+        /// Requests left in the current window.
+        remaining: Option<u64>,
+    },
+    /// A request completed with a redirect (HTTP 301, 302, 303, 307 or 308) carrying a `Location`.
+    ///
+    /// The crate follows these itself under the configured
+    /// [`RedirectPolicy`](crate::RedirectPolicy), so this only reaches a caller that issues
+    /// requests through an [`HttpClient`](crate::http_client::HttpClient) directly. `location` is
+    /// the header value exactly as sent (it may carry a presigned signature); `Display` redacts it.
+    #[non_exhaustive]
+    Redirect {
+        /// The HTTP status code.
+        status: u16,
+        /// The URL whose response was this redirect.
+        url: String,
+        /// The `Location` header value.
+        location: String,
+    },
+    /// A redirect was not followed because the [`RedirectPolicy`](crate::RedirectPolicy) refused
+    /// it: too many hops, a host outside the allowed set, an `https` to `http` downgrade, or
+    /// redirects disabled.
+    #[non_exhaustive]
+    RedirectRejected {
+        /// The URL whose response was the refused redirect.
+        url: String,
+        /// The URL the redirect pointed at.
+        location: String,
+        /// Why the redirect was refused.
+        reason: String,
+    },
     /// No release (or no release asset matching the requested target) was found.
     ///
     /// This is the clean negative outcome of a release lookup: the remote listing had no release,
@@ -131,12 +179,124 @@ pub enum Error {
     /// (`check_install_path_writable(true)`), which probes before any download, or by the install
     /// step itself when the replace/move fails with a permission error. `path` is the configured
     /// `bin_install_path`. Re-run with elevated privileges, or choose a user-writable
-    /// `bin_install_path`; the crate never escalates privileges itself.
+    /// `bin_install_path`; the crate escalates privileges itself only when asked to with the
+    /// `elevate` setter (`elevate` feature).
     #[non_exhaustive]
     InstallPathNotWritable {
         /// The install path (`bin_install_path`) that could not be written.
         path: std::path::PathBuf,
     },
+    /// With `preserve_metadata(true)`, an attribute of the installed binary could not be carried
+    /// over to its replacement (`preserve-metadata`); nothing has been replaced.
+    ///
+    /// `attribute` names what was being copied (`"owner"`, `"mode"`, or `"xattr <name>"`, e.g.
+    /// `"xattr security.capability"`), `path` is the installed binary, and the underlying IO
+    /// error is surfaced via [`std::error::Error::source`]. Changing the owner or setting
+    /// `security.*` attributes usually needs privileges (root, or `CAP_CHOWN` / `CAP_SETFCAP`).
+    #[cfg(feature = "preserve-metadata")]
+    #[non_exhaustive]
+    MetadataNotPreserved {
+        /// The installed binary whose metadata was being preserved.
+        path: std::path::PathBuf,
+        /// The attribute that could not be copied.
+        attribute: String,
+        /// The underlying IO error.
+        source: std::io::Error,
+    },
+    /// With `elevate(..)`, the replace refused for lack of permission could not be finished with
+    /// elevated privileges either (`elevate`).
+    ///
+    /// `reason` says why: no elevation tool was found, the tool failed or was cancelled (a wrong
+    /// password, a dismissed prompt, or the privileged step's own error, printed to stderr), or
+    /// the re-invoked executable exited without installing anything because it does not call
+    /// `elevate::handle_elevated_install()`.
+    #[cfg(feature = "elevate")]
+    #[non_exhaustive]
+    ElevationFailed {
+        /// The install path (`bin_install_path`) that was to be replaced.
+        path: std::path::PathBuf,
+        /// Why the privileged install did not happen.
+        reason: String,
+    },
+    /// A staged update (`stage`) was stale, corrupt or tampered with, so
+    /// `ApplyStaged::apply` discarded it instead of installing it.
+    ///
+    /// `reason` says which check failed: the staged version is not newer than the running one,
+    /// it was staged for another install path, the installed binary changed since it was staged,
+    /// the staged binary no longer matches its recorded SHA-256, the manifest is unreadable, or,
+    /// with verifying keys given to `ApplyStaged`, the staged archive's signature does not verify.
+    /// The running binary is untouched.
+    #[cfg(feature = "stage")]
+    #[non_exhaustive]
+    StagedUpdateRejected {
+        /// The staging directory.
+        dir: std::path::PathBuf,
+        /// Why the staged update was rejected.
+        reason: String,
+    },
+    /// The opt-in package-manager guard (`check_package_manager(true)`) found the install path
+    /// owned by a package manager, so self-updating it would corrupt that manager's records.
+    ///
+    /// `manager` names it (`"dpkg"`, `"rpm"`, `"Nix"`, `"Homebrew"`, `"cargo install"`) and
+    /// `command` is the command that updates the binary through it instead. Nothing was
+    /// downloaded.
+    #[non_exhaustive]
+    PackageManaged {
+        /// The install path found to be package-managed.
+        path: std::path::PathBuf,
+        /// The package manager owning it.
+        manager: &'static str,
+        /// The command that updates it through the package manager.
+        command: String,
+    },
+    /// The opt-in free-space preflight (`check_free_space(true)`) found too little room for the
+    /// update on the filesystem holding `path`: the temp directory the asset is downloaded and
+    /// extracted into, or the directory it is installed into.
+    ///
+    /// `required` is the asset size scaled by the expansion factor (both needs added up when the
+    /// two directories share a filesystem); `available` is what the filesystem reported. Nothing
+    /// was written to `path`.
+    #[non_exhaustive]
+    InsufficientSpace {
+        /// The directory whose filesystem is short of space.
+        path: std::path::PathBuf,
+        /// The bytes the update needs there.
+        required: u64,
+        /// The bytes available there.
+        available: u64,
+    },
+    /// Unpacking an archive went over one of its [`ExtractLimits`](crate::ExtractLimits), as a
+    /// decompression bomb would: too many uncompressed bytes in total or in one entry, too many
+    /// entries, or too deep a path.
+    ///
+    /// `limit` names the limit (`"max_total_size"`, `"max_entry_size"`, `"max_entries"` or
+    /// `"max_path_depth"`) and `max` its configured value. What was unpacked before the limit was
+    /// hit is left in the destination directory.
+    #[non_exhaustive]
+    ArchiveLimitExceeded {
+        /// The limit exceeded.
+        limit: &'static str,
+        /// Its configured value.
+        max: u64,
+    },
+    /// Bundle mode (`bundle_root_in_archive`) was selected without a `bundle_install_path`, and
+    /// no ancestor of the running executable is a macOS `.app` bundle to default to.
+    ///
+    /// `exe` is the running executable's path. Set `bundle_install_path` explicitly.
+    #[non_exhaustive]
+    NoAppBundle {
+        /// The running executable, none of whose ancestors is a `.app` directory.
+        exe: std::path::PathBuf,
+    },
+    /// Two builder settings that cannot be combined were both set (e.g. an explicit
+    /// `bin_install_path` together with the bundle mode selected by `bundle_root_in_archive`).
+    #[non_exhaustive]
+    ConflictingConfig {
+        /// The setting that was rejected.
+        field: &'static str,
+        /// The setting it conflicts with.
+        conflicts_with: &'static str,
+    },
     /// A bare release listing ([`ReleaseList::fetch`](crate::backends)) carries no current version,
     /// so [`Releases::is_update_available`](crate::update::Releases::is_update_available) has nothing
     /// to compare its releases against.
@@ -172,6 +332,17 @@ pub enum Error {
         /// The underlying certificate-parse / client-build error.
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    /// A proxy supplied via a `proxy` setter could not be applied: a malformed or unsupported proxy
+    /// URL, or credentials the client cannot represent.
+    ///
+    /// Produced from `build()` (via a backend builder's `proxy`) or from a
+    /// [`Download`](crate::Download) with a `proxy`. Wraps the underlying error, surfaced via
+    /// [`std::error::Error::source`].
+    #[non_exhaustive]
+    InvalidProxy {
+        /// The underlying proxy-parse error.
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
     /// A progress-bar template string was not valid (`progress-bar`).
     ///
     /// Wraps the underlying `indicatif` template error, surfaced via [`std::error::Error::source`].
@@ -218,7 +389,8 @@ pub enum Error {
     /// concerns the container format; without this, a gzip asset would install its still-compressed
     /// bytes as the binary.
     CompressionNotEnabled(String),
-    /// Used when the repository archive does not contain any signatures to verify with.
+    /// Used when the downloaded file carries no signature to verify with: its format cannot embed
+    /// one (only `.tar.gz` and `.zip` can) and no detached `<file>.sig` was found next to it.
     #[cfg(feature = "signatures")]
     NoSignatures(crate::ArchiveKind),
     /// A wrapper over a signature-verification error (`signatures`).
@@ -228,6 +400,14 @@ pub enum Error {
     /// to inspect the underlying error.
     #[cfg(feature = "signatures")]
     Signature(Box<dyn std::error::Error + Send + Sync>),
+    /// Signature verification is configured, but no verifying key is currently trusted
+    /// (`signatures`).
+    ///
+    /// Every configured key is revoked or outside its validity window (see
+    /// [`keys`](crate::keys)), so the download cannot be authenticated. The update is refused
+    /// rather than run without signature verification.
+    #[cfg(feature = "signatures")]
+    NoTrustedKeys,
     /// The release asset name contains path traversal components or separators.
     ///
     /// Returned when the server-supplied asset name is empty, is `.` or `..`, contains a `/` or
@@ -264,23 +444,30 @@ pub enum Error {
 
 impl Error {
     /// The HTTP status code if this error came from a completed non-2xx response
-    /// (`NotFound` => 404, `Unauthorized`/`HttpStatus` => their code); `None` otherwise.
+    /// (`NotFound` => 404, `Unauthorized`/`RateLimited`/`HttpStatus`/`Redirect` => their code);
+    /// `None` otherwise.
     pub fn http_status(&self) -> Option<u16> {
         match self {
             Error::NotFound { .. } => Some(404),
             Error::Unauthorized { status, .. } => Some(*status),
+            Error::RateLimited { status, .. } => Some(*status),
             Error::HttpStatus { status, .. } => Some(*status),
+            Error::Redirect { status, .. } => Some(*status),
             _ => None,
         }
     }
 
     /// The URL of the request that failed, for the HTTP error variants
-    /// (`NotFound`/`Unauthorized`/`HttpStatus`); `None` otherwise.
+    /// (`NotFound`/`Unauthorized`/`RateLimited`/`HttpStatus`/`Redirect`/`RedirectRejected`); `None`
+    /// otherwise.
     pub fn url(&self) -> Option<&str> {
         match self {
             Error::NotFound { url } => Some(url.as_str()),
             Error::Unauthorized { url, .. } => Some(url.as_str()),
+            Error::RateLimited { url, .. } => Some(url.as_str()),
             Error::HttpStatus { url, .. } => Some(url.as_str()),
+            Error::Redirect { url, .. } => Some(url.as_str()),
+            Error::RedirectRejected { url, .. } => Some(url.as_str()),
             _ => None,
         }
     }
@@ -334,11 +521,42 @@ impl Error {
     }
 
     /// Construct the HTTP status error for a completed non-2xx response: `NotFound` for 404,
-    /// `Unauthorized` for 401/403, else `HttpStatus`.
+    /// `Unauthorized` for 401/403, `RateLimited` for 429, else `HttpStatus`.
     pub fn http_status_error(status: u16, url: impl Into<String>) -> Error {
         status_to_error(status, &url.into())
     }
 
+    /// Like [`http_status_error`](Error::http_status_error), but also reads the response headers so
+    /// a rate-limit rejection becomes [`RateLimited`](Error::RateLimited) with its reset time and
+    /// `Retry-After`. A custom [`HttpClient`](crate::http_client::HttpClient) should prefer this
+    /// when it has the headers, so the retry loop can honor the server's wait.
+    pub fn http_response_error(
+        status: u16,
+        url: impl Into<String>,
+        headers: &crate::http_client::HeaderMap,
+    ) -> Error {
+        response_to_error(status, &url.into(), headers)
+    }
+
+    /// The rate-limit headers carried by a [`RateLimited`](Error::RateLimited) error.
+    pub(crate) fn rate_limit(&self) -> Option<crate::RateLimit> {
+        match *self {
+            Error::RateLimited {
+                reset_at,
+                retry_after,
+                limit,
+                remaining,
+                ..
+            } => Some(crate::RateLimit {
+                limit,
+                remaining,
+                reset_at,
+                retry_after,
+            }),
+            _ => None,
+        }
+    }
+
     /// Construct a [`Transport`](Error::Transport) error wrapping the underlying
     /// connection/TLS/timeout failure, for a custom [`HttpClient`](crate::http_client::HttpClient) /
     /// [`AsyncHttpClient`](crate::http_client::AsyncHttpClient) whose request could not be
@@ -401,11 +619,54 @@ impl std::fmt::Display for Error {
                 "UnauthorizedError: request to {} was not authorized (HTTP {})",
                 url, status
             ),
+            RateLimited {
+                status,
+                url,
+                reset_at,
+                retry_after,
+                ..
+            } => {
+                write!(
+                    f,
+                    "RateLimitedError: request to {} was rate limited (HTTP {})",
+                    url, status
+                )?;
+                let wait = retry_after.or_else(|| {
+                    reset_at.map(|at| {
+                        at.duration_since(std::time::SystemTime::now())
+                            .unwrap_or_default()
+                    })
+                });
+                match wait {
+                    Some(wait) => write!(f, "; retry in {}s", wait.as_secs()),
+                    None => Ok(()),
+                }
+            }
             HttpStatus { status, url } => write!(
                 f,
                 "HttpStatusError: request to {} failed with status {}",
                 url, status
             ),
+            Redirect {
+                status,
+                url,
+                location,
+            } => write!(
+                f,
+                "RedirectError: request to {} was redirected to {} (HTTP {})",
+                url,
+                redact_url(location),
+                status
+            ),
+            RedirectRejected {
+                url,
+                location,
+                reason,
+            } => write!(
+                f,
+                "RedirectError: redirect from {} to {} was not followed: {}",
+                url, location, reason
+            ),
             NoReleaseFound { target } => match target {
                 Some(target) => write!(
                     f,
@@ -425,6 +686,72 @@ impl std::fmt::Display for Error {
                  privileges or choose a user-writable bin_install_path",
                 path.display()
             ),
+            #[cfg(feature = "preserve-metadata")]
+            MetadataNotPreserved {
+                path,
+                attribute,
+                source,
+            } => write!(
+                f,
+                "MetadataNotPreservedError: could not preserve the {} of {} on its replacement: {}",
+                attribute,
+                path.display(),
+                source
+            ),
+            #[cfg(feature = "elevate")]
+            ElevationFailed { path, reason } => write!(
+                f,
+                "ElevationError: could not install {} with elevated privileges: {}",
+                path.display(),
+                reason
+            ),
+            #[cfg(feature = "stage")]
+            StagedUpdateRejected { dir, reason } => write!(
+                f,
+                "StagedUpdateError: discarded the update staged in {}: {}",
+                dir.display(),
+                reason
+            ),
+            PackageManaged {
+                path,
+                manager,
+                command,
+            } => write!(
+                f,
+                "PackageManagedError: {} is managed by {}; update it with `{}` instead",
+                path.display(),
+                manager,
+                command
+            ),
+            InsufficientSpace {
+                path,
+                required,
+                available,
+            } => write!(
+                f,
+                "InsufficientSpaceError: the update needs {} bytes in {} but only {} are available",
+                required,
+                path.display(),
+                available
+            ),
+            ArchiveLimitExceeded { limit, max } => write!(
+                f,
+                "ArchiveLimitError: the archive exceeds its {} limit of {}",
+                limit, max
+            ),
+            NoAppBundle { exe } => write!(
+                f,
+                "ConfigError: no `.app` ancestor of {}; set bundle_install_path explicitly",
+                exe.display()
+            ),
+            ConflictingConfig {
+                field,
+                conflicts_with,
+            } => write!(
+                f,
+                "ConfigError: `{}` cannot be combined with `{}`",
+                field, conflicts_with
+            ),
             NoCurrentVersion => write!(
                 f,
                 "ReleaseError: this Releases has no current_version to compare against; use \
@@ -437,6 +764,9 @@ impl std::fmt::Display for Error {
             InvalidCertificate { source } => {
                 write!(f, "ConfigError: invalid root certificate: {}", source)
             }
+            InvalidProxy { source } => {
+                write!(f, "ConfigError: invalid proxy: {}", source)
+            }
             #[cfg(feature = "progress-bar")]
             InvalidProgressStyle { source } => {
                 write!(f, "ConfigError: invalid progress bar template: {}", source)
@@ -460,12 +790,18 @@ impl std::fmt::Display for Error {
             #[cfg(feature = "signatures")]
             NoSignatures(kind) => write!(
                 f,
-                "SignatureError: signature verification is only implemented for `.tar.gz` and \
-                 `.zip` assets, not {} files",
+                "SignatureError: no signature found: only `.tar.gz` and `.zip` assets embed one, \
+                 and this {} file has no detached `.sig`",
                 kind
             ),
             #[cfg(feature = "signatures")]
             Signature(e) => write!(f, "SignatureError: {}", e),
+            #[cfg(feature = "signatures")]
+            NoTrustedKeys => write!(
+                f,
+                "SignatureError: no verifying key is currently trusted (all configured keys are \
+                 revoked or outside their validity window)"
+            ),
             InvalidAssetName { name } => {
                 write!(f, "InvalidAssetNameError: unsafe asset name: {:?}", name)
             }
@@ -497,8 +833,11 @@ impl std::error::Error for Error {
             Error::InvalidHeader { ref source } => &**source,
             Error::InvalidAuthToken { ref source } => &**source,
             Error::InvalidCertificate { ref source } => &**source,
+            Error::InvalidProxy { ref source } => &**source,
             #[cfg(feature = "progress-bar")]
             Error::InvalidProgressStyle { ref source } => &**source,
+            #[cfg(feature = "preserve-metadata")]
+            Error::MetadataNotPreserved { ref source, .. } => source,
             Error::Io(ref e) => e,
             Error::Json(ref e) => &**e,
             Error::Transport(ref e) => &**e,
@@ -607,7 +946,8 @@ impl From<time::error::ComponentRange> for Error {
 
 /// Map an HTTP status code and URL to the appropriate structured error variant.
 ///
-/// 404 -> `Error::NotFound`, 401/403 -> `Error::Unauthorized`, else -> `Error::HttpStatus`.
+/// 404 -> `Error::NotFound`, 401/403 -> `Error::Unauthorized`, 429 -> `Error::RateLimited`, else ->
+/// `Error::HttpStatus`.
 ///
 /// The URL is stored redacted (see [`redact_url`]) so an s3 presigned request URL does not carry a
 /// live `X-Amz-Signature` or the `X-Amz-Credential` access-key id into error messages, logs, or the
@@ -617,10 +957,53 @@ pub(crate) fn status_to_error(status: u16, url: &str) -> Error {
     match status {
         404 => Error::NotFound { url },
         401 | 403 => Error::Unauthorized { status, url },
+        429 => Error::RateLimited {
+            status,
+            url,
+            reset_at: None,
+            retry_after: None,
+            limit: None,
+            remaining: None,
+        },
         _ => Error::HttpStatus { status, url },
     }
 }
 
+/// [`status_to_error`] for a response whose headers are available: a 429, or a 403 whose
+/// rate-limit headers mark it as a rate-limit rejection, becomes `Error::RateLimited` carrying them,
+/// and a redirect with a `Location` becomes `Error::Redirect` for the crate's redirect loop to
+/// follow.
+pub(crate) fn response_to_error(
+    status: u16,
+    url: &str,
+    headers: &crate::http_client::HeaderMap,
+) -> Error {
+    if matches!(status, 301 | 302 | 303 | 307 | 308)
+        && let Some(location) = headers
+            .get(crate::http_client::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+    {
+        return Error::Redirect {
+            status,
+            url: redact_url(url),
+            location: location.to_string(),
+        };
+    }
+    let rate_limit = crate::RateLimit::from_headers(headers);
+    if !crate::rate_limit::is_rate_limited(status, rate_limit.as_ref()) {
+        return status_to_error(status, url);
+    }
+    let rate_limit = rate_limit.unwrap_or_default();
+    Error::RateLimited {
+        status,
+        url: redact_url(url),
+        reset_at: rate_limit.reset_at,
+        retry_after: rate_limit.retry_after,
+        limit: rate_limit.limit,
+        remaining: rate_limit.remaining,
+    }
+}
+
 /// Redact sensitive query-parameter values from a URL for display/logging. Blanks the value of any
 /// `X-Amz-Signature` (a live capability until expiry) and `X-Amz-Credential` (the access-key id) so
 /// a presigned s3 URL is safe to surface. Non-s3 URLs are returned unchanged.
@@ -854,6 +1237,19 @@ mod tests {
         );
     }
 
+    // `NoTrustedKeys` (every configured key revoked or expired) carries the `SignatureError:`
+    // prefix and has no source.
+    #[cfg(feature = "signatures")]
+    #[test]
+    fn no_trusted_keys_displays_with_signature_prefix() {
+        let err = Error::NoTrustedKeys;
+        assert!(
+            err.to_string()
+                .starts_with("SignatureError: no verifying key")
+        );
+        assert!(err.source().is_none());
+    }
+
     // Transport variant: opaque (boxed), source() derefs the box, Display prefix "TransportError:".
     // From<reqwest::Error> maps to Transport (reqwest feature).
     #[cfg(feature = "reqwest")]
@@ -1095,6 +1491,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn status_to_error_maps_429_to_rate_limited() {
+        let e = super::status_to_error(429, "https://example.com/r");
+        assert!(
+            matches!(
+                e,
+                Error::RateLimited {
+                    status: 429,
+                    reset_at: None,
+                    ..
+                }
+            ),
+            "status 429 must map to Error::RateLimited, got {:?}",
+            e
+        );
+        assert_eq!(e.http_status(), Some(429));
+        assert_eq!(e.url(), Some("https://example.com/r"));
+    }
+
+    // A 403 is only a rate limit when its headers say so; otherwise it stays `Unauthorized`.
+    #[test]
+    fn response_to_error_separates_rate_limit_403_from_auth_403() {
+        let mut headers = crate::http_client::HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("retry-after", "30".parse().unwrap());
+        let e = super::response_to_error(403, "https://api.github.com/r", &headers);
+        assert!(
+            matches!(
+                e,
+                Error::RateLimited { status: 403, remaining: Some(0), retry_after: Some(d), .. }
+                    if d.as_secs() == 30
+            ),
+            "an exhausted-quota 403 must map to Error::RateLimited, got {:?}",
+            e
+        );
+        assert_eq!(
+            e.to_string(),
+            "RateLimitedError: request to https://api.github.com/r was rate limited (HTTP 403); \
+             retry in 30s"
+        );
+        let plain = super::response_to_error(
+            403,
+            "https://api.github.com/r",
+            &crate::http_client::HeaderMap::new(),
+        );
+        assert!(matches!(plain, Error::Unauthorized { status: 403, .. }));
+    }
+
+    // A redirect is only `Redirect` when it says where to go; the raw `Location` is kept for the
+    // redirect loop and redacted on display.
+    #[test]
+    fn response_to_error_maps_a_located_redirect_to_redirect() {
+        let mut headers = crate::http_client::HeaderMap::new();
+        headers.insert(
+            "location",
+            "https://bucket.s3.amazonaws.com/a?X-Amz-Signature=sig"
+                .parse()
+                .unwrap(),
+        );
+        let e = super::response_to_error(302, "https://api.github.com/asset", &headers);
+        assert!(
+            matches!(e, Error::Redirect { status: 302, ref location, .. } if location.ends_with("=sig")),
+            "a 302 with a Location must map to Error::Redirect, got {:?}",
+            e
+        );
+        assert_eq!(e.http_status(), Some(302));
+        assert_eq!(
+            e.to_string(),
+            "RedirectError: request to https://api.github.com/asset was redirected to \
+             https://bucket.s3.amazonaws.com/a?X-Amz-Signature=REDACTED (HTTP 302)"
+        );
+        let e = super::response_to_error(
+            302,
+            "https://api.github.com/asset",
+            &crate::http_client::HeaderMap::new(),
+        );
+        assert!(matches!(e, Error::HttpStatus { status: 302, .. }));
+        let e = super::response_to_error(304, "https://api.github.com/asset", &headers);
+        assert!(matches!(e, Error::HttpStatus { status: 304, .. }));
+    }
+
     // --- New structured variants (ChecksumMismatch, Aborted) ----------------------------------
 
     // ChecksumMismatch: exact Display string, no http_status(), no url().
@@ -1253,6 +1730,123 @@ mod tests {
         assert_eq!(err.url(), None);
     }
 
+    // `ConflictingConfig` Display names both settings.
+    #[test]
+    fn conflicting_config_display_names_both_settings() {
+        let err = Error::ConflictingConfig {
+            field: "bin_install_path",
+            conflicts_with: "bundle_root_in_archive",
+        };
+        assert_eq!(
+            err.to_string(),
+            "ConfigError: `bin_install_path` cannot be combined with `bundle_root_in_archive`"
+        );
+        assert!(err.source().is_none());
+    }
+
+    // `MetadataNotPreserved` names the attribute and the binary, and exposes the IO error.
+    #[cfg(feature = "preserve-metadata")]
+    #[test]
+    fn metadata_not_preserved_display_and_source() {
+        let err = Error::MetadataNotPreserved {
+            path: std::path::PathBuf::from("/usr/local/bin/app"),
+            attribute: "xattr security.capability".to_string(),
+            source: std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+        };
+        let shown = err.to_string();
+        assert!(
+            shown.starts_with(
+                "MetadataNotPreservedError: could not preserve the xattr security.capability of \
+                 /usr/local/bin/app"
+            ),
+            "got: {shown}"
+        );
+        let source = err
+            .source()
+            .expect("MetadataNotPreserved must expose its IO error");
+        assert_eq!(
+            source.downcast_ref::<std::io::Error>().map(|e| e.kind()),
+            Some(std::io::ErrorKind::PermissionDenied)
+        );
+    }
+
+    // `PackageManaged` names the path, the manager and the command to use instead.
+    #[test]
+    fn package_managed_display_suggests_the_package_manager_command() {
+        let err = Error::PackageManaged {
+            path: std::path::PathBuf::from("/usr/bin/app"),
+            manager: "dpkg",
+            command: "sudo apt install --only-upgrade app".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "PackageManagedError: /usr/bin/app is managed by dpkg; update it with \
+             `sudo apt install --only-upgrade app` instead"
+        );
+        assert!(err.source().is_none());
+    }
+
+    // `InsufficientSpace` names the directory and both byte counts.
+    #[test]
+    fn insufficient_space_display_names_the_directory_and_the_shortfall() {
+        let err = Error::InsufficientSpace {
+            path: std::path::PathBuf::from("/tmp"),
+            required: 3000,
+            available: 1024,
+        };
+        assert_eq!(
+            err.to_string(),
+            "InsufficientSpaceError: the update needs 3000 bytes in /tmp but only 1024 are available"
+        );
+        assert!(err.source().is_none());
+    }
+
+    // `ArchiveLimitExceeded` names the limit and its configured value.
+    #[test]
+    fn archive_limit_exceeded_display_names_the_limit() {
+        let err = Error::ArchiveLimitExceeded {
+            limit: "max_entries",
+            max: 10_000,
+        };
+        assert_eq!(
+            err.to_string(),
+            "ArchiveLimitError: the archive exceeds its max_entries limit of 10000"
+        );
+        assert!(err.source().is_none());
+    }
+
+    // `ElevationFailed` names the install path and the reason; it carries no source.
+    #[cfg(feature = "elevate")]
+    #[test]
+    fn elevation_failed_display_names_the_path_and_reason() {
+        let err = Error::ElevationFailed {
+            path: std::path::PathBuf::from("/usr/local/bin/app"),
+            reason: "none of sudo, doas, pkexec found on PATH".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "ElevationError: could not install /usr/local/bin/app with elevated privileges: \
+             none of sudo, doas, pkexec found on PATH"
+        );
+        assert!(err.source().is_none());
+    }
+
+    // `StagedUpdateRejected` names the staging directory and the failed check.
+    #[cfg(feature = "stage")]
+    #[test]
+    fn staged_update_rejected_display_names_the_dir_and_reason() {
+        let err = Error::StagedUpdateRejected {
+            dir: std::path::PathBuf::from("/var/lib/app/update"),
+            reason: "staged version 1.0.0 is not newer than the running 1.0.0".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "StagedUpdateError: discarded the update staged in /var/lib/app/update: staged \
+             version 1.0.0 is not newer than the running 1.0.0"
+        );
+        assert!(err.source().is_none());
+    }
+
     // `InstallPathNotWritable` Display names the path and suggests elevated privileges or a
     // user-writable bin_install_path. It carries no source and exposes no http_status()/url().
     #[test]
@@ -1687,6 +2281,19 @@ mod tests {
                 "ReleaseError:",
             ),
             (Error::MissingField { field: "f" }, "ConfigError:"),
+            (
+                Error::NoAppBundle {
+                    exe: std::path::PathBuf::from("/opt/app/bin/app"),
+                },
+                "ConfigError:",
+            ),
+            (
+                Error::ConflictingConfig {
+                    field: "a",
+                    conflicts_with: "b",
+                },
+                "ConfigError:",
+            ),
             (
                 Error::InvalidHeader {
                     source: Box::new(MessageError("x".into())),
@@ -1699,6 +2306,16 @@ mod tests {
                 },
                 "ConfigError:",
             ),
+            (
+                Error::InvalidProxy {
+                    source: Box::new(MessageError("x".into())),
+                },
+                "ConfigError:",
+            ),
+            (
+                Error::http_status_error(429, "https://example.com/r"),
+                "RateLimitedError:",
+            ),
         ];
         for (err, prefix) in cases {
             let shown = err.to_string();
@@ -0,0 +1,328 @@
+/*!
+Limits on what [`Extract`](crate::Extract) unpacks, against decompression bombs.
+
+[`Download::max_download_size`](crate::Download::max_download_size) bounds what is fetched, but a
+few kilobytes of `.tar.xz` can inflate to hundreds of gigabytes, or unpack into millions of
+entries. [`ExtractLimits`] bounds the unpacked side: total uncompressed bytes, bytes per entry,
+number of entries and path depth. They are enforced while the archive is streamed, for tar, zip
+and plain compressed assets alike, and the first one exceeded fails extraction with
+[`Error::ArchiveLimitExceeded`].
+*/
+
+use std::cell::Cell;
+use std::io;
+use std::path::{Component, Path};
+use std::rc::Rc;
+
+use crate::errors::*;
+
+/// Limits enforced by [`Extract`](crate::Extract) while it streams an archive, against
+/// decompression bombs. Every limit is off by default; the first one exceeded fails extraction
+/// with [`Error::ArchiveLimitExceeded`].
+///
+/// ```
+/// let limits = self_update::ExtractLimits::new()
+///     .max_total_size(512 * 1024 * 1024)
+///     .max_entries(10_000)
+///     .max_path_depth(16);
+/// self_update::Extract::from_source("app.tar.xz").limits(limits);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExtractLimits {
+    max_total_size: Option<u64>,
+    max_entry_size: Option<u64>,
+    max_entries: Option<u64>,
+    max_path_depth: Option<usize>,
+}
+
+impl ExtractLimits {
+    /// No limits; add them with the setters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cap the uncompressed bytes read out of the archive: the decompressed stream of a tar or
+    /// plain asset (tar headers included), the sum of the entries of a zip.
+    pub fn max_total_size(mut self, bytes: u64) -> Self {
+        self.max_total_size = Some(bytes);
+        self
+    }
+
+    /// Cap the uncompressed size of any one entry.
+    pub fn max_entry_size(mut self, bytes: u64) -> Self {
+        self.max_entry_size = Some(bytes);
+        self
+    }
+
+    /// Cap the number of entries, directories and links included. A plain compressed asset is
+    /// one entry.
+    pub fn max_entries(mut self, entries: u64) -> Self {
+        self.max_entries = Some(entries);
+        self
+    }
+
+    /// Cap the number of components in an entry's path: `bin/app` has a depth of 2.
+    pub fn max_path_depth(mut self, depth: usize) -> Self {
+        self.max_path_depth = Some(depth);
+        self
+    }
+}
+
+/// Which limit tripped inside a reader or writer, kept beside the IO error it raised: the tar,
+/// zip and xz layers wrap that error in their own, so the caller reports this instead.
+type Tripwire = Rc<Cell<Option<(&'static str, u64)>>>;
+
+/// The running state of one extraction against its [`ExtractLimits`].
+pub(crate) struct Budget {
+    limits: ExtractLimits,
+    entries: u64,
+    /// Bytes streamed through [`copy_entry`](Self::copy_entry) so far.
+    total: u64,
+    tripped: Tripwire,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: ExtractLimits) -> Self {
+        Self {
+            limits,
+            entries: 0,
+            total: 0,
+            tripped: Tripwire::default(),
+        }
+    }
+
+    /// Count an entry at `path` declaring `size` bytes, checking the entry count, the path depth,
+    /// the declared size and, for entries copied with `copy_entry`, the running total.
+    pub(crate) fn entry(&mut self, path: &Path, size: u64) -> Result<()> {
+        self.entries += 1;
+        exceeds("max_entries", self.limits.max_entries, self.entries)?;
+        let depth = path
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .count();
+        exceeds(
+            "max_path_depth",
+            self.limits.max_path_depth.map(|d| d as u64),
+            depth as u64,
+        )?;
+        exceeds("max_entry_size", self.limits.max_entry_size, size)?;
+        exceeds(
+            "max_total_size",
+            self.limits.max_total_size,
+            self.total.saturating_add(size),
+        )
+    }
+
+    /// Wrap the decompressed stream of a tar, capped at `max_total_size`.
+    #[cfg(feature = "archive-tar")]
+    pub(crate) fn stream<R: io::Read>(&self, inner: R) -> Capped<R> {
+        Capped::new(
+            inner,
+            "max_total_size",
+            self.limits.max_total_size,
+            &self.tripped,
+        )
+    }
+
+    /// Wrap the sink an xz stream is decoded into, capped at `max_total_size`.
+    #[cfg(feature = "compression-tar-xz")]
+    pub(crate) fn sink<W: io::Write>(&self, inner: W) -> Capped<W> {
+        Capped::new(
+            inner,
+            "max_total_size",
+            self.limits.max_total_size,
+            &self.tripped,
+        )
+    }
+
+    /// Copy one zip entry, whose declared size may understate it, within the per-entry cap and
+    /// what is left of the total.
+    pub(crate) fn copy_entry(
+        &mut self,
+        entry: &mut impl io::Read,
+        out: &mut impl io::Write,
+    ) -> Result<()> {
+        let left = self
+            .limits
+            .max_total_size
+            .map(|max| max - self.total.min(max));
+        let (limit, max) = match (self.limits.max_entry_size, left) {
+            (Some(entry_max), Some(left)) if left < entry_max => ("max_total_size", Some(left)),
+            (Some(entry_max), _) => ("max_entry_size", Some(entry_max)),
+            (None, left) => ("max_total_size", left),
+        };
+        let mut capped = Capped::new(entry, limit, max, &self.tripped);
+        let copied = self.check(io::copy(&mut capped, out).map_err(Error::from))?;
+        self.total += copied;
+        Ok(())
+    }
+
+    /// `result`, unless a capped reader or writer tripped a limit on the way: that is the error
+    /// to report, whatever the archive layer turned it into.
+    pub(crate) fn check<T>(&self, result: Result<T>) -> Result<T> {
+        match (result, self.tripped.get()) {
+            (Err(_), Some((limit, max))) => Err(Error::ArchiveLimitExceeded { limit, max }),
+            (result, _) => result,
+        }
+    }
+}
+
+fn exceeds(limit: &'static str, max: Option<u64>, value: u64) -> Result<()> {
+    match max {
+        Some(max) if value > max => Err(Error::ArchiveLimitExceeded { limit, max }),
+        _ => Ok(()),
+    }
+}
+
+/// A reader or writer that fails once more than `max` bytes have passed through it.
+pub(crate) struct Capped<T> {
+    inner: T,
+    left: Option<u64>,
+    limit: &'static str,
+    max: u64,
+    tripped: Tripwire,
+}
+
+impl<T> Capped<T> {
+    fn new(inner: T, limit: &'static str, max: Option<u64>, tripped: &Tripwire) -> Self {
+        Self {
+            inner,
+            left: max,
+            limit,
+            max: max.unwrap_or(u64::MAX),
+            tripped: tripped.clone(),
+        }
+    }
+
+    /// Account for `n` more bytes, tripping once they exceed the cap.
+    fn take(&mut self, n: usize) -> io::Result<()> {
+        let Some(left) = self.left else {
+            return Ok(());
+        };
+        match left.checked_sub(n as u64) {
+            Some(left) => {
+                self.left = Some(left);
+                Ok(())
+            }
+            None => {
+                self.tripped.set(Some((self.limit, self.max)));
+                Err(io::Error::other(format!(
+                    "archive exceeds its {} limit of {}",
+                    self.limit, self.max
+                )))
+            }
+        }
+    }
+
+    /// At most one byte past the cap, so crossing it is seen without reading much further.
+    fn window(&self, len: usize) -> usize {
+        match self.left {
+            Some(left) => len.min(usize::try_from(left.saturating_add(1)).unwrap_or(usize::MAX)),
+            None => len,
+        }
+    }
+}
+
+impl<R: io::Read> io::Read for Capped<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let window = self.window(buf.len());
+        let n = self.inner.read(&mut buf[..window])?;
+        self.take(n)?;
+        Ok(n)
+    }
+}
+
+impl<W: io::Write> io::Write for Capped<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let window = self.window(buf.len());
+        self.take(window)?;
+        let n = self.inner.write(&buf[..window])?;
+        // Give back what the inner writer did not take.
+        if let Some(left) = &mut self.left {
+            *left += (window - n) as u64;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn entry_checks_count_depth_and_size() {
+        let limits = ExtractLimits::new()
+            .max_entries(2)
+            .max_path_depth(2)
+            .max_entry_size(10);
+        let mut budget = Budget::new(limits);
+        budget.entry(Path::new("./bin/app"), 10).unwrap();
+
+        for (path, size, limit) in [
+            ("a/b/c", 1, "max_path_depth"),
+            ("big", 11, "max_entry_size"),
+        ] {
+            match Budget::new(limits)
+                .entry(Path::new(path), size)
+                .unwrap_err()
+            {
+                Error::ArchiveLimitExceeded { limit: l, .. } => assert_eq!(l, limit),
+                other => panic!("expected Error::ArchiveLimitExceeded, got {:?}", other),
+            }
+        }
+        budget.entry(Path::new("lib"), 0).unwrap();
+        match budget.entry(Path::new("more"), 0).unwrap_err() {
+            Error::ArchiveLimitExceeded { limit, max } => {
+                assert_eq!((limit, max), ("max_entries", 2));
+            }
+            other => panic!("expected Error::ArchiveLimitExceeded, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn capped_streams_trip_past_the_limit_and_are_reported() {
+        let budget = Budget::new(ExtractLimits::new().max_total_size(8));
+        let capped = |bytes| Capped::new(bytes, "max_total_size", Some(8), &budget.tripped);
+        let mut out = Vec::new();
+        capped(&[0u8; 8][..]).read_to_end(&mut out).unwrap();
+        assert_eq!(out.len(), 8);
+        assert!(budget.tripped.get().is_none());
+
+        let err = capped(&[0u8; 9][..]).read_to_end(&mut out).unwrap_err();
+        match budget.check::<()>(Err(err.into())).unwrap_err() {
+            Error::ArchiveLimitExceeded { limit, max } => {
+                assert_eq!((limit, max), ("max_total_size", 8));
+            }
+            other => panic!("expected Error::ArchiveLimitExceeded, got {:?}", other),
+        }
+
+        let mut sink = Capped::new(Vec::new(), "max_total_size", Some(8), &Tripwire::default());
+        sink.write_all(&[0u8; 8]).unwrap();
+        assert!(sink.write_all(&[0u8; 1]).is_err());
+    }
+
+    #[test]
+    fn copy_entry_caps_each_entry_and_the_running_total() {
+        let mut budget = Budget::new(ExtractLimits::new().max_entry_size(6).max_total_size(10));
+        budget
+            .copy_entry(&mut &[0u8; 6][..], &mut Vec::new())
+            .unwrap();
+
+        // 4 bytes left of the total, below the per-entry cap.
+        match budget
+            .copy_entry(&mut &[0u8; 5][..], &mut Vec::new())
+            .unwrap_err()
+        {
+            Error::ArchiveLimitExceeded { limit, max } => {
+                assert_eq!((limit, max), ("max_total_size", 4));
+            }
+            other => panic!("expected Error::ArchiveLimitExceeded, got {:?}", other),
+        }
+    }
+}
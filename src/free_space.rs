@@ -0,0 +1,172 @@
+/*!
+The free-space preflight (`check_free_space`).
+
+Before downloading, the asset's size (from the release listing, or from the download's
+`Content-Length` when the listing has none) is checked against the space available on the two
+filesystems the update writes to:
+
+- the temp directory, which holds the download and what is extracted from it:
+  `size + size * expansion_factor`,
+- the install directory, where the new binary is staged beside the one it replaces:
+  `size * expansion_factor`.
+
+When both are on one filesystem the two amounts are added up. A shortfall is
+[`Error::InsufficientSpace`] naming the directory, before anything is written to it.
+*/
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::errors::*;
+
+/// The default ratio of what an asset unpacks to over its own size.
+pub(crate) const DEFAULT_EXPANSION_FACTOR: f64 = 3.0;
+
+/// The directories an update writes to, checked against an asset size once it is known.
+#[derive(Clone, Debug)]
+pub(crate) struct SpaceCheck {
+    pub(crate) temp_dir: PathBuf,
+    pub(crate) install_dir: PathBuf,
+    pub(crate) expansion_factor: f64,
+}
+
+impl SpaceCheck {
+    /// Fail with [`Error::InsufficientSpace`] when an asset of `asset_size` bytes will not fit.
+    pub(crate) fn check(&self, asset_size: u64) -> Result<()> {
+        let unpacked = (asset_size as f64 * self.expansion_factor).ceil() as u64;
+        let temp_needed = asset_size.saturating_add(unpacked);
+        if same_filesystem(&self.temp_dir, &self.install_dir) {
+            return require(&self.install_dir, temp_needed.saturating_add(unpacked));
+        }
+        require(&self.temp_dir, temp_needed)?;
+        require(&self.install_dir, unpacked)
+    }
+}
+
+/// Fail when `dir`'s filesystem has less than `required` bytes available. A filesystem whose
+/// free space cannot be read is let through: the write itself reports a real shortage.
+fn require(dir: &Path, required: u64) -> Result<()> {
+    match available_space(dir) {
+        Ok(available) if available < required => Err(Error::InsufficientSpace {
+            path: dir.to_path_buf(),
+            required,
+            available,
+        }),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            log::debug!("could not read the free space of {}: {}", dir.display(), e);
+            Ok(())
+        }
+    }
+}
+
+/// `path` itself, or its nearest existing ancestor when it is yet to be created.
+fn existing_ancestor(path: &Path) -> &Path {
+    path.ancestors()
+        .find(|p| !p.as_os_str().is_empty() && p.exists())
+        .unwrap_or(Path::new("."))
+}
+
+/// The bytes available to this user on the filesystem holding `path`.
+#[cfg(unix)]
+pub(crate) fn available_space(path: &Path) -> io::Result<u64> {
+    let stat = rustix::fs::statvfs(existing_ancestor(path))?;
+    Ok(stat.f_bavail.saturating_mul(stat.f_frsize))
+}
+
+/// The bytes available to this user on the volume holding `path`.
+#[cfg(windows)]
+pub(crate) fn available_space(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = existing_ancestor(path)
+        .as_os_str()
+        .encode_wide()
+        .chain(Some(0))
+        .collect();
+    let mut available = 0u64;
+    // SAFETY: `wide` is NUL-terminated; the two totals we do not need may be null.
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn available_space(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "free space is not available on this platform",
+    ))
+}
+
+/// Whether `a` and `b` (or their nearest existing ancestors) are on one filesystem.
+#[cfg(unix)]
+fn same_filesystem(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (
+        existing_ancestor(a).metadata(),
+        existing_ancestor(b).metadata(),
+    ) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev(),
+        _ => false,
+    }
+}
+
+/// Whether `a` and `b` share a volume, judged by their drive or UNC share prefix.
+#[cfg(not(unix))]
+fn same_filesystem(a: &Path, b: &Path) -> bool {
+    let prefix = |p: &Path| {
+        std::path::absolute(p)
+            .ok()
+            .and_then(|p| p.components().next().map(|c| c.as_os_str().to_owned()))
+    };
+    prefix(a).is_some_and(|pa| Some(pa) == prefix(b))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn available_space_reads_the_nearest_existing_ancestor() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("not/yet/created");
+        assert_eq!(
+            available_space(&missing).unwrap() > 0,
+            available_space(dir.path()).unwrap() > 0
+        );
+        assert!(same_filesystem(dir.path(), &missing));
+    }
+
+    #[test]
+    fn check_adds_up_both_needs_on_one_filesystem() {
+        let dir = tempfile::tempdir().unwrap();
+        let available = available_space(dir.path()).unwrap();
+        let check = SpaceCheck {
+            temp_dir: dir.path().join("tmp"),
+            install_dir: dir.path().to_path_buf(),
+            expansion_factor: 1.0,
+        };
+
+        // The download plus two unpacked copies: three times the asset size.
+        match check.check(available / 3 + 1).unwrap_err() {
+            Error::InsufficientSpace { path, required, .. } => {
+                assert_eq!(path, dir.path());
+                assert_eq!(required, 3 * (available / 3 + 1));
+            }
+            other => panic!("expected Error::InsufficientSpace, got {:?}", other),
+        }
+        check.check(1024).unwrap();
+    }
+}
@@ -6,6 +6,9 @@ use crate::Result;
 pub use http::HeaderMap;
 pub use http::header;
 
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod replay;
 mod reqwest;
 mod ureq;
 
@@ -21,9 +24,9 @@ pub use ureq::UreqClient;
 
 /// Object-safe HTTP transport seam. The crate only ever issues GETs, so `get` is the whole
 /// surface. Each implementation maps a non-2xx status to the structured
-/// `NotFound`/`Unauthorized`/`HttpStatus` error variant *before* returning `Ok` (preserving the
-/// per-client `status_to_error` logic), so callers see a structured error for a bad status and an
-/// `Ok(response)` only for a 2xx.
+/// `NotFound`/`Unauthorized`/`RateLimited`/`HttpStatus` error variant *before* returning `Ok`
+/// (see [`Error::http_response_error`](crate::Error::http_response_error)), so callers see a
+/// structured error for a bad status and an `Ok(response)` only for a 2xx.
 ///
 /// Object safety is what makes the transport injectable: a user can hand the crate any
 /// `Arc<dyn HttpClient>` (e.g. a test double or a wrapper around a custom client). Retries are
@@ -128,37 +131,64 @@ pub(crate) fn default_client() -> Box<dyn HttpClient> {
     Box::new(UreqClient::default())
 }
 
-/// A boxed error used by the cert-client builders, so the underlying certificate-parse / client
-/// build failure is preserved as a `source` chain rather than stringified.
+/// A boxed error used by the configured-client builders, so the underlying certificate-parse /
+/// proxy-parse / client build failure is preserved as a `source` chain rather than stringified.
 pub(crate) type ClientBuildError = Box<dyn std::error::Error + Send + Sync>;
 
-/// Build a sync HTTP client pre-configured with custom root CA certificates.
-/// Returns `Err` if the cert bytes are invalid or the client cannot be built.
-/// When both `reqwest` and `ureq` are enabled, reqwest is preferred (same priority as default_client).
-pub(crate) fn client_with_root_certs(
+/// Why [`configured_client`] (or its async sibling) failed: a root certificate that could not be
+/// parsed (or a client-build failure, reported alongside the certificates as before proxies
+/// existed), or a proxy that could not be applied. Lets callers surface
+/// `Error::InvalidCertificate` and `Error::InvalidProxy` separately.
+#[derive(Debug)]
+pub(crate) enum ClientConfigError {
+    Certificate(ClientBuildError),
+    Proxy(ClientBuildError),
+}
+
+impl From<ClientConfigError> for crate::Error {
+    fn from(err: ClientConfigError) -> Self {
+        match err {
+            ClientConfigError::Certificate(source) => crate::Error::InvalidCertificate { source },
+            ClientConfigError::Proxy(source) => crate::Error::InvalidProxy { source },
+        }
+    }
+}
+
+/// Build a sync HTTP client pre-configured with custom root CA certificates, a root store choice
+/// and/or an explicit proxy. Returns `Err` if the cert bytes or the proxy are invalid, or the
+/// client cannot be built. When both `reqwest` and `ureq` are enabled, reqwest is preferred (same
+/// priority as default_client). `root_store` only affects ureq: reqwest always verifies against the
+/// OS trust store.
+pub(crate) fn configured_client(
     certs: &[crate::tls::Certificate],
-) -> std::result::Result<std::sync::Arc<dyn HttpClient>, ClientBuildError> {
+    proxy: Option<&crate::Proxy>,
+    root_store: crate::RootStore,
+) -> std::result::Result<std::sync::Arc<dyn HttpClient>, ClientConfigError> {
     #[cfg(feature = "reqwest")]
     {
-        crate::http_client::ReqwestClient::build_with_certs(certs)
+        let _ = root_store;
+        crate::http_client::ReqwestClient::build_configured(certs, proxy)
     }
     #[cfg(all(feature = "ureq", not(feature = "reqwest")))]
     {
-        crate::http_client::UreqClient::build_with_certs(certs)
+        crate::http_client::UreqClient::build_configured(certs, proxy, root_store)
     }
     #[cfg(not(any(feature = "reqwest", feature = "ureq")))]
     {
-        let _ = certs;
-        Err("no HTTP client feature enabled".into())
+        let _ = (certs, proxy, root_store);
+        Err(ClientConfigError::Certificate(
+            "no HTTP client feature enabled".into(),
+        ))
     }
 }
 
-/// Async sibling of [`client_with_root_certs`]. Only reqwest is supported (async is reqwest-only).
+/// Async sibling of [`configured_client`]. Only reqwest is supported (async is reqwest-only).
 #[cfg(feature = "async")]
-pub(crate) fn async_client_with_root_certs(
+pub(crate) fn async_configured_client(
     certs: &[crate::tls::Certificate],
-) -> std::result::Result<std::sync::Arc<dyn AsyncHttpClient>, ClientBuildError> {
-    crate::http_client::ReqwestAsyncClient::build_async_with_certs(certs)
+    proxy: Option<&crate::Proxy>,
+) -> std::result::Result<std::sync::Arc<dyn AsyncHttpClient>, ClientConfigError> {
+    crate::http_client::ReqwestAsyncClient::build_async_configured(certs, proxy)
 }
 
 // Records the backend name chosen by the most recent `default_client` call on this thread.
@@ -0,0 +1,507 @@
+/*!
+Record/replay fixture transport for offline tests (`test-util` feature).
+
+[`RecordingClient`] (and [`AsyncRecordingClient`] with `async`) wraps a real client and saves every
+response it sees into a fixture directory. [`ReplayClient`] serves those fixtures back, so a test
+can snapshot a forge's responses once and then run against any backend without the network:
+
+```rust,no_run
+# #[cfg(all(feature = "github", feature = "reqwest"))]
+# fn run() -> self_update::Result<()> {
+use std::sync::Arc;
+use self_update::http_client::ReqwestClient;
+use self_update::http_client::replay::{RecordingClient, ReplayClient};
+
+// Once, online: record the listing.
+self_update::backends::github::ReleaseList::configure()
+    .repo_owner("o")
+    .repo_name("r")
+    .http_client(Arc::new(RecordingClient::new(
+        Arc::new(ReqwestClient::default()),
+        "tests/fixtures/github",
+    )))
+    .build()?
+    .fetch()?;
+
+// In the test, offline: serve it back.
+let releases = self_update::backends::github::ReleaseList::configure()
+    .repo_owner("o")
+    .repo_name("r")
+    .http_client(Arc::new(ReplayClient::new("tests/fixtures/github")))
+    .build()?
+    .fetch()?;
+# let _ = releases;
+# Ok(())
+# }
+```
+
+Fixtures are keyed by request URL; request headers (including the auth token) are neither matched
+nor stored. Query parameters starting with `X-Amz-` are left out of the key, so a presigned s3 URL
+replays even though its signature changes on every run, and its credentials never reach the
+fixture. Each fixture is one `<fnv1a-64 of the key>.fixture` file: a single JSON line (the key,
+the status and the response headers), then the raw body. Recording the same URL again overwrites
+its fixture.
+
+A response the wrapped client turned into an HTTP status error (`NotFound`, `RateLimited`, ...) is
+recorded with its status and replayed as the same error. Transport failures are passed through
+and not recorded. Asking a [`ReplayClient`] for a URL without a fixture is a
+[`Transport`](crate::Error::Transport) error naming the URL.
+*/
+
+use std::io::{Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{HeaderMap, HttpClient, HttpResponse, header};
+use crate::{Error, Result};
+
+#[cfg(feature = "async")]
+use super::{AsyncHttpClient, AsyncHttpResponse};
+
+/// The JSON line heading a fixture file.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FixtureMeta {
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+/// A recorded response held in memory.
+struct Fixture {
+    status: u16,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+/// Sync [`HttpClient`] that forwards every request to an inner client and saves the response to a
+/// fixture directory for [`ReplayClient`].
+pub struct RecordingClient {
+    inner: Arc<dyn HttpClient>,
+    dir: PathBuf,
+}
+
+impl RecordingClient {
+    /// Record the responses of `inner` into `dir` (created on the first write).
+    pub fn new(inner: Arc<dyn HttpClient>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+}
+
+impl HttpClient for RecordingClient {
+    fn get(
+        &self,
+        url: &str,
+        headers: &HeaderMap,
+        timeout: Option<Duration>,
+    ) -> Result<Box<dyn HttpResponse>> {
+        let response = match self.inner.get(url, headers, timeout) {
+            Ok(response) => response,
+            Err(e) => return Err(record_error(&self.dir, url, e)),
+        };
+        let headers = response.headers().clone();
+        let mut body = Vec::new();
+        response.body().read_to_end(&mut body)?;
+        let fixture = Fixture {
+            status: 200,
+            headers,
+            body,
+        };
+        write_fixture(&self.dir, url, &fixture)?;
+        Ok(Box::new(ReplayResponse::from(fixture)))
+    }
+}
+
+/// Async sibling of [`RecordingClient`], wrapping an [`AsyncHttpClient`].
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct AsyncRecordingClient {
+    inner: Arc<dyn AsyncHttpClient>,
+    dir: PathBuf,
+}
+
+#[cfg(feature = "async")]
+impl AsyncRecordingClient {
+    /// Record the responses of `inner` into `dir` (created on the first write).
+    pub fn new(inner: Arc<dyn AsyncHttpClient>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncHttpClient for AsyncRecordingClient {
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+        headers: &'a HeaderMap,
+        timeout: Option<Duration>,
+    ) -> futures_util::future::BoxFuture<'a, Result<Box<dyn AsyncHttpResponse>>> {
+        Box::pin(async move {
+            use futures_util::StreamExt;
+
+            let response = match self.inner.get(url, headers, timeout).await {
+                Ok(response) => response,
+                Err(e) => return Err(record_error(&self.dir, url, e)),
+            };
+            let headers = response.headers().clone();
+            let mut body = Vec::new();
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                body.extend_from_slice(&chunk?);
+            }
+            let fixture = Fixture {
+                status: 200,
+                headers,
+                body,
+            };
+            write_fixture(&self.dir, url, &fixture)?;
+            Ok(Box::new(ReplayResponse::from(fixture)) as Box<dyn AsyncHttpResponse>)
+        })
+    }
+}
+
+/// Serves the fixtures saved by a [`RecordingClient`] / [`AsyncRecordingClient`] without touching
+/// the network. Implements both [`HttpClient`] and (with `async`) [`AsyncHttpClient`].
+pub struct ReplayClient {
+    dir: PathBuf,
+}
+
+impl ReplayClient {
+    /// Replay the fixtures in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The recorded response for `url`, or the recorded status error.
+    fn replay(&self, url: &str) -> Result<ReplayResponse> {
+        let fixture = read_fixture(&self.dir, url)?;
+        if !(200..300).contains(&fixture.status) {
+            return Err(Error::http_response_error(
+                fixture.status,
+                url,
+                &fixture.headers,
+            ));
+        }
+        Ok(ReplayResponse::from(fixture))
+    }
+}
+
+impl HttpClient for ReplayClient {
+    fn get(
+        &self,
+        url: &str,
+        _headers: &HeaderMap,
+        _timeout: Option<Duration>,
+    ) -> Result<Box<dyn HttpResponse>> {
+        Ok(Box::new(self.replay(url)?))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncHttpClient for ReplayClient {
+    fn get<'a>(
+        &'a self,
+        url: &'a str,
+        _headers: &'a HeaderMap,
+        _timeout: Option<Duration>,
+    ) -> futures_util::future::BoxFuture<'a, Result<Box<dyn AsyncHttpResponse>>> {
+        let response = self
+            .replay(url)
+            .map(|response| Box::new(response) as Box<dyn AsyncHttpResponse>);
+        Box::pin(std::future::ready(response))
+    }
+}
+
+/// A response served from memory: a replayed fixture, or the one just recorded.
+struct ReplayResponse {
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl From<Fixture> for ReplayResponse {
+    fn from(fixture: Fixture) -> Self {
+        Self {
+            headers: fixture.headers,
+            body: fixture.body,
+        }
+    }
+}
+
+impl HttpResponse for ReplayResponse {
+    fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    fn body(self: Box<Self>) -> Box<dyn std::io::Read> {
+        Box::new(std::io::Cursor::new(self.body))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncHttpResponse for ReplayResponse {
+    fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    fn text(self: Box<Self>) -> futures_util::future::BoxFuture<'static, Result<String>> {
+        Box::pin(async move { Ok(String::from_utf8_lossy(&self.body).into_owned()) })
+    }
+
+    fn bytes_stream(
+        self: Box<Self>,
+    ) -> futures_util::stream::BoxStream<'static, Result<bytes::Bytes>> {
+        Box::pin(futures_util::stream::once(async move {
+            Ok(bytes::Bytes::from(self.body))
+        }))
+    }
+}
+
+/// Record the status of an HTTP status error (with no body) and hand the error back unchanged.
+/// Any other error is returned without a fixture.
+fn record_error(dir: &Path, url: &str, err: Error) -> Error {
+    let Some(status) = err.http_status() else {
+        return err;
+    };
+    // A redirect keeps its target, so the replay is followed to the same place.
+    let mut headers = HeaderMap::new();
+    if let Error::Redirect { location, .. } = &err
+        && let Ok(location) = location.parse()
+    {
+        headers.insert(crate::http_client::header::LOCATION, location);
+    }
+    let fixture = Fixture {
+        status,
+        headers,
+        body: Vec::new(),
+    };
+    match write_fixture(dir, url, &fixture) {
+        Ok(()) => err,
+        Err(write_err) => write_err,
+    }
+}
+
+fn write_fixture(dir: &Path, url: &str, fixture: &Fixture) -> Result<()> {
+    let meta = FixtureMeta {
+        url: fixture_key(url),
+        status: fixture.status,
+        headers: fixture
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+            .collect(),
+    };
+    std::fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    serde_json::to_writer(&mut tmp, &meta)?;
+    tmp.write_all(b"\n")?;
+    tmp.write_all(&fixture.body)?;
+    tmp.flush()?;
+    tmp.persist(fixture_path(dir, url)).map_err(|e| e.error)?;
+    Ok(())
+}
+
+fn read_fixture(dir: &Path, url: &str) -> Result<Fixture> {
+    let key = fixture_key(url);
+    let missing = || {
+        Error::transport(format!(
+            "no replay fixture for {} in {}",
+            crate::errors::redact_url(url),
+            dir.display()
+        ))
+    };
+    let bytes = match std::fs::read(fixture_path(dir, url)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(missing()),
+        Err(e) => return Err(e.into()),
+    };
+    let split = bytes
+        .iter()
+        .position(|b| *b == b'\n')
+        .ok_or_else(|| Error::invalid_response("replay fixture has no header line"))?;
+    let meta: FixtureMeta =
+        serde_json::from_slice(&bytes[..split]).map_err(Error::invalid_response)?;
+    // The file name is a hash of the key; a colliding fixture belongs to another URL.
+    if meta.url != key {
+        return Err(missing());
+    }
+    let mut headers = HeaderMap::new();
+    for (name, value) in meta.headers {
+        let name =
+            header::HeaderName::from_bytes(name.as_bytes()).map_err(Error::invalid_response)?;
+        headers.append(name, value.parse().map_err(Error::invalid_response)?);
+    }
+    Ok(Fixture {
+        status: meta.status,
+        headers,
+        body: bytes[split + 1..].to_vec(),
+    })
+}
+
+/// `url` without its `X-Amz-*` query parameters (the per-request SigV4 presigning).
+fn fixture_key(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_owned();
+    };
+    let query: Vec<&str> = query
+        .split('&')
+        .filter(|param| !param.starts_with("X-Amz-"))
+        .collect();
+    if query.is_empty() {
+        base.to_owned()
+    } else {
+        format!("{base}?{}", query.join("&"))
+    }
+}
+
+/// `<dir>/<fnv1a-64 of the key>.fixture`: a stable, filesystem-safe name for any URL.
+fn fixture_path(dir: &Path, url: &str) -> PathBuf {
+    let hash = fixture_key(url)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    dir.join(format!("{hash:016x}.fixture"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers every request with `status`: a 2xx returns `body`, anything else the status error.
+    struct StubClient {
+        status: u16,
+        body: &'static [u8],
+    }
+
+    impl HttpClient for StubClient {
+        fn get(
+            &self,
+            url: &str,
+            _headers: &HeaderMap,
+            _timeout: Option<Duration>,
+        ) -> Result<Box<dyn HttpResponse>> {
+            if self.status != 200 {
+                return Err(Error::http_status_error(self.status, url));
+            }
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ETAG, "\"v1\"".parse().unwrap());
+            Ok(Box::new(ReplayResponse {
+                headers,
+                body: self.body.to_vec(),
+            }))
+        }
+    }
+
+    fn get_body(client: &dyn HttpClient, url: &str) -> Result<(HeaderMap, Vec<u8>)> {
+        let response = client.get(url, &HeaderMap::new(), None)?;
+        let headers = response.headers().clone();
+        let mut body = Vec::new();
+        response.body().read_to_end(&mut body)?;
+        Ok((headers, body))
+    }
+
+    #[test]
+    fn recorded_responses_replay_with_their_headers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let recorder = RecordingClient::new(
+            Arc::new(StubClient {
+                status: 200,
+                body: b"\x00binary\xff",
+            }),
+            dir.path(),
+        );
+        let url = "https://example.com/app.tar.gz";
+        let (_, recorded) = get_body(&recorder, url).unwrap();
+        assert_eq!(
+            recorded, b"\x00binary\xff",
+            "the recorder passes the body through"
+        );
+
+        let (headers, body) = get_body(&ReplayClient::new(dir.path()), url).unwrap();
+        assert_eq!(body, b"\x00binary\xff");
+        assert_eq!(headers.get(header::ETAG).unwrap(), "\"v1\"");
+    }
+
+    #[test]
+    fn a_recorded_status_error_replays_as_the_same_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let recorder = RecordingClient::new(
+            Arc::new(StubClient {
+                status: 404,
+                body: b"",
+            }),
+            dir.path(),
+        );
+        let url = "https://example.com/missing";
+        assert!(matches!(
+            get_body(&recorder, url),
+            Err(Error::NotFound { .. })
+        ));
+
+        let err = get_body(&ReplayClient::new(dir.path()), url).unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }), "got {err:?}");
+    }
+
+    #[test]
+    fn an_unrecorded_url_is_a_transport_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let err = get_body(&ReplayClient::new(dir.path()), "https://example.com/x").unwrap_err();
+        assert!(matches!(err, Error::Transport(_)), "got {err:?}");
+        assert!(
+            err.to_string().contains("https://example.com/x"),
+            "got {err}"
+        );
+    }
+
+    #[test]
+    fn presigning_parameters_are_left_out_of_the_key() {
+        assert_eq!(
+            fixture_key(
+                "https://b.s3.amazonaws.com/app?X-Amz-Date=1&prefix=a&X-Amz-Signature=dead"
+            ),
+            "https://b.s3.amazonaws.com/app?prefix=a"
+        );
+        assert_eq!(
+            fixture_key("https://b.s3.amazonaws.com/app?X-Amz-Date=1"),
+            "https://b.s3.amazonaws.com/app"
+        );
+        assert_eq!(
+            fixture_path(Path::new("d"), "https://h/app?X-Amz-Signature=1"),
+            fixture_path(Path::new("d"), "https://h/app?X-Amz-Signature=2")
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn replays_on_the_async_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = "https://example.com/api/releases";
+        get_body(
+            &RecordingClient::new(
+                Arc::new(StubClient {
+                    status: 200,
+                    body: b"[]",
+                }),
+                dir.path(),
+            ),
+            url,
+        )
+        .unwrap();
+
+        let replay: Arc<dyn AsyncHttpClient> = Arc::new(ReplayClient::new(dir.path()));
+        let text = replay
+            .get(url, &HeaderMap::new(), None)
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(text, "[]");
+    }
+}
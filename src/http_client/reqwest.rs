@@ -12,8 +12,9 @@ use crate::Result;
 /// The default (`ReqwestClient(None)`) builds a fresh per-call client honoring the per-request
 /// timeout, the TLS feature, proxy-env, and http2 adaptive window. A `ReqwestClient(Some(client))`
 /// (built via `From<reqwest::blocking::Client>`, used by the `reqwest_client` convenience setter)
-/// reuses the injected client; the per-request timeout/headers are still layered on, but proxy-env
-/// and TLS defer to the injected client.
+/// reuses the injected client; the per-request timeout/headers are still layered on, but proxy-env,
+/// TLS and redirect handling defer to the injected client. The crate-built clients follow no
+/// redirects: a redirect comes back as `Error::Redirect` for the crate's redirect loop.
 #[derive(Default)]
 pub struct ReqwestClient(Option<reqwest::blocking::Client>);
 
@@ -24,13 +25,14 @@ impl From<reqwest::blocking::Client> for ReqwestClient {
 }
 
 impl ReqwestClient {
-    /// Build a ReqwestClient with custom root CA certificates baked in.
+    /// Build a ReqwestClient with custom root CA certificates and/or an explicit proxy baked in.
     /// Uses the same TLS backend selection (rustls wins over native-tls) as the per-call path.
-    pub(crate) fn build_with_certs(
+    pub(crate) fn build_configured(
         certs: &[crate::tls::Certificate],
+        proxy: Option<&crate::Proxy>,
     ) -> std::result::Result<
         std::sync::Arc<dyn crate::http_client::HttpClient>,
-        crate::http_client::ClientBuildError,
+        crate::http_client::ClientConfigError,
     > {
         let mut builder = reqwest::blocking::ClientBuilder::new();
         #[cfg(feature = "rustls")]
@@ -41,27 +43,69 @@ impl ReqwestClient {
         {
             builder = builder.use_native_tls();
         }
-        builder = builder.http2_adaptive_window(true);
-        // Collect all certs and merge in a single call: `tls_certs_merge` accumulates the passed
-        // certs onto the trust store, so one call with the whole set is equivalent to (and clearer
-        // than) one call per cert.
-        let mut collected = Vec::with_capacity(certs.len());
-        for cert in certs {
-            let c = if cert.is_pem() {
+        // Redirects are followed by the crate under its `RedirectPolicy` (see `crate::redirect`).
+        builder = builder
+            .http2_adaptive_window(true)
+            .redirect(reqwest::redirect::Policy::none());
+        if !certs.is_empty() {
+            // `tls_certs_merge` accumulates the passed certs onto the trust store, so one call with
+            // the whole set is equivalent to (and clearer than) one call per cert.
+            builder = builder.tls_certs_merge(reqwest_certs(certs)?);
+        }
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(reqwest_proxy(proxy)?);
+        }
+        let client = builder.build().map_err(|e| {
+            crate::http_client::ClientConfigError::Certificate(
+                format!("failed to build HTTP client: {e}").into(),
+            )
+        })?;
+        Ok(std::sync::Arc::new(ReqwestClient::from(client)))
+    }
+}
+
+/// Parse the crate-level certificates into reqwest ones, shared by the sync and async builders.
+fn reqwest_certs(
+    certs: &[crate::tls::Certificate],
+) -> std::result::Result<Vec<reqwest::Certificate>, crate::http_client::ClientConfigError> {
+    certs
+        .iter()
+        .map(|cert| {
+            let parsed = if cert.is_pem() {
                 reqwest::Certificate::from_pem(cert.bytes())
-                    .map_err(|e| format!("invalid PEM certificate: {e}"))?
+                    .map_err(|e| format!("invalid PEM certificate: {e}"))
             } else {
                 reqwest::Certificate::from_der(cert.bytes())
-                    .map_err(|e| format!("invalid DER certificate: {e}"))?
+                    .map_err(|e| format!("invalid DER certificate: {e}"))
             };
-            collected.push(c);
-        }
-        builder = builder.tls_certs_merge(collected);
-        let client = builder
-            .build()
-            .map_err(|e| format!("failed to build HTTP client: {e}"))?;
-        Ok(std::sync::Arc::new(ReqwestClient::from(client)))
+            parsed.map_err(|e| crate::http_client::ClientConfigError::Certificate(e.into()))
+        })
+        .collect()
+}
+
+/// Translate a [`Proxy`](crate::Proxy) into a `reqwest::Proxy` routing every request through it.
+/// Env-var proxies stay active behind it (reqwest tries the configured proxies in order), so a
+/// host on the no-proxy list still honors `HTTP(S)_PROXY`.
+fn reqwest_proxy(
+    proxy: &crate::Proxy,
+) -> std::result::Result<reqwest::Proxy, crate::http_client::ClientConfigError> {
+    proxy
+        .check_scheme()
+        .map_err(|e| crate::http_client::ClientConfigError::Proxy(e.into()))?;
+    let mut built = reqwest::Proxy::all(proxy.url()).map_err(|e| {
+        crate::http_client::ClientConfigError::Proxy(
+            format!("invalid proxy URL `{}`: {e}", proxy.redacted_url()).into(),
+        )
+    })?;
+    if let Some((user, pass)) = proxy.credentials() {
+        built = built.basic_auth(user, pass);
+    }
+    if !proxy.no_proxy_list().is_empty() {
+        built = built.no_proxy(reqwest::NoProxy::from_string(
+            &proxy.no_proxy_list().join(","),
+        ));
     }
+    Ok(built)
 }
 
 impl HttpClient for ReqwestClient {
@@ -94,13 +138,20 @@ impl HttpClient for ReqwestClient {
                 {
                     client_builder = client_builder.use_native_tls();
                 }
-                let client = client_builder.http2_adaptive_window(true).build()?;
+                let client = client_builder
+                    .http2_adaptive_window(true)
+                    .redirect(reqwest::redirect::Policy::none())
+                    .build()?;
                 client.get(url).headers(headers.clone()).send()?
             }
         };
 
         if !resp.status().is_success() {
-            return Err(crate::errors::status_to_error(resp.status().as_u16(), url));
+            return Err(crate::errors::response_to_error(
+                resp.status().as_u16(),
+                url,
+                resp.headers(),
+            ));
         }
         Ok(Box::new(resp))
     }
@@ -131,14 +182,15 @@ impl From<reqwest::Client> for ReqwestAsyncClient {
 
 #[cfg(feature = "async")]
 impl ReqwestAsyncClient {
-    /// Async sibling of [`ReqwestClient::build_with_certs`]: build a `ReqwestAsyncClient` with
-    /// custom root CA certificates baked in, using `reqwest::ClientBuilder` (async) and the same
-    /// TLS backend selection.
-    pub(crate) fn build_async_with_certs(
+    /// Async sibling of [`ReqwestClient::build_configured`]: build a `ReqwestAsyncClient` with
+    /// custom root CA certificates and/or an explicit proxy baked in, using
+    /// `reqwest::ClientBuilder` (async) and the same TLS backend selection.
+    pub(crate) fn build_async_configured(
         certs: &[crate::tls::Certificate],
+        proxy: Option<&crate::Proxy>,
     ) -> std::result::Result<
         std::sync::Arc<dyn crate::http_client::AsyncHttpClient>,
-        crate::http_client::ClientBuildError,
+        crate::http_client::ClientConfigError,
     > {
         let mut builder = reqwest::ClientBuilder::new();
         #[cfg(feature = "rustls")]
@@ -149,22 +201,21 @@ impl ReqwestAsyncClient {
         {
             builder = builder.use_native_tls();
         }
-        builder = builder.http2_adaptive_window(true);
-        let mut collected = Vec::with_capacity(certs.len());
-        for cert in certs {
-            let c = if cert.is_pem() {
-                reqwest::Certificate::from_pem(cert.bytes())
-                    .map_err(|e| format!("invalid PEM certificate: {e}"))?
-            } else {
-                reqwest::Certificate::from_der(cert.bytes())
-                    .map_err(|e| format!("invalid DER certificate: {e}"))?
-            };
-            collected.push(c);
+        // Redirects are followed by the crate under its `RedirectPolicy` (see `crate::redirect`).
+        builder = builder
+            .http2_adaptive_window(true)
+            .redirect(reqwest::redirect::Policy::none());
+        if !certs.is_empty() {
+            builder = builder.tls_certs_merge(reqwest_certs(certs)?);
         }
-        builder = builder.tls_certs_merge(collected);
-        let client = builder
-            .build()
-            .map_err(|e| format!("failed to build HTTP client: {e}"))?;
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(reqwest_proxy(proxy)?);
+        }
+        let client = builder.build().map_err(|e| {
+            crate::http_client::ClientConfigError::Certificate(
+                format!("failed to build HTTP client: {e}").into(),
+            )
+        })?;
         Ok(std::sync::Arc::new(ReqwestAsyncClient::from(client)))
     }
 }
@@ -199,12 +250,19 @@ impl super::AsyncHttpClient for ReqwestAsyncClient {
                     {
                         client_builder = client_builder.use_native_tls();
                     }
-                    let client = client_builder.http2_adaptive_window(true).build()?;
+                    let client = client_builder
+                        .http2_adaptive_window(true)
+                        .redirect(reqwest::redirect::Policy::none())
+                        .build()?;
                     client.get(url).headers(headers.clone()).send().await?
                 }
             };
             if !resp.status().is_success() {
-                return Err(crate::errors::status_to_error(resp.status().as_u16(), url));
+                return Err(crate::errors::response_to_error(
+                    resp.status().as_u16(),
+                    url,
+                    resp.headers(),
+                ));
             }
             Ok(Box::new(resp) as Box<dyn super::AsyncHttpResponse>)
         })
@@ -282,6 +340,93 @@ mod tests {
         base
     }
 
+    /// A loopback "proxy" that accepts one connection, forwards the raw request head over the
+    /// returned channel, and answers `200 OK` with body `proxied`. Returns `http://127.0.0.1:<port>`.
+    fn recording_proxy() -> (String, std::sync::mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = tx.send(String::from_utf8_lossy(&head).into_owned());
+                let _ = stream.write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nproxied",
+                );
+            }
+        });
+        (url, rx)
+    }
+
+    #[test]
+    fn build_configured_routes_through_proxy_with_basic_auth() {
+        // A plain-http request through an HTTP proxy goes out in absolute form to the proxy, with
+        // the `basic_auth` credentials as a `Proxy-Authorization: Basic` header.
+        let (proxy_url, head) = recording_proxy();
+        let client = ReqwestClient::build_configured(
+            &[],
+            Some(&crate::Proxy::new(proxy_url).basic_auth("bot", "s3cret")),
+        )
+        .unwrap();
+        let resp = client
+            .get("http://target.invalid/app.bin", &HeaderMap::new(), None)
+            .expect("the proxy answers 200");
+        let mut body = String::new();
+        resp.body().read_to_string(&mut body).unwrap();
+        assert_eq!(body, "proxied");
+        let head = head.recv().unwrap();
+        assert!(
+            head.starts_with("GET http://target.invalid/app.bin "),
+            "request must be sent to the proxy in absolute form: {head}"
+        );
+        // base64("bot:s3cret")
+        assert!(
+            head.lines().any(
+                |l| l.to_ascii_lowercase().starts_with("proxy-authorization:")
+                    && l.ends_with("Basic Ym90OnMzY3JldA==")
+            ),
+            "missing Proxy-Authorization: {head}"
+        );
+    }
+
+    #[test]
+    fn build_configured_bypasses_proxy_for_no_proxy_hosts() {
+        // The proxy points at a closed port; the target is on the no-proxy list, so the request
+        // goes direct and reaches the stub.
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+        let client = ReqwestClient::build_configured(
+            &[],
+            Some(&crate::Proxy::new(proxy_url).no_proxy(["127.0.0.1"])),
+        )
+        .unwrap();
+        let err = client
+            .get(&stub("404 Not Found"), &HeaderMap::new(), None)
+            .err()
+            .expect("the stub answers 404");
+        assert!(
+            matches!(err, Error::NotFound { .. }),
+            "the request must reach the stub directly, got {err:?}"
+        );
+    }
+
+    #[test]
+    fn build_configured_rejects_unsupported_proxy_scheme() {
+        let res = ReqwestClient::build_configured(&[], Some(&crate::Proxy::new("ftp://proxy:21")));
+        assert!(matches!(
+            res,
+            Err(crate::http_client::ClientConfigError::Proxy(_))
+        ));
+    }
+
     /// A PEM block carrying a CERTIFICATE marker but a body that decodes to bytes which are not a
     /// valid X.509 DER certificate. reqwest's TLS backend accepts the PEM framing but rejects this
     /// at client-build time, exercising the deferred-validation path (the `from_*` constructors are
@@ -290,12 +435,14 @@ mod tests {
         b"-----BEGIN CERTIFICATE-----\nbm90IGEgdmFsaWQgY2VydA==\n-----END CERTIFICATE-----\n";
 
     #[test]
-    fn build_with_certs_rejects_garbage_pem() {
+    fn build_configured_rejects_garbage_pem() {
         // A PEM-framed but non-certificate body must surface a config-time `Err` from
-        // `build_with_certs` (the parse is deferred to here from the infallible
+        // `build_configured` (the parse is deferred to here from the infallible
         // `Certificate::from_pem` constructor) rather than panicking or building a usable client.
-        let res =
-            ReqwestClient::build_with_certs(&[crate::tls::Certificate::from_pem(BAD_PEM.to_vec())]);
+        let res = ReqwestClient::build_configured(
+            &[crate::tls::Certificate::from_pem(BAD_PEM.to_vec())],
+            None,
+        );
         assert!(
             res.is_err(),
             "garbage PEM must be rejected at build time, got Ok"
@@ -303,11 +450,12 @@ mod tests {
     }
 
     #[test]
-    fn build_with_certs_rejects_garbage_der() {
+    fn build_configured_rejects_garbage_der() {
         // Same as the PEM case for the DER decoder: invalid DER bytes must produce an `Err`.
-        let res = ReqwestClient::build_with_certs(&[crate::tls::Certificate::from_der(
-            b"not der".to_vec(),
-        )]);
+        let res = ReqwestClient::build_configured(
+            &[crate::tls::Certificate::from_der(b"not der".to_vec())],
+            None,
+        );
         assert!(
             res.is_err(),
             "garbage DER must be rejected at build time, got Ok"
@@ -358,6 +506,15 @@ mod tests {
             get_status("503 Service Unavailable"),
             Error::HttpStatus { status: 503, .. }
         ));
+        // A rate limit keeps its headers: the retry loop waits for `Retry-After`.
+        assert!(matches!(
+            get_status("429 Too Many Requests\r\nRetry-After: 12"),
+            Error::RateLimited { status: 429, retry_after: Some(d), .. } if d.as_secs() == 12
+        ));
+        assert!(matches!(
+            get_status("403 Forbidden\r\nX-RateLimit-Remaining: 0"),
+            Error::RateLimited { status: 403, .. }
+        ));
     }
 
     #[test]
@@ -381,6 +538,28 @@ mod tests {
         assert_eq!(err.http_status(), None);
     }
 
+    // The crate-built clients leave redirects to the crate's redirect loop: a 302 comes back as
+    // `Error::Redirect` carrying the raw `Location` (the stub serves one connection, so a client
+    // that followed it would fail with a transport error instead).
+    #[test]
+    fn sync_get_returns_a_redirect_unfollowed() {
+        let err = get_status("302 Found\r\nLocation: /elsewhere");
+        assert!(
+            matches!(err, Error::Redirect { status: 302, ref location, .. } if location == "/elsewhere"),
+            "got {err:?}"
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_get_returns_a_redirect_unfollowed() {
+        let err = get_async_status("307 Temporary Redirect\r\nLocation: /elsewhere").await;
+        assert!(
+            matches!(err, Error::Redirect { status: 307, ref location, .. } if location == "/elsewhere"),
+            "got {err:?}"
+        );
+    }
+
     /// Async `get` (through the trait) against the loopback stub serving `status`; returns the
     /// mapped error.
     #[cfg(feature = "async")]
@@ -6,12 +6,10 @@ use ureq::tls::TlsProvider;
 use ureq::{Agent, Body, http::Response};
 
 use super::{HeaderMap, HttpClient, HttpResponse};
-use crate::{Error, Result, errors::status_to_error};
-
-/// The certificate set a crate-built ureq agent trusts. `Vec<Certificate<'static>>` so the roots
-/// outlive the per-call agent built from them.
-#[cfg(any(not(feature = "reqwest"), test))]
-type UreqRootCerts = std::sync::Arc<Vec<ureq::tls::Certificate<'static>>>;
+use crate::{
+    Error, Result,
+    errors::{response_to_error, status_to_error},
+};
 
 /// How a [`UreqClient`] obtains the agent for each request.
 enum UreqInner {
@@ -21,9 +19,14 @@ enum UreqInner {
     /// timeout/TLS/proxy config, so the per-request timeout is *not* applied to it.
     Injected(Agent),
     /// Build a fresh per-call agent (like [`Default`](UreqInner::Default), so it still honors the
-    /// per-request timeout and proxy-env) that trusts these custom root certificates.
+    /// per-request timeout) that trusts this root store and/or routes through this explicit proxy.
+    /// Without a proxy (or for a host on its no-proxy list) proxy-env still applies. `None` roots
+    /// keep ureq's default (the bundled Mozilla set).
     #[cfg(any(not(feature = "reqwest"), test))]
-    Certs(UreqRootCerts),
+    Configured {
+        root_certs: Option<ureq::tls::RootCerts>,
+        proxy: Option<(crate::Proxy, ureq::Proxy)>,
+    },
 }
 
 /// Sync [`HttpClient`] backed by a `ureq::Agent`.
@@ -41,11 +44,13 @@ impl From<Agent> for UreqClient {
     }
 }
 
-/// Build a per-call ureq agent honoring the per-request `timeout`, the TLS feature, and proxy-env.
-/// `root_certs`, when `Some`, replaces the default trust store with the supplied certificates.
+/// Build a per-call ureq agent honoring the per-request `timeout`, the TLS feature, and `proxy`
+/// (proxy-env unless an explicit proxy applies to this request). `root_certs`, when `Some`,
+/// replaces the default trust store (see [`ureq_root_certs`]).
 fn build_call_agent(
     timeout: Option<Duration>,
-    #[cfg(any(not(feature = "reqwest"), test))] root_certs: Option<UreqRootCerts>,
+    #[cfg(any(not(feature = "reqwest"), test))] root_certs: Option<ureq::tls::RootCerts>,
+    proxy: Option<ureq::Proxy>,
 ) -> Agent {
     use ureq::tls::TlsConfig;
     // When both TLS features are enabled, rustls wins (it is the crate default); otherwise fall
@@ -60,43 +65,52 @@ fn build_call_agent(
     #[cfg(all(feature = "reqwest", not(test)))]
     let tls = TlsConfig::builder().provider(provider);
     #[cfg(any(not(feature = "reqwest"), test))]
-    if let Some(certs) = root_certs {
-        tls = tls.root_certs(ureq::tls::RootCerts::Specific(certs));
+    if let Some(roots) = root_certs {
+        tls = tls.root_certs(roots);
     }
     let config = Agent::config_builder()
         .tls_config(tls.build())
         .timeout_global(timeout)
-        // Honor HTTP(S)_PROXY / NO_PROXY env vars (reqwest does this automatically).
-        .proxy(ureq::Proxy::try_from_env())
+        .proxy(proxy)
         // Disable ureq's built-in status-error so we reach our own is_success() check, which maps
         // the status to the structured NotFound/Unauthorized/HttpStatus variants.
         .http_status_as_error(false)
+        // Return a redirect as is: the crate follows it under its `RedirectPolicy` (see
+        // `crate::redirect`), which also decides whether `Authorization` survives the hop.
+        .max_redirects(0)
         .build();
     Agent::new_with_config(config)
 }
 
-// `client_with_root_certs` only dispatches to the ureq builder when reqwest is NOT also enabled
+// `configured_client` only dispatches to the ureq builder when reqwest is NOT also enabled
 // (reqwest wins, exactly like `default_client`), so this is dead in a both-features lib build. Gate
 // it to the lanes that actually reach it (and to `test`, where the ureq cert test exercises it).
 #[cfg(any(not(feature = "reqwest"), test))]
 impl UreqClient {
-    /// Build a UreqClient that trusts the supplied custom root CA certificates.
+    /// Build a UreqClient that trusts `root_store` plus the supplied custom root CA certificates
+    /// and/or routes through an explicit proxy.
     ///
-    /// The certificates are parsed and validated here (a malformed PEM certificate returns `Err`);
-    /// the agent itself is built per request in [`get`](HttpClient::get), so it still honors the
-    /// per-request timeout and proxy-env. `RootCerts::Specific` replaces the default trust store, so
-    /// only the supplied certificates are trusted (see the `add_root_certificate` docs).
-    pub(crate) fn build_with_certs(
+    /// The certificates and the proxy URL are parsed and validated here (a malformed PEM
+    /// certificate or proxy URL returns `Err`), and the OS trust store is loaded here when
+    /// `root_store` needs it alongside added certificates; the agent itself is built per request in
+    /// [`get`](HttpClient::get), so it still honors the per-request timeout and the proxy's
+    /// no-proxy list. An explicit proxy replaces proxy-env for every request it applies to (a ureq
+    /// agent has a single proxy slot).
+    pub(crate) fn build_configured(
         certs: &[crate::tls::Certificate],
+        proxy: Option<&crate::Proxy>,
+        root_store: crate::RootStore,
     ) -> std::result::Result<
         std::sync::Arc<dyn crate::http_client::HttpClient>,
-        crate::http_client::ClientBuildError,
+        crate::http_client::ClientConfigError,
     > {
+        use crate::http_client::ClientConfigError;
         let mut ureq_certs = Vec::with_capacity(certs.len());
         for cert in certs {
             let c = if cert.is_pem() {
-                ureq::tls::Certificate::from_pem(cert.bytes())
-                    .map_err(|e| format!("invalid PEM certificate: {e}"))?
+                ureq::tls::Certificate::from_pem(cert.bytes()).map_err(|e| {
+                    ClientConfigError::Certificate(format!("invalid PEM certificate: {e}").into())
+                })?
             } else {
                 // `from_der` is infallible in ureq; invalid DER bytes are surfaced at connection
                 // time, not here (documented on `Certificate::from_der` / `add_root_certificate`).
@@ -104,10 +118,92 @@ impl UreqClient {
             };
             ureq_certs.push(c.to_owned());
         }
-        Ok(std::sync::Arc::new(UreqClient(UreqInner::Certs(
-            std::sync::Arc::new(ureq_certs),
-        ))))
+        let proxy = match proxy {
+            Some(proxy) => {
+                proxy
+                    .check_scheme()
+                    .map_err(|e| ClientConfigError::Proxy(e.into()))?;
+                let url = proxy
+                    .url_with_credentials()
+                    .map_err(|e| ClientConfigError::Proxy(e.into()))?;
+                let built = ureq::Proxy::new(&url).map_err(|e| {
+                    ClientConfigError::Proxy(
+                        format!("invalid proxy URL `{}`: {e}", proxy.redacted_url()).into(),
+                    )
+                })?;
+                Some((proxy.clone(), built))
+            }
+            None => None,
+        };
+        Ok(std::sync::Arc::new(UreqClient(UreqInner::Configured {
+            root_certs: ureq_root_certs(ureq_certs, root_store)?,
+            proxy,
+        })))
+    }
+}
+
+/// Resolve the trust store of a configured client. `None` keeps ureq's default (the bundled Mozilla
+/// set) and a plain [`RootStore::System`](crate::RootStore::System) uses the platform verifier.
+/// Any other combination is materialized as one explicit certificate list -- the bundled and/or
+/// loaded OS roots with `added` on top -- because ureq trusts a single `RootCerts` source.
+#[cfg(any(not(feature = "reqwest"), test))]
+fn ureq_root_certs(
+    added: Vec<ureq::tls::Certificate<'static>>,
+    root_store: crate::RootStore,
+) -> std::result::Result<Option<ureq::tls::RootCerts>, crate::http_client::ClientConfigError> {
+    use crate::RootStore;
+    match root_store {
+        RootStore::Bundled if added.is_empty() => return Ok(None),
+        #[cfg(feature = "native-certs")]
+        RootStore::System if added.is_empty() => {
+            return Ok(Some(ureq::tls::RootCerts::PlatformVerifier));
+        }
+        #[cfg(not(feature = "native-certs"))]
+        RootStore::System | RootStore::Both => {
+            return Err(crate::http_client::ClientConfigError::Certificate(
+                format!("{root_store:?} root store requires the `native-certs` feature").into(),
+            ));
+        }
+        _ => {}
     }
+    let mut roots = Vec::new();
+    if matches!(root_store, RootStore::Bundled | RootStore::Both) {
+        roots.extend(
+            webpki_root_certs::TLS_SERVER_ROOT_CERTS
+                .iter()
+                .map(|cert| ureq::tls::Certificate::from_der(cert.as_ref())),
+        );
+    }
+    #[cfg(feature = "native-certs")]
+    if matches!(root_store, RootStore::System | RootStore::Both) {
+        roots.extend(system_root_certs()?);
+    }
+    roots.extend(added);
+    Ok(Some(ureq::tls::RootCerts::Specific(std::sync::Arc::new(
+        roots,
+    ))))
+}
+
+/// Load the OS trust store. Individual unreadable certificates are logged and skipped; finding no
+/// certificate at all is an error rather than silently trusting only the added ones.
+#[cfg(all(feature = "native-certs", any(not(feature = "reqwest"), test)))]
+fn system_root_certs()
+-> std::result::Result<Vec<ureq::tls::Certificate<'static>>, crate::http_client::ClientConfigError>
+{
+    let loaded = rustls_native_certs::load_native_certs();
+    for err in &loaded.errors {
+        log::warn!("self_update: skipping a system root certificate: {err}");
+    }
+    if loaded.certs.is_empty() {
+        return Err(crate::http_client::ClientConfigError::Certificate(
+            "no system root certificates could be loaded".into(),
+        ));
+    }
+    Ok(loaded
+        .certs
+        .iter()
+        .map(|cert| ureq::tls::Certificate::from_der(cert.as_ref()).to_owned())
+        .collect())
 }
 
 impl HttpClient for UreqClient {
@@ -118,7 +214,7 @@ impl HttpClient for UreqClient {
         timeout: Option<Duration>,
     ) -> Result<Box<dyn HttpResponse>> {
         // An injected agent owns its own timeout/TLS/proxy config, so the per-request `timeout` is
-        // only applied to the crate-built (Default / Certs) agents.
+        // only applied to the crate-built (Default / Configured) agents.
         let built_agent;
         let (agent, is_injected): (&Agent, bool) = match &self.0 {
             UreqInner::Injected(agent) => (agent, true),
@@ -127,12 +223,21 @@ impl HttpClient for UreqClient {
                     timeout,
                     #[cfg(any(not(feature = "reqwest"), test))]
                     None,
+                    // Honor HTTP(S)_PROXY / NO_PROXY env vars (reqwest does this automatically).
+                    ureq::Proxy::try_from_env(),
                 );
                 (&built_agent, false)
             }
             #[cfg(any(not(feature = "reqwest"), test))]
-            UreqInner::Certs(certs) => {
-                built_agent = build_call_agent(timeout, Some(certs.clone()));
+            UreqInner::Configured { root_certs, proxy } => {
+                // The explicit proxy wins unless this host is on its no-proxy list, in which case
+                // fall back to proxy-env exactly like reqwest (whose env proxies stay active behind
+                // a configured one).
+                let proxy = match proxy {
+                    Some((config, built)) if !config.bypasses(url) => Some(built.clone()),
+                    _ => ureq::Proxy::try_from_env(),
+                };
+                built_agent = build_call_agent(timeout, root_certs.clone(), proxy);
                 (&built_agent, false)
             }
         };
@@ -153,7 +258,7 @@ impl HttpClient for UreqClient {
         };
 
         if !res.status().is_success() {
-            return Err(status_to_error(res.status().as_u16(), url));
+            return Err(response_to_error(res.status().as_u16(), url, res.headers()));
         }
 
         Ok(Box::new(res))
@@ -212,6 +317,17 @@ mod tests {
             .expect("non-2xx must be an Err")
     }
 
+    // The per-call agent has `max_redirects(0)`, so a redirect reaches the `is_success()` check
+    // and becomes `Error::Redirect` for the crate's redirect loop.
+    #[test]
+    fn default_agent_returns_a_redirect_unfollowed() {
+        let err = get_default("302 Found\r\nLocation: /elsewhere");
+        assert!(
+            matches!(err, Error::Redirect { status: 302, ref location, .. } if location == "/elsewhere"),
+            "got {err:?}"
+        );
+    }
+
     /// Injected-agent path (`is_injected == true`): a user-supplied default `ureq::Agent` has
     /// `http_status_as_error == true` (the ureq default) and fires `ureq::Error::StatusCode(code)`
     /// on a non-2xx. That hits the `Err(ureq::Error::StatusCode(code)) if is_injected` arm, which
@@ -245,14 +361,18 @@ mod tests {
     }
 
     #[test]
-    fn build_with_certs_rejects_non_pem() {
+    fn build_configured_rejects_non_pem() {
         // ureq's `tls::Certificate::from_pem` validates the PEM framing eagerly and errors when the
-        // bytes contain no PEM certificate, so `build_with_certs` must surface a config-time `Err`
+        // bytes contain no PEM certificate, so `build_configured` must surface a config-time `Err`
         // (the parse is deferred to here from the infallible `Certificate::from_pem` constructor)
         // rather than panicking or building an agent over garbage.
-        let res = UreqClient::build_with_certs(&[crate::tls::Certificate::from_pem(
-            b"not a pem certificate".to_vec(),
-        )]);
+        let res = UreqClient::build_configured(
+            &[crate::tls::Certificate::from_pem(
+                b"not a pem certificate".to_vec(),
+            )],
+            None,
+            crate::RootStore::Bundled,
+        );
         assert!(
             res.is_err(),
             "bytes with no PEM certificate must be rejected at build time, got Ok"
@@ -260,21 +380,149 @@ mod tests {
     }
 
     #[test]
-    fn build_with_certs_accepts_garbage_der_deferring_validation() {
+    fn build_configured_accepts_garbage_der_deferring_validation() {
         // ureq's `tls::Certificate::from_der` is infallible, so invalid DER bytes are accepted
         // here and only surface at connection time. This is intentional and documented (on
         // `add_root_certificate` and in the reference specs); the reqwest client rejects the same
-        // bytes at build time (`build_with_certs_rejects_garbage_der` in reqwest.rs). This test
+        // bytes at build time (`build_configured_rejects_garbage_der` in reqwest.rs). This test
         // pins the asymmetry: if ureq ever gains eager DER validation, this fails and the
         // deferred-validation caveat in the docs should be removed.
-        let res =
-            UreqClient::build_with_certs(&[crate::tls::Certificate::from_der(b"not der".to_vec())]);
+        let res = UreqClient::build_configured(
+            &[crate::tls::Certificate::from_der(b"not der".to_vec())],
+            None,
+            crate::RootStore::Bundled,
+        );
         assert!(
             res.is_ok(),
             "ureq DER validation is deferred to connection time; build must accept the bytes"
         );
     }
 
+    #[test]
+    fn build_configured_tunnels_through_proxy_with_basic_auth() {
+        // ureq tunnels every request through an HTTP proxy with CONNECT; the `basic_auth`
+        // credentials (carried in the proxy URL) are sent as `Proxy-Authorization: Basic`. The
+        // recording proxy refuses the tunnel, so the request itself fails.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = tx.send(String::from_utf8_lossy(&head).into_owned());
+                let _ = stream.write_all(
+                    b"HTTP/1.1 407 Proxy Authentication Required\r\nContent-Length: 0\r\n\r\n",
+                );
+            }
+        });
+        let client = UreqClient::build_configured(
+            &[],
+            Some(&crate::Proxy::new(proxy_url).basic_auth("bot", "s3cret")),
+            crate::RootStore::Bundled,
+        )
+        .unwrap();
+        assert!(
+            client
+                .get("http://target.invalid/app.bin", &HeaderMap::new(), None)
+                .is_err()
+        );
+        let head = rx.recv().unwrap();
+        assert!(
+            head.starts_with("CONNECT target.invalid:80 "),
+            "request must open a tunnel through the proxy: {head}"
+        );
+        // base64("bot:s3cret")
+        assert!(
+            head.contains("Proxy-Authorization: Basic Ym90OnMzY3JldA=="),
+            "missing Proxy-Authorization: {head}"
+        );
+    }
+
+    #[test]
+    fn build_configured_bypasses_proxy_for_no_proxy_hosts() {
+        // The proxy points at a closed port; the target is on the no-proxy list, so the request
+        // goes direct and reaches the stub.
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+        let client = UreqClient::build_configured(
+            &[],
+            Some(&crate::Proxy::new(proxy_url).no_proxy(["127.0.0.1"])),
+            crate::RootStore::Bundled,
+        )
+        .unwrap();
+        let err = client
+            .get(&stub("404 Not Found"), &HeaderMap::new(), None)
+            .err()
+            .expect("the stub answers 404");
+        assert!(
+            matches!(err, Error::NotFound { .. }),
+            "the request must reach the stub directly, got {err:?}"
+        );
+    }
+
+    #[test]
+    fn build_configured_rejects_unrepresentable_proxy_credentials() {
+        let res = UreqClient::build_configured(
+            &[],
+            Some(&crate::Proxy::new("http://proxy:3128").basic_auth("bot", "p@ss")),
+            crate::RootStore::Bundled,
+        );
+        assert!(matches!(
+            res,
+            Err(crate::http_client::ClientConfigError::Proxy(_))
+        ));
+    }
+
+    #[test]
+    fn added_certificates_extend_the_bundled_root_set() {
+        // The default store with nothing added keeps ureq's own default; an added certificate is
+        // trusted on top of the bundled Mozilla set rather than replacing it.
+        assert!(
+            ureq_root_certs(Vec::new(), crate::RootStore::Bundled)
+                .unwrap()
+                .is_none()
+        );
+        let added = ureq::tls::Certificate::from_der(b"custom-ca");
+        match ureq_root_certs(vec![added], crate::RootStore::Bundled).unwrap() {
+            Some(ureq::tls::RootCerts::Specific(roots)) => {
+                assert_eq!(
+                    roots.len(),
+                    webpki_root_certs::TLS_SERVER_ROOT_CERTS.len() + 1
+                );
+                assert_eq!(roots.last().unwrap().der(), b"custom-ca");
+            }
+            other => panic!("expected the bundled set plus the added cert, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "native-certs")]
+    #[test]
+    fn system_root_store_uses_the_platform_verifier() {
+        assert!(matches!(
+            ureq_root_certs(Vec::new(), crate::RootStore::System),
+            Ok(Some(ureq::tls::RootCerts::PlatformVerifier))
+        ));
+    }
+
+    #[cfg(not(feature = "native-certs"))]
+    #[test]
+    fn system_root_store_requires_native_certs() {
+        for store in [crate::RootStore::System, crate::RootStore::Both] {
+            assert!(matches!(
+                ureq_root_certs(Vec::new(), store),
+                Err(crate::http_client::ClientConfigError::Certificate(_))
+            ));
+        }
+    }
+
     #[test]
     fn injected_agent_no_status_error_falls_through_to_is_success_check() {
         // 404 must still map to NotFound via the bottom-of-`get` is_success() path (not the
@@ -297,6 +545,18 @@ mod tests {
         assert_eq!(err.http_status(), Some(500));
     }
 
+    #[test]
+    fn default_agent_maps_a_rate_limit_with_its_headers() {
+        let err = get_default("429 Too Many Requests\r\nRetry-After: 12");
+        assert!(
+            matches!(err, Error::RateLimited { status: 429, retry_after: Some(d), .. } if d.as_secs() == 12),
+            "429 must map to Error::RateLimited carrying Retry-After, got {:?}",
+            err
+        );
+        let err = get_default("403 Forbidden\r\nX-RateLimit-Remaining: 0");
+        assert!(matches!(err, Error::RateLimited { status: 403, .. }));
+    }
+
     #[test]
     fn injected_agent_status_code_arm_maps_404_to_not_found() {
         let err = get_injected("404 Not Found");
@@ -0,0 +1,872 @@
+/*! Key lifetimes, revocation, and rotation for signature verification (`signatures`).
+
+The builders' plain `verifying_keys` setter trusts every key it is given, forever. That is fine
+until a signing key leaks: binaries already in the field keep accepting anything signed with it.
+This module layers a trust policy on top:
+
+- **Validity windows.** A [`TrustedKey`] carries an optional `not_before` / `not_after`; outside
+  that window the key is not used to verify anything. Configure them with the builders'
+  `trusted_keys` setter (alongside, or instead of, `verifying_keys`).
+- **Revocation.** A publisher can attach a signed [`RevocationList`] to a release (builder setter
+  `revocation_list_asset`). Before the archive is verified, the list is fetched, its detached
+  signature is checked against the keys trusted *so far*, and every key it names stops being
+  trusted — for this update and, when a `key_store_path` is configured, for every later one.
+- **Rotation.** A release signed by a currently-trusted key can deliver a new [`KeySet`] (builder
+  setter `key_set_asset`). Once the archive has verified, the key set's detached signature is
+  checked with the same keys, and once the update is installed the set is persisted to the
+  `key_store_path`; the updated binary (and any later run reading the same store) then trusts it.
+  A run that fails after verification, or only stages, stores nothing. Each set carries a serial that must
+  grow from one rotation to the next, so an older signed set replayed with a later release cannot
+  undo a rotation.
+
+## Effective trust
+
+The keys an update is verified against are computed fresh each time:
+
+1. the compiled `verifying_keys` (no validity window) together with the `trusted_keys`;
+2. merged with the key set persisted in the key store, whose entry wins for a key present in both
+   (so a delivered key set can put a `not_after` on a compiled key);
+3. minus every key in the stored revocation list and in the list fetched with this release;
+4. filtered to the keys whose validity window contains the current time.
+
+If keys were configured (or trust documents were fetched) but none survives these steps, the
+update fails with [`Error::NoTrustedKeys`] rather than
+skipping verification.
+
+## Document formats
+
+A key set is JSON with a serial, hex-encoded keys and unix-epoch-seconds bounds, both optional:
+
+```json
+{"serial": 2, "keys": [{"key": "<64 hex chars>", "not_before": 1700000000, "not_after": 1800000000}]}
+```
+
+A delivered set is persisted only if its serial is greater than the stored set's; an older or
+different same-serial set fails the update with [`Error::InvalidResponse`], and re-delivering the
+stored set itself changes nothing.
+
+A revocation list is JSON naming the revoked keys:
+
+```json
+{"revoked": ["<64 hex chars>"]}
+```
+
+Each document is published next to its detached signature, the asset of the same name with a
+`.sig` suffix (`keys.json` + `keys.json.sig`). The signature is a zipsign signature block (the
+same header and ed25519ph signatures zipsign embeds in an archive) over the document's bytes, with
+the document's asset name as the signing context; [`sign_detached`] produces one. A configured
+document that a release does not carry is skipped; one that is present without its `.sig` fails
+the update.
+
+The key store is a small JSON file owned by the application (like the stamp of
+[`UpdateCheckGuard`](crate::check_interval::UpdateCheckGuard)). It is replaced atomically, via a
+temporary file in the same directory. A store that exists but cannot be parsed fails the update
+instead of silently falling back to the compiled keys, since that fallback could re-trust a
+revoked key.
+*/
+
+use crate::VerifyingKey;
+use crate::errors::*;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A verifying key with an optional validity window. See the [module docs](crate::keys).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustedKey {
+    key: VerifyingKey,
+    not_before: Option<u64>,
+    not_after: Option<u64>,
+}
+
+impl TrustedKey {
+    /// Trust `key` with no validity window (always valid until revoked).
+    pub fn new(key: VerifyingKey) -> Self {
+        Self {
+            key,
+            not_before: None,
+            not_after: None,
+        }
+    }
+
+    /// Only trust the key from `time` on (inclusive).
+    pub fn not_before(mut self, time: SystemTime) -> Self {
+        self.not_before = Some(epoch_secs(time));
+        self
+    }
+
+    /// Stop trusting the key after `time` (inclusive: the key is still valid at exactly `time`).
+    pub fn not_after(mut self, time: SystemTime) -> Self {
+        self.not_after = Some(epoch_secs(time));
+        self
+    }
+
+    /// The raw ed25519 verifying key.
+    pub fn key(&self) -> &VerifyingKey {
+        &self.key
+    }
+
+    /// Whether `time` falls inside the key's validity window. Revocation is not considered here.
+    pub fn is_valid_at(&self, time: SystemTime) -> bool {
+        self.is_valid_at_secs(epoch_secs(time))
+    }
+
+    fn is_valid_at_secs(&self, secs: u64) -> bool {
+        self.not_before.is_none_or(|nb| secs >= nb) && self.not_after.is_none_or(|na| secs <= na)
+    }
+}
+
+impl From<VerifyingKey> for TrustedKey {
+    fn from(key: VerifyingKey) -> Self {
+        Self::new(key)
+    }
+}
+
+/// A set of [`TrustedKey`]s, as delivered by a rotation (`key_set_asset`) and persisted in the key
+/// store. See the [module docs](crate::keys) for the JSON format.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeySet {
+    serial: u64,
+    keys: Vec<TrustedKey>,
+}
+
+impl KeySet {
+    /// Build a key set from its keys, with serial 0.
+    pub fn new(keys: impl Into<Vec<TrustedKey>>) -> Self {
+        Self {
+            serial: 0,
+            keys: keys.into(),
+        }
+    }
+
+    /// Set the serial. Every rotation must publish a set with a greater serial than the last.
+    pub fn with_serial(mut self, serial: u64) -> Self {
+        self.serial = serial;
+        self
+    }
+
+    /// The serial of the set.
+    pub fn serial(&self) -> u64 {
+        self.serial
+    }
+
+    /// The keys of the set, in document order.
+    pub fn keys(&self) -> &[TrustedKey] {
+        &self.keys
+    }
+
+    /// Parse a key set document. A key that is not 64 hex characters is an
+    /// [`Error::InvalidResponse`].
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        Self::from_doc(serde_json::from_slice(bytes)?)
+    }
+
+    fn from_doc(doc: KeySetDoc) -> Result<Self> {
+        let keys = doc
+            .keys
+            .into_iter()
+            .map(|entry| {
+                Ok(TrustedKey {
+                    key: decode_key(&entry.key)?,
+                    not_before: entry.not_before,
+                    not_after: entry.not_after,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            serial: doc.serial,
+            keys,
+        })
+    }
+
+    /// Serialize the key set to its JSON document form (the input to [`sign_detached`] when
+    /// publishing a rotation).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.to_doc()).expect("a key set always serializes")
+    }
+
+    fn to_doc(&self) -> KeySetDoc {
+        KeySetDoc {
+            serial: self.serial,
+            keys: self
+                .keys
+                .iter()
+                .map(|k| KeyEntryDoc {
+                    key: encode_key(&k.key),
+                    not_before: k.not_before,
+                    not_after: k.not_after,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A list of revoked verifying keys, as published with a release (`revocation_list_asset`). See
+/// the [module docs](crate::keys) for the JSON format.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RevocationList {
+    revoked: Vec<VerifyingKey>,
+}
+
+impl RevocationList {
+    /// Build a revocation list naming `revoked`.
+    pub fn new(revoked: impl Into<Vec<VerifyingKey>>) -> Self {
+        Self {
+            revoked: revoked.into(),
+        }
+    }
+
+    /// The revoked keys.
+    pub fn revoked(&self) -> &[VerifyingKey] {
+        &self.revoked
+    }
+
+    /// Whether `key` is on the list.
+    pub fn is_revoked(&self, key: &VerifyingKey) -> bool {
+        self.revoked.contains(key)
+    }
+
+    /// Parse a revocation list document. A key that is not 64 hex characters is an
+    /// [`Error::InvalidResponse`].
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let doc: RevocationListDoc = serde_json::from_slice(bytes)?;
+        let revoked = doc
+            .revoked
+            .iter()
+            .map(|k| decode_key(k))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { revoked })
+    }
+
+    /// Serialize the list to its JSON document form (the input to [`sign_detached`]).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&RevocationListDoc {
+            revoked: self.revoked.iter().map(encode_key).collect(),
+        })
+        .expect("a revocation list always serializes")
+    }
+
+    fn extend(&mut self, keys: &[VerifyingKey]) {
+        for key in keys {
+            if !self.revoked.contains(key) {
+                self.revoked.push(*key);
+            }
+        }
+    }
+}
+
+/// Produce a detached signature block over `data` with every key in `signing_keys`, bound to
+/// `context` (the asset name the document is published under). Publish the result as
+/// `<asset>.sig` next to the document. This is release tooling; the updater only verifies.
+pub fn sign_detached(
+    data: &[u8],
+    signing_keys: &[zipsign_api::SigningKey],
+    context: &str,
+) -> Result<Vec<u8>> {
+    let prehash = zipsign_api::Prehash::calculate(&mut &data[..])?;
+    let block =
+        zipsign_api::sign::gather_signature_data(signing_keys, &prehash, Some(context.as_bytes()))
+            .map_err(zipsign_api::ZipsignError::from)?;
+    Ok(block)
+}
+
+/// Verify the detached signature block at `signature_path` over the file at `data_path` against
+/// any of `keys`. The signing context is the file name of `data_path`, so the document must be
+/// stored under its asset name.
+///
+/// Unlike [`verify_signature`](crate::verify_signature), an empty `keys` slice is an error
+/// ([`Error::NoTrustedKeys`]): a detached document is only
+/// fetched because the caller wants it checked.
+pub fn verify_detached(
+    data_path: impl AsRef<Path>,
+    signature_path: impl AsRef<Path>,
+    keys: &[VerifyingKey],
+) -> Result<()> {
+    let data_path = data_path.as_ref();
+    if keys.is_empty() {
+        return Err(Error::NoTrustedKeys);
+    }
+    let context = data_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or(Error::SignatureNonUTF8)?;
+
+    let keys = zipsign_api::verify::collect_keys(keys.iter().copied().map(Ok))
+        .map_err(zipsign_api::ZipsignError::from)?;
+    let mut sig = std::fs::File::open(signature_path.as_ref())?;
+    let signatures =
+        zipsign_api::verify::read_signatures(&mut sig).map_err(zipsign_api::ZipsignError::from)?;
+    let prehash = zipsign_api::Prehash::calculate(&mut std::fs::File::open(data_path)?)?;
+    zipsign_api::verify::find_match(&keys, &signatures, &prehash, Some(context.as_bytes()))
+        .map_err(zipsign_api::ZipsignError::from)?;
+    Ok(())
+}
+
+/// A trust document downloaded next to the archive: the document and its detached signature.
+#[derive(Debug)]
+pub(crate) struct SignedDocument {
+    pub(crate) path: PathBuf,
+    pub(crate) signature_path: PathBuf,
+}
+
+/// The trust documents fetched from the selected release, if configured and present.
+#[derive(Debug, Default)]
+pub(crate) struct TrustDocuments {
+    pub(crate) revocation_list: Option<SignedDocument>,
+    pub(crate) key_set: Option<SignedDocument>,
+}
+
+/// The configured trust material of an updater, copied out for the finish tail.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TrustPolicy {
+    pub(crate) verifying_keys: Vec<VerifyingKey>,
+    pub(crate) trusted_keys: Vec<TrustedKey>,
+    pub(crate) key_store_path: Option<PathBuf>,
+}
+
+impl TrustPolicy {
+    /// The keys the archive must be verified against right now. Applies (and persists) a fetched
+    /// revocation list first. Empty only when nothing is configured and no document was fetched,
+    /// in which case signature verification stays disabled as before.
+    pub(crate) fn active_keys(&self, docs: &TrustDocuments) -> Result<Vec<VerifyingKey>> {
+        self.active_keys_at(docs, now_epoch_secs())
+    }
+
+    fn active_keys_at(&self, docs: &TrustDocuments, now: u64) -> Result<Vec<VerifyingKey>> {
+        let mut store = self.load_store()?;
+        let configured = !self.verifying_keys.is_empty()
+            || !self.trusted_keys.is_empty()
+            || store.key_set.is_some();
+
+        if let Some(doc) = &docs.revocation_list {
+            // The list is authenticated by the keys trusted *before* it is applied, so a list can
+            // revoke the very key that signed it.
+            verify_detached(&doc.path, &doc.signature_path, &self.keys_at(&store, now))?;
+            let fetched = RevocationList::from_json(&std::fs::read(&doc.path)?)?;
+            store.revoked.extend(fetched.revoked());
+            if let Some(path) = &self.key_store_path {
+                store.save(path)?;
+            }
+        }
+
+        let keys = self.keys_at(&store, now);
+        if keys.is_empty()
+            && (configured || docs.revocation_list.is_some() || docs.key_set.is_some())
+        {
+            return Err(Error::NoTrustedKeys);
+        }
+        Ok(keys)
+    }
+
+    /// Verify a delivered key set against `keys` (the keys that just verified the archive) and
+    /// return it for [`save_key_set`](Self::save_key_set) once the update is installed. A set
+    /// whose serial is not greater than the stored set's is a replay (or a rotation published
+    /// without bumping the serial) and is rejected, unless it is the stored set itself, which
+    /// returns `None` like a release without a key set.
+    pub(crate) fn accept_key_set(
+        &self,
+        docs: &TrustDocuments,
+        keys: &[VerifyingKey],
+    ) -> Result<Option<KeySet>> {
+        let Some(doc) = &docs.key_set else {
+            return Ok(None);
+        };
+        verify_detached(&doc.path, &doc.signature_path, keys)?;
+        let key_set = KeySet::from_json(&std::fs::read(&doc.path)?)?;
+        match &self.load_store()?.key_set {
+            Some(stored) if *stored == key_set => Ok(None),
+            Some(stored) if stored.serial >= key_set.serial => {
+                Err(Error::invalid_response(MessageError(format!(
+                    "key set serial {} is not newer than the stored serial {}",
+                    key_set.serial, stored.serial
+                ))))
+            }
+            _ => Ok(Some(key_set)),
+        }
+    }
+
+    /// Persist a key set returned by [`accept_key_set`](Self::accept_key_set) to the key store, so
+    /// later updates trust it. Called only once the update is installed: a run that fails later,
+    /// or only stages, leaves the stored set and its serial as they were, so a retry is not taken
+    /// for a replay. A set no newer than the one stored meanwhile by another run is dropped.
+    pub(crate) fn save_key_set(&self, key_set: Option<KeySet>) -> Result<()> {
+        // `build()` requires a key store whenever a key-set asset is configured.
+        let (Some(key_set), Some(path)) = (key_set, &self.key_store_path) else {
+            return Ok(());
+        };
+        let mut store = self.load_store()?;
+        if store
+            .key_set
+            .as_ref()
+            .is_some_and(|stored| stored.serial >= key_set.serial)
+        {
+            return Ok(());
+        }
+        store.key_set = Some(key_set);
+        store.save(path)
+    }
+
+    fn load_store(&self) -> Result<KeyStore> {
+        match &self.key_store_path {
+            Some(path) => KeyStore::load(path),
+            None => Ok(KeyStore::default()),
+        }
+    }
+
+    /// Steps 1-4 of the effective-trust computation in the module docs.
+    fn keys_at(&self, store: &KeyStore, now: u64) -> Vec<VerifyingKey> {
+        let mut candidates: Vec<TrustedKey> = self
+            .verifying_keys
+            .iter()
+            .copied()
+            .map(TrustedKey::new)
+            .chain(self.trusted_keys.iter().cloned())
+            .collect();
+        if let Some(stored) = &store.key_set {
+            for key in stored.keys() {
+                candidates.retain(|c| c.key != key.key);
+                candidates.push(key.clone());
+            }
+        }
+        let mut keys: Vec<VerifyingKey> = Vec::new();
+        for candidate in candidates {
+            if candidate.is_valid_at_secs(now)
+                && !store.revoked.is_revoked(&candidate.key)
+                && !keys.contains(&candidate.key)
+            {
+                keys.push(candidate.key);
+            }
+        }
+        keys
+    }
+}
+
+/// The persisted trust state: the last delivered key set and every revocation seen so far.
+#[derive(Debug, Default)]
+struct KeyStore {
+    key_set: Option<KeySet>,
+    revoked: RevocationList,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyStoreDoc {
+    #[serde(default)]
+    key_set: Option<KeySetDoc>,
+    #[serde(default)]
+    revoked: Vec<String>,
+}
+
+impl KeyStore {
+    /// Read the store at `path`. A missing file is an empty store; an unparseable one is an error.
+    fn load(path: &Path) -> Result<Self> {
+        let bytes = match std::fs::read(path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::Io(e)),
+        };
+        let doc: KeyStoreDoc = serde_json::from_slice(&bytes)?;
+        let key_set = doc.key_set.map(KeySet::from_doc).transpose()?;
+        let revoked = doc
+            .revoked
+            .iter()
+            .map(|k| decode_key(k))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            key_set,
+            revoked: RevocationList::new(revoked),
+        })
+    }
+
+    /// Write the store to `path` via a temporary file in the same directory and a rename, so a
+    /// concurrent reader never sees a partial write.
+    fn save(&self, path: &Path) -> Result<()> {
+        let doc = KeyStoreDoc {
+            key_set: self.key_set.as_ref().map(KeySet::to_doc),
+            revoked: self.revoked.revoked().iter().map(encode_key).collect(),
+        };
+        let dir = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(&mut tmp, &doc)?;
+        tmp.flush()?;
+        tmp.persist(path).map_err(|e| Error::Io(e.error))?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeySetDoc {
+    serial: u64,
+    keys: Vec<KeyEntryDoc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyEntryDoc {
+    key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_before: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_after: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RevocationListDoc {
+    revoked: Vec<String>,
+}
+
+fn encode_key(key: &VerifyingKey) -> String {
+    key.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_key(hex: &str) -> Result<VerifyingKey> {
+    let invalid = || {
+        Error::invalid_response(MessageError(format!(
+            "invalid verifying key {:?}: expected {} hex characters",
+            hex,
+            2 * zipsign_api::PUBLIC_KEY_LENGTH
+        )))
+    };
+    if hex.len() != 2 * zipsign_api::PUBLIC_KEY_LENGTH || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut key = [0u8; zipsign_api::PUBLIC_KEY_LENGTH];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(key)
+}
+
+/// Seconds since the unix epoch, clamped at `0` for a pre-1970 time.
+fn epoch_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn now_epoch_secs() -> u64 {
+    epoch_secs(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn signing_key(seed: u8) -> zipsign_api::SigningKey {
+        zipsign_api::SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn vkey(seed: u8) -> VerifyingKey {
+        signing_key(seed).verifying_key().to_bytes()
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    // Write `body` as `name` plus its detached signature by `signer` into `dir`.
+    fn signed_doc(dir: &Path, name: &str, body: &str, signer: u8) -> SignedDocument {
+        let path = dir.join(name);
+        let signature_path = dir.join(format!("{name}.sig"));
+        std::fs::write(&path, body).unwrap();
+        let sig = sign_detached(body.as_bytes(), &[signing_key(signer)], name).unwrap();
+        std::fs::write(&signature_path, sig).unwrap();
+        SignedDocument {
+            path,
+            signature_path,
+        }
+    }
+
+    // Accept a delivered key set and save it right away, as an installed update does.
+    fn accept(policy: &TrustPolicy, docs: &TrustDocuments, keys: &[VerifyingKey]) -> Result<()> {
+        policy.save_key_set(policy.accept_key_set(docs, keys)?)
+    }
+
+    // The validity window is inclusive at both ends and open when a bound is absent.
+    #[test]
+    fn trusted_key_window_is_inclusive() {
+        let key = TrustedKey::new(vkey(1))
+            .not_before(at(100))
+            .not_after(at(200));
+        assert!(!key.is_valid_at(at(99)));
+        assert!(key.is_valid_at(at(100)));
+        assert!(key.is_valid_at(at(200)));
+        assert!(!key.is_valid_at(at(201)));
+        assert!(TrustedKey::new(vkey(1)).is_valid_at(at(0)));
+    }
+
+    // A key set and a revocation list survive a JSON round trip, and absent bounds are omitted.
+    #[test]
+    fn documents_round_trip_through_json() {
+        let set = KeySet::new(vec![
+            TrustedKey::new(vkey(1)).not_after(at(500)),
+            TrustedKey::new(vkey(2)),
+        ])
+        .with_serial(7);
+        let json = set.to_json();
+        assert!(
+            !json.contains("not_before"),
+            "absent bounds are omitted: {json}"
+        );
+        assert_eq!(KeySet::from_json(json.as_bytes()).unwrap(), set);
+
+        let list = RevocationList::new(vec![vkey(3)]);
+        let parsed = RevocationList::from_json(list.to_json().as_bytes()).unwrap();
+        assert!(parsed.is_revoked(&vkey(3)));
+        assert!(!parsed.is_revoked(&vkey(1)));
+    }
+
+    // A malformed key is an InvalidResponse, not a panic or a silently dropped entry.
+    #[test]
+    fn malformed_key_is_rejected() {
+        let err = RevocationList::from_json(br#"{"revoked":["abcd"]}"#).unwrap_err();
+        assert!(matches!(err, Error::InvalidResponse { .. }), "got {err:?}");
+    }
+
+    // A detached signature verifies with the signing key and fails with any other key, or when the
+    // document is stored under a different name (the name is the signing context).
+    #[test]
+    fn detached_signature_binds_key_and_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let doc = signed_doc(dir.path(), "keys.json", "{}", 1);
+        verify_detached(&doc.path, &doc.signature_path, &[vkey(1)]).unwrap();
+        assert!(matches!(
+            verify_detached(&doc.path, &doc.signature_path, &[vkey(2)]),
+            Err(Error::Signature(_))
+        ));
+
+        let renamed = dir.path().join("other.json");
+        std::fs::copy(&doc.path, &renamed).unwrap();
+        assert!(verify_detached(&renamed, &doc.signature_path, &[vkey(1)]).is_err());
+        assert!(matches!(
+            verify_detached(&doc.path, &doc.signature_path, &[]),
+            Err(Error::NoTrustedKeys)
+        ));
+    }
+
+    // Expired keys drop out, and when nothing configured is still valid the policy refuses rather
+    // than disabling verification.
+    #[test]
+    fn expired_keys_are_not_active() {
+        let policy = TrustPolicy {
+            verifying_keys: vec![],
+            trusted_keys: vec![
+                TrustedKey::new(vkey(1)).not_after(at(100)),
+                TrustedKey::new(vkey(2)).not_before(at(50)),
+            ],
+            key_store_path: None,
+        };
+        let docs = TrustDocuments::default();
+        assert_eq!(
+            policy.active_keys_at(&docs, 75).unwrap(),
+            vec![vkey(1), vkey(2)]
+        );
+        assert_eq!(policy.active_keys_at(&docs, 150).unwrap(), vec![vkey(2)]);
+
+        let expired = TrustPolicy {
+            trusted_keys: vec![TrustedKey::new(vkey(1)).not_after(at(100))],
+            ..TrustPolicy::default()
+        };
+        assert!(matches!(
+            expired.active_keys_at(&docs, 150),
+            Err(Error::NoTrustedKeys)
+        ));
+        assert!(
+            TrustPolicy::default()
+                .active_keys_at(&docs, 150)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    // A revocation list signed by a trusted key removes the revoked key for this update and is
+    // persisted, so a later run without the document still distrusts it.
+    #[test]
+    fn revocation_is_applied_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trust.json");
+        let policy = TrustPolicy {
+            verifying_keys: vec![vkey(1), vkey(2)],
+            trusted_keys: vec![],
+            key_store_path: Some(store.clone()),
+        };
+        let list = RevocationList::new(vec![vkey(1)]).to_json();
+        let docs = TrustDocuments {
+            revocation_list: Some(signed_doc(dir.path(), "revoked.json", &list, 2)),
+            key_set: None,
+        };
+        assert_eq!(policy.active_keys_at(&docs, 0).unwrap(), vec![vkey(2)]);
+        assert_eq!(
+            policy
+                .active_keys_at(&TrustDocuments::default(), 0)
+                .unwrap(),
+            vec![vkey(2)],
+            "the revocation must outlive the run that fetched it"
+        );
+    }
+
+    // A revocation list signed by an untrusted key is rejected and revokes nothing.
+    #[test]
+    fn forged_revocation_list_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trust.json");
+        let policy = TrustPolicy {
+            verifying_keys: vec![vkey(1)],
+            trusted_keys: vec![],
+            key_store_path: Some(store.clone()),
+        };
+        let list = RevocationList::new(vec![vkey(1)]).to_json();
+        let docs = TrustDocuments {
+            revocation_list: Some(signed_doc(dir.path(), "revoked.json", &list, 9)),
+            key_set: None,
+        };
+        assert!(matches!(
+            policy.active_keys_at(&docs, 0),
+            Err(Error::Signature(_))
+        ));
+        assert!(!store.exists(), "a rejected list must not be persisted");
+    }
+
+    // Rotation: a key set signed by the current key is persisted and then trusted alongside the
+    // compiled key, with its entry overriding the compiled key's (unbounded) window.
+    #[test]
+    fn delivered_key_set_rotates_trust() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trust.json");
+        let policy = TrustPolicy {
+            verifying_keys: vec![vkey(1)],
+            trusted_keys: vec![],
+            key_store_path: Some(store.clone()),
+        };
+        let set = KeySet::new(vec![
+            TrustedKey::new(vkey(1)).not_after(at(100)),
+            TrustedKey::new(vkey(2)),
+        ])
+        .to_json();
+        let docs = TrustDocuments {
+            revocation_list: None,
+            key_set: Some(signed_doc(dir.path(), "keys.json", &set, 1)),
+        };
+        let keys = policy.active_keys_at(&docs, 0).unwrap();
+        assert_eq!(keys, vec![vkey(1)], "the delivered set is not trusted yet");
+        accept(&policy, &docs, &keys).unwrap();
+
+        let none = TrustDocuments::default();
+        assert_eq!(
+            policy.active_keys_at(&none, 50).unwrap(),
+            vec![vkey(1), vkey(2)]
+        );
+        assert_eq!(policy.active_keys_at(&none, 150).unwrap(), vec![vkey(2)]);
+    }
+
+    // A key set accepted but not saved (the update then failed) leaves the store untouched, so
+    // the retry delivering the same serial is accepted again.
+    #[test]
+    fn accepted_key_set_is_stored_only_once_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trust.json");
+        let policy = TrustPolicy {
+            verifying_keys: vec![vkey(1)],
+            trusted_keys: vec![],
+            key_store_path: Some(store.clone()),
+        };
+        let set = KeySet::new(vec![TrustedKey::new(vkey(2))]).with_serial(1);
+        let docs = TrustDocuments {
+            revocation_list: None,
+            key_set: Some(signed_doc(dir.path(), "keys.json", &set.to_json(), 1)),
+        };
+        assert_eq!(
+            policy.accept_key_set(&docs, &[vkey(1)]).unwrap(),
+            Some(set.clone())
+        );
+        assert!(!store.exists());
+
+        let retried = policy.accept_key_set(&docs, &[vkey(1)]).unwrap();
+        assert_eq!(retried, Some(set));
+        policy.save_key_set(retried).unwrap();
+        assert_eq!(
+            policy
+                .active_keys_at(&TrustDocuments::default(), 0)
+                .unwrap(),
+            vec![vkey(1), vkey(2)]
+        );
+    }
+
+    // An older signed key set replayed after a rotation is rejected, so it cannot re-trust a key
+    // whose window the newer set shortened; re-delivering the stored set is a no-op.
+    #[test]
+    fn replayed_older_key_set_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trust.json");
+        let policy = TrustPolicy {
+            verifying_keys: vec![vkey(1)],
+            trusted_keys: vec![],
+            key_store_path: Some(store.clone()),
+        };
+        let deliver = |set: KeySet| TrustDocuments {
+            revocation_list: None,
+            key_set: Some(signed_doc(dir.path(), "keys.json", &set.to_json(), 1)),
+        };
+        let old = KeySet::new(vec![TrustedKey::new(vkey(1))]).with_serial(1);
+        let new = KeySet::new(vec![TrustedKey::new(vkey(1)).not_after(at(100))]).with_serial(2);
+        accept(&policy, &deliver(old.clone()), &[vkey(1)]).unwrap();
+        accept(&policy, &deliver(new.clone()), &[vkey(1)]).unwrap();
+        assert_eq!(
+            policy.accept_key_set(&deliver(new), &[vkey(1)]).unwrap(),
+            None
+        );
+
+        for replay in [
+            old,
+            KeySet::new(vec![TrustedKey::new(vkey(1))]).with_serial(2),
+        ] {
+            assert!(matches!(
+                policy.accept_key_set(&deliver(replay), &[vkey(1)]),
+                Err(Error::InvalidResponse { .. })
+            ));
+        }
+        assert!(matches!(
+            policy.active_keys_at(&TrustDocuments::default(), 150),
+            Err(Error::NoTrustedKeys)
+        ));
+    }
+
+    // A key set signed by a key outside the active set is not accepted.
+    #[test]
+    fn key_set_signed_by_untrusted_key_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trust.json");
+        let policy = TrustPolicy {
+            verifying_keys: vec![vkey(1)],
+            trusted_keys: vec![],
+            key_store_path: Some(store.clone()),
+        };
+        let set = KeySet::new(vec![TrustedKey::new(vkey(9))]).to_json();
+        let docs = TrustDocuments {
+            revocation_list: None,
+            key_set: Some(signed_doc(dir.path(), "keys.json", &set, 9)),
+        };
+        assert!(policy.accept_key_set(&docs, &[vkey(1)]).is_err());
+        assert!(!store.exists());
+    }
+
+    // A corrupt key store fails closed instead of falling back to the compiled keys.
+    #[test]
+    fn corrupt_store_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("trust.json");
+        std::fs::write(&store, "not json").unwrap();
+        let policy = TrustPolicy {
+            verifying_keys: vec![vkey(1)],
+            trusted_keys: vec![],
+            key_store_path: Some(store),
+        };
+        assert!(matches!(
+            policy.active_keys_at(&TrustDocuments::default(), 0),
+            Err(Error::Json(_))
+        ));
+    }
+}
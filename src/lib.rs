@@ -61,6 +61,7 @@ are present):
 * `rustls` (default): [pure-Rust TLS](https://github.com/rustls/rustls); does _not_ support 32-bit macOS;
 * `native-tls`: opt-in native/OpenSSL TLS for the selected client;
 * `native-tls-vendored`: build OpenSSL from source and link it statically (for targets where a usable system OpenSSL is awkward, e.g. musl or some cross-compiles); implies `native-tls`, applies to the reqwest client;
+* `native-certs`: let the ureq client trust the OS certificate store (`.tls_root_store(RootStore::System)` or `RootStore::Both`); reqwest already does;
 
 Note that enabling a client with neither TLS feature compiles (plain-`http` release hosts remain
 reachable) but any `https` URL then fails at request time with a transport error; enable `rustls`
@@ -86,9 +87,13 @@ The following are opt-in; activate the one(s) your release files need:
 * `compression-tar-xz`: support for _xz_ compression (`.tar.xz`, `.txz`, plain `.xz`); pure-Rust, no C `liblzma` dependency;
 * `compression-zip-deflate`: support for _zip_'s _deflate_ compression format;
 * `compression-zip-bzip2`: support for _zip_'s _bzip2_ compression format;
-* `signatures`: use [zipsign](https://github.com/Kijewski/zipsign) to verify `.zip` and `.tar.gz` artifacts. Artifacts are assumed to have been signed using zipsign;
-* `checksums`: verify a downloaded artifact against a SHA-256/SHA-512 checksum before installing it -- automatically against the digest github publishes per release asset, and/or against a known checksum you pass in (e.g. from a `SHA256SUMS` file); see [Checksum verification](#checksum-verification) below;
-* `async`: add async (`*_async`) update methods alongside the unchanged blocking API; tokio-only, requires `reqwest` (ureq and reqwest can coexist -- reqwest serves the async path, and the sync API prefers reqwest when both are present); see [Async](#async) below.
+* `signatures`: use [zipsign](https://github.com/Kijewski/zipsign) to verify artifacts. `.zip` and `.tar.gz` artifacts carry an embedded zipsign signature; any other format (`.tar.xz`, a raw binary, ...) is verified against a detached `<asset>.sig` published next to it;
+* `checksums`: verify a downloaded artifact against a SHA-256/SHA-384/SHA-512 checksum before installing it -- automatically against the digest github publishes per release asset, and/or against a known checksum you pass in (e.g. from a `SHA256SUMS` file); see [Checksum verification](#checksum-verification) below;
+* `checksums-blake3`: additionally accept BLAKE3 checksums (`Checksum::Blake3`, `blake3:<hex>` digests); enables `checksums` and adds the `blake3` dependency;
+* `stage`: download and verify an update now with `stage()`, install it on the next start with [`staged::ApplyStaged`](crate::staged::ApplyStaged); enables `checksums`;
+* `elevate`: finish a replace refused for lack of permission (e.g. in `/usr/local/bin`) through `sudo`, `doas` or `pkexec`; see [`elevate`](crate::elevate); enables `checksums`;
+* `async`: add async (`*_async`) update methods alongside the unchanged blocking API; tokio-only, requires `reqwest` (ureq and reqwest can coexist -- reqwest serves the async path, and the sync API prefers reqwest when both are present); see [Async](#async) below;
+* `test-util`: the record/replay fixture transport [`http_client::replay`](crate::http_client::replay), to snapshot a forge's responses once and run an integration's tests offline against them, and [`test_server`](crate::test_server), a local stand-in release host (GitHub, GitLab, Gitea and S3 APIs plus downloads) for end-to-end tests.
 
 `github` is the only backend in the default feature set. The S3 backend requires the `s3` feature; `s3-auth` implies `s3`. `gitlab`, `gitea`, `gitee`, and `manifest` each require their own feature.
 
@@ -261,7 +266,8 @@ applied (when both apply, both must pass):
   the digest if an asset is replaced — so it is not a substitute for the `signatures` feature.
 - **A known digest you pass explicitly** (e.g. one published in a `SHA256SUMS` file alongside
   the release) via `verify_checksum`. The algorithm is chosen by the `Checksum` variant
-  (`Sha256` / `Sha512`).
+  (`Sha256` / `Sha384` / `Sha512`, plus `Blake3` with the `checksums-blake3` feature), or parsed
+  from an `algorithm:hex` or SRI (`sha384-<base64>`) string with `Checksum::parse_digest`.
 
 Both complement the `signatures` feature (zipsign), which verifies authenticity rather than a
 published digest.
@@ -282,6 +288,15 @@ fn update() -> Result<(), Box<dyn std::error::Error>> {
 }
 ```
 
+### Testing the new binary before installing
+
+`self_test(SelfTest::new())` runs the extracted binary as `<new> --version` before it replaces
+anything, with an empty environment and a timeout, and rejects the update unless it exits 0 and
+prints the release version. That catches a wrong-architecture or truncated binary. Arguments,
+timeout, and kept environment variables are configurable; see the
+[`self_test`](crate::self_test) module. For any other check, register a `verify_binary` hook,
+which runs after the self-test.
+
 ### Checking for an update without installing
 
 To check whether a newer release exists without downloading or installing anything, call
@@ -375,14 +390,20 @@ Requests to the GitHub REST API are rate limited by GitHub itself, not by this c
 - An update check costs **one** API request (the latest-release lookup, or one request per page of a
   paginated listing). The asset **download** itself is a CDN redirect and does not count against the
   core API limit.
-- When you are rate limited, GitHub responds with **HTTP 403** (and an `x-ratelimit-remaining: 0`
-  header), which this crate surfaces as `Error::Unauthorized { status: 403, .. }` -- the same
-  variant as a genuine auth failure, so recognize it by the symptom (a 403 that appears only under
-  frequent checking).
+- When you are rate limited, GitHub responds with **HTTP 403** (with `x-ratelimit-remaining: 0`)
+  or **429**, which this crate surfaces as `Error::RateLimited { reset_at, retry_after, .. }` --
+  distinct from a genuine auth failure (`Error::Unauthorized`). GitLab and Gitea rate limits
+  (`RateLimit-*` headers, 429) are recognized the same way.
+- With a retry budget (`retries`), a rate-limited request waits for the time the server asks for
+  (`Retry-After`, or the quota reset) instead of the usual backoff, as long as that is no longer
+  than `max_rate_limit_wait` (60s by default); a longer wait fails at once with `RateLimited`.
+- `UpdateConfig::rate_limit()` on a built `Update` reports the last rate-limit headers its requests
+  saw (`limit`, `remaining`, `reset_at`), so you can check the remaining quota.
 - To avoid it: set an `auth_token` (5000/hour), and check less often -- the
   [`UpdateCheckGuard`](crate::check_interval::UpdateCheckGuard) above throttles how often you check.
-  The retry/backoff setters do **not** help here; retrying a rate-limited request only consumes more
-  quota.
+  Setting `cache_dir` keeps the listing pages on disk and revalidates them with `If-None-Match`; an
+  unchanged listing comes back as `304 Not Modified`, which GitHub does not count against the
+  limit.
 
 ### Listing releases (`ReleaseList`)
 
@@ -503,9 +524,16 @@ fn wont_compile() -> Result<(), Box<dyn std::error::Error>> {
 
 ### Custom HTTP client
 
-The `.timeout()` / `.request_header()` / `.retries()` builder knobs cover most transport needs, but
-for full control — custom TLS roots / mTLS, connection pooling, redirect policy, proxy-with-auth, or
-simply reusing your application's existing client — you can hand the crate a **pre-built client**.
+The `.timeout()` / `.request_header()` / `.retries()` / `.add_root_certificate()` / `.proxy()`
+builder knobs cover most transport needs. `.proxy()` takes a proxy URL (`http://`, `https://`,
+`socks5://`, `socks5h://`) or a `self_update::Proxy` carrying basic-auth credentials and a list of
+hosts to reach directly, e.g.
+`.proxy(Proxy::new("http://proxy.corp:3128").basic_auth("user", "pass").no_proxy(["localhost"]))`;
+`HTTP(S)_PROXY` / `NO_PROXY` from the environment keep working without it. The ureq client trusts
+the bundled Mozilla roots unless `.tls_root_store(..)` selects the OS store (`native-certs`). For
+full control —
+mTLS, connection pooling, redirect policy, or simply reusing your application's existing client —
+you can hand the crate a **pre-built client**.
 It is used for both the release listing and the download. The client-specific convenience setters
 are `reqwest_client` (a blocking `reqwest::blocking::Client`, used by the blocking API),
 `reqwest_async_client` (an async `reqwest::Client`, used by the `*_async` verbs), and `ureq_agent`
@@ -657,6 +685,9 @@ pub use zipsign_api;
 /// New binaries embed only the new key.  Once the transition window has passed
 /// and no old binaries remain in the field, releases only need the new key's
 /// signature.
+///
+/// Dual-signing cannot withdraw trust from a leaked key. The [`keys`] module adds
+/// validity windows, signed revocation lists, and key sets a release can deliver.
 #[cfg(feature = "signatures")]
 #[cfg_attr(docsrs, doc(cfg(feature = "signatures")))]
 pub type VerifyingKey = [u8; zipsign_api::PUBLIC_KEY_LENGTH];
@@ -664,7 +695,6 @@ pub type VerifyingKey = [u8; zipsign_api::PUBLIC_KEY_LENGTH];
 #[cfg(feature = "progress-bar")]
 use indicatif::{ProgressBar, ProgressStyle as IndicatifProgressStyle};
 use log::debug;
-#[cfg(feature = "progress-bar")]
 use std::cmp::min;
 use std::fs;
 use std::io;
@@ -676,12 +706,36 @@ pub mod backends;
 pub mod check_interval;
 #[cfg(feature = "checksums")]
 mod checksum;
+#[cfg(feature = "elevate")]
+#[cfg_attr(docsrs, doc(cfg(feature = "elevate")))]
+pub mod elevate;
 pub mod errors;
+mod extract_limits;
+mod free_space;
 pub mod http_client;
+#[cfg(feature = "signatures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "signatures")))]
+pub mod keys;
+mod package_manager;
+#[cfg(all(unix, feature = "preserve-metadata"))]
+mod preserve;
+mod proxy;
+mod rate_limit;
+pub mod receipt;
+mod redirect;
 pub mod restart;
+pub mod self_test;
+#[cfg(feature = "stage")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stage")))]
+pub mod staged;
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_server;
 mod tls;
+pub mod uninstall;
 pub mod update;
 pub mod version;
+mod versioned;
 
 /// An opaque TLS root CA certificate, supplied to a backend builder or a [`Download`] via the
 /// `add_root_certificate` setter so the crate-built HTTP client trusts a private/internal CA.
@@ -690,17 +744,53 @@ pub mod version;
 /// is built, not at construction.
 pub use tls::Certificate;
 
+/// Which root CA set the crate-built ureq client trusts: the bundled Mozilla roots, the OS trust
+/// store (`native-certs`), or both. Chosen with the `tls_root_store` setter.
+pub use tls::RootStore;
+
+/// An explicit HTTP(S)/SOCKS5 proxy with optional basic-auth credentials and a no-proxy list,
+/// supplied to a backend builder or a [`Download`] via the `proxy` setter. Construct with
+/// [`Proxy::new`](crate::Proxy::new) (or pass a URL string); the URL is validated when the client
+/// is built, not at construction.
+pub use proxy::Proxy;
+
+/// Rate-limit headers (`Retry-After`, `X-RateLimit-*` / `RateLimit-*`) parsed from a release host's
+/// response. Carried by the last-observed state on an `Update`
+/// ([`UpdateConfig::rate_limit`](crate::update::UpdateConfig::rate_limit)); a rejected request
+/// surfaces as [`Error::RateLimited`].
+pub use rate_limit::RateLimit;
+
+/// Limits on the uncompressed bytes, entries and path depth an [`Extract`] unpacks, against
+/// decompression bombs. Set with [`Extract::limits`], or the `extract_limits` builder setter for
+/// an update.
+pub use extract_limits::ExtractLimits;
+
+/// Which redirects a request may follow (hop limit, allowed hosts, `https` downgrade), supplied to
+/// a backend builder or a [`Download`] via the `redirect_policy` setter, and one followed hop as
+/// reported to the `redirect_callback`.
+pub use redirect::{RedirectHop, RedirectPolicy};
+
+/// A versioned side-by-side install layout (`versions/<version>/` plus an atomically flipped
+/// `current` symlink), supplied to a backend builder via the `versioned_install` setter.
+pub use versioned::VersionedInstall;
+
 /// Re-export the crate's [`Error`] and [`Result`] at the crate root,
 /// so consumers (and `ReleaseSource` implementors) can write `self_update::Result<T>` /
 /// `self_update::Error` without naming the `errors` module.
 pub use errors::{Error, Result};
 
-/// A checksum variant (`Sha256` / `Sha512`) used with `verify_checksum` to validate a downloaded
-/// artifact against a known digest before installation. Requires the `checksums` feature.
+/// A checksum variant (`Sha256` / `Sha384` / `Sha512`, or `Blake3`) used with `verify_checksum`
+/// to validate a downloaded artifact against a known digest before installation. Requires the `checksums` feature.
 #[cfg(feature = "checksums")]
 #[cfg_attr(docsrs, doc(cfg(feature = "checksums")))]
 pub use checksum::Checksum;
 
+/// A writer adapter that verifies a download against several [`Checksum`]s in the same pass
+/// that writes it. Requires the `checksums` feature.
+#[cfg(feature = "checksums")]
+#[cfg_attr(docsrs, doc(cfg(feature = "checksums")))]
+pub use checksum::ChecksumWriter;
+
 use http_client::header;
 
 /// The User-Agent sent on the crate's own requests (API listings and downloads) when the caller
@@ -1018,11 +1108,13 @@ fn detect_archive(path: &path::Path) -> Result<ArchiveKind> {
 ///     * Io - opening files
 ///     * Io - gzip decoding
 ///     * Io - archive unpacking
+///     * ArchiveLimitExceeded - the archive unpacks past one of its [`limits`](Self::limits)
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct Extract {
     source: path::PathBuf,
     archive: Option<ArchiveKind>,
+    limits: ExtractLimits,
 }
 /// A [`Read`](io::Read) over an archive's bytes with any single compression layer (`.gz`, `.xz`)
 /// transparently decoded, so the tar/plain readers above it see the decompressed stream. `Plain`
@@ -1030,7 +1122,7 @@ pub struct Extract {
 /// feature is enabled; [`detect_archive`] rejects a compression whose feature is off before this
 /// is ever built. The gzip layer decodes as a stream; the xz layer is decoded up front into memory
 /// (the `lzma-rs` decoder is one-shot), which is fine for the modestly sized release artifacts this
-/// crate downloads to a temp file.
+/// crate downloads to a temp file, and is bounded by `max_total_size` when one is set.
 enum ArchiveReader {
     Plain(fs::File),
     // Boxed: a `GzDecoder` is far larger than the other variants, so an unboxed variant would bloat
@@ -1060,6 +1152,7 @@ impl Extract {
         Self {
             source: source.as_ref().to_path_buf(),
             archive: None,
+            limits: ExtractLimits::default(),
         }
     }
 
@@ -1070,10 +1163,18 @@ impl Extract {
         self
     }
 
+    /// Bound what extraction may unpack. A limit exceeded while streaming the archive fails with
+    /// [`Error::ArchiveLimitExceeded`]; none are set by default.
+    pub fn limits(&mut self, limits: ExtractLimits) -> &mut Self {
+        self.limits = limits;
+        self
+    }
+
     #[allow(unused_variables)]
     fn get_archive_reader(
         source: fs::File,
         compression: Option<Compression>,
+        budget: &extract_limits::Budget,
     ) -> Result<ArchiveReader> {
         match compression {
             None => Ok(ArchiveReader::Plain(source)),
@@ -1087,10 +1188,12 @@ impl Extract {
                 // whole `.xz` stream into memory and hand the tar/plain layer a cursor over it.
                 let mut input = io::BufReader::new(source);
                 let mut decoded = Vec::new();
-                lzma_rs::xz_decompress(&mut input, &mut decoded).map_err(|e| Error::Internal {
-                    message: format!("failed to decode xz stream: {e}"),
-                    source: None,
-                })?;
+                let result = lzma_rs::xz_decompress(&mut input, &mut budget.sink(&mut decoded))
+                    .map_err(|e| Error::Internal {
+                        message: format!("failed to decode xz stream: {e}"),
+                        source: None,
+                    });
+                budget.check(result)?;
                 Ok(ArchiveReader::Xz(io::Cursor::new(decoded)))
             }
             // A compression whose decoder feature is disabled is rejected by `detect_archive`
@@ -1139,7 +1242,8 @@ impl Extract {
         // We cannot use a feature flag in a match arm. To bypass this the code block is
         // isolated in a closure and called accordingly.
         let extract_into_plain_or_tar = |source: fs::File, compression: Option<Compression>| {
-            let mut reader = Self::get_archive_reader(source, compression)?;
+            let mut budget = extract_limits::Budget::new(self.limits);
+            let mut reader = Self::get_archive_reader(source, compression, &budget)?;
 
             match archive {
                 ArchiveKind::Plain(_) => {
@@ -1157,13 +1261,15 @@ impl Extract {
                     })?;
                     let mut out_path = into_dir.join(file_name);
                     out_path.set_extension("");
+                    budget.entry(path::Path::new(file_name), 0)?;
                     let mut out_file = fs::File::create(&out_path)?;
-                    io::copy(&mut reader, &mut out_file)?;
+                    budget.copy_entry(&mut reader, &mut out_file)?;
                 }
                 #[cfg(feature = "archive-tar")]
                 ArchiveKind::Tar(_) => {
-                    let mut archive = tar::Archive::new(reader);
-                    archive.unpack(into_dir)?;
+                    let mut archive = tar::Archive::new(budget.stream(reader));
+                    let result = unpack_tar(&mut archive, into_dir, &mut budget);
+                    budget.check(result)?;
                 }
                 #[allow(unreachable_patterns)]
                 _ => unreachable!(
@@ -1193,6 +1299,7 @@ impl Extract {
                 // symlinked-parent traversal that a per-entry lexical check cannot catch).
                 fs::create_dir_all(into_dir)?;
                 let canonical_root = fs::canonicalize(into_dir)?;
+                let mut budget = extract_limits::Budget::new(self.limits);
 
                 for i in 0..archive.len() {
                     let mut file = archive.by_index(i)?;
@@ -1206,6 +1313,7 @@ impl Extract {
                         });
                     };
                     let output_path = into_dir.join(&rel_path);
+                    budget.entry(&rel_path, file.size())?;
 
                     if file.is_dir() {
                         fs::create_dir_all(&output_path)?;
@@ -1262,12 +1370,11 @@ impl Extract {
                     #[cfg(unix)]
                     if file.is_symlink() {
                         use std::ffi::OsStr;
-                        use std::io::Read;
                         use std::os::unix::ffi::OsStrExt;
 
                         let entry_name = file.name().to_string();
                         let mut target_bytes = Vec::new();
-                        file.read_to_end(&mut target_bytes)?;
+                        budget.copy_entry(&mut file, &mut target_bytes)?;
                         let target = path::Path::new(OsStr::from_bytes(&target_bytes));
 
                         // The link lives at `into_dir/rel_path`; a relative target resolves against
@@ -1299,7 +1406,7 @@ impl Extract {
                     }
 
                     let mut output = fs::File::create(&output_path)?;
-                    io::copy(&mut file, &mut output)?;
+                    budget.copy_entry(&mut file, &mut output)?;
                     // Preserve the archived unix permission mode (notably the executable bit) so a
                     // binary extracted from a zip is runnable when installed to a custom path.
                     // Mask off the setuid/setgid/sticky bits (`0o7000`): a crafted archive must not
@@ -1349,7 +1456,8 @@ impl Extract {
         // We cannot use a feature flag in a match arm. To bypass this the code block is
         // isolated in a closure and called accordingly.
         let extract_file_plain_or_tar = |source: fs::File, compression: Option<Compression>| {
-            let mut reader = Self::get_archive_reader(source, compression)?;
+            let mut budget = extract_limits::Budget::new(self.limits);
+            let mut reader = Self::get_archive_reader(source, compression, &budget)?;
 
             match archive {
                 ArchiveKind::Plain(_) => {
@@ -1367,30 +1475,38 @@ impl Extract {
                         source: None,
                     })?;
                     let out_path = into_dir.join(file_name);
+                    budget.entry(path::Path::new(file_name), 0)?;
                     let mut out_file = fs::File::create(out_path)?;
-                    io::copy(&mut reader, &mut out_file)?;
+                    budget.copy_entry(&mut reader, &mut out_file)?;
                 }
                 #[cfg(feature = "archive-tar")]
                 ArchiveKind::Tar(_) => {
                     debug!("Extracting from tar");
 
-                    let mut archive = tar::Archive::new(reader);
-                    let mut entry = archive
-                        .entries()?
-                        .filter_map(|e| e.ok())
-                        .find(|e| {
-                            let p = e.path();
-                            debug!("Archive path: {:?}", p);
-                            p.ok().filter(|p| p == file_to_extract).is_some()
-                        })
-                        .ok_or_else(|| Error::Internal {
-                            message: format!(
-                                "Could not find the required path in the archive: {:?}",
-                                file_to_extract
-                            ),
-                            source: None,
-                        })?;
-                    entry.unpack_in(into_dir)?;
+                    let mut archive = tar::Archive::new(budget.stream(reader));
+                    let mut found = None;
+                    // Entries skipped on the way count against the limits too.
+                    for e in archive.entries()?.filter_map(|e| e.ok()) {
+                        let p = e.path().map(|p| p.into_owned());
+                        debug!("Archive path: {:?}", p);
+                        let p = p.ok();
+                        budget.entry(p.as_deref().unwrap_or(path::Path::new("")), e.size())?;
+                        if p.as_deref() == Some(file_to_extract) {
+                            found = Some(e);
+                            break;
+                        }
+                    }
+                    // A limit tripped mid-stream ends the iteration early: report it rather than
+                    // a missing path.
+                    let mut entry = budget.check(found.ok_or_else(|| Error::Internal {
+                        message: format!(
+                            "Could not find the required path in the archive: {:?}",
+                            file_to_extract
+                        ),
+                        source: None,
+                    }))?;
+                    let result = entry.unpack_in(into_dir).map_err(Error::from);
+                    budget.check(result)?;
                 }
                 #[allow(unreachable_patterns)]
                 _ => unreachable!(
@@ -1421,6 +1537,7 @@ impl Extract {
                     source: None,
                 })?;
                 let mut file = archive.by_name(file_name)?;
+                let mut budget = extract_limits::Budget::new(self.limits);
 
                 let Some(rel_path) = file.enclosed_name() else {
                     return Err(Error::Internal {
@@ -1428,6 +1545,7 @@ impl Extract {
                         source: None,
                     });
                 };
+                budget.entry(&rel_path, file.size())?;
                 // A symlink entry has no regular-file payload; its "contents" are the link target
                 // path. Rather than write that target string out as `file_to_extract`, reject it
                 // (see the rustdoc): use `extract_into` to restore symlinks. Rejecting on every
@@ -1451,7 +1569,7 @@ impl Extract {
                 }
 
                 let mut output = fs::File::create(&output_path)?;
-                io::copy(&mut file, &mut output)?;
+                budget.copy_entry(&mut file, &mut output)?;
                 // Preserve the archived unix permission mode so the extracted binary is runnable,
                 // but mask off the setuid/setgid/sticky bits (`0o7000`) so a crafted archive cannot
                 // install a setuid binary; only the standard `rwx` bits (`0o777`) are honored.
@@ -1466,6 +1584,38 @@ impl Extract {
     }
 }
 
+/// `tar::Archive::unpack`, one entry at a time so each is checked against `budget` before it is
+/// written. Directories are deferred and applied deepest first, as `tar` does, so a read-only
+/// directory does not block what is unpacked beneath it.
+#[cfg(feature = "archive-tar")]
+fn unpack_tar<R: io::Read>(
+    archive: &mut tar::Archive<R>,
+    into_dir: &path::Path,
+    budget: &mut extract_limits::Budget,
+) -> Result<()> {
+    if into_dir.symlink_metadata().is_err() {
+        fs::create_dir_all(into_dir)?;
+    }
+    // Canonical, so Windows treats long unpacked paths as extended-length ones.
+    let dst = fs::canonicalize(into_dir).unwrap_or_else(|_| into_dir.to_path_buf());
+
+    let mut directories = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        budget.entry(&entry.path()?, entry.size())?;
+        if entry.header().entry_type() == tar::EntryType::Directory {
+            directories.push(entry);
+        } else {
+            entry.unpack_in(&dst)?;
+        }
+    }
+    directories.sort_by(|a, b| b.path_bytes().cmp(&a.path_bytes()));
+    for mut dir in directories {
+        dir.unpack_in(&dst)?;
+    }
+    Ok(())
+}
+
 /// Lexically decide whether a zip symlink target escapes the extraction root.
 ///
 /// `link_parent` is the link entry's parent directory expressed relative to the extraction root
@@ -1680,6 +1830,13 @@ impl MoveAll {
     /// The queued moves are drained as they are applied, so calling `commit` again is a no-op that
     /// returns `Ok(())`.
     pub fn commit(&mut self) -> Result<()> {
+        self.commit_then(|| Ok(()))
+    }
+
+    /// [`commit`](Self::commit), then run `last` once every move has applied, rolling every move
+    /// back if it fails. The update pipeline replaces the running executable here (via
+    /// `self_replace`, which is not a plain rename), so it joins the same transaction.
+    pub(crate) fn commit_then(&mut self, last: impl FnOnce() -> Result<()>) -> Result<()> {
         // Drain the queue so a second `commit` is a no-op rather than re-running already-applied
         // moves against now-missing sources.
         let moves = std::mem::take(&mut self.moves);
@@ -1724,6 +1881,10 @@ impl MoveAll {
             });
         }
 
+        if let Err(e) = last() {
+            rollback(&applied);
+            return Err(e);
+        }
         Ok(())
     }
 }
@@ -1808,6 +1969,14 @@ impl std::fmt::Debug for AssetMatcher {
     }
 }
 
+/// An alternate URL a [`Download`] fails over to, with the headers to send it. `None` sends the
+/// primary URL's headers, minus an `Authorization` when the mirror is on another host.
+#[derive(Clone, Debug)]
+struct DownloadMirror {
+    url: String,
+    headers: Option<http_client::header::HeaderMap>,
+}
+
 /// Download things into files
 ///
 /// With optional progress bar
@@ -1816,6 +1985,9 @@ pub struct Download {
     show_progress: bool,
     url: String,
     headers: http_client::header::HeaderMap,
+    /// Alternate URLs tried in order when the request to [`url`](Self::url) cannot connect or gets
+    /// a 5xx (see [`mirror`](Self::mirror)).
+    mirrors: Vec<DownloadMirror>,
     #[cfg(feature = "progress-bar")]
     progress_template: String,
     #[cfg(feature = "progress-bar")]
@@ -1826,6 +1998,20 @@ pub struct Download {
     /// preserving prior unbounded behavior. When set, the streaming download aborts with an error as
     /// soon as the total bytes written would exceed this many bytes.
     max_download_size: Option<u64>,
+    /// The update's free-space preflight, run on the response's `Content-Length` before anything
+    /// is written when the release listing gave no asset size.
+    space_check: Option<free_space::SpaceCheck>,
+    /// Upper bound on concurrent `Range` requests [`download_to_file`](Self::download_to_file)
+    /// splits the download into. `1` (the default) always uses a single request.
+    parallel_chunks: usize,
+    /// Cap on the download rate in bytes per second across all of its requests. `None` (the
+    /// default) does not throttle.
+    max_bandwidth: Option<u64>,
+    /// Which redirects the download's requests follow (see
+    /// [`redirect_policy`](Self::redirect_policy)).
+    redirect_policy: RedirectPolicy,
+    /// Called with each redirect the download's requests follow.
+    on_redirect: Option<redirect::RedirectCallback>,
     /// Number of times to retry establishing the download request (before any bytes are streamed)
     /// with exponential backoff. `0` (the default) means a single attempt, preserving the prior
     /// no-retry behavior. A failure that occurs *after* streaming has begun is not retried (it would
@@ -1833,6 +2019,8 @@ pub struct Download {
     retries: u32,
     retry_base_delay: std::time::Duration,
     retry_max_delay: std::time::Duration,
+    /// Longest server-requested wait a rate-limited download request retry sleeps through.
+    max_rate_limit_wait: std::time::Duration,
     /// Optional user-supplied sync HTTP client (used through the trait); `None` => crate default.
     client: Option<std::sync::Arc<dyn http_client::HttpClient>>,
     /// Optional user-supplied async HTTP client; `None` => crate default. Async is reqwest-only.
@@ -1841,6 +2029,12 @@ pub struct Download {
     /// Custom TLS root CA certificates to bake into the crate-built client when no client was
     /// injected (see [`add_root_certificate`](Self::add_root_certificate)).
     root_certificates: Vec<Certificate>,
+    /// Which root CA set a crate-built ureq client trusts (see
+    /// [`tls_root_store`](Self::tls_root_store)).
+    root_store: RootStore,
+    /// Explicit proxy to bake into the crate-built client when no client was injected (see
+    /// [`proxy`](Self::proxy)).
+    proxy: Option<Proxy>,
     /// First error from a `request_header(name, value)` argument that wasn't a valid HTTP header.
     /// Deferred like the builders' `request_header` so the setter stays infallible; surfaced from
     /// [`download_to`](Self::download_to) as an `Error::InvalidHeader`.
@@ -1852,7 +2046,8 @@ impl std::fmt::Debug for Download {
         let mut s = f.debug_struct("Download");
         s.field("show_progress", &self.show_progress)
             .field("url", &self.url)
-            .field("headers", &self.headers);
+            .field("headers", &self.headers)
+            .field("mirrors", &self.mirrors);
         #[cfg(feature = "progress-bar")]
         s.field("progress_template", &self.progress_template)
             .field("progress_chars", &self.progress_chars);
@@ -1862,6 +2057,14 @@ impl std::fmt::Debug for Download {
                 &self.on_progress.as_ref().map(|_| "<callback>"),
             )
             .field("max_download_size", &self.max_download_size)
+            .field("space_check", &self.space_check)
+            .field("parallel_chunks", &self.parallel_chunks)
+            .field("max_bandwidth", &self.max_bandwidth)
+            .field("redirect_policy", &self.redirect_policy)
+            .field(
+                "on_redirect",
+                &self.on_redirect.as_ref().map(|_| "<callback>"),
+            )
             .field("client", &self.client.as_ref().map(|_| "<http_client>"));
         #[cfg(feature = "async")]
         s.field(
@@ -1871,7 +2074,9 @@ impl std::fmt::Debug for Download {
         s.field(
             "root_certificates",
             &format_args!("<{} root_certificates>", self.root_certificates.len()),
-        );
+        )
+        .field("root_store", &self.root_store)
+        .field("proxy", &self.proxy);
         s.finish()
     }
 }
@@ -1885,6 +2090,12 @@ fn max_download_size_exceeded(cap: u64) -> Error {
     )))
 }
 
+/// Whether a failed download request should move on to the next mirror: the host could not be
+/// reached (a transport error) or answered with a server error (5xx).
+fn fails_over_to_mirror(e: &Error) -> bool {
+    matches!(e, Error::Transport(_)) || e.http_status().is_some_and(|status| status >= 500)
+}
+
 impl Download {
     /// Specify download url. Accepts anything string-like (`&str`, `String`, …).
     pub fn from_url(url: impl Into<String>) -> Self {
@@ -1892,6 +2103,7 @@ impl Download {
             show_progress: false,
             url: url.into(),
             headers: http_client::header::HeaderMap::new(),
+            mirrors: vec![],
             #[cfg(feature = "progress-bar")]
             progress_template: DEFAULT_PROGRESS_TEMPLATE.to_string(),
             #[cfg(feature = "progress-bar")]
@@ -1899,17 +2111,80 @@ impl Download {
             timeout: None,
             on_progress: None,
             max_download_size: None,
+            space_check: None,
+            parallel_chunks: 1,
+            max_bandwidth: None,
+            redirect_policy: RedirectPolicy::new(),
+            on_redirect: None,
             retries: 0,
             retry_base_delay: std::time::Duration::from_millis(100),
             retry_max_delay: std::time::Duration::from_millis(3200),
+            max_rate_limit_wait: std::time::Duration::from_secs(60),
             client: None,
             #[cfg(feature = "async")]
             async_client: None,
             root_certificates: vec![],
+            root_store: RootStore::default(),
+            proxy: None,
             header_error: None,
         }
     }
 
+    /// Add an alternate URL for the same file, tried when the previous URL cannot be reached or
+    /// answers with a 5xx status. Call multiple times to add more; mirrors are tried in the order
+    /// added, each with the full retry budget. A mirror is sent the same request headers as the
+    /// primary URL, except `Authorization`, which only goes to a mirror on the primary URL's host.
+    pub fn mirror(&mut self, url: impl Into<String>) -> &mut Self {
+        self.mirrors.push(DownloadMirror {
+            url: url.into(),
+            headers: None,
+        });
+        self
+    }
+
+    /// Internal: add a mirror with its own request headers (used by the update flow, which gates
+    /// the auth token on each mirror's host).
+    pub(crate) fn add_mirror_with_headers(
+        &mut self,
+        url: impl Into<String>,
+        headers: http_client::header::HeaderMap,
+    ) -> &mut Self {
+        self.mirrors.push(DownloadMirror {
+            url: url.into(),
+            headers: Some(headers),
+        });
+        self
+    }
+
+    /// The URLs to request, primary first, each with the headers to send it (the default
+    /// User-Agent filled in).
+    fn sources(&self) -> Vec<(&str, http_client::header::HeaderMap)> {
+        let primary_host = backends::common::host_of(&self.url);
+        let mirrors = self.mirrors.iter().map(|mirror| {
+            let headers = mirror.headers.clone().unwrap_or_else(|| {
+                let mut headers = self.headers.clone();
+                if primary_host.is_none() || backends::common::host_of(&mirror.url) != primary_host
+                {
+                    headers.remove(header::AUTHORIZATION);
+                }
+                headers
+            });
+            (mirror.url.as_str(), headers)
+        });
+        std::iter::once((self.url.as_str(), self.headers.clone()))
+            .chain(mirrors)
+            .map(|(url, mut headers)| {
+                if !headers.contains_key(header::USER_AGENT) {
+                    headers.insert(
+                        header::USER_AGENT,
+                        DEFAULT_USER_AGENT.parse().expect("invalid user-agent"),
+                    );
+                }
+                (url, headers)
+            })
+            .collect()
+    }
+
     /// Toggle the download progress bar. Named to match the `Update` builder's setter of the same
     /// name.
     pub fn show_download_progress(&mut self, b: bool) -> &mut Self {
@@ -1932,6 +2207,93 @@ impl Download {
         self
     }
 
+    /// Let [`download_to_file`](Self::download_to_file) split the download into up to `chunks`
+    /// concurrent `Range` requests when the server advertises `Accept-Ranges: bytes` and a
+    /// `Content-Length`. Each chunk is at least 1 MiB, so smaller files use fewer requests.
+    /// Defaults to `1` (a single request); `0` is treated as `1`. Has no effect on
+    /// [`download_to`](Self::download_to), whose writer cannot be written at offsets.
+    pub fn parallel_chunks(&mut self, chunks: usize) -> &mut Self {
+        self.parallel_chunks = chunks.max(1);
+        self
+    }
+
+    /// Limit the download to `bytes_per_sec`, so a background update does not saturate a slow
+    /// link. A token bucket holding up to one second of bytes paces every read, including the
+    /// chunks of a parallel download, which share one budget. Defaults to no limit; `0` is
+    /// treated as 1 byte per second.
+    ///
+    /// The [`progress_callback`](Self::progress_callback) is only given byte counts, so derive
+    /// the effective rate from when it is called: it runs after each pause, so the bytes between
+    /// two calls over the time between them is the throttled rate (the progress bar's rate is
+    /// computed the same way).
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Instant;
+    ///
+    /// let last = Arc::new(Mutex::new((0, Instant::now())));
+    /// let mut download = self_update::Download::from_url("https://example.com/app.tar.gz");
+    /// download
+    ///     .max_bandwidth(512 * 1024)
+    ///     .progress_callback(move |done, _total| {
+    ///         let mut last = last.lock().unwrap();
+    ///         let secs = last.1.elapsed().as_secs_f64();
+    ///         if secs > 0.0 {
+    ///             let rate = (done - last.0) as f64 / secs;
+    ///             println!("{rate:.0} bytes/s");
+    ///         }
+    ///         *last = (done, Instant::now());
+    ///     });
+    /// ```
+    pub fn max_bandwidth(&mut self, bytes_per_sec: u64) -> &mut Self {
+        self.max_bandwidth = Some(bytes_per_sec.max(1));
+        self
+    }
+
+    /// Choose which redirects the download follows: the hop limit, the hosts a redirect may lead
+    /// to, and whether an `https` to `http` downgrade is allowed (see [`RedirectPolicy`]). The
+    /// `Authorization` header is dropped on a hop to another host or off `https`. Defaults to
+    /// [`RedirectPolicy::new`]. Applies to a client injected via `set_http_client` only when that
+    /// client reports redirects instead of following them itself.
+    pub fn redirect_policy(&mut self, policy: RedirectPolicy) -> &mut Self {
+        self.redirect_policy = policy;
+        self
+    }
+
+    /// Register a callback invoked with each redirect the download follows, before the next hop
+    /// is requested. The last hop names the URL the file finally came from (for example the
+    /// presigned storage URL behind a GitHub asset). With
+    /// [`parallel_chunks`](Self::parallel_chunks), each chunk request reports its own hops.
+    pub fn redirect_callback(
+        &mut self,
+        callback: impl Fn(&RedirectHop) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_redirect = Some(redirect::RedirectCallback(std::sync::Arc::new(callback)));
+        self
+    }
+
+    /// Internal: set the redirect callback from an already-wrapped one (used by the update flow to
+    /// forward an `Update`'s callback to its download).
+    pub(crate) fn set_redirect_callback(
+        &mut self,
+        callback: Option<redirect::RedirectCallback>,
+    ) -> &mut Self {
+        self.on_redirect = callback;
+        self
+    }
+
+    /// Internal: run the update's free-space preflight on the response's `Content-Length` (used
+    /// by the update flow when the release listing gave no asset size).
+    pub(crate) fn set_space_check(&mut self, check: free_space::SpaceCheck) -> &mut Self {
+        self.space_check = Some(check);
+        self
+    }
+
+    /// Internal: whether [`download_to_file`](Self::download_to_file) may split the download.
+    pub(crate) fn is_parallel(&self) -> bool {
+        self.parallel_chunks > 1
+    }
+
     /// Register a callback invoked as the download streams, with
     /// `(bytes_downloaded_so_far, total_bytes)` — `total_bytes` is `None` when the server does
     /// not send a `Content-Length`. Independent of the terminal progress bar
@@ -1986,6 +2348,13 @@ impl Download {
         self
     }
 
+    /// Internal: set the longest rate-limit wait the request-establishment retry sleeps through
+    /// (forwarded from an `Update`'s `max_rate_limit_wait`).
+    pub(crate) fn set_max_rate_limit_wait(&mut self, max: std::time::Duration) -> &mut Self {
+        self.max_rate_limit_wait = max;
+        self
+    }
+
     /// Internal: set the injected HTTP clients from already-built `Arc`s (used by the update flow to
     /// forward an `Update`'s injected client to its download).
     pub(crate) fn set_http_client(
@@ -2008,15 +2377,37 @@ impl Download {
     /// (the injected client owns its own TLS config). A malformed certificate surfaces as an
     /// [`Error::InvalidCertificate`] from [`download_to`](Self::download_to).
     ///
-    /// **ureq-only builds**: when the `reqwest` feature is disabled, the crate-built ureq client
-    /// trusts *only* the supplied certificates (replacing the default Mozilla root set). Supply all
-    /// CA certificates you need, including any public roots, or inject a `ureq::Agent` via
-    /// `set_http_client` with a merged root set instead.
+    /// The certificates are trusted in addition to the client's normal roots: the OS trust store
+    /// for reqwest, and the [`tls_root_store`](Self::tls_root_store) choice for ureq.
     pub fn add_root_certificate(&mut self, cert: Certificate) -> &mut Self {
         self.root_certificates.push(cert);
         self
     }
 
+    /// Choose which root CA set a crate-built ureq client trusts: the bundled Mozilla roots (the
+    /// default), the OS trust store, or both (see [`RootStore`]). `System` and `Both` need the
+    /// `native-certs` feature. Ignored by the reqwest client, which always uses the OS trust store,
+    /// and by an injected client.
+    pub fn tls_root_store(&mut self, root_store: RootStore) -> &mut Self {
+        self.root_store = root_store;
+        self
+    }
+
+    /// Route the download through an explicit proxy: a URL string or a [`Proxy`] with basic-auth
+    /// credentials and a no-proxy list. Ignored when an HTTP client is injected via
+    /// `set_http_client` (the injected client owns its own proxy config). A malformed proxy surfaces
+    /// as an [`Error::InvalidProxy`] from [`download_to`](Self::download_to).
+    pub fn proxy(&mut self, proxy: impl Into<Proxy>) -> &mut Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Internal: the configured proxy (used by tests to confirm proxy forwarding).
+    #[cfg(test)]
+    pub(crate) fn proxy_config(&self) -> Option<&Proxy> {
+        self.proxy.as_ref()
+    }
+
     /// Internal: the configured custom root CA certificates (used by tests to confirm cert
     /// forwarding). Empty unless [`add_root_certificate`](Self::add_root_certificate) was called.
     #[cfg(test)]
@@ -2064,77 +2455,165 @@ impl Download {
         Ok(())
     }
 
-    /// Download the file behind the given `url` into the specified `dest`.
-    /// Show a sliding progress bar if specified.
-    /// If the resource doesn't specify a content-length, the progress bar will not be shown
-    ///
-    /// * Errors:
-    ///     * HTTP client network errors
-    ///     * Unsuccessful response status
-    ///     * Progress-bar errors
-    ///     * Reading from response to `BufReader`-buffer
-    ///     * Writing from `BufReader`-buffer to `File`
-    pub fn download_to<T: io::Write>(&self, mut dest: T) -> Result<()> {
-        use io::BufRead;
-        self.check_header_error()?;
-        let mut headers = self.headers.clone();
-        if !headers.contains_key(header::USER_AGENT) {
-            headers.insert(
-                header::USER_AGENT,
-                DEFAULT_USER_AGENT.parse().expect("invalid user-agent"),
-            );
-        }
+    /// The blocking client the download uses: the injected one, else one built with the custom
+    /// root CAs, root store and proxy when any were supplied, else the crate default.
+    fn http_client(&self) -> Result<std::sync::Arc<dyn http_client::HttpClient>> {
+        Ok(match &self.client {
+            Some(c) => c.clone(),
+            None if !self.root_certificates.is_empty()
+                || self.proxy.is_some()
+                || self.root_store != RootStore::default() =>
+            {
+                // A malformed cert / build failure surfaces here as `Error::InvalidCertificate`, a
+                // malformed proxy as `Error::InvalidProxy`.
+                http_client::configured_client(
+                    &self.root_certificates,
+                    self.proxy.as_ref(),
+                    self.root_store,
+                )?
+            }
+            None => http_client::default_client().into(),
+        })
+    }
 
-        let default;
-        let built;
-        let client: &dyn http_client::HttpClient = match self.client.as_deref() {
-            Some(c) => c,
-            None if !self.root_certificates.is_empty() => {
-                // No injected client but custom root CAs were supplied: build a client that trusts
-                // them. A malformed cert / build failure surfaces here as `Error::InvalidCertificate`.
-                built = http_client::client_with_root_certs(&self.root_certificates)
-                    .map_err(|source| Error::InvalidCertificate { source })?;
-                &*built
+    /// Async sibling of [`http_client`](Self::http_client). Async is reqwest-only.
+    #[cfg(feature = "async")]
+    fn async_http_client(&self) -> Result<std::sync::Arc<dyn http_client::AsyncHttpClient>> {
+        Ok(match &self.async_client {
+            Some(c) => c.clone(),
+            None if !self.root_certificates.is_empty() || self.proxy.is_some() => {
+                http_client::async_configured_client(&self.root_certificates, self.proxy.as_ref())?
             }
-            None => {
-                default = http_client::default_client();
-                &*default
+            None => http_client::default_async_client().into(),
+        })
+    }
+
+    /// Establish the download request, returning the response and the index of the source it came
+    /// from.
+    ///
+    /// Retry only the request-establishment phase (before any bytes are streamed): a failure after
+    /// streaming begins would corrupt the partially-written destination. With the default
+    /// `retries == 0` this is a single attempt per URL. Failover to the next mirror happens in the
+    /// same phase, once a URL's retries are spent.
+    fn request(
+        &self,
+        client: &dyn http_client::HttpClient,
+        sources: &[(&str, http_client::header::HeaderMap)],
+    ) -> Result<(Box<dyn http_client::HttpResponse>, usize)> {
+        let mut index = 0;
+        loop {
+            let (url, headers) = &sources[index];
+            match self.request_url(client, url, headers) {
+                Err(e) if index + 1 < sources.len() && fails_over_to_mirror(&e) => {
+                    log::warn!(
+                        "self_update: download from {} failed ({e}); trying the next mirror",
+                        crate::errors::redact_url(url)
+                    );
+                    index += 1;
+                }
+                result => return Ok((result?, index)),
             }
-        };
-        // Retry only the request-establishment phase (before any bytes are streamed): a failure
-        // after streaming begins would corrupt the partially-written destination. With the default
-        // `retries == 0` this is a single attempt.
-        let resp = backends::retry(
+        }
+    }
+
+    /// One URL's request, retried with the configured budget.
+    fn request_url(
+        &self,
+        client: &dyn http_client::HttpClient,
+        url: &str,
+        headers: &http_client::header::HeaderMap,
+    ) -> Result<Box<dyn http_client::HttpResponse>> {
+        backends::retry(
             self.retries,
             self.retry_base_delay,
             self.retry_max_delay,
-            || client.get(&self.url, &headers, self.timeout),
+            self.max_rate_limit_wait,
+            || {
+                redirect::follow(
+                    &self.redirect_policy,
+                    self.on_redirect.as_ref(),
+                    url,
+                    headers,
+                    |url, headers| client.get(url, headers, self.timeout),
+                )
+            },
             |e, backoff| {
                 log::warn!(
                     "self_update: download request to {} failed ({e}); retrying in {backoff}ms",
-                    crate::errors::redact_url(&self.url)
+                    crate::errors::redact_url(url)
                 );
                 std::thread::sleep(std::time::Duration::from_millis(backoff));
             },
-        )?;
-        let size = resp
-            .headers()
-            .get(http_client::header::CONTENT_LENGTH)
-            .map(|val| {
-                val.to_str()
-                    .map(|s| s.parse::<u64>().unwrap_or(0))
-                    .unwrap_or(0)
-            })
-            .unwrap_or(0);
-        // `http_client::get` already errored on a non-success status (see `download_to_async`).
-        let total = if size == 0 { None } else { Some(size) };
-        #[cfg(feature = "progress-bar")]
-        let show_progress = if size == 0 { false } else { self.show_progress };
+        )
+    }
 
-        let mut src = io::BufReader::new(resp.body());
-        let mut downloaded: u64 = 0;
+    /// Async sibling of [`request`](Self::request).
+    #[cfg(feature = "async")]
+    async fn request_async(
+        &self,
+        client: &dyn http_client::AsyncHttpClient,
+        sources: &[(&str, http_client::header::HeaderMap)],
+    ) -> Result<(Box<dyn http_client::AsyncHttpResponse>, usize)> {
+        let mut index = 0;
+        loop {
+            let (url, headers) = &sources[index];
+            match self.request_url_async(client, url, headers).await {
+                Err(e) if index + 1 < sources.len() && fails_over_to_mirror(&e) => {
+                    log::warn!(
+                        "self_update: download from {} failed ({e}); trying the next mirror",
+                        crate::errors::redact_url(url)
+                    );
+                    index += 1;
+                }
+                result => return Ok((result?, index)),
+            }
+        }
+    }
+
+    /// Async sibling of [`request_url`](Self::request_url).
+    #[cfg(feature = "async")]
+    async fn request_url_async(
+        &self,
+        client: &dyn http_client::AsyncHttpClient,
+        url: &str,
+        headers: &http_client::header::HeaderMap,
+    ) -> Result<Box<dyn http_client::AsyncHttpResponse>> {
+        backends::retry_async(
+            self.retries,
+            self.retry_base_delay,
+            self.retry_max_delay,
+            self.max_rate_limit_wait,
+            || {
+                redirect::follow_async(
+                    &self.redirect_policy,
+                    self.on_redirect.as_ref(),
+                    url,
+                    headers,
+                    |url, headers| async move { client.get(&url, &headers, self.timeout).await },
+                )
+            },
+            |e, backoff| {
+                log::warn!(
+                    "self_update: download request to {} failed ({e}); retrying in {backoff}ms",
+                    crate::errors::redact_url(url)
+                );
+            },
+            |backoff| tokio::time::sleep(std::time::Duration::from_millis(backoff)),
+        )
+        .await
+    }
+
+    /// The progress state for a response of `size` bytes (`0` when the server sent no
+    /// `Content-Length`, which also hides the progress bar). Fails first when the free-space
+    /// preflight finds no room for `size` bytes.
+    fn progress(&self, size: u64) -> Result<DownloadProgress<'_>> {
+        if size != 0
+            && let Some(check) = &self.space_check
+        {
+            check.check(size)?;
+        }
         #[cfg(feature = "progress-bar")]
-        let mut bar = if show_progress {
+        let bar = if size != 0 && self.show_progress {
             let style = IndicatifProgressStyle::default_bar()
                 .template(&self.progress_template)
                 .map_err(|e| Error::InvalidProgressStyle {
@@ -2147,35 +2626,129 @@ impl Download {
         } else {
             None
         };
-        loop {
-            let n = {
-                let buf = src.fill_buf()?;
-                dest.write_all(buf)?;
-                buf.len()
-            };
-            if n == 0 {
-                break;
-            }
-            src.consume(n);
-            downloaded += n as u64;
-            if let Some(cap) = self.max_download_size
-                && downloaded > cap
-            {
-                return Err(max_download_size_exceeded(cap));
-            }
-
+        Ok(DownloadProgress {
+            downloaded: std::sync::Mutex::new(0),
+            size,
+            cap: self.max_download_size,
+            callback: self.on_progress.as_ref(),
+            aborted: std::sync::atomic::AtomicBool::new(false),
+            throttle: self.max_bandwidth.map(Throttle::new),
             #[cfg(feature = "progress-bar")]
-            if let Some(ref mut bar) = bar {
-                bar.set_position(min(downloaded, size));
-            }
-            if let Some(ref cb) = self.on_progress {
-                (cb.0)(downloaded, total);
-            }
+            bar,
+        })
+    }
+
+    /// The byte ranges to fetch concurrently, or `None` to stream the response as is. Splitting
+    /// needs [`parallel_chunks`](Self::parallel_chunks) above 1, a response advertising
+    /// `Accept-Ranges: bytes` and a `Content-Length`, and at least [`MIN_PARALLEL_CHUNK_SIZE`]
+    /// bytes per chunk.
+    fn chunk_ranges(&self, headers: &http_client::header::HeaderMap) -> Option<Vec<(u64, u64)>> {
+        let accepts_ranges = headers
+            .get(header::ACCEPT_RANGES)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.split(',').any(|unit| unit.trim() == "bytes"));
+        let size = content_length(headers);
+        let chunks = (self.parallel_chunks as u64).min(size / MIN_PARALLEL_CHUNK_SIZE);
+        if !accepts_ranges || chunks < 2 {
+            return None;
         }
-        #[cfg(feature = "progress-bar")]
-        if let Some(ref mut bar) = bar {
-            bar.finish_with_message("Done");
+        let chunk_len = size.div_ceil(chunks);
+        Some(
+            (0..size)
+                .step_by(chunk_len as usize)
+                .map(|start| (start, min(start + chunk_len, size) - 1))
+                .collect(),
+        )
+    }
+
+    /// Download the file behind the given `url` into the specified `dest`.
+    /// Show a sliding progress bar if specified.
+    /// If the resource doesn't specify a content-length, the progress bar will not be shown
+    ///
+    /// Always a single request: see [`download_to_file`](Self::download_to_file) for parallel
+    /// chunked downloads.
+    ///
+    /// * Errors:
+    ///     * HTTP client network errors
+    ///     * Unsuccessful response status
+    ///     * Progress-bar errors
+    ///     * Reading from response to `BufReader`-buffer
+    ///     * Writing from `BufReader`-buffer to `File`
+    pub fn download_to<T: io::Write>(&self, mut dest: T) -> Result<()> {
+        self.check_header_error()?;
+        let sources = self.sources();
+        let client = self.http_client()?;
+        let (resp, _) = self.request(&*client, &sources)?;
+        // `http_client::get` already errored on a non-success status (see `download_to_async`).
+        let progress = self.progress(content_length(resp.headers()))?;
+        stream_into(resp.body(), &mut dest, &progress)?;
+        progress.finish();
+        Ok(())
+    }
+
+    /// Download into `file`, splitting the transfer into up to
+    /// [`parallel_chunks`](Self::parallel_chunks) concurrent `Range` requests when the server
+    /// supports them.
+    ///
+    /// The first request is an ordinary GET. When its response advertises `Accept-Ranges: bytes`
+    /// and a `Content-Length`, the file is sized up front, the first chunk is read from that
+    /// response, and the remaining chunks are fetched on their own threads and written at their
+    /// offsets. Otherwise the response is streamed into `file` as [`download_to`](Self::download_to)
+    /// would. Each chunk request gets the configured retries; a failed chunk fails the download.
+    /// [`max_download_size`](Self::max_download_size) and the progress bar / callback apply to the
+    /// whole file (the callback may be invoked from any of the download threads).
+    pub fn download_to_file(&self, file: &std::fs::File) -> Result<()> {
+        self.check_header_error()?;
+        let sources = self.sources();
+        let client = self.http_client()?;
+        let (resp, source) = self.request(&*client, &sources)?;
+        let size = content_length(resp.headers());
+        let progress = self.progress(size)?;
+        match self.chunk_ranges(resp.headers()) {
+            None => stream_into(resp.body(), &mut &*file, &progress)?,
+            Some(ranges) => {
+                if let Some(cap) = self.max_download_size
+                    && size > cap
+                {
+                    return Err(max_download_size_exceeded(cap));
+                }
+                file.set_len(size)?;
+                let (url, headers) = &sources[source];
+                let first = resp.headers();
+                let validator = range_validator(first);
+                let (validator, etag) = (validator.as_ref(), first.get(header::ETAG).cloned());
+                let etag = etag.as_ref();
+                let client = &*client;
+                let progress = &progress;
+                std::thread::scope(|scope| {
+                    let rest: Vec<_> = ranges[1..]
+                        .iter()
+                        .map(|&range| {
+                            scope.spawn(move || {
+                                let headers = range_headers(headers, range, validator);
+                                let result =
+                                    self.request_url(client, url, &headers).and_then(|resp| {
+                                        check_content_range(resp.headers(), range, size, etag)?;
+                                        write_range(resp.body(), file, range, progress)
+                                    });
+                                progress.abort_on_error(result)
+                            })
+                        })
+                        .collect();
+                    let first = progress.abort_on_error(write_range(
+                        resp.body(),
+                        file,
+                        ranges[0],
+                        progress,
+                    ));
+                    rest.into_iter().fold(first, |result, handle| {
+                        let chunk = handle.join().expect("download chunk thread panicked");
+                        result.and(chunk)
+                    })
+                })?;
+            }
         }
+        progress.finish();
         Ok(())
     }
 
@@ -2187,100 +2760,355 @@ impl Download {
         use futures_util::StreamExt;
 
         self.check_header_error()?;
-        let mut headers = self.headers.clone();
-        if !headers.contains_key(header::USER_AGENT) {
-            headers.insert(
-                header::USER_AGENT,
-                DEFAULT_USER_AGENT.parse().expect("invalid user-agent"),
-            );
+        let sources = self.sources();
+        let client = self.async_http_client()?;
+        let (resp, _) = self.request_async(&*client, &sources).await?;
+        // `get_async` already errored on a non-success status.
+        let progress = self.progress(content_length(resp.headers()))?;
+        let mut stream = resp.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            dest.write_all(&chunk)?;
+            progress.advance_async(chunk.len() as u64).await?;
         }
+        progress.finish();
+        Ok(())
+    }
 
-        let default;
-        let built;
-        let client: &dyn http_client::AsyncHttpClient = match self.async_client.as_deref() {
-            Some(c) => c,
-            None if !self.root_certificates.is_empty() => {
-                // No injected async client but custom root CAs were supplied: build one that trusts
-                // them. A malformed cert / build failure surfaces here as `Error::InvalidCertificate`.
-                built = http_client::async_client_with_root_certs(&self.root_certificates)
-                    .map_err(|source| Error::InvalidCertificate { source })?;
-                &*built
-            }
+    /// Async sibling of [`download_to_file`](Self::download_to_file). The chunks are fetched
+    /// concurrently on the current task rather than on threads; the first failed chunk cancels the
+    /// others.
+    #[cfg(feature = "async")]
+    pub async fn download_to_file_async(&self, file: &std::fs::File) -> Result<()> {
+        self.check_header_error()?;
+        let sources = self.sources();
+        let client = self.async_http_client()?;
+        let (resp, source) = self.request_async(&*client, &sources).await?;
+        let size = content_length(resp.headers());
+        let progress = self.progress(size)?;
+        match self.chunk_ranges(resp.headers()) {
             None => {
-                default = http_client::default_async_client();
-                &*default
+                write_range_async(resp.bytes_stream(), file, (0, u64::MAX), &progress).await?;
             }
-        };
-        // Retry only the request-establishment phase (see `download_to`).
-        let resp = backends::retry_async(
-            self.retries,
-            self.retry_base_delay,
-            self.retry_max_delay,
-            || client.get(&self.url, &headers, self.timeout),
-            |e, backoff| {
-                log::warn!(
-                    "self_update: download request to {} failed ({e}); retrying in {backoff}ms",
-                    crate::errors::redact_url(&self.url)
-                );
-            },
-            |backoff| tokio::time::sleep(std::time::Duration::from_millis(backoff)),
-        )
-        .await?;
-        let size = resp
-            .headers()
-            .get(http_client::header::CONTENT_LENGTH)
-            .map(|val| {
-                val.to_str()
-                    .map(|s| s.parse::<u64>().unwrap_or(0))
-                    .unwrap_or(0)
-            })
-            .unwrap_or(0);
-        // `get_async` already errored on a non-success status.
-        let total = if size == 0 { None } else { Some(size) };
+            Some(ranges) => {
+                if let Some(cap) = self.max_download_size
+                    && size > cap
+                {
+                    return Err(max_download_size_exceeded(cap));
+                }
+                file.set_len(size)?;
+                let (url, headers) = &sources[source];
+                let validator = range_validator(resp.headers());
+                let etag = resp.headers().get(header::ETAG).cloned();
+                let (validator, etag) = (validator.as_ref(), etag.as_ref());
+                let client = &*client;
+                let progress = &progress;
+                let rest = ranges[1..].iter().map(|&range| async move {
+                    let resp = self
+                        .request_url_async(client, url, &range_headers(headers, range, validator))
+                        .await?;
+                    check_content_range(resp.headers(), range, size, etag)?;
+                    write_range_async(resp.bytes_stream(), file, range, progress).await
+                });
+                futures_util::future::try_join(
+                    write_range_async(resp.bytes_stream(), file, ranges[0], progress),
+                    futures_util::future::try_join_all(rest),
+                )
+                .await?;
+            }
+        }
+        progress.finish();
+        Ok(())
+    }
+}
+
+/// The smallest chunk worth a request of its own in a parallel download: smaller files are fetched
+/// with fewer requests (or a single one).
+const MIN_PARALLEL_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// The `Content-Length` of a response, or `0` when it is missing or unparsable.
+fn content_length(headers: &http_client::header::HeaderMap) -> u64 {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
+/// Progress shared by a download's streams: the running byte count checked against
+/// `max_download_size`, the progress bar, and the progress callback.
+struct DownloadProgress<'a> {
+    /// Guarded rather than atomic so the callback sees the count grow monotonically when several
+    /// chunk threads report at once.
+    downloaded: std::sync::Mutex<u64>,
+    size: u64,
+    cap: Option<u64>,
+    callback: Option<&'a ProgressCallback>,
+    /// Set when a chunk of a parallel download fails, so the other chunks stop early.
+    aborted: std::sync::atomic::AtomicBool,
+    /// The `max_bandwidth` token bucket, shared by all of the download's requests.
+    throttle: Option<Throttle>,
+    #[cfg(feature = "progress-bar")]
+    bar: Option<ProgressBar>,
+}
+
+impl DownloadProgress<'_> {
+    /// Count `n` more bytes written, failing once the total exceeds the `max_download_size` cap.
+    fn advance(&self, n: u64) -> Result<()> {
+        let mut downloaded = self
+            .downloaded
+            .lock()
+            .expect("download progress lock poisoned");
+        *downloaded += n;
+        if let Some(cap) = self.cap
+            && *downloaded > cap
+        {
+            return Err(max_download_size_exceeded(cap));
+        }
         #[cfg(feature = "progress-bar")]
-        let show_progress = if size == 0 { false } else { self.show_progress };
+        if let Some(ref bar) = self.bar {
+            bar.set_position(min(*downloaded, self.size));
+        }
+        if let Some(cb) = self.callback {
+            let total = if self.size == 0 {
+                None
+            } else {
+                Some(self.size)
+            };
+            (cb.0)(*downloaded, total);
+        }
+        Ok(())
+    }
+
+    /// Pause for the throttle (if any), then count `n` bytes via [`advance`](Self::advance).
+    fn advance_blocking(&self, n: u64) -> Result<()> {
+        if let Some(throttle) = &self.throttle {
+            std::thread::sleep(throttle.delay(n, std::time::Instant::now()));
+        }
+        self.advance(n)
+    }
+
+    /// Async sibling of [`advance_blocking`](Self::advance_blocking).
+    #[cfg(feature = "async")]
+    async fn advance_async(&self, n: u64) -> Result<()> {
+        if let Some(throttle) = &self.throttle {
+            tokio::time::sleep(throttle.delay(n, std::time::Instant::now())).await;
+        }
+        self.advance(n)
+    }
+
+    fn is_aborted(&self) -> bool {
+        self.aborted.load(std::sync::atomic::Ordering::Relaxed)
+    }
 
-        let mut downloaded: u64 = 0;
+    /// Pass a chunk's result through, telling the other chunks to stop if it failed.
+    fn abort_on_error(&self, result: Result<()>) -> Result<()> {
+        if result.is_err() {
+            self.aborted
+                .store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn finish(&self) {
         #[cfg(feature = "progress-bar")]
-        let mut bar = if show_progress {
-            let style = IndicatifProgressStyle::default_bar()
-                .template(&self.progress_template)
-                .map_err(|e| Error::InvalidProgressStyle {
-                    source: Box::new(e),
-                })?
-                .progress_chars(&self.progress_chars);
-            let pb = ProgressBar::new(size);
-            pb.set_style(style);
-            Some(pb)
+        if let Some(ref bar) = self.bar {
+            bar.finish_with_message("Done");
+        }
+    }
+}
+
+/// A token bucket pacing a download to `rate` bytes per second. It starts empty and holds at most
+/// one second of tokens, so an idle stretch allows only a short burst.
+struct Throttle {
+    rate: u64,
+    /// Available tokens (negative while in debt) as of the instant.
+    state: std::sync::Mutex<(f64, std::time::Instant)>,
+}
+
+impl Throttle {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            state: std::sync::Mutex::new((0.0, std::time::Instant::now())),
+        }
+    }
+
+    /// Take `n` tokens at `now`, returning how long to wait until the bucket is out of debt.
+    fn delay(&self, n: u64, now: std::time::Instant) -> std::time::Duration {
+        let rate = self.rate as f64;
+        let mut state = self.state.lock().expect("download throttle lock poisoned");
+        let (tokens, last) = *state;
+        let refill = now.saturating_duration_since(last).as_secs_f64() * rate;
+        let tokens = (tokens + refill).min(rate) - n as f64;
+        *state = (tokens, now.max(last));
+        if tokens >= 0.0 {
+            std::time::Duration::ZERO
         } else {
-            None
+            std::time::Duration::from_secs_f64(-tokens / rate)
+        }
+    }
+}
+
+/// Stream a whole response body into `dest`.
+fn stream_into(
+    body: Box<dyn io::Read>,
+    dest: &mut impl io::Write,
+    progress: &DownloadProgress<'_>,
+) -> Result<()> {
+    use io::BufRead;
+    let mut src = io::BufReader::new(body);
+    loop {
+        let n = {
+            let buf = src.fill_buf()?;
+            dest.write_all(buf)?;
+            buf.len()
         };
+        if n == 0 {
+            return Ok(());
+        }
+        src.consume(n);
+        progress.advance_blocking(n as u64)?;
+    }
+}
 
-        let mut stream = resp.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            dest.write_all(&chunk)?;
-            downloaded += chunk.len() as u64;
-            if let Some(cap) = self.max_download_size
-                && downloaded > cap
-            {
-                return Err(max_download_size_exceeded(cap));
-            }
+/// The validator the chunk requests send as `If-Range`, so a server whose copy changed since the
+/// first response answers with the whole new file instead of a slice of it: that response's
+/// strong `ETag`, else its `Last-Modified`. `None` when it has neither (a weak `ETag` cannot be
+/// used with `If-Range`).
+fn range_validator(
+    headers: &http_client::header::HeaderMap,
+) -> Option<http_client::header::HeaderValue> {
+    headers
+        .get(header::ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(header::LAST_MODIFIED))
+        .cloned()
+}
 
-            #[cfg(feature = "progress-bar")]
-            if let Some(ref mut bar) = bar {
-                bar.set_position(min(downloaded, size));
-            }
-            if let Some(ref cb) = self.on_progress {
-                (cb.0)(downloaded, total);
-            }
+/// The request headers for one chunk: the source's headers plus `Range: bytes=start-end`, and
+/// `If-Range` when the first response carried a validator.
+fn range_headers(
+    headers: &http_client::header::HeaderMap,
+    (start, end): (u64, u64),
+    validator: Option<&http_client::header::HeaderValue>,
+) -> http_client::header::HeaderMap {
+    let mut headers = headers.clone();
+    headers.insert(
+        header::RANGE,
+        format!("bytes={start}-{end}")
+            .parse()
+            .expect("a byte range is a valid header value"),
+    );
+    if let Some(validator) = validator {
+        headers.insert(header::IF_RANGE, validator.clone());
+    }
+    headers
+}
+
+/// Reject a chunk response that is not the requested range of the same `size`-byte object as the
+/// first response, so a file republished mid-download, or served from another copy, is never
+/// spliced together. A server that ignored `Range`, or answered `If-Range` with the whole changed
+/// file, sends no `Content-Range`; a different `ETag` (when both responses have one) or total size
+/// means another version.
+fn check_content_range(
+    headers: &http_client::header::HeaderMap,
+    (start, end): (u64, u64),
+    size: u64,
+    etag: Option<&http_client::header::HeaderValue>,
+) -> Result<()> {
+    let expected = format!("bytes {start}-{end}/{size}");
+    match headers
+        .get(header::CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(range) if range == expected => {}
+        other => {
+            return Err(Error::invalid_response(format!(
+                "expected a `Content-Range: {expected}` response for a download chunk, got {other:?}"
+            )));
         }
-        #[cfg(feature = "progress-bar")]
-        if let Some(ref mut bar) = bar {
-            bar.finish_with_message("Done");
+    }
+    match (etag, headers.get(header::ETAG)) {
+        (Some(first), Some(chunk)) if first != chunk => Err(Error::invalid_response(format!(
+            "the download changed between requests: `ETag` {first:?}, then {chunk:?}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Write the bytes `start..=end` of the file from `body` (which starts at `start`) into `file`,
+/// failing if the body ends early. Bytes past `end` are not read. Stops quietly once another chunk
+/// has failed.
+fn write_range(
+    mut body: Box<dyn io::Read>,
+    file: &std::fs::File,
+    (start, end): (u64, u64),
+    progress: &DownloadProgress<'_>,
+) -> Result<()> {
+    let mut buf = vec![0; 64 * 1024];
+    let mut offset = start;
+    while offset <= end {
+        if progress.is_aborted() {
+            return Ok(());
         }
-        Ok(())
+        let want = min(buf.len() as u64, end - offset + 1) as usize;
+        let n = body.read(&mut buf[..want])?;
+        if n == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        write_all_at(file, &buf[..n], offset)?;
+        offset += n as u64;
+        progress.advance_blocking(n as u64)?;
+    }
+    Ok(())
+}
+
+/// Async sibling of [`write_range`]. An `end` of `u64::MAX` writes the whole stream.
+#[cfg(feature = "async")]
+async fn write_range_async(
+    mut stream: futures_util::stream::BoxStream<'static, Result<bytes::Bytes>>,
+    file: &std::fs::File,
+    (start, end): (u64, u64),
+    progress: &DownloadProgress<'_>,
+) -> Result<()> {
+    use futures_util::StreamExt;
+    let mut offset = start;
+    while offset <= end {
+        let Some(chunk) = stream.next().await else {
+            if end == u64::MAX {
+                return Ok(());
+            }
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        };
+        let chunk = chunk?;
+        let n = min(chunk.len() as u64, (end - offset).saturating_add(1)) as usize;
+        write_all_at(file, &chunk[..n], offset)?;
+        offset += n as u64;
+        progress.advance_async(n as u64).await?;
+    }
+    Ok(())
+}
+
+/// Write all of `buf` at `offset` without moving a shared file cursor, so chunks can be written
+/// from several threads at once.
+#[cfg(unix)]
+fn write_all_at(file: &std::fs::File, buf: &[u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn write_all_at(file: &std::fs::File, mut buf: &[u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        let n = file.seek_write(buf, offset)?;
+        if n == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+        buf = &buf[n..];
+        offset += n as u64;
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -2531,6 +3359,31 @@ mod tests {
         assert_eq!(fs::read(&dst_b).unwrap(), b"new-b");
     }
 
+    // A failing `commit_then` hook undoes every move, restoring stashed originals and removing
+    // fresh installs, and surfaces the hook's error.
+    #[test]
+    fn move_all_commit_then_rolls_back_when_last_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let temp = tempfile::tempdir().unwrap();
+        let src_a = dir.path().join("src_a");
+        let src_b = dir.path().join("src_b");
+        fs::write(&src_a, b"new-a").unwrap();
+        fs::write(&src_b, b"new-b").unwrap();
+        let dst_a = dir.path().join("dst_a");
+        let dst_b = dir.path().join("dst_b");
+        fs::write(&dst_a, b"old-a").unwrap();
+
+        let err = MoveAll::from_temp(temp.path())
+            .add(&src_a, &dst_a)
+            .add(&src_b, &dst_b)
+            .commit_then(|| Err(Error::Aborted))
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Aborted), "got {:?}", err);
+        assert_eq!(fs::read(&dst_a).unwrap(), b"old-a");
+        assert!(!dst_b.exists(), "a fresh install is removed on rollback");
+    }
+
     #[test]
     fn move_all_rolls_back_on_failure() {
         let dir = tempfile::tempdir().unwrap();
@@ -2845,6 +3698,38 @@ mod tests {
         assert_eq!(urls[0], "https://nonroutable.invalid/asset.bin");
     }
 
+    #[test]
+    fn download_to_refuses_a_content_length_without_room_before_writing() {
+        // With the update's free-space check attached, the response's Content-Length is checked
+        // before the body is streamed; an expansion factor no filesystem can meet must fail.
+        let body = b"payload".to_vec();
+        let client = std::sync::Arc::new(DlClient {
+            body: body.clone(),
+            content_length: Some(body.len() as u64),
+            requested: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+        });
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut out = Vec::new();
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.set_http_client(
+            Some(client),
+            #[cfg(feature = "async")]
+            None,
+        );
+        dl.set_space_check(free_space::SpaceCheck {
+            temp_dir: dir.path().to_path_buf(),
+            install_dir: dir.path().to_path_buf(),
+            expansion_factor: f64::MAX,
+        });
+
+        match dl.download_to(&mut out).unwrap_err() {
+            Error::InsufficientSpace { path, .. } => assert_eq!(path, dir.path()),
+            other => panic!("expected Error::InsufficientSpace, got {:?}", other),
+        }
+        assert!(out.is_empty(), "nothing was written");
+    }
+
     #[test]
     fn download_to_handles_injected_client_without_content_length() {
         // When the injected response carries no Content-Length, `download_to` must still stream the
@@ -3076,6 +3961,99 @@ mod tests {
         assert_eq!(out, body, "a body under the cap downloads in full");
     }
 
+    #[test]
+    fn throttle_paces_reads_to_the_configured_rate() {
+        let start = std::time::Instant::now();
+        let throttle = Throttle::new(1000);
+        // The bucket starts empty: the first 500 bytes cost half a second.
+        assert_eq!(
+            throttle.delay(500, start),
+            std::time::Duration::from_millis(500)
+        );
+        // Debt accumulates across callers (e.g. parallel chunks sharing the budget).
+        assert_eq!(
+            throttle.delay(500, start),
+            std::time::Duration::from_millis(1000)
+        );
+        // Time pays the debt back; an idle stretch refills at most one second of tokens.
+        let later = start + std::time::Duration::from_secs(10);
+        assert_eq!(throttle.delay(1000, later), std::time::Duration::ZERO);
+        assert_eq!(
+            throttle.delay(250, later),
+            std::time::Duration::from_millis(250)
+        );
+    }
+
+    // The rate derived from the progress callback's timing, as `max_bandwidth` documents it, stays
+    // at the limit: 24 KiB read in three 8 KiB reads at 64 KiB/s are reported 125ms apart.
+    #[test]
+    fn download_max_bandwidth_rate_from_callback_timing_stays_at_the_limit() {
+        let body = vec![5u8; 24 * 1024];
+        let client = std::sync::Arc::new(DlClient {
+            body: body.clone(),
+            content_length: Some(body.len() as u64),
+            requested: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+        });
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.max_bandwidth(64 * 1024)
+            .progress_callback(move |done, _| {
+                sink.lock().unwrap().push((done, std::time::Instant::now()));
+            })
+            .set_http_client(
+                Some(client),
+                #[cfg(feature = "async")]
+                None,
+            );
+        dl.download_to(Vec::new()).unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(
+            reports.iter().map(|&(done, _)| done).collect::<Vec<_>>(),
+            [8192, 16384, 24576]
+        );
+        let ((first, at_first), (last, at_last)) = (reports[0], reports[2]);
+        let rate = (last - first) as f64 / (at_last - at_first).as_secs_f64();
+        assert!(
+            rate <= 64.0 * 1024.0 * 1.1,
+            "the rate seen by the callback is the throttled one, got {rate:.0} bytes/s"
+        );
+    }
+
+    #[test]
+    fn download_max_bandwidth_throttles_and_reports_the_effective_rate() {
+        let body = vec![3u8; 2048];
+        let client = std::sync::Arc::new(DlClient {
+            body: body.clone(),
+            content_length: Some(body.len() as u64),
+            requested: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+        });
+        let start = std::time::Instant::now();
+        let last_report = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let sink = last_report.clone();
+        let mut out = Vec::new();
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.max_bandwidth(8192)
+            .progress_callback(move |done, _| {
+                *sink.lock().unwrap() = Some((done, start.elapsed()));
+            })
+            .set_http_client(
+                Some(client),
+                #[cfg(feature = "async")]
+                None,
+            );
+        dl.download_to(&mut out).unwrap();
+        assert_eq!(out, body);
+        // 2 KiB at 8 KiB/s from an empty bucket: at least a quarter of a second.
+        let (done, elapsed) = last_report.lock().unwrap().unwrap();
+        assert_eq!(done, 2048);
+        assert!(
+            elapsed >= std::time::Duration::from_millis(240),
+            "the final progress report comes after the throttle delay, got {elapsed:?}"
+        );
+    }
+
     /// Async test-double response: yields the body as a single `bytes_stream` chunk and as `text`.
     #[cfg(feature = "async")]
     struct DlAsyncResponse {
@@ -3164,6 +4142,24 @@ mod tests {
         assert_eq!(urls[0], "https://nonroutable.invalid/asset.bin");
     }
 
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn download_to_async_honors_max_bandwidth() {
+        let body = vec![5u8; 2048];
+        let client = std::sync::Arc::new(DlAsyncClient {
+            body: body.clone(),
+            content_length: Some(body.len() as u64),
+            requested: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+        });
+        let mut out = Vec::new();
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.max_bandwidth(8192).set_http_client(None, Some(client));
+        let start = std::time::Instant::now();
+        dl.download_to_async(&mut out).await.unwrap();
+        assert_eq!(out, body);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(240));
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     async fn sync_and_async_injection_are_independent() {
@@ -3192,6 +4188,265 @@ mod tests {
         assert_eq!(out, body);
     }
 
+    /// A test-double client serving `body` with `Accept-Ranges: bytes` and `ETag: "v1"`, answering
+    /// a `Range` request with just that slice and its `Content-Range` (unless `honor_ranges` is off,
+    /// when it sends the whole body like a server that ignores ranges). With `republished`, every
+    /// request after the first is served that body under `ETag: "v2"` instead, and an `If-Range`
+    /// naming the old version gets the whole new body (unless `honor_if_range` is off). Records each
+    /// request's `Range` and `If-Range` headers.
+    struct RangeClient {
+        body: Vec<u8>,
+        honor_ranges: bool,
+        republished: Option<Vec<u8>>,
+        honor_if_range: bool,
+        ranges: std::sync::Mutex<Vec<Option<String>>>,
+        if_ranges: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl RangeClient {
+        fn new(body: Vec<u8>) -> Self {
+            Self {
+                body,
+                honor_ranges: true,
+                republished: None,
+                honor_if_range: true,
+                ranges: Default::default(),
+                if_ranges: Default::default(),
+            }
+        }
+
+        fn respond(&self, headers: &http_client::header::HeaderMap) -> DlResponse {
+            let header = |name| {
+                headers
+                    .get(name)
+                    .map(|v: &http_client::header::HeaderValue| v.to_str().unwrap().to_string())
+            };
+            let (range, if_range) = (
+                header(http_client::header::RANGE),
+                header(http_client::header::IF_RANGE),
+            );
+            let mut ranges = self.ranges.lock().unwrap();
+            let (mut body, etag) = match &self.republished {
+                Some(new) if !ranges.is_empty() => (new.clone(), "\"v2\""),
+                _ => (self.body.clone(), "\"v1\""),
+            };
+            ranges.push(range.clone());
+            drop(ranges);
+            self.if_ranges.lock().unwrap().push(if_range.clone());
+            let stale = self.honor_if_range && if_range.is_some_and(|v| v != etag);
+            let mut out = http_client::header::HeaderMap::new();
+            out.insert(http_client::header::ACCEPT_RANGES, "bytes".parse().unwrap());
+            out.insert(http_client::header::ETAG, etag.parse().unwrap());
+            if let Some(range) = range.filter(|_| self.honor_ranges && !stale) {
+                let (start, end) = range
+                    .strip_prefix("bytes=")
+                    .unwrap()
+                    .split_once('-')
+                    .unwrap();
+                let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+                out.insert(
+                    http_client::header::CONTENT_RANGE,
+                    format!("bytes {start}-{end}/{}", body.len())
+                        .parse()
+                        .unwrap(),
+                );
+                body = body[start..=end].to_vec();
+            }
+            out.insert(
+                http_client::header::CONTENT_LENGTH,
+                body.len().to_string().parse().unwrap(),
+            );
+            DlResponse { body, headers: out }
+        }
+    }
+
+    impl http_client::HttpClient for RangeClient {
+        fn get(
+            &self,
+            _url: &str,
+            headers: &http_client::header::HeaderMap,
+            _timeout: Option<std::time::Duration>,
+        ) -> Result<Box<dyn http_client::HttpResponse>> {
+            Ok(Box::new(self.respond(headers)))
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl http_client::AsyncHttpClient for RangeClient {
+        fn get<'a>(
+            &'a self,
+            _url: &'a str,
+            headers: &'a http_client::header::HeaderMap,
+            _timeout: Option<std::time::Duration>,
+        ) -> futures_util::future::BoxFuture<'a, Result<Box<dyn http_client::AsyncHttpResponse>>>
+        {
+            let DlResponse { body, headers } = self.respond(headers);
+            Box::pin(async move {
+                Ok(Box::new(DlAsyncResponse { body, headers })
+                    as Box<dyn http_client::AsyncHttpResponse>)
+            })
+        }
+    }
+
+    /// 3.5 MiB of non-repeating-ish bytes: large enough for three 1 MiB+ chunks.
+    fn range_body() -> Vec<u8> {
+        (0..3 * 1024 * 1024 + 512 * 1024)
+            .map(|i: u32| (i % 251) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn download_to_file_splits_into_parallel_range_requests() {
+        let body = range_body();
+        let client = std::sync::Arc::new(RangeClient::new(body.clone()));
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        let sink = reports.clone();
+        dl.parallel_chunks(8)
+            .progress_callback(move |done, total| sink.lock().unwrap().push((done, total)))
+            .set_http_client(
+                Some(client.clone()),
+                #[cfg(feature = "async")]
+                None,
+            );
+        let file = tempfile::NamedTempFile::new().unwrap();
+        dl.download_to_file(file.as_file()).unwrap();
+
+        assert_eq!(std::fs::read(file.path()).unwrap(), body);
+        let mut ranges = client.ranges.lock().unwrap().clone();
+        ranges.sort();
+        // Capped at one chunk per MiB: the initial GET serves chunk 0, two Range requests the rest.
+        assert_eq!(
+            ranges,
+            [
+                None,
+                Some("bytes=1223339-2446677".to_string()),
+                Some("bytes=2446678-3670015".to_string()),
+            ]
+        );
+        let reports = reports.lock().unwrap();
+        let total = body.len() as u64;
+        assert_eq!(reports.last(), Some(&(total, Some(total))));
+        assert!(
+            reports.windows(2).all(|w| w[0].0 < w[1].0),
+            "progress grows monotonically across chunk threads"
+        );
+    }
+
+    #[test]
+    fn download_to_file_streams_small_files_in_one_request() {
+        let body = b"small enough for one request".to_vec();
+        let client = std::sync::Arc::new(RangeClient::new(body.clone()));
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.parallel_chunks(4).set_http_client(
+            Some(client.clone()),
+            #[cfg(feature = "async")]
+            None,
+        );
+        let file = tempfile::NamedTempFile::new().unwrap();
+        dl.download_to_file(file.as_file()).unwrap();
+        assert_eq!(std::fs::read(file.path()).unwrap(), body);
+        assert_eq!(*client.ranges.lock().unwrap(), [None]);
+    }
+
+    #[test]
+    fn download_to_file_rejects_a_server_that_ignores_ranges() {
+        let client = std::sync::Arc::new(RangeClient {
+            honor_ranges: false,
+            ..RangeClient::new(range_body())
+        });
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.parallel_chunks(4).set_http_client(
+            Some(client),
+            #[cfg(feature = "async")]
+            None,
+        );
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let err = dl.download_to_file(file.as_file()).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidResponse { .. }),
+            "a chunk answered with the whole file must fail the download, got {err:?}"
+        );
+    }
+
+    #[test]
+    fn download_to_file_rejects_an_object_republished_between_ranges() {
+        let mut changed = range_body();
+        changed.reverse();
+        let mut longer = range_body();
+        longer.extend_from_slice(b"appended");
+        // `If-Range` gets the whole new file; without it, slices of the new version come back
+        // under its new `ETag`, or with a different total size.
+        for (honor_if_range, republished) in
+            [(true, changed.clone()), (false, changed), (false, longer)]
+        {
+            let client = std::sync::Arc::new(RangeClient {
+                republished: Some(republished),
+                honor_if_range,
+                ..RangeClient::new(range_body())
+            });
+            let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+            dl.parallel_chunks(4).set_http_client(
+                Some(client.clone()),
+                #[cfg(feature = "async")]
+                None,
+            );
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let err = dl.download_to_file(file.as_file()).unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidResponse { .. }),
+                "a chunk of another version must fail the download, got {err:?}"
+            );
+            let if_ranges = client.if_ranges.lock().unwrap();
+            assert_eq!(if_ranges[0], None);
+            assert!(
+                if_ranges[1..]
+                    .iter()
+                    .all(|v| v.as_deref() == Some("\"v1\"")),
+                "every chunk request is pinned to the first response's `ETag`: {if_ranges:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn download_to_file_enforces_max_download_size_before_splitting() {
+        let client = std::sync::Arc::new(RangeClient::new(range_body()));
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.parallel_chunks(4)
+            .max_download_size(1024 * 1024)
+            .set_http_client(
+                Some(client.clone()),
+                #[cfg(feature = "async")]
+                None,
+            );
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let err = dl.download_to_file(file.as_file()).unwrap_err();
+        assert!(err.to_string().contains("max_download_size"), "{err}");
+        assert_eq!(
+            client.ranges.lock().unwrap().len(),
+            1,
+            "no chunk requests are issued for a file over the cap"
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn download_to_file_async_splits_into_parallel_range_requests() {
+        let body = range_body();
+        let client = std::sync::Arc::new(RangeClient::new(body.clone()));
+        let mut dl = Download::from_url("https://nonroutable.invalid/asset.bin");
+        dl.parallel_chunks(2)
+            .set_http_client(None, Some(client.clone()));
+        let file = tempfile::NamedTempFile::new().unwrap();
+        dl.download_to_file_async(file.as_file()).await.unwrap();
+
+        assert_eq!(std::fs::read(file.path()).unwrap(), body);
+        assert_eq!(
+            *client.ranges.lock().unwrap(),
+            [None, Some("bytes=1835008-3670015".to_string())]
+        );
+    }
+
     // Regression: `progress_callback` (the byte-level hook) must still fire even when the
     // `progress-bar` feature is disabled. The terminal `indicatif` bar and the callback are
     // orthogonal; disabling the former must not silence the latter.
@@ -4564,4 +5819,118 @@ mod tests {
             other => panic!("expected Error::Internal, got {:?}", other),
         }
     }
+
+    #[allow(dead_code)]
+    fn assert_limit_exceeded(err: Error, expected: &str) {
+        match err {
+            Error::ArchiveLimitExceeded { limit, .. } => assert_eq!(limit, expected),
+            other => panic!("expected Error::ArchiveLimitExceeded, got {:?}", other),
+        }
+    }
+
+    // A small `.xz` inflating past `max_total_size` is stopped while it is decoded.
+    #[cfg(feature = "compression-tar-xz")]
+    #[test]
+    fn extract_limits_stop_an_xz_bomb() {
+        let tmp_dir = tempfile::tempdir().expect("tempdir");
+        let fp = tmp_dir.path().join("bomb.xz");
+        let mut tmp_file = File::create(&fp).expect("temp file create fail");
+        lzma_rs::xz_compress(&mut &vec![0u8; 1 << 20][..], &mut tmp_file).expect("xz encode fail");
+
+        let out_tmp = tempfile::tempdir().expect("out tempdir");
+        let err = Extract::from_source(&fp)
+            .limits(ExtractLimits::new().max_total_size(64 * 1024))
+            .extract_into(out_tmp.path())
+            .expect_err("the bomb must be refused");
+        assert_limit_exceeded(err, "max_total_size");
+    }
+
+    // Entry count, path depth and entry size are checked per tar and zip entry; generous limits
+    // unpack as before.
+    #[cfg(all(
+        feature = "archive-tar",
+        feature = "compression-tar-gz",
+        feature = "archive-zip"
+    ))]
+    #[test]
+    fn extract_limits_are_checked_per_entry() {
+        let tmp_dir = tempfile::tempdir().expect("tempdir");
+        let tar_path = tmp_dir.path().join("archive.tar.gz");
+        build_test_archive(
+            File::create(&tar_path).expect("create archive"),
+            &tar_path,
+            ArchiveKind::Tar(Some(Compression::Gz)),
+        );
+        let zip_path = tmp_dir.path().join("archive.zip");
+        build_test_archive(
+            File::create(&zip_path).expect("create archive"),
+            &zip_path,
+            ArchiveKind::Zip,
+        );
+
+        for (archive, limits, expected) in [
+            (
+                &tar_path,
+                ExtractLimits::new().max_entries(1),
+                "max_entries",
+            ),
+            (
+                &tar_path,
+                ExtractLimits::new().max_path_depth(1),
+                "max_path_depth",
+            ),
+            (
+                &tar_path,
+                ExtractLimits::new().max_entry_size(16),
+                "max_entry_size",
+            ),
+            (
+                &zip_path,
+                ExtractLimits::new().max_path_depth(1),
+                "max_path_depth",
+            ),
+            (
+                &zip_path,
+                ExtractLimits::new().max_entry_size(16),
+                "max_entry_size",
+            ),
+            (
+                &zip_path,
+                ExtractLimits::new().max_total_size(20),
+                "max_total_size",
+            ),
+        ] {
+            let out_tmp = tempfile::tempdir().expect("out tempdir");
+            let err = Extract::from_source(archive)
+                .limits(limits)
+                .extract_into(out_tmp.path())
+                .expect_err("the limit must be enforced");
+            assert_limit_exceeded(err, expected);
+        }
+
+        // Reaching `inner_archive/temp2.txt` walks past the entries before it.
+        let out_tmp = tempfile::tempdir().expect("out tempdir");
+        let err = Extract::from_source(&tar_path)
+            .limits(ExtractLimits::new().max_entries(2))
+            .extract_file(out_tmp.path(), "inner_archive/temp2.txt")
+            .expect_err("the limit must be enforced");
+        assert_limit_exceeded(err, "max_entries");
+
+        let limits = ExtractLimits::new()
+            .max_total_size(1 << 20)
+            .max_entry_size(64)
+            .max_entries(16)
+            .max_path_depth(2);
+        for archive in [&tar_path, &zip_path] {
+            let out_tmp = tempfile::tempdir().expect("out tempdir");
+            Extract::from_source(archive)
+                .limits(limits)
+                .extract_into(out_tmp.path())
+                .expect("extract fail");
+            cmp_content(
+                out_tmp.path().join("inner_archive/temp2.txt"),
+                "This is a second test!",
+            );
+        }
+    }
 }
@@ -71,6 +71,99 @@ macro_rules! request_config_setters {
             self
         }
 
+        /// Longest wait the retry loop will sleep through when a request is rate limited (HTTP 429,
+        /// or GitHub's rate-limit 403) and the server says when to come back (`Retry-After`, or the
+        /// `X-RateLimit-Reset` / `RateLimit-Reset` of an exhausted quota). Such a retry waits for the
+        /// server-requested time instead of the exponential backoff; if that is longer than `max`,
+        /// no retry is attempted and the request fails with
+        /// [`Error::RateLimited`](crate::errors::Error::RateLimited) straight away. Defaults to 60s.
+        /// Only applies when [`retries`](Self::retries) allows a retry.
+        pub fn max_rate_limit_wait(&mut self, max: std::time::Duration) -> &mut Self {
+            self.$($path).+.max_rate_limit_wait = max;
+            self
+        }
+
+        /// Cache release-listing pages in `dir` and revalidate them with conditional requests.
+        ///
+        /// Each listing page is stored with its `ETag` / `Last-Modified`. The next listing sends
+        /// them as `If-None-Match` / `If-Modified-Since`, and an unchanged page (`304 Not
+        /// Modified`) is read from the cache instead of downloaded again. GitHub does not count a
+        /// 304 against the rate limit, so a fleet that checks often stays well under the
+        /// unauthenticated quota. Applies to the release API requests of the github, gitlab,
+        /// gitea, gitee and s3 backends (listings and single-release lookups); the manifest fetch
+        /// and the binary download are not cached. The directory is created on first use. Cache
+        /// failures are logged and never fail a request. Off by default.
+        pub fn cache_dir(&mut self, dir: impl Into<std::path::PathBuf>) -> &mut Self {
+            self.$($path).+.cache_dir = Some(dir.into());
+            self
+        }
+
+        /// Add a mirror for every asset whose download URL starts with `url_prefix`: the same URL
+        /// with that prefix replaced by `mirror_prefix`.
+        ///
+        /// For example `asset_mirror("https://github.com/", "https://mirror.example.net/gh/")`
+        /// downloads `https://github.com/o/r/releases/download/v1/app.tar.gz` from
+        /// `https://mirror.example.net/gh/o/r/releases/download/v1/app.tar.gz` when GitHub cannot
+        /// be reached or answers with a 5xx status. Call multiple times to add more mirrors; they
+        /// are tried in the order added, after the asset's own
+        /// [`mirrors`](crate::update::ReleaseAsset::mirrors). The auth token is only sent to a
+        /// mirror whose host is authorized (see [`allow_auth_host`](Self::allow_auth_host)).
+        pub fn asset_mirror(
+            &mut self,
+            url_prefix: impl Into<String>,
+            mirror_prefix: impl Into<String>,
+        ) -> &mut Self {
+            self.$($path).+
+                .mirror_rewrites
+                .push((url_prefix.into(), mirror_prefix.into()));
+            self
+        }
+
+        /// Split the asset download into up to `chunks` concurrent `Range` requests when the
+        /// server advertises `Accept-Ranges: bytes` and a `Content-Length` (see
+        /// [`Download::parallel_chunks`](crate::Download::parallel_chunks)). Each chunk is at least
+        /// 1 MiB. With `checksums`, the finished archive is then verified by reading it back once
+        /// instead of while it streams. Defaults to `1` (a single request).
+        pub fn parallel_chunks(&mut self, chunks: usize) -> &mut Self {
+            self.$($path).+.parallel_chunks = chunks.max(1);
+            self
+        }
+
+        /// Limit the asset download to `bytes_per_sec` (see
+        /// [`Download::max_bandwidth`](crate::Download::max_bandwidth)), so an update running in
+        /// the background stays polite on a slow link. Release API requests are not throttled.
+        /// Defaults to no limit.
+        pub fn max_bandwidth(&mut self, bytes_per_sec: u64) -> &mut Self {
+            self.$($path).+.max_bandwidth = Some(bytes_per_sec.max(1));
+            self
+        }
+
+        /// Choose which redirects every request (release listing and the download) follows: the
+        /// hop limit, the hosts a redirect may lead to, and whether an `https` to `http` downgrade
+        /// is allowed (see [`RedirectPolicy`](crate::RedirectPolicy)). The crate follows redirects
+        /// itself, so the policy applies the same way on reqwest, ureq and a custom
+        /// [`HttpClient`](crate::http_client::HttpClient); the `Authorization` header is dropped on
+        /// a hop to another host or off `https`. An injected reqwest client or ureq agent keeps its
+        /// own redirect handling; build it without redirects for this policy to apply. Defaults to
+        /// [`RedirectPolicy::new`](crate::RedirectPolicy::new).
+        pub fn redirect_policy(&mut self, policy: crate::RedirectPolicy) -> &mut Self {
+            self.$($path).+.redirect_policy = policy;
+            self
+        }
+
+        /// Register a callback invoked with each redirect a request follows, before the next hop
+        /// is requested. For the download, the last hop names the URL the asset finally came from
+        /// (e.g. the presigned storage URL behind a GitHub release asset).
+        pub fn redirect_callback(
+            &mut self,
+            callback: impl Fn(&crate::RedirectHop) + Send + Sync + 'static,
+        ) -> &mut Self {
+            self.$($path).+.on_redirect = Some(crate::redirect::RedirectCallback(
+                std::sync::Arc::new(callback),
+            ));
+            self
+        }
+
         /// Use a custom [`HttpClient`](crate::http_client::HttpClient) for every request (release
         /// listing and the download) instead of the client the crate builds per call. This is the
         /// canonical, client-agnostic injection seam: hand over any `Arc<dyn HttpClient>` (a test
@@ -149,16 +242,49 @@ macro_rules! request_config_setters {
         /// validated at `build()` on the reqwest backend, but on a ureq-only build a malformed DER
         /// certificate is surfaced at connection time instead.
         ///
-        /// **ureq-only builds**: when the `reqwest` feature is disabled, the crate-built ureq client
-        /// trusts *only* the supplied certificates (replacing the default Mozilla root set). Supply
-        /// all CA certificates you need, including any public roots. If you need the Mozilla set plus
-        /// a custom CA, inject a pre-built `ureq::Agent` via [`ureq_agent`](Self::ureq_agent)
-        /// configured with `RootCerts::PlatformVerifier` or a merged root set instead.
+        /// The certificates are trusted in addition to the client's normal roots: the OS trust
+        /// store for reqwest, and the [`tls_root_store`](Self::tls_root_store) choice for ureq.
         pub fn add_root_certificate(&mut self, cert: crate::Certificate) -> &mut Self {
             self.$($path).+.root_certificates.push(cert);
             self
         }
 
+        /// Choose which root CA set the crate-built **ureq** client trusts: the bundled Mozilla
+        /// roots ([`RootStore::Bundled`](crate::RootStore::Bundled), the default), the OS trust
+        /// store ([`RootStore::System`](crate::RootStore::System)) or both
+        /// ([`RootStore::Both`](crate::RootStore::Both)). Pick `System` or `Both` behind a
+        /// TLS-inspecting corporate proxy whose CA is installed on the machine. Certificates from
+        /// [`add_root_certificate`](Self::add_root_certificate) are trusted on top of the choice.
+        ///
+        /// `System` and `Both` need the `native-certs` feature; without it they fail `build()` with
+        /// [`Error::InvalidCertificate`](crate::errors::Error::InvalidCertificate) on a ureq-only
+        /// build. The reqwest client always verifies against the OS trust store and ignores this,
+        /// as does an injected client.
+        pub fn tls_root_store(&mut self, root_store: crate::RootStore) -> &mut Self {
+            self.$($path).+.root_store = root_store;
+            self
+        }
+
+        /// Route every request (release listing and the download) through an explicit proxy.
+        /// Accepts a URL string (`http://`, `https://`, `socks5://` or `socks5h://`, credentials
+        /// may be embedded as `user:pass@host`) or a [`Proxy`](crate::Proxy) carrying
+        /// [`basic_auth`](crate::Proxy::basic_auth) credentials and a
+        /// [`no_proxy`](crate::Proxy::no_proxy) list. Calling it again replaces the previous proxy.
+        /// A malformed or unsupported proxy surfaces as an
+        /// [`Error::InvalidProxy`](crate::errors::Error::InvalidProxy) from `build()`.
+        ///
+        /// Like [`add_root_certificate`](Self::add_root_certificate), the proxy applies per
+        /// transport: a client injected via [`http_client`](Self::http_client) (or
+        /// `http_client_async`) owns its own proxy config and ignores this one.
+        ///
+        /// `HTTP(S)_PROXY` / `NO_PROXY` environment proxies still apply to hosts on the
+        /// `no_proxy` list. On a ureq-only build the credentials are carried in the proxy URL, so a
+        /// username containing `:` or credentials containing `@ / ? #` are rejected at `build()`.
+        pub fn proxy(&mut self, proxy: impl Into<crate::Proxy>) -> &mut Self {
+            self.$($path).+.proxy = Some(proxy.into());
+            self
+        }
+
         /// Authorize an additional host to receive the auth token.
         ///
         /// By default the token set via `auth_token` is sent only to the backend's own API host, so
@@ -249,6 +375,14 @@ macro_rules! impl_update_config_accessors {
             fn verify_callback(&self) -> Option<std::sync::Arc<crate::DynVerifyFn>> {
                 self.common.verify.as_ref().map(|c| c.0.clone())
             }
+            fn self_test(&self) -> Option<&crate::self_test::SelfTest> {
+                self.common.self_test.as_ref()
+            }
+            // The backend is named after the module the macro is invoked in.
+            fn backend(&self) -> &'static str {
+                let module = module_path!();
+                module.rsplit("::").next().unwrap_or(module)
+            }
             fn asset_matcher(&self) -> Option<std::sync::Arc<crate::DynAssetMatcher>> {
                 self.common.asset_matcher.as_ref().map(|c| c.0.clone())
             }
@@ -264,6 +398,22 @@ macro_rules! impl_update_config_accessors {
             fn verifying_keys(&self) -> &[crate::VerifyingKey] {
                 &self.common.verifying_keys
             }
+            #[cfg(feature = "signatures")]
+            fn trusted_keys(&self) -> &[crate::keys::TrustedKey] {
+                &self.common.trusted_keys
+            }
+            #[cfg(feature = "signatures")]
+            fn revocation_list_asset(&self) -> Option<&str> {
+                self.common.revocation_list_asset.as_deref()
+            }
+            #[cfg(feature = "signatures")]
+            fn key_set_asset(&self) -> Option<&str> {
+                self.common.key_set_asset.as_deref()
+            }
+            #[cfg(feature = "signatures")]
+            fn key_store_path(&self) -> Option<&std::path::Path> {
+                self.common.key_store_path.as_deref()
+            }
         }
     };
     (@emit ($($header:tt)*), { $($extra:tt)* }) => {
@@ -291,9 +441,51 @@ macro_rules! impl_update_config_accessors {
         fn check_install_path_writable(&self) -> bool {
             self.common.check_install_path_writable
         }
+        fn check_package_manager(&self) -> bool {
+            self.common.check_package_manager
+        }
+        fn check_free_space(&self) -> bool {
+            self.common.check_free_space
+        }
+        fn free_space_expansion_factor(&self) -> f64 {
+            self.common.free_space_expansion_factor
+        }
+        fn temp_dir_on_install_fs(&self) -> bool {
+            self.common.temp_dir_on_install_fs
+        }
+        fn extract_limits(&self) -> crate::ExtractLimits {
+            self.common.extract_limits
+        }
+        fn install_receipt(&self) -> Option<&std::path::Path> {
+            self.common.install_receipt.as_deref()
+        }
         fn bin_path_in_archive(&self) -> &str {
             &self.common.bin_path_in_archive
         }
+        fn bundle_root_in_archive(&self) -> Option<&str> {
+            self.common.bundle_root_in_archive.as_deref()
+        }
+        fn bundle_install_path(&self) -> Option<&std::path::Path> {
+            self.common.bundle_install_path.as_deref()
+        }
+        fn install_files(&self) -> &[(String, std::path::PathBuf)] {
+            &self.common.install_files
+        }
+        fn versioned_install(&self) -> Option<&crate::VersionedInstall> {
+            self.common.versioned_install.as_ref()
+        }
+        #[cfg(feature = "preserve-metadata")]
+        fn preserve_metadata(&self) -> bool {
+            self.common.preserve_metadata
+        }
+        #[cfg(feature = "elevate")]
+        fn elevate(&self) -> Option<crate::elevate::Elevator> {
+            self.common.elevate
+        }
+        #[cfg(feature = "stage")]
+        fn staging_dir(&self) -> Option<&std::path::Path> {
+            self.common.staging_dir.as_deref()
+        }
         fn show_download_progress(&self) -> bool {
             self.common.show_download_progress
         }
@@ -322,6 +514,9 @@ macro_rules! impl_update_config_accessors {
             // `apply_auth` reads it for both the listing and download paths.
             self.common.request.auth_token.as_deref()
         }
+        fn rate_limit(&self) -> Option<crate::RateLimit> {
+            self.common.request.last_rate_limit()
+        }
         }
     };
 }
@@ -349,6 +544,14 @@ macro_rules! impl_sync_update_verbs {
                 <Self as crate::ReleaseUpdate>::update_extended(self)
             }
 
+            /// Download and verify the latest release, then store it in the `staging_dir` for
+            /// [`staged::ApplyStaged`](crate::staged::ApplyStaged) instead of installing it. See
+            /// [`ReleaseUpdate::stage`](crate::ReleaseUpdate::stage).
+            #[cfg(feature = "stage")]
+            pub fn stage(&self) -> crate::Result<Option<crate::Release>> {
+                <Self as crate::ReleaseUpdate>::stage(self)
+            }
+
             /// Fetch the single newest release (raw, unfiltered). See
             /// [`ReleaseUpdate::get_latest_release`](crate::ReleaseUpdate::get_latest_release).
             pub fn get_latest_release(&self) -> crate::Result<crate::Releases> {
@@ -408,6 +611,14 @@ macro_rules! impl_async_update_verbs {
                 crate::AsyncReleaseUpdate::update_extended_async(&self.0).await
             }
 
+            /// Download and verify the latest release, then store it in the `staging_dir` for
+            /// [`staged::ApplyStaged`](crate::staged::ApplyStaged) instead of installing it. See
+            /// [`AsyncReleaseUpdate::stage_async`](crate::AsyncReleaseUpdate::stage_async).
+            #[cfg(feature = "stage")]
+            pub async fn stage_async(&self) -> crate::Result<Option<crate::Release>> {
+                crate::AsyncReleaseUpdate::stage_async(&self.0).await
+            }
+
             /// Fetch the single newest release (raw, unfiltered). See
             /// [`AsyncReleaseUpdate::get_latest_release_async`](crate::AsyncReleaseUpdate::get_latest_release_async).
             pub async fn get_latest_release_async(&self) -> crate::Result<crate::Releases> {
@@ -578,6 +789,134 @@ macro_rules! impl_common_builder_setters {
             self
         }
 
+        /// Opt-in preflight: before anything is downloaded, refuse to update an install path
+        /// owned by a package manager (a dpkg or rpm package, the Nix store, a Homebrew Cellar,
+        /// or a `cargo install` recorded in `.crates2.json`), failing with
+        /// [`Error::PackageManaged`](crate::errors::Error::PackageManaged), which names the
+        /// command that updates it instead. Self-updating such a binary would leave the package
+        /// manager's records pointing at a file it did not install. Defaults to `false` (off).
+        pub fn check_package_manager(&mut self, check: bool) -> &mut Self {
+            self.common.check_package_manager = check;
+            self
+        }
+
+        /// Opt-in preflight: before anything is downloaded, check that the temp directory and the
+        /// install directory have room for the update, failing with
+        /// [`Error::InsufficientSpace`](crate::errors::Error::InsufficientSpace) otherwise. The
+        /// temp directory needs the asset plus what it unpacks to, the install directory what it
+        /// unpacks to (see [`free_space_expansion_factor`](Self::free_space_expansion_factor)),
+        /// and both together when they share a filesystem. The asset size comes from the release
+        /// listing; when the backend lists none, the check runs on the download's
+        /// `Content-Length` before any of it is written. Defaults to `false` (off).
+        pub fn check_free_space(&mut self, check: bool) -> &mut Self {
+            self.common.check_free_space = check;
+            self
+        }
+
+        /// What the free-space preflight ([`check_free_space`](Self::check_free_space)) assumes an
+        /// asset unpacks to, as a multiple of its size. Defaults to `3.0`, room for a compressed
+        /// archive; use `1.0` for a bare binary. Values below `1.0` are treated as `1.0`.
+        pub fn free_space_expansion_factor(&mut self, factor: f64) -> &mut Self {
+            self.common.free_space_expansion_factor = if factor >= 1.0 { factor } else { 1.0 };
+            self
+        }
+
+        /// Download and extract into a temporary directory beside the install path instead of the
+        /// system temp dir, so the new binary is moved into place with a same-filesystem rename
+        /// (useful when `/tmp` is a small tmpfs). The directory is created under the install
+        /// path's parent (the layout root for `versioned_install`, the `staging_dir` for
+        /// `stage()`) and removed afterwards. Defaults to `false`.
+        pub fn temp_dir_on_install_fs(&mut self, on_install_fs: bool) -> &mut Self {
+            self.common.temp_dir_on_install_fs = on_install_fs;
+            self
+        }
+
+        /// Bound what extracting the downloaded archive may unpack: total and per-entry
+        /// uncompressed bytes, entry count and path depth (see
+        /// [`ExtractLimits`](crate::ExtractLimits)). An archive that goes over a limit, such as a
+        /// decompression bomb, fails the update with
+        /// [`Error::ArchiveLimitExceeded`](crate::errors::Error::ArchiveLimitExceeded) before
+        /// anything is installed. Defaults to no limits.
+        pub fn extract_limits(&mut self, limits: crate::ExtractLimits) -> &mut Self {
+            self.common.extract_limits = limits;
+            self
+        }
+
+        /// Write an install receipt to `path` after each successful update: the backend, the
+        /// installed version, the install path, the SHA-256 of the downloaded asset (with the
+        /// `checksums` feature) and the time, as JSON. Read it back with
+        /// [`InstallReceipt::read`](crate::receipt::InstallReceipt::read). A receipt that cannot be
+        /// written is logged, not returned: the update itself has succeeded. `build()` rejects it
+        /// together with [`staging_dir`](Self::staging_dir): a staged update is applied without
+        /// writing a receipt.
+        pub fn install_receipt<A: AsRef<std::path::Path>>(&mut self, path: A) -> &mut Self {
+            self.common.install_receipt = Some(std::path::PathBuf::from(path.as_ref()));
+            self
+        }
+
+        /// Opt-in: carry the installed binary's owner and group, mode (including setuid/setgid
+        /// bits) and extended attributes (file capabilities such as `cap_net_bind_service` in
+        /// `security.capability`, the SELinux label in `security.selinux`) over to the new binary,
+        /// which a plain replace would otherwise drop. Defaults to `false`.
+        ///
+        /// The new binary is staged beside the installed one, the metadata copied onto it, and
+        /// the staged file renamed into place. If an attribute cannot be copied (a different
+        /// owner or `security.*` attributes usually need root, or `CAP_CHOWN` / `CAP_SETFCAP`),
+        /// the update fails with
+        /// [`Error::MetadataNotPreserved`](crate::errors::Error::MetadataNotPreserved) and
+        /// nothing is replaced. Only has an effect on unix, and only for the single-binary
+        /// install: `build()` rejects it together with
+        /// [`bundle_root_in_archive`](Self::bundle_root_in_archive),
+        /// [`versioned_install`](Self::versioned_install), [`install_file`](Self::install_file)
+        /// or `staging_dir` (a staged update is applied with a plain replace). Requires the
+        /// `preserve-metadata` feature.
+        #[cfg(feature = "preserve-metadata")]
+        pub fn preserve_metadata(&mut self, preserve: bool) -> &mut Self {
+            self.common.preserve_metadata = preserve;
+            self
+        }
+
+        /// Retry a replace of the installed binary that is refused for lack of permission (e.g. in
+        /// `/usr/local/bin`) through `sudo`, `doas` or `pkexec`, instead of failing with
+        /// [`Error::InstallPathNotWritable`](crate::errors::Error::InstallPathNotWritable) after
+        /// the download. Off by default.
+        ///
+        /// Everything up to the replace (download, checksum and signature checks, `verify_binary`,
+        /// `self_test`) still runs as the current user; only the replace re-invokes the current
+        /// executable through `elevator`, which re-checks the staged binary's SHA-256 before
+        /// installing it. **`main` must call
+        /// [`elevate::handle_elevated_install`](crate::elevate::handle_elevated_install) before
+        /// anything else**, or the re-invocation runs the application itself with privileges. See
+        /// the [`elevate`](crate::elevate) module. The writability preflight
+        /// ([`check_install_path_writable`](Self::check_install_path_writable)) no longer probes
+        /// `bin_install_path` with this set. `build()` rejects it together with
+        /// [`bundle_root_in_archive`](Self::bundle_root_in_archive),
+        /// [`versioned_install`](Self::versioned_install), [`install_file`](Self::install_file)
+        /// or `staging_dir` (a staged update is applied without elevation). Requires the
+        /// `elevate` feature.
+        #[cfg(feature = "elevate")]
+        pub fn elevate(&mut self, elevator: crate::elevate::Elevator) -> &mut Self {
+            self.common.elevate = Some(elevator);
+            self
+        }
+
+        /// Set the directory `stage()` stores a downloaded and verified update in, for
+        /// [`staged::ApplyStaged`](crate::staged::ApplyStaged) to install on the next start.
+        /// Required by `stage()`; `update()` ignores it.
+        ///
+        /// Keep it somewhere only the updating user can write (not a shared temp dir): whoever
+        /// can write it can stage a binary. `build()` rejects it together with
+        /// [`bundle_root_in_archive`](Self::bundle_root_in_archive),
+        /// [`versioned_install`](Self::versioned_install) or [`install_file`](Self::install_file),
+        /// and with [`install_receipt`](Self::install_receipt), `preserve_metadata` or `elevate`,
+        /// which [`ApplyStaged`](crate::staged::ApplyStaged) does not carry over. Requires the
+        /// `stage` feature.
+        #[cfg(feature = "stage")]
+        pub fn staging_dir<A: AsRef<std::path::Path>>(&mut self, dir: A) -> &mut Self {
+            self.common.staging_dir = Some(dir.as_ref().to_path_buf());
+            self
+        }
+
         /// Set the path of the exe inside the release tarball. This is the location of the
         /// executable relative to the base of the tar'd directory and is the path that will
         /// be copied to the `bin_install_path`. If not specified, this will default to the
@@ -604,6 +943,81 @@ macro_rules! impl_common_builder_setters {
             self
         }
 
+        /// Install one more file from the archive alongside the binary, e.g. a companion binary,
+        /// a man page or a shell completion. May be called multiple times.
+        ///
+        /// `path_in_archive` supports the same `{{ bin }}` / `{{ target }}` / `{{ version }}`
+        /// variables as [`bin_path_in_archive`](Self::bin_path_in_archive). The binary and every
+        /// extra file are installed in one transaction (see [`MoveAll`](crate::MoveAll)): if any
+        /// of them cannot be installed, the ones already in place are restored. A destination
+        /// that is the running executable is replaced via `self_replace`, last. Missing parent
+        /// directories of `install_path` are created.
+        ///
+        /// The files are staged and the replaced originals stashed next to
+        /// [`bin_install_path`](Self::bin_install_path), so every `install_path` must be on the
+        /// same filesystem as it. Cannot be combined with
+        /// [`bundle_root_in_archive`](Self::bundle_root_in_archive).
+        pub fn install_file<A: AsRef<std::path::Path>>(
+            &mut self,
+            path_in_archive: impl Into<String>,
+            install_path: A,
+        ) -> &mut Self {
+            self.common.install_files.push((
+                path_in_archive.into(),
+                std::path::PathBuf::from(install_path.as_ref()),
+            ));
+            self
+        }
+
+        /// Select bundle mode: install a whole directory tree instead of a single binary. `path`
+        /// names the bundle root directory inside the archive, relative to the archive root (e.g.
+        /// `"MyApp.app"` or `"{{ bin }}-{{ version }}"`), and supports the same `{{ bin }}` /
+        /// `{{ target }}` / `{{ version }}` variables as
+        /// [`bin_path_in_archive`](Self::bin_path_in_archive).
+        ///
+        /// The archive is extracted in full next to
+        /// [`bundle_install_path`](Self::bundle_install_path), and the staged root replaces the
+        /// installed directory by rename, keeping the old tree for rollback until the swap has
+        /// succeeded. When the running executable lives inside the bundle it is moved aside first,
+        /// and the new tree's copy takes its place.
+        ///
+        /// Cannot be combined with an explicit [`bin_install_path`](Self::bin_install_path) or
+        /// [`bin_path_in_archive`](Self::bin_path_in_archive): `build()` fails with
+        /// [`Error::ConflictingConfig`](crate::errors::Error::ConflictingConfig).
+        pub fn bundle_root_in_archive(&mut self, path: impl Into<String>) -> &mut Self {
+            self.common.bundle_root_in_archive = Some(path.into());
+            self
+        }
+
+        /// Set the installed bundle directory that bundle mode replaces (e.g.
+        /// `/Applications/MyApp.app` or `/opt/myapp`).
+        ///
+        /// On macOS this defaults to the nearest ancestor of the current executable whose name
+        /// ends in `.app` (failing `build()` with
+        /// [`Error::NoAppBundle`](crate::errors::Error::NoAppBundle) when there is none); elsewhere
+        /// it is required in bundle mode. Setting it without
+        /// [`bundle_root_in_archive`](Self::bundle_root_in_archive) is a `MissingField` error.
+        pub fn bundle_install_path<A: AsRef<std::path::Path>>(&mut self, path: A) -> &mut Self {
+            self.common.bundle_install_path = Some(std::path::PathBuf::from(path.as_ref()));
+            self
+        }
+
+        /// Install into a versioned side-by-side layout instead of replacing the binary in place:
+        /// the new binary goes to `<root>/versions/<version>/<bin_name>`, and the layout's
+        /// `current` symlink is flipped to it only after verification and the self-test have
+        /// passed. Older versions are kept for rollback, pruned to the layout's
+        /// [`keep`](crate::VersionedInstall::keep) count. See
+        /// [`VersionedInstall`](crate::VersionedInstall).
+        ///
+        /// Cannot be combined with an explicit [`bin_install_path`](Self::bin_install_path),
+        /// [`bundle_root_in_archive`](Self::bundle_root_in_archive), or
+        /// [`install_file`](Self::install_file): `build()` fails with
+        /// [`Error::ConflictingConfig`](crate::errors::Error::ConflictingConfig).
+        pub fn versioned_install(&mut self, layout: crate::VersionedInstall) -> &mut Self {
+            self.common.versioned_install = Some(layout);
+            self
+        }
+
         /// Toggle download progress bar, defaults to `off`.
         pub fn show_download_progress(&mut self, show: bool) -> &mut Self {
             self.common.show_download_progress = show;
@@ -715,9 +1129,9 @@ macro_rules! impl_common_builder_setters {
         /// downloaded archive. The full order is: [`verify_checksum`](Self::verify_checksum) (digest
         /// of the archive) -> release digest ([`verify_release_digest`](Self::verify_release_digest),
         /// over the archive) -> signature ([`verifying_keys`](Self::verifying_keys), over the archive) ->
-        /// extract -> `verify_binary` (the extracted binary) -> replace. Use
-        /// `verify_checksum`/`verifying_keys` to gate the download by content; use `verify_binary` to
-        /// gate it by running the new binary. Reject with
+        /// extract -> [`self_test`](Self::self_test) -> `verify_binary` (the extracted binary) ->
+        /// replace. Use `verify_checksum`/`verifying_keys` to gate the download by content; use
+        /// `verify_binary` to gate it by running the new binary. Reject with
         /// [`Error::verification_rejected("reason")`](crate::Error::verification_rejected), which is
         /// surfaced as-is; any other returned error's message becomes the reason of the resulting
         /// `Error::VerificationRejected`.
@@ -729,6 +1143,19 @@ macro_rules! impl_common_builder_setters {
             self
         }
 
+        /// Run the extracted binary before installing it, with a scrubbed environment and a
+        /// timeout, and reject the update unless it exits successfully and reports the release
+        /// version. Catches wrong-architecture, truncated, or crashing binaries without writing a
+        /// [`verify_binary`](Self::verify_binary) hook. See [`SelfTest`](crate::self_test::SelfTest)
+        /// for the arguments, timeout, and environment.
+        ///
+        /// Runs after extraction and before `verify_binary`; a failure is an
+        /// `Error::VerificationRejected` and nothing is installed.
+        pub fn self_test(&mut self, self_test: crate::self_test::SelfTest) -> &mut Self {
+            self.common.self_test = Some(self_test);
+            self
+        }
+
         /// Verify the downloaded artifact against an expected [`Checksum`](crate::Checksum)
         /// (e.g. one published in a `SHA256SUMS` file) before installing it. The algorithm is
         /// chosen by the `Checksum` variant.
@@ -780,6 +1207,51 @@ macro_rules! impl_common_builder_setters {
             self.common.verifying_keys = keys.into();
             self
         }
+
+        /// Trust keys with validity windows, in addition to any
+        /// [`verifying_keys`](Self::verifying_keys). A key is only used while its
+        /// `not_before`/`not_after` window contains the current time; see
+        /// [`keys`](crate::keys) for how these combine with revocation and rotation. Like
+        /// `verifying_keys`, this **replaces** the set on each call.
+        #[cfg(feature = "signatures")]
+        pub fn trusted_keys(
+            &mut self,
+            keys: impl Into<Vec<crate::keys::TrustedKey>>,
+        ) -> &mut Self {
+            self.common.trusted_keys = keys.into();
+            self
+        }
+
+        /// Name of a signed [`RevocationList`](crate::keys::RevocationList) asset to fetch from the
+        /// selected release (its detached signature is the asset of the same name plus `.sig`).
+        /// Keys it names stop being trusted before the archive is verified; with a
+        /// [`key_store_path`](Self::key_store_path) the revocation is also remembered. A release
+        /// without the asset is not an error.
+        #[cfg(feature = "signatures")]
+        pub fn revocation_list_asset(&mut self, name: impl Into<String>) -> &mut Self {
+            self.common.revocation_list_asset = Some(name.into());
+            self
+        }
+
+        /// Name of a signed [`KeySet`](crate::keys::KeySet) asset to fetch from the selected
+        /// release (its detached signature is the asset of the same name plus `.sig`). After the
+        /// archive verifies, a key set signed by a currently-trusted key is saved to the
+        /// [`key_store_path`](Self::key_store_path), which `build()` then requires, and is trusted
+        /// from then on. A release without the asset is not an error.
+        #[cfg(feature = "signatures")]
+        pub fn key_set_asset(&mut self, name: impl Into<String>) -> &mut Self {
+            self.common.key_set_asset = Some(name.into());
+            self
+        }
+
+        /// Path of the file persisting delivered key sets and revocations between runs. The
+        /// application chooses the location (typically a per-user data directory); the file and
+        /// its contents are managed by the crate, see [`keys`](crate::keys).
+        #[cfg(feature = "signatures")]
+        pub fn key_store_path(&mut self, path: impl Into<std::path::PathBuf>) -> &mut Self {
+            self.common.key_store_path = Some(path.into());
+            self
+        }
     };
 }
 
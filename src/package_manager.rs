@@ -0,0 +1,249 @@
+/*!
+Detecting an install path owned by a package manager (`check_package_manager(true)`).
+
+Replacing a binary that apt, dnf, Nix, Homebrew or `cargo install` put in place leaves the package
+manager's records pointing at a file it no longer recognizes: its checksums fail, an upgrade or
+removal clobbers the self-updated version, and on Nix the store is read-only anyway. The opt-in
+preflight refuses such a path with [`Error::PackageManaged`], naming the command that updates it
+the supported way.
+
+Detection is filesystem-only except for rpm, whose database has to be queried through `rpm -qf`
+(only attempted when an rpm database exists). Both the configured path and its canonical form are
+checked, so `/opt/homebrew/bin/app` (a symlink into the Cellar) and a `/usr/bin` symlink are
+recognized.
+*/
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::Result;
+use crate::errors::Error;
+
+/// Where each package manager keeps the records consulted during detection.
+pub(crate) struct Databases {
+    /// dpkg's per-package file lists (`<package>.list`).
+    dpkg_info: PathBuf,
+    /// Possible rpm database locations; `rpm -qf` runs only when one of them exists.
+    rpm_dbs: Vec<PathBuf>,
+    /// `$CARGO_HOME` (default `~/.cargo`), whose `.crates2.json` records `cargo install`s.
+    cargo_home: Option<PathBuf>,
+}
+
+impl Databases {
+    /// The standard system locations, and `CARGO_HOME` / `HOME` from the environment.
+    pub(crate) fn system() -> Self {
+        let cargo_home = std::env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")));
+        Self {
+            dpkg_info: PathBuf::from("/var/lib/dpkg/info"),
+            rpm_dbs: vec![
+                PathBuf::from("/var/lib/rpm"),
+                PathBuf::from("/usr/lib/sysimage/rpm"),
+            ],
+            cargo_home,
+        }
+    }
+}
+
+/// Fail with [`Error::PackageManaged`] when `path` is owned by a package manager.
+pub(crate) fn refuse_package_managed(path: &Path, dbs: &Databases) -> Result<()> {
+    match detect(path, dbs) {
+        Some((manager, command)) => Err(Error::PackageManaged {
+            path: path.to_path_buf(),
+            manager,
+            command,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The package manager owning `path` and the command that updates it, if any.
+fn detect(path: &Path, dbs: &Databases) -> Option<(&'static str, String)> {
+    let mut candidates = vec![path.to_path_buf()];
+    if let Ok(canonical) = fs::canonicalize(path)
+        && canonical != path
+    {
+        candidates.push(canonical);
+    }
+    candidates.iter().find_map(|path| {
+        nix(path)
+            .or_else(|| homebrew(path))
+            .or_else(|| cargo_install(path, dbs.cargo_home.as_deref()?))
+            .or_else(|| dpkg(path, &dbs.dpkg_info))
+            .or_else(|| rpm(path, &dbs.rpm_dbs))
+    })
+}
+
+fn nix(path: &Path) -> Option<(&'static str, String)> {
+    path.starts_with("/nix/store")
+        .then(|| ("Nix", "nix profile upgrade".to_string()))
+}
+
+/// A file under `<prefix>/Cellar/<formula>/<version>/`.
+fn homebrew(path: &Path) -> Option<(&'static str, String)> {
+    let mut components = path.components();
+    components.find(|c| c.as_os_str() == "Cellar")?;
+    match components.next()? {
+        Component::Normal(formula) => Some((
+            "Homebrew",
+            format!("brew upgrade {}", formula.to_string_lossy()),
+        )),
+        _ => None,
+    }
+}
+
+/// A binary in `<cargo_home>/bin` that `.crates2.json` lists for an installed crate.
+fn cargo_install(path: &Path, cargo_home: &Path) -> Option<(&'static str, String)> {
+    if path.parent()? != cargo_home.join("bin") {
+        return None;
+    }
+    let bin = path.file_name()?.to_str()?;
+    let bin_stem = bin.strip_suffix(".exe").unwrap_or(bin);
+    let contents = fs::read_to_string(cargo_home.join(".crates2.json")).ok()?;
+    let record: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    // `installs` maps `"<crate> <version> (<source>)"` to the install, including its `bins`.
+    record["installs"]
+        .as_object()?
+        .iter()
+        .find_map(|(key, install)| {
+            let bins = install["bins"].as_array()?;
+            let lists_bin = bins
+                .iter()
+                .filter_map(|b| b.as_str())
+                .any(|b| b == bin || b == bin_stem);
+            let krate = key.split(' ').next()?;
+            lists_bin.then(|| ("cargo install", format!("cargo install {}", krate)))
+        })
+}
+
+/// A path listed in one of dpkg's `<package>[:<arch>].list` files.
+fn dpkg(path: &Path, dpkg_info: &Path) -> Option<(&'static str, String)> {
+    let wanted = path.to_str()?;
+    fs::read_dir(dpkg_info).ok()?.flatten().find_map(|entry| {
+        let name = entry.file_name();
+        let package = name.to_str()?.strip_suffix(".list")?;
+        let package = package.split(':').next()?;
+        let list = fs::read_to_string(entry.path()).ok()?;
+        list.lines().any(|line| line == wanted).then(|| {
+            (
+                "dpkg",
+                format!("sudo apt install --only-upgrade {}", package),
+            )
+        })
+    })
+}
+
+/// A path `rpm -qf` attributes to a package, when an rpm database exists.
+fn rpm(path: &Path, rpm_dbs: &[PathBuf]) -> Option<(&'static str, String)> {
+    if !rpm_dbs.iter().any(|db| db.is_dir()) {
+        return None;
+    }
+    let output = std::process::Command::new("rpm")
+        .args(["-qf", "--queryformat", "%{NAME}"])
+        .arg(path)
+        .output()
+        .ok()?;
+    let package = String::from_utf8(output.stdout).ok()?;
+    let package = package.trim();
+    (output.status.success() && !package.is_empty())
+        .then(|| ("rpm", format!("sudo dnf upgrade {}", package)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Databases rooted in `dir`, none of which exists until a test writes it.
+    fn databases(dir: &Path) -> Databases {
+        Databases {
+            dpkg_info: dir.join("dpkg/info"),
+            rpm_dbs: vec![dir.join("rpm")],
+            cargo_home: Some(dir.join("cargo")),
+        }
+    }
+
+    #[test]
+    fn an_unmanaged_path_passes() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin/app");
+        assert!(refuse_package_managed(&bin, &databases(dir.path())).is_ok());
+    }
+
+    #[test]
+    fn nix_store_and_homebrew_cellar_paths_are_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let dbs = databases(dir.path());
+        assert_eq!(
+            detect(Path::new("/nix/store/abc123-app-1.0/bin/app"), &dbs),
+            Some(("Nix", "nix profile upgrade".to_string()))
+        );
+        assert_eq!(
+            detect(Path::new("/opt/homebrew/Cellar/app/1.0.0/bin/app"), &dbs),
+            Some(("Homebrew", "brew upgrade app".to_string()))
+        );
+    }
+
+    // A Homebrew-style symlink into the Cellar is resolved before matching.
+    #[cfg(unix)]
+    #[test]
+    fn a_symlink_into_a_managed_tree_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let cellar_bin = dir.path().join("Cellar/app/1.0.0/bin");
+        fs::create_dir_all(&cellar_bin).unwrap();
+        fs::write(cellar_bin.join("app"), "").unwrap();
+        fs::create_dir(dir.path().join("bin")).unwrap();
+        let link = dir.path().join("bin/app");
+        std::os::unix::fs::symlink(cellar_bin.join("app"), &link).unwrap();
+
+        match refuse_package_managed(&link, &databases(dir.path())).unwrap_err() {
+            Error::PackageManaged {
+                path,
+                manager,
+                command,
+            } => {
+                assert_eq!(path, link);
+                assert_eq!(manager, "Homebrew");
+                assert_eq!(command, "brew upgrade app");
+            }
+            other => panic!("expected Error::PackageManaged, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_cargo_installed_binary_is_detected_only_when_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let dbs = databases(dir.path());
+        let cargo = dir.path().join("cargo");
+        fs::create_dir_all(cargo.join("bin")).unwrap();
+        fs::write(
+            cargo.join(".crates2.json"),
+            r#"{"installs":{"my-app 1.2.0 (registry+https://github.com/rust-lang/crates.io-index)":{"bins":["app"]}}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            detect(&cargo.join("bin/app"), &dbs),
+            Some(("cargo install", "cargo install my-app".to_string()))
+        );
+        assert_eq!(detect(&cargo.join("bin/other"), &dbs), None);
+    }
+
+    #[test]
+    fn a_path_in_a_dpkg_file_list_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let dbs = databases(dir.path());
+        fs::create_dir_all(&dbs.dpkg_info).unwrap();
+        fs::write(
+            dbs.dpkg_info.join("my-app:amd64.list"),
+            "/.\n/usr\n/usr/bin\n/usr/bin/app\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            detect(Path::new("/usr/bin/app"), &dbs),
+            Some(("dpkg", "sudo apt install --only-upgrade my-app".to_string()))
+        );
+        assert_eq!(detect(Path::new("/usr/bin/other"), &dbs), None);
+    }
+}
@@ -0,0 +1,144 @@
+/*!
+Carrying the installed binary's metadata over to its replacement (`preserve_metadata(true)`).
+
+A plain replace installs a fresh file, so whatever was set on the old binary after it was first
+installed is lost on every update: its owner and group, setuid/setgid bits, file capabilities
+(`security.capability`, e.g. `cap_net_bind_service`), SELinux label (`security.selinux`) and any
+other extended attribute. Here the new binary is instead staged as a temporary file beside the
+installed one, the old binary's metadata is copied onto it, and the staged file is renamed over
+the installed path, so the swap stays a single atomic `rename`.
+
+The copy order matters: `chown` clears setuid/setgid bits and file capabilities, so the owner is
+set first, then the extended attributes, then the mode. Any step that fails aborts the install
+with [`Error::MetadataNotPreserved`] before anything is replaced.
+*/
+
+use std::fs;
+use std::io;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+
+use crate::Result;
+use crate::errors::Error;
+
+/// Stage `new_exe` beside `installed` with `installed`'s owner, extended attributes and mode.
+///
+/// Returns `None` when there is no installed binary to take metadata from, so the caller falls
+/// back to its plain install. Staging IO errors are returned as `Error::Io`; a metadata copy
+/// failure is `Error::MetadataNotPreserved`.
+pub(crate) fn stage_beside(
+    new_exe: &Path,
+    installed: &Path,
+) -> Result<Option<tempfile::NamedTempFile>> {
+    let meta = match fs::metadata(installed) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut staged = tempfile::Builder::new()
+        .prefix(".self_update-preserve")
+        .tempfile_in(crate::update::install_parent(installed))?;
+    io::copy(&mut fs::File::open(new_exe)?, staged.as_file_mut())?;
+    copy_metadata(installed, &meta, staged.path())?;
+    Ok(Some(staged))
+}
+
+/// Copy the owner, extended attributes and mode of `from` (with metadata `meta`) onto `to`.
+fn copy_metadata(from: &Path, meta: &fs::Metadata, to: &Path) -> Result<()> {
+    let not_preserved = |attribute: String| {
+        move |source: io::Error| Error::MetadataNotPreserved {
+            path: from.to_path_buf(),
+            attribute,
+            source,
+        }
+    };
+
+    // Only the ids that differ are changed, so an unprivileged update of a binary it owns never
+    // needs `chown` rights.
+    let staged = fs::metadata(to)?;
+    let uid = (staged.uid() != meta.uid()).then_some(meta.uid());
+    let gid = (staged.gid() != meta.gid()).then_some(meta.gid());
+    if uid.is_some() || gid.is_some() {
+        std::os::unix::fs::chown(to, uid, gid).map_err(not_preserved("owner".to_string()))?;
+    }
+
+    match xattr::list(from) {
+        Ok(names) => {
+            for name in names {
+                let attribute = format!("xattr {}", name.to_string_lossy());
+                let value = xattr::get(from, &name).map_err(not_preserved(attribute.clone()))?;
+                if let Some(value) = value {
+                    xattr::set(to, &name, &value).map_err(not_preserved(attribute))?;
+                }
+            }
+        }
+        // A filesystem without extended attributes has none to carry over.
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => {}
+        Err(e) => return Err(not_preserved("xattrs".to_string())(e)),
+    }
+
+    fs::set_permissions(to, fs::Permissions::from_mode(meta.mode() & 0o7777))
+        .map_err(not_preserved("mode".to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_beside_copies_contents_and_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let new_exe = dir.path().join("new");
+        fs::write(&new_exe, "new").unwrap();
+        let installed = dir.path().join("app");
+        fs::write(&installed, "old").unwrap();
+        fs::set_permissions(&installed, fs::Permissions::from_mode(0o2750)).unwrap();
+
+        let staged = stage_beside(&new_exe, &installed).unwrap().unwrap();
+
+        assert_eq!(staged.path().parent(), Some(dir.path()));
+        assert_eq!(fs::read_to_string(staged.path()).unwrap(), "new");
+        let meta = fs::metadata(staged.path()).unwrap();
+        assert_eq!(meta.mode() & 0o7777, 0o2750);
+        let installed_meta = fs::metadata(&installed).unwrap();
+        assert_eq!(
+            (meta.uid(), meta.gid()),
+            (installed_meta.uid(), installed_meta.gid())
+        );
+    }
+
+    // Not every filesystem the tests run on supports `user.*` attributes; skip when it does not.
+    #[test]
+    fn stage_beside_copies_extended_attributes() {
+        let dir = tempfile::tempdir().unwrap();
+        let new_exe = dir.path().join("new");
+        fs::write(&new_exe, "new").unwrap();
+        let installed = dir.path().join("app");
+        fs::write(&installed, "old").unwrap();
+        if xattr::set(&installed, "user.self_update.test", b"kept").is_err() {
+            return;
+        }
+
+        let staged = stage_beside(&new_exe, &installed).unwrap().unwrap();
+
+        assert_eq!(
+            xattr::get(staged.path(), "user.self_update.test").unwrap(),
+            Some(b"kept".to_vec())
+        );
+    }
+
+    #[test]
+    fn stage_beside_is_none_without_an_installed_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let new_exe = dir.path().join("new");
+        fs::write(&new_exe, "new").unwrap();
+
+        assert!(
+            stage_beside(&new_exe, &dir.path().join("app"))
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,241 @@
+/*!
+Rate-limit state reported by a release host.
+
+GitHub answers with `X-RateLimit-Limit` / `X-RateLimit-Remaining` / `X-RateLimit-Reset`, GitLab and
+Gitea with the unprefixed `RateLimit-*` headers, and any host may send `Retry-After` alongside a
+`429 Too Many Requests` (or GitHub's rate-limit `403`). [`RateLimit`] is the parsed, host-agnostic
+view of those headers. The retry loop waits for the time the server asks for (capped by
+`max_rate_limit_wait`), a rejected request surfaces as
+[`Error::RateLimited`](crate::errors::Error::RateLimited), and the last state observed on an
+`Update`'s API requests is available from
+[`UpdateConfig::rate_limit`](crate::update::UpdateConfig::rate_limit).
+*/
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::http_client::HeaderMap;
+
+/// `*-Reset` values at or above this are read as a Unix timestamp (GitHub, GitLab); smaller values
+/// as seconds from now (the IETF `RateLimit` header draft).
+const RESET_EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+/// Rate-limit information parsed from a response's headers.
+///
+/// Every field is optional: a host reports whichever subset it supports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RateLimit {
+    /// The request quota for the current window (`X-RateLimit-Limit` / `RateLimit-Limit`).
+    pub limit: Option<u64>,
+    /// Requests left in the current window (`X-RateLimit-Remaining` / `RateLimit-Remaining`).
+    pub remaining: Option<u64>,
+    /// When the current window resets (`X-RateLimit-Reset` / `RateLimit-Reset`).
+    pub reset_at: Option<SystemTime>,
+    /// How long the server asked the client to wait before retrying (`Retry-After`).
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimit {
+    /// Parse the rate-limit headers of a response, or `None` if it carries none of them.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        Self::from_headers_at(headers, SystemTime::now())
+    }
+
+    fn from_headers_at(headers: &HeaderMap, now: SystemTime) -> Option<Self> {
+        let header = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| headers.get(*name))
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+        };
+        let number = |names: &[&str]| header(names).and_then(|v| v.parse::<u64>().ok());
+        let rate_limit = RateLimit {
+            limit: number(&["x-ratelimit-limit", "ratelimit-limit"]),
+            remaining: number(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+            reset_at: number(&["x-ratelimit-reset", "ratelimit-reset"]).map(|reset| {
+                if reset >= RESET_EPOCH_THRESHOLD {
+                    UNIX_EPOCH + Duration::from_secs(reset)
+                } else {
+                    now + Duration::from_secs(reset)
+                }
+            }),
+            retry_after: header(&["retry-after"]).and_then(|v| parse_retry_after(v, now)),
+        };
+        (rate_limit != RateLimit::default()).then_some(rate_limit)
+    }
+
+    /// Whether the quota for the current window is used up (`remaining == 0`).
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+
+    /// How long to wait before the next request should succeed: `Retry-After` when the server sent
+    /// it, else the time until [`reset_at`](Self::reset_at) once the quota is exhausted. `None`
+    /// when the headers carry no such hint.
+    pub fn wait(&self) -> Option<Duration> {
+        self.wait_at(SystemTime::now())
+    }
+
+    pub(crate) fn wait_at(&self, now: SystemTime) -> Option<Duration> {
+        if let Some(retry_after) = self.retry_after {
+            return Some(retry_after);
+        }
+        match self.reset_at {
+            Some(reset_at) if self.is_exhausted() => {
+                Some(reset_at.duration_since(now).unwrap_or_default())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether a non-2xx response is a rate-limit rejection: a `429`, or a `403` whose headers show an
+/// exhausted quota or ask for a `Retry-After` (GitHub's primary and secondary limits).
+pub(crate) fn is_rate_limited(status: u16, rate_limit: Option<&RateLimit>) -> bool {
+    status == 429
+        || (status == 403
+            && rate_limit.is_some_and(|r| r.is_exhausted() || r.retry_after.is_some()))
+}
+
+/// `Retry-After` is either delay-seconds or an HTTP date (IMF-fixdate); a date in the past means
+/// "retry now". The obsolete RFC 850 / asctime date forms are not accepted.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = parse_imf_fixdate(value)?;
+    Some(at.duration_since(now).unwrap_or_default())
+}
+
+/// Parse `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_imf_fixdate(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = value.split_ascii_whitespace();
+    let (_weekday, day, month, year, time, zone) = (
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+    );
+    if zone != "GMT" || parts.next().is_some() {
+        return None;
+    }
+    let day: u32 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u32 + 1;
+    let year: i64 = year.parse().ok()?;
+    let mut hms = time.split(':').map(|p| p.parse::<u64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    if hms.next().is_some() || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    // Days since the Unix epoch for a proleptic Gregorian date (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = u64::try_from(era * 146_097 + doe - 719_468).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + hour * 3600 + minute * 60 + second))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateLimit, is_rate_limited};
+    use crate::http_client::HeaderMap;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn parses_github_and_gitlab_headers() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let github = RateLimit::from_headers_at(
+            &headers(&[
+                ("x-ratelimit-limit", "60"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", "1700000042"),
+            ]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(github.limit, Some(60));
+        assert!(github.is_exhausted());
+        assert_eq!(github.wait_at(now), Some(Duration::from_secs(42)));
+
+        let gitlab = RateLimit::from_headers_at(
+            &headers(&[
+                ("ratelimit-remaining", "17"),
+                ("ratelimit-reset", "1700000100"),
+            ]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(gitlab.remaining, Some(17));
+        // Quota left and no Retry-After: no reason to wait.
+        assert_eq!(gitlab.wait_at(now), None);
+
+        // A small reset value is seconds from now, not a timestamp.
+        let relative =
+            RateLimit::from_headers_at(&headers(&[("ratelimit-reset", "30")]), now).unwrap();
+        assert_eq!(relative.reset_at, Some(now + Duration::from_secs(30)));
+
+        assert_eq!(RateLimit::from_headers_at(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        let now = UNIX_EPOCH + Duration::from_secs(784_111_770); // Sun, 06 Nov 1994 08:49:30 GMT
+        let secs = RateLimit::from_headers_at(&headers(&[("retry-after", "120")]), now).unwrap();
+        assert_eq!(secs.wait_at(now), Some(Duration::from_secs(120)));
+        let date = RateLimit::from_headers_at(
+            &headers(&[("retry-after", "Sun, 06 Nov 1994 08:49:37 GMT")]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(date.retry_after, Some(Duration::from_secs(7)));
+        // Retry-After wins over the reset time.
+        let both = RateLimit::from_headers_at(
+            &headers(&[
+                ("retry-after", "5"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", "1000000000"),
+            ]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(both.wait_at(now), Some(Duration::from_secs(5)));
+        assert_eq!(
+            RateLimit::from_headers_at(&headers(&[("retry-after", "soon")]), now),
+            None
+        );
+    }
+
+    #[test]
+    fn only_429_and_quota_403_are_rate_limits() {
+        let exhausted = RateLimit {
+            remaining: Some(0),
+            ..RateLimit::default()
+        };
+        let plenty = RateLimit {
+            remaining: Some(10),
+            ..RateLimit::default()
+        };
+        assert!(is_rate_limited(429, None));
+        assert!(is_rate_limited(403, Some(&exhausted)));
+        assert!(!is_rate_limited(403, Some(&plenty)));
+        assert!(!is_rate_limited(403, None));
+        assert!(!is_rate_limited(500, Some(&exhausted)));
+    }
+}
@@ -0,0 +1,166 @@
+/*! Install receipts: a record of what the last successful update installed.
+
+With `install_receipt(path)` set on a builder, every successful `update()` writes an
+[`InstallReceipt`] to `path`: the backend the release came from, the installed version, where it
+was installed along with any companion `install_file`s, the SHA-256 of the downloaded release
+asset (with the `checksums` feature), and when. Support tooling, an uninstaller, or the next run
+can read it back with [`InstallReceipt::read`] to learn how the binary on disk got there, and
+[`Uninstall`](crate::uninstall::Uninstall) reads it to find the companion files to remove.
+
+The receipt is JSON, written to a temporary file beside `path` and renamed into place, so a
+reader never sees a partial write:
+
+```json
+{"backend":"github","version":"1.2.0","install_path":"/home/me/.local/bin/app","sha256":"9f86d0…","installed_at":1767225600}
+```
+
+A receipt that cannot be written does not fail the update (the new binary is already in
+place); it is logged as a warning instead.
+*/
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::*;
+
+/// What a successful update installed, as written to the `install_receipt` path. See the
+/// [module docs](crate::receipt).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallReceipt {
+    backend: String,
+    version: String,
+    install_path: PathBuf,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    files: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+    installed_at: u64,
+}
+
+impl InstallReceipt {
+    /// A receipt stamped with the current time.
+    pub(crate) fn new(
+        backend: &str,
+        version: &str,
+        install_path: &Path,
+        files: Vec<PathBuf>,
+        sha256: Option<String>,
+    ) -> Self {
+        Self {
+            backend: backend.to_string(),
+            version: version.to_string(),
+            install_path: install_path.to_path_buf(),
+            files,
+            sha256,
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Read the receipt at `path`, or `None` when there is none yet. A receipt that is not valid
+    /// JSON is an [`Error::Json`].
+    pub fn read(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    /// Write the receipt to `path` atomically, creating its parent directory.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let dir = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        std::fs::create_dir_all(dir)?;
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(&mut tmp, self)?;
+        tmp.flush()?;
+        tmp.persist(path).map_err(|e| Error::Io(e.error))?;
+        Ok(())
+    }
+
+    /// The backend the release came from (`"github"`, `"gitlab"`, `"s3"`, `"custom"`, ...).
+    pub fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    /// The installed version.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Where the update was installed: the binary, the bundle directory in bundle mode, or the
+    /// version's binary in a versioned layout.
+    pub fn install_path(&self) -> &Path {
+        &self.install_path
+    }
+
+    /// The companion files installed with the binary (`install_file`), if any.
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Hex SHA-256 of the downloaded release asset; `None` without the `checksums` feature.
+    pub fn sha256(&self) -> Option<&str> {
+        self.sha256.as_deref()
+    }
+
+    /// When the update was installed, in seconds since the unix epoch.
+    pub fn installed_at(&self) -> u64 {
+        self.installed_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InstallReceipt;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn write_then_read_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state/receipt.json");
+        let receipt = InstallReceipt::new(
+            "github",
+            "1.2.0",
+            Path::new("/usr/local/bin/app"),
+            vec![PathBuf::from("/usr/local/share/man/man1/app.1")],
+            Some("ab".repeat(32)),
+        );
+
+        receipt.write(&path).unwrap();
+
+        assert_eq!(InstallReceipt::read(&path).unwrap(), Some(receipt));
+        assert_eq!(
+            std::fs::read_dir(dir.path().join("state")).unwrap().count(),
+            1
+        );
+    }
+
+    #[test]
+    fn read_of_a_missing_receipt_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            InstallReceipt::read(dir.path().join("receipt.json")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn read_of_a_corrupt_receipt_is_a_json_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("receipt.json");
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            InstallReceipt::read(&path),
+            Err(crate::errors::Error::Json(_))
+        ));
+    }
+}
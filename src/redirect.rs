@@ -0,0 +1,502 @@
+/*!
+Redirect handling shared by every HTTP client the crate drives.
+
+The clients the crate builds (reqwest, async reqwest, ureq) never follow a redirect themselves: a
+`301`/`302`/`303`/`307`/`308` response with a `Location` header comes back as
+[`Error::Redirect`](crate::errors::Error::Redirect), and the crate follows it under the configured
+[`RedirectPolicy`]. Every hop is checked the same way whichever client made the request: the hop
+count is capped, the target host must be allowed, and an `https` to `http` downgrade is refused.
+`Authorization` is dropped on a hop that changes host or leaves `https`, so a token sent to the
+release API does not follow an asset download onto a CDN or presigned storage URL. A refused hop
+fails with [`Error::RedirectRejected`](crate::errors::Error::RedirectRejected).
+
+An injected [`HttpClient`](crate::http_client::HttpClient) takes part by reporting a redirect with
+[`Error::http_response_error`](crate::errors::Error::http_response_error) (as it does for any other
+non-2xx status). An injected reqwest client or ureq agent that follows redirects on its own keeps
+doing so; build it with `reqwest::redirect::Policy::none()` / `max_redirects(0)` to have the
+policy apply instead.
+*/
+
+use std::borrow::Cow;
+
+use crate::Result;
+use crate::backends::common::host_of;
+use crate::errors::{Error, redact_url};
+use crate::http_client::HeaderMap;
+use crate::http_client::header::AUTHORIZATION;
+
+/// Hops followed by [`RedirectPolicy::new`] before giving up, matching reqwest's and ureq's own
+/// default.
+const DEFAULT_MAX_REDIRECTS: u32 = 10;
+
+/// Which redirects a request may follow.
+///
+/// The default follows up to 10 hops to any host, but never from `https` to `http`:
+///
+/// ```
+/// let policy = self_update::RedirectPolicy::new()
+///     .max_redirects(3)
+///     .allow_hosts(["objects.githubusercontent.com"]);
+/// # let _ = policy;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedirectPolicy {
+    max_redirects: u32,
+    allowed_hosts: Vec<String>,
+    allow_https_downgrade: bool,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RedirectPolicy {
+    /// Follow up to 10 redirects to any host, refusing an `https` to `http` downgrade.
+    pub fn new() -> Self {
+        Self {
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            allowed_hosts: Vec::new(),
+            allow_https_downgrade: false,
+        }
+    }
+
+    /// Follow no redirects: a redirect response fails the request with
+    /// [`Error::RedirectRejected`](crate::errors::Error::RedirectRejected).
+    pub fn none() -> Self {
+        Self::new().max_redirects(0)
+    }
+
+    /// Follow at most `max` redirects per request; one more fails the request.
+    pub fn max_redirects(mut self, max: u32) -> Self {
+        self.max_redirects = max;
+        self
+    }
+
+    /// Only follow redirects to these hosts (or back to the host of the original request).
+    /// Matching is by host, case-insensitive, like `allow_auth_host`. Appends to any hosts already
+    /// set; with none set (the default) any host is allowed.
+    pub fn allow_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_hosts
+            .extend(hosts.into_iter().map(|h| h.into().to_ascii_lowercase()));
+        self
+    }
+
+    /// Allow a redirect from `https` to plain `http`. Off by default: such a downgrade would
+    /// expose the rest of the exchange to anyone on the path.
+    pub fn allow_https_downgrade(mut self, allow: bool) -> Self {
+        self.allow_https_downgrade = allow;
+        self
+    }
+
+    /// Why the hop from `from` to `to` (the `hop`-th of the request, 1-based) is refused, if it is.
+    fn rejects(&self, hop: u32, origin_host: Option<&str>, from: &str, to: &str) -> Option<String> {
+        if self.max_redirects == 0 {
+            return Some("redirects are disabled".to_string());
+        }
+        if hop > self.max_redirects {
+            return Some(format!(
+                "more than {} redirects were followed",
+                self.max_redirects
+            ));
+        }
+        let to_scheme = scheme_of(to);
+        if !matches!(to_scheme.as_deref(), Some("http" | "https")) {
+            return Some("the target is not an http(s) URL".to_string());
+        }
+        if !self.allow_https_downgrade
+            && scheme_of(from).as_deref() == Some("https")
+            && to_scheme.as_deref() == Some("http")
+        {
+            return Some("it downgrades https to http".to_string());
+        }
+        if !self.allowed_hosts.is_empty() {
+            let host = host_of(to);
+            let allowed = host.as_deref().is_some_and(|host| {
+                origin_host == Some(host) || self.allowed_hosts.iter().any(|h| h == host)
+            });
+            if !allowed {
+                return Some("the target host is not in the allowed set".to_string());
+            }
+        }
+        None
+    }
+}
+
+/// One redirect a request followed, as reported to a `redirect_callback`.
+///
+/// The URLs are redacted like the ones in errors (a presigned `X-Amz-Signature` is blanked). The
+/// last hop reported for a request names the URL its response finally came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RedirectHop {
+    from: String,
+    to: String,
+    status: u16,
+    auth_stripped: bool,
+}
+
+impl RedirectHop {
+    /// The URL that answered with the redirect.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// The URL the redirect pointed at, resolved against [`from`](Self::from).
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// The redirect's HTTP status (301, 302, 303, 307 or 308).
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Whether the `Authorization` header was dropped for this hop because it changed host or
+    /// left `https`.
+    pub fn auth_stripped(&self) -> bool {
+        self.auth_stripped
+    }
+}
+
+/// A redirect observer: called with each hop a request follows.
+pub(crate) type DynRedirectFn = dyn Fn(&RedirectHop) + Send + Sync;
+
+/// Wrapper around a [`DynRedirectFn`] so structs holding one can still derive `Clone`/`Debug`.
+#[derive(Clone)]
+pub(crate) struct RedirectCallback(pub(crate) std::sync::Arc<DynRedirectFn>);
+
+impl std::fmt::Debug for RedirectCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RedirectCallback(..)")
+    }
+}
+
+/// The URL and headers of the next request in a redirect chain, and the hops taken so far.
+struct Chain<'a> {
+    policy: &'a RedirectPolicy,
+    on_redirect: Option<&'a RedirectCallback>,
+    origin_host: Option<String>,
+    url: Cow<'a, str>,
+    headers: Cow<'a, HeaderMap>,
+    hops: u32,
+}
+
+impl<'a> Chain<'a> {
+    fn new(
+        policy: &'a RedirectPolicy,
+        on_redirect: Option<&'a RedirectCallback>,
+        url: &'a str,
+        headers: &'a HeaderMap,
+    ) -> Self {
+        Self {
+            policy,
+            on_redirect,
+            origin_host: host_of(url),
+            url: Cow::Borrowed(url),
+            headers: Cow::Borrowed(headers),
+            hops: 0,
+        }
+    }
+
+    /// Move on to the redirect target after the current request failed with `err`, or hand `err`
+    /// back when it is not a redirect or the policy refuses the hop.
+    fn advance(&mut self, err: Error) -> Result<()> {
+        let Error::Redirect {
+            status, location, ..
+        } = err
+        else {
+            return Err(err);
+        };
+        let to = resolve(&self.url, &location);
+        self.hops += 1;
+        if let Some(reason) =
+            self.policy
+                .rejects(self.hops, self.origin_host.as_deref(), &self.url, &to)
+        {
+            return Err(Error::RedirectRejected {
+                url: redact_url(&self.url),
+                location: redact_url(&to),
+                reason,
+            });
+        }
+        let leaves_https = scheme_of(&self.url).as_deref() == Some("https")
+            && scheme_of(&to).as_deref() != Some("https");
+        let auth_stripped = (leaves_https || host_of(&self.url) != host_of(&to))
+            && self.headers.contains_key(AUTHORIZATION);
+        if auth_stripped {
+            self.headers.to_mut().remove(AUTHORIZATION);
+        }
+        let hop = RedirectHop {
+            from: redact_url(&self.url),
+            to: redact_url(&to),
+            status,
+            auth_stripped,
+        };
+        log::debug!(
+            "self_update: following a {} redirect from {} to {}{}",
+            hop.status,
+            hop.from,
+            hop.to,
+            if auth_stripped {
+                " (Authorization dropped)"
+            } else {
+                ""
+            }
+        );
+        if let Some(callback) = self.on_redirect {
+            (callback.0)(&hop);
+        }
+        self.url = Cow::Owned(to);
+        Ok(())
+    }
+}
+
+/// Issue `get` for `url`, following each redirect it reports under `policy` and passing every
+/// followed hop to `on_redirect`.
+pub(crate) fn follow<R>(
+    policy: &RedirectPolicy,
+    on_redirect: Option<&RedirectCallback>,
+    url: &str,
+    headers: &HeaderMap,
+    mut get: impl FnMut(&str, &HeaderMap) -> Result<R>,
+) -> Result<R> {
+    let mut chain = Chain::new(policy, on_redirect, url, headers);
+    loop {
+        match get(&chain.url, &chain.headers) {
+            Err(err) => chain.advance(err)?,
+            result => return result,
+        }
+    }
+}
+
+/// Async sibling of [`follow`]. `get` receives owned copies of the URL and headers so its future
+/// does not borrow the chain.
+#[cfg(feature = "async")]
+pub(crate) async fn follow_async<R, Fut>(
+    policy: &RedirectPolicy,
+    on_redirect: Option<&RedirectCallback>,
+    url: &str,
+    headers: &HeaderMap,
+    mut get: impl FnMut(String, HeaderMap) -> Fut,
+) -> Result<R>
+where
+    Fut: std::future::Future<Output = Result<R>>,
+{
+    let mut chain = Chain::new(policy, on_redirect, url, headers);
+    loop {
+        match get(chain.url.to_string(), chain.headers.clone().into_owned()).await {
+            Err(err) => chain.advance(err)?,
+            result => return result,
+        }
+    }
+}
+
+/// The lowercased scheme of an absolute URL.
+fn scheme_of(url: &str) -> Option<String> {
+    url.parse::<http::Uri>()
+        .ok()?
+        .scheme_str()
+        .map(str::to_ascii_lowercase)
+}
+
+/// Resolve a `Location` header value against the URL that returned it: an absolute URL is used as
+/// is, a scheme-relative (`//host/path`) one takes the base scheme, an absolute path the base
+/// origin, and a relative path replaces the last segment of the base path. A fragment is dropped.
+fn resolve(base: &str, location: &str) -> String {
+    let location = location.trim();
+    let location = location.split_once('#').map_or(location, |(l, _)| l);
+    if location.contains("://") {
+        return location.to_string();
+    }
+    let Ok(uri) = base.parse::<http::Uri>() else {
+        return location.to_string();
+    };
+    let scheme = uri.scheme_str().unwrap_or("https");
+    if let Some(rest) = location.strip_prefix("//") {
+        return format!("{scheme}://{rest}");
+    }
+    let origin = match uri.authority() {
+        Some(authority) => format!("{scheme}://{authority}"),
+        None => return location.to_string(),
+    };
+    if location.starts_with('/') {
+        return format!("{origin}{location}");
+    }
+    if location.starts_with('?') {
+        return format!("{origin}{}{location}", uri.path());
+    }
+    let dir = uri.path().rsplit_once('/').map_or("", |(dir, _)| dir);
+    format!("{origin}{dir}/{location}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redirect(status: u16, url: &str, location: &str) -> Error {
+        Error::Redirect {
+            status,
+            url: url.to_string(),
+            location: location.to_string(),
+        }
+    }
+
+    #[test]
+    fn resolve_handles_absolute_and_relative_locations() {
+        let base = "https://api.example.com/repos/o/r/assets/1?x=1";
+        assert_eq!(
+            resolve(base, "https://cdn.example.net/a?sig=1"),
+            "https://cdn.example.net/a?sig=1"
+        );
+        assert_eq!(
+            resolve(base, "//cdn.example.net/a"),
+            "https://cdn.example.net/a"
+        );
+        assert_eq!(
+            resolve(base, "/storage/1"),
+            "https://api.example.com/storage/1"
+        );
+        assert_eq!(
+            resolve(base, "2#frag"),
+            "https://api.example.com/repos/o/r/assets/2"
+        );
+        assert_eq!(
+            resolve(base, "?y=2"),
+            "https://api.example.com/repos/o/r/assets/1?y=2"
+        );
+    }
+
+    #[test]
+    fn follow_reports_each_hop_and_drops_auth_across_hosts() {
+        let hops = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = hops.clone();
+        let callback = RedirectCallback(std::sync::Arc::new(move |hop: &RedirectHop| {
+            seen.lock().unwrap().push(hop.clone());
+        }));
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "token secret".parse().unwrap());
+        let mut sent_auth = Vec::new();
+
+        let final_url = follow(
+            &RedirectPolicy::new(),
+            Some(&callback),
+            "https://api.example.com/asset",
+            &headers,
+            |url, headers| {
+                sent_auth.push(headers.contains_key(AUTHORIZATION));
+                match url {
+                    "https://api.example.com/asset" => Err(redirect(302, url, "/download")),
+                    "https://api.example.com/download" => {
+                        Err(redirect(307, url, "https://cdn.example.net/blob"))
+                    }
+                    _ => Ok(url.to_string()),
+                }
+            },
+        )
+        .unwrap();
+
+        assert_eq!(final_url, "https://cdn.example.net/blob");
+        assert_eq!(sent_auth, [true, true, false]);
+        let hops = hops.lock().unwrap();
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].to(), "https://api.example.com/download");
+        assert!(!hops[0].auth_stripped());
+        assert_eq!(hops[1].status(), 307);
+        assert_eq!(hops[1].to(), "https://cdn.example.net/blob");
+        assert!(hops[1].auth_stripped());
+    }
+
+    fn follow_chain(policy: &RedirectPolicy, from: &str, location: &str) -> Result<()> {
+        follow(policy, None, from, &HeaderMap::new(), |url, _| {
+            if url == from {
+                Err(redirect(302, url, location))
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    fn rejection(result: Result<()>) -> String {
+        match result {
+            Err(Error::RedirectRejected { reason, .. }) => reason,
+            other => panic!("expected RedirectRejected, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn policy_refuses_a_downgrade_unless_allowed() {
+        let reason = rejection(follow_chain(
+            &RedirectPolicy::new(),
+            "https://a.example.com/x",
+            "http://a.example.com/y",
+        ));
+        assert!(reason.contains("downgrades"), "{reason}");
+        follow_chain(
+            &RedirectPolicy::new().allow_https_downgrade(true),
+            "https://a.example.com/x",
+            "http://a.example.com/y",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn policy_limits_hosts_to_the_origin_and_the_allowed_set() {
+        let policy = RedirectPolicy::new().allow_hosts(["CDN.example.net"]);
+        follow_chain(&policy, "https://a.example.com/x", "/y").unwrap();
+        follow_chain(
+            &policy,
+            "https://a.example.com/x",
+            "https://cdn.example.net/y",
+        )
+        .unwrap();
+        let reason = rejection(follow_chain(
+            &policy,
+            "https://a.example.com/x",
+            "https://evil.example.org/y",
+        ));
+        assert!(reason.contains("allowed"), "{reason}");
+    }
+
+    #[test]
+    fn policy_caps_the_hop_count() {
+        let loop_forever = |policy: &RedirectPolicy| {
+            let mut requests = 0;
+            let result = follow(
+                policy,
+                None,
+                "https://a.example.com/0",
+                &HeaderMap::new(),
+                |url, _| {
+                    requests += 1;
+                    Err::<(), _>(redirect(302, url, &format!("/{requests}")))
+                },
+            );
+            (rejection(result), requests)
+        };
+        let (reason, requests) = loop_forever(&RedirectPolicy::new().max_redirects(2));
+        assert!(reason.contains("more than 2"), "{reason}");
+        assert_eq!(requests, 3);
+        let (reason, requests) = loop_forever(&RedirectPolicy::none());
+        assert!(reason.contains("disabled"), "{reason}");
+        assert_eq!(requests, 1);
+    }
+
+    #[test]
+    fn follow_passes_other_errors_through() {
+        let err = follow(
+            &RedirectPolicy::new(),
+            None,
+            "https://a.example.com/x",
+            &HeaderMap::new(),
+            |url, _| Err::<(), _>(crate::errors::status_to_error(404, url)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }), "got {err:?}");
+    }
+}
@@ -0,0 +1,435 @@
+/*! Run the new binary before it is installed.
+
+A [`SelfTest`] executes the freshly extracted binary (by default as `<new> --version`) and checks
+that it exits successfully within a timeout and that its output mentions the version being
+installed. A binary built for the wrong architecture, a truncated download, or one that crashes on
+startup is rejected with [`Error::VerificationRejected`] before it replaces anything.
+
+Register one with the `self_test` builder setter; it runs after extraction and before the
+`verify_binary` hook:
+
+```rust,no_run
+# fn run() -> Result<(), Box<dyn std::error::Error>> {
+use self_update::self_test::SelfTest;
+use std::time::Duration;
+
+self_update::backends::github::Update::configure()
+    .repo_owner("jaemk")
+    .repo_name("self_update")
+    .bin_name("myapp")
+    .current_version(self_update::cargo_crate_version!())
+    .self_test(SelfTest::new().timeout(Duration::from_secs(5)))
+    .build()?
+    .update()?;
+# Ok(())
+# }
+```
+
+## Isolation
+
+The binary is run with an **empty environment** (plus the variables named with
+[`keep_env`](SelfTest::keep_env) or set with [`env`](SelfTest::env); on windows `SystemRoot` is
+kept, without which processes fail to start), a null stdin, and the update's temp directory as its
+working directory. It is killed once the timeout elapses. This keeps the test from reading the
+caller's configuration or credentials by accident; it is not an OS-level sandbox, so only
+self-test a binary whose archive was verified with `checksums` / `signatures` first.
+*/
+
+use crate::errors::*;
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+
+/// At most this much of each output stream is kept for the version check and error messages; the
+/// rest is read and discarded so a chatty binary cannot block on a full pipe.
+const MAX_OUTPUT: usize = 64 * 1024;
+
+/// How often the child is polled for exit while waiting on the timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long the output of a binary that exited right at the timeout is still waited on.
+const OUTPUT_GRACE: Duration = Duration::from_millis(100);
+
+/// A built-in verifier that runs the extracted binary before it is installed. See the
+/// [module docs](crate::self_test).
+///
+/// Defaults: arguments `["--version"]`, a 10 second timeout, the version check on, and an empty
+/// environment.
+#[derive(Clone, Debug)]
+pub struct SelfTest {
+    args: Vec<OsString>,
+    timeout: Duration,
+    check_version: bool,
+    keep_env: Vec<OsString>,
+    env: Vec<(OsString, OsString)>,
+}
+
+impl Default for SelfTest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SelfTest {
+    /// A self-test running `<new binary> --version` with the defaults above.
+    pub fn new() -> Self {
+        Self {
+            args: vec!["--version".into()],
+            timeout: Duration::from_secs(10),
+            check_version: true,
+            keep_env: default_keep_env(),
+            env: Vec::new(),
+        }
+    }
+
+    /// Arguments to run the binary with, replacing the default `["--version"]`.
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// How long the binary may run before it is killed and the update rejected.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether the binary's output (stdout or stderr) must mention the release version. On by
+    /// default; turn it off for arguments that do not print the version.
+    pub fn check_version(mut self, check: bool) -> Self {
+        self.check_version = check;
+        self
+    }
+
+    /// Pass the named variable through from the current environment, if it is set.
+    pub fn keep_env(mut self, name: impl Into<OsString>) -> Self {
+        self.keep_env.push(name.into());
+        self
+    }
+
+    /// Set a variable in the binary's environment.
+    pub fn env(mut self, name: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.env.push((name.into(), value.into()));
+        self
+    }
+
+    /// Run `exe` and check that it exits successfully within the timeout and, when the version
+    /// check is on, that its output mentions `expected_version` (a leading `v` on either side is
+    /// ignored, and the version must appear as a whole word, so `1.2.3` does not match `1.2.30`).
+    ///
+    /// On unix a file with no execute bit at all (a raw asset copied out of the download has none)
+    /// is first made executable by its owner, since it is about to be installed as a binary.
+    ///
+    /// * Errors:
+    ///     * [`Error::VerificationRejected`] if the binary cannot be started, times out, exits
+    ///       unsuccessfully, or does not print the version
+    ///     * [`Error::Io`] if the file's permissions cannot be read or set, or waiting on the
+    ///       process fails
+    pub fn run(&self, exe: &Path, expected_version: &str) -> Result<()> {
+        ensure_executable(exe)?;
+        let mut cmd = Command::new(exe);
+        cmd.args(&self.args)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(dir) = exe.parent().filter(|d| !d.as_os_str().is_empty()) {
+            cmd.current_dir(dir);
+        }
+        for name in &self.keep_env {
+            if let Some(value) = std::env::var_os(name) {
+                cmd.env(name, value);
+            }
+        }
+        for (name, value) in &self.env {
+            cmd.env(name, value);
+        }
+
+        let mut child = spawn(&mut cmd).map_err(|e| {
+            Error::verification_rejected(format!(
+                "self-test could not start {}: {e}",
+                exe.display()
+            ))
+        })?;
+        let stdout = child.stdout.take().map(collect_output);
+        let stderr = child.stderr.take().map(collect_output);
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::verification_rejected(format!(
+                    "self-test of {} timed out after {:?}",
+                    exe.display(),
+                    self.timeout
+                )));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        };
+
+        // A background process the binary left behind may hold the pipes open: wait for the
+        // end of its output only until the timeout, then take what was read so far.
+        let deadline = deadline.max(Instant::now() + OUTPUT_GRACE);
+        let mut output = String::new();
+        for reader in [stdout, stderr].into_iter().flatten() {
+            let bytes = reader.finish(deadline);
+            output.push_str(&String::from_utf8_lossy(&bytes));
+            output.push('\n');
+        }
+
+        if !status.success() {
+            return Err(Error::verification_rejected(format!(
+                "self-test of {} failed ({status}): {}",
+                exe.display(),
+                summary(&output)
+            )));
+        }
+        if self.check_version && !mentions_version(&output, expected_version) {
+            return Err(Error::verification_rejected(format!(
+                "self-test of {} did not report version {expected_version}: {}",
+                exe.display(),
+                summary(&output)
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Spawn `cmd`, retrying briefly while the file is still open for writing elsewhere (`ETXTBSY`,
+/// e.g. a descriptor to the freshly written binary inherited by a concurrently forked process).
+fn spawn(cmd: &mut Command) -> std::io::Result<std::process::Child> {
+    let mut attempts = 0;
+    loop {
+        match cmd.spawn() {
+            Err(e) if e.kind() == std::io::ErrorKind::ExecutableFileBusy && attempts < 5 => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Give `path` the owner execute bit when it has no execute bit at all.
+#[cfg(unix)]
+fn ensure_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = std::fs::metadata(path)?.permissions();
+    if perms.mode() & 0o111 == 0 {
+        perms.set_mode(perms.mode() | 0o100);
+        std::fs::set_permissions(path, perms)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// The variables kept by default: none, except `SystemRoot` on windows.
+fn default_keep_env() -> Vec<OsString> {
+    if cfg!(windows) {
+        vec!["SystemRoot".into()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// An output stream drained on its own thread.
+struct OutputReader {
+    kept: Arc<Mutex<Vec<u8>>>,
+    /// Disconnected once the thread has seen the end of the stream.
+    done: mpsc::Receiver<()>,
+}
+
+impl OutputReader {
+    /// The output kept once the stream ends or `deadline` passes, whichever comes first. The
+    /// thread is left to finish on its own in the second case.
+    fn finish(self, deadline: Instant) -> Vec<u8> {
+        let _ = self
+            .done
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()));
+        let mut kept = self.kept.lock().unwrap_or_else(|e| e.into_inner());
+        std::mem::take(&mut *kept)
+    }
+}
+
+/// Drain `stream` on a thread, keeping the first [`MAX_OUTPUT`] bytes.
+fn collect_output(mut stream: impl Read + Send + 'static) -> OutputReader {
+    let kept = Arc::new(Mutex::new(Vec::new()));
+    let (done_tx, done) = mpsc::channel::<()>();
+    let shared = Arc::clone(&kept);
+    std::thread::spawn(move || {
+        let _done = done_tx;
+        let mut buf = [0u8; 8192];
+        while let Ok(n) = stream.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            let mut kept = shared.lock().unwrap_or_else(|e| e.into_inner());
+            let room = MAX_OUTPUT.saturating_sub(kept.len());
+            kept.extend_from_slice(&buf[..n.min(room)]);
+        }
+    });
+    OutputReader { kept, done }
+}
+
+/// Whether `version` appears in `output` as a whole word, ignoring a leading `v` on either side.
+fn mentions_version(output: &str, version: &str) -> bool {
+    let version = version.trim().trim_start_matches('v');
+    !version.is_empty()
+        && output
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')' | '"' | '\''))
+            .map(|word| word.trim_start_matches('v').trim_end_matches(['.', ':']))
+            .any(|word| word == version)
+}
+
+/// The first non-empty line of `output`, for error messages.
+fn summary(output: &str) -> &str {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("<no output>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mentions_version_matches_whole_words_only() {
+        assert!(mentions_version("myapp 1.2.3\n", "1.2.3"));
+        assert!(mentions_version("myapp v1.2.3 (abc123)", "1.2.3"));
+        assert!(mentions_version("myapp 1.2.3", "v1.2.3"));
+        assert!(mentions_version("version: 1.2.3.", "1.2.3"));
+        assert!(!mentions_version("myapp 1.2.30", "1.2.3"));
+        assert!(!mentions_version("myapp 11.2.3", "1.2.3"));
+        assert!(!mentions_version("myapp", ""));
+    }
+
+    /// Write an executable shell script into `dir` and return its path.
+    #[cfg(unix)]
+    fn script(dir: &Path, body: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("app");
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn reason(err: Error) -> String {
+        match err {
+            Error::VerificationRejected {
+                reason: Some(reason),
+            } => reason,
+            other => panic!("expected VerificationRejected, got {other:?}"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn passes_when_the_binary_prints_the_release_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = script(dir.path(), r#"[ "$1" = "--version" ] && echo "app 1.2.3""#);
+        SelfTest::new().run(&exe, "1.2.3").unwrap();
+
+        // Custom args, and stderr counts too.
+        let exe = script(dir.path(), r#"[ "$1" = "-V" ] && echo "v1.2.3" >&2"#);
+        SelfTest::new().args(["-V"]).run(&exe, "1.2.3").unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_wrong_version_failure_and_unrunnable_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = script(dir.path(), "echo app 1.2.2");
+        let msg = reason(SelfTest::new().run(&exe, "1.2.3").unwrap_err());
+        assert!(
+            msg.contains("did not report version 1.2.3: app 1.2.2"),
+            "{msg}"
+        );
+        // ... unless the version check is off.
+        SelfTest::new()
+            .check_version(false)
+            .run(&exe, "1.2.3")
+            .unwrap();
+
+        let exe = script(dir.path(), "echo 'app 1.2.3'; echo boom >&2; exit 3");
+        let msg = reason(SelfTest::new().run(&exe, "1.2.3").unwrap_err());
+        assert!(msg.contains("failed") && msg.contains('3'), "{msg}");
+
+        // Not a valid executable (e.g. a truncated or foreign-architecture binary).
+        let garbage = dir.path().join("garbage");
+        std::fs::write(&garbage, b"\x7fELF\x00").unwrap();
+        let msg = reason(SelfTest::new().run(&garbage, "1.2.3").unwrap_err());
+        assert!(msg.contains("could not start"), "{msg}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kills_a_binary_that_outlives_the_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = script(dir.path(), "exec sleep 10");
+        let start = Instant::now();
+        let msg = reason(
+            SelfTest::new()
+                .timeout(Duration::from_millis(100))
+                .run(&exe, "1.2.3")
+                .unwrap_err(),
+        );
+        assert!(msg.contains("timed out"), "{msg}");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    // A background process holding the pipes open does not stall the self-test past its timeout.
+    #[cfg(unix)]
+    #[test]
+    fn does_not_wait_on_output_held_open_by_a_background_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = script(dir.path(), "echo app 1.2.3; sleep 10 &");
+        let start = Instant::now();
+        SelfTest::new()
+            .timeout(Duration::from_millis(300))
+            .run(&exe, "1.2.3")
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_with_a_scrubbed_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = script(
+            dir.path(),
+            r#"echo "app 1.2.3 home=[$HOME] extra=[$EXTRA] cwd=[$(/bin/pwd)]""#,
+        );
+        // A wrong expected version makes the rejection message quote what the binary saw.
+        let expect = |test: SelfTest, needle: &str| {
+            let msg = reason(test.run(&exe, "9.9.9").unwrap_err());
+            assert!(msg.contains(needle), "{needle} not in {msg}");
+        };
+        expect(SelfTest::new(), "home=[] extra=[]");
+        expect(SelfTest::new().env("EXTRA", "1"), "extra=[1]");
+        if let Some(home) = std::env::var_os("HOME") {
+            expect(
+                SelfTest::new().keep_env("HOME"),
+                &format!("home=[{}]", home.to_string_lossy()),
+            );
+        }
+        let cwd = dir.path().canonicalize().unwrap();
+        expect(SelfTest::new(), &format!("cwd=[{}]", cwd.display()));
+    }
+}
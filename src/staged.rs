@@ -0,0 +1,516 @@
+/*!
+Staged updates: download now, apply on the next start (`stage` feature).
+
+A long-running daemon often cannot replace its binary or restart at an arbitrary moment. Instead
+of [`update()`](crate::update::ReleaseUpdate::update), it calls `stage()` (or `stage_async()`) on
+an updater built with `staging_dir(dir)`. That runs the usual pipeline up to and including
+verification: download, checksum and signature checks, `self_test`, `verify_binary`. The verified
+binary is then stored in `dir` with a small JSON manifest instead of being installed.
+
+The next start installs it with [`ApplyStaged`], called at the very top of `main`:
+
+```rust,no_run
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let staged = self_update::staged::ApplyStaged::new(
+        "/var/lib/mydaemon/update",
+        self_update::cargo_crate_version!(),
+    )
+    .restart(true)
+    .apply();
+    if let Err(e) = staged {
+        eprintln!("discarded the staged update: {}", e);
+    }
+    // ... run the daemon, and `stage()` the next update when one is out ...
+    Ok(())
+}
+```
+
+A staged update is rejected with [`Error::StagedUpdateRejected`], and discarded, when it is
+stale, corrupt or tampered with:
+
+- its version is not newer than the running one,
+- it was staged for a different install path,
+- the installed binary changed since it was staged (say, reinstalled by other means),
+- the staged binary's SHA-256 no longer matches the one recorded when it was staged,
+- with verifying keys configured, the signed archive it was extracted from does not verify, or
+- the manifest cannot be read.
+
+The recorded SHA-256 lives in the manifest beside the binary, so on its own it only catches
+corruption: whoever can replace the staged binary can rewrite the manifest too. To reject
+tampering, give `ApplyStaged` the keys the updater verifies releases with (`verifying_keys`,
+and with `trusted_keys` / `key_store_path` the same trust policy). With the `signatures` feature, `stage()` keeps the downloaded archive
+(and its detached `.sig`, if any) beside the binary; `apply` then checks that archive's signature
+against those keys and installs the binary extracted from it again, not the staged copy.
+*/
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::checksum::sha256_file_hex;
+use crate::errors::*;
+
+/// The staged binary's file name in the staging directory.
+const BINARY: &str = "binary";
+/// The signed archive the binary was extracted from, and its detached signature.
+const ARCHIVE: &str = "archive";
+const ARCHIVE_SIG: &str = "archive.sig";
+/// The manifest's file name; a staged update exists exactly when it does.
+const MANIFEST: &str = "staged.json";
+
+/// What was staged, written last so a reader never pairs it with a partially staged binary.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: String,
+    install_path: PathBuf,
+    /// Hex SHA-256 of the staged binary.
+    sha256: String,
+    /// Hex SHA-256 of the installed binary when the update was staged, if there was one.
+    installed_sha256: Option<String>,
+    staged_at: u64,
+    /// The name the archive stored as `archive` was downloaded under (its signature is bound to
+    /// it), when one was kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    archive_name: Option<String>,
+    /// The binary's path inside that archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bin_path_in_archive: Option<String>,
+}
+
+/// Store the verified `new_exe` of `version` in `dir`, to be installed at `install_path` by
+/// [`ApplyStaged`]. With `signatures`, the `archive` it was extracted from (at
+/// `bin_path_in_archive`) is kept too, for `ApplyStaged` to verify again. Replaces anything staged
+/// before.
+#[cfg_attr(not(feature = "signatures"), allow(unused_variables))]
+pub(crate) fn stage(
+    new_exe: &Path,
+    dir: &Path,
+    version: &str,
+    install_path: &Path,
+    archive: &Path,
+    bin_path_in_archive: &str,
+) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    // Unstage the previous update first, so a failure below leaves nothing to apply.
+    remove_if_present(&dir.join(MANIFEST))?;
+    remove_if_present(&dir.join(ARCHIVE_SIG))?;
+
+    let binary = dir.join(BINARY);
+    copy_through_temp(new_exe, &binary, true)?;
+    #[cfg(feature = "signatures")]
+    let archive_name = {
+        copy_through_temp(archive, &dir.join(ARCHIVE), false)?;
+        let signature = crate::update::detached_signature_path(archive);
+        if signature.is_file() {
+            copy_through_temp(&signature, &dir.join(ARCHIVE_SIG), false)?;
+        }
+        archive
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_string)
+    };
+    #[cfg(not(feature = "signatures"))]
+    let archive_name = None;
+
+    let installed_sha256 = match sha256_file_hex(install_path) {
+        Ok(sha256) => Some(sha256),
+        Err(Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let manifest = Manifest {
+        version: version.to_string(),
+        install_path: install_path.to_path_buf(),
+        sha256: sha256_file_hex(&binary)?,
+        installed_sha256,
+        staged_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        bin_path_in_archive: archive_name
+            .as_ref()
+            .map(|_| bin_path_in_archive.to_string()),
+        archive_name,
+    };
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    serde_json::to_writer(&mut tmp, &manifest)?;
+    tmp.flush()?;
+    tmp.persist(dir.join(MANIFEST))
+        .map_err(|e| Error::Io(e.error))?;
+    Ok(())
+}
+
+/// The files `stage` writes into `dir`, manifest first.
+pub(crate) fn staged_files(dir: &Path) -> [PathBuf; 4] {
+    [MANIFEST, BINARY, ARCHIVE, ARCHIVE_SIG].map(|name| dir.join(name))
+}
+
+/// Copy `src` to `dest` through a temp file beside it, so `dest` is never partially written.
+fn copy_through_temp(src: &Path, dest: &Path, executable: bool) -> Result<()> {
+    let dir = dest.parent().unwrap_or(Path::new("."));
+    let mut tmp = tempfile::Builder::new()
+        .prefix(".self_update-staged")
+        .tempfile_in(dir)?;
+    io::copy(&mut fs::File::open(src)?, tmp.as_file_mut())?;
+    #[cfg(unix)]
+    if executable {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(tmp.path(), fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(not(unix))]
+    let _ = executable;
+    tmp.persist(dest).map_err(|e| Error::Io(e.error))?;
+    Ok(())
+}
+
+fn remove_if_present(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Install the update staged in a directory by `stage()`, if there is one. See the
+/// [module docs](crate::staged).
+///
+/// Built with [`new`](Self::new), adjusted with the consuming setters, and run with
+/// [`apply`](Self::apply).
+#[derive(Clone, Debug)]
+pub struct ApplyStaged {
+    dir: PathBuf,
+    current_version: String,
+    install_path: Option<PathBuf>,
+    restart: bool,
+    #[cfg(feature = "signatures")]
+    trust: crate::keys::TrustPolicy,
+}
+
+impl ApplyStaged {
+    /// Apply the update staged in `dir` over the running binary, whose version is
+    /// `current_version`.
+    pub fn new(dir: impl AsRef<Path>, current_version: &str) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            current_version: current_version.to_string(),
+            install_path: None,
+            restart: false,
+            #[cfg(feature = "signatures")]
+            trust: Default::default(),
+        }
+    }
+
+    /// The binary the update was staged for, when it is not the running executable (the
+    /// `bin_install_path` the staging updater was built with). A staged update for any other
+    /// path is rejected.
+    pub fn install_path(mut self, install_path: impl AsRef<Path>) -> Self {
+        self.install_path = Some(install_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Restart into the new binary with [`restart::restart`](crate::restart::restart) once it
+    /// is installed, so the process never runs the old code past this call. Defaults to `false`.
+    pub fn restart(mut self, restart: bool) -> Self {
+        self.restart = restart;
+        self
+    }
+
+    /// Authenticate the stage before installing it: the archive it was extracted from must carry
+    /// a valid signature by one of `keys` (pass the updater's `verifying_keys`). A stage without
+    /// such an archive is rejected. Like the builders' setter, this **replaces** the keys on each
+    /// call. Requires the `signatures` feature.
+    #[cfg(feature = "signatures")]
+    pub fn verifying_keys(mut self, keys: impl Into<Vec<crate::VerifyingKey>>) -> Self {
+        self.trust.verifying_keys = keys.into();
+        self
+    }
+
+    /// Also accept keys with validity windows, as the builders' `trusted_keys`. Requires the
+    /// `signatures` feature.
+    #[cfg(feature = "signatures")]
+    pub fn trusted_keys(mut self, keys: impl Into<Vec<crate::keys::TrustedKey>>) -> Self {
+        self.trust.trusted_keys = keys.into();
+        self
+    }
+
+    /// Apply the rotations and revocations persisted in the updater's key store, as the
+    /// builders' `key_store_path`. Requires the `signatures` feature.
+    #[cfg(feature = "signatures")]
+    pub fn key_store_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.trust.key_store_path = Some(path.into());
+        self
+    }
+
+    /// Install the staged update and clear the staging directory, returning the installed
+    /// version; `None` when nothing is staged.
+    ///
+    /// A stale, corrupt or tampered staged update is discarded and returned as
+    /// [`Error::StagedUpdateRejected`]; the running binary is left as it was. With verifying keys
+    /// configured, an error computing the trusted keys (such as an unreadable key store) is
+    /// returned as is and leaves the stage in place. With [`restart(true)`](Self::restart), a
+    /// successful install does not return.
+    pub fn apply(self) -> Result<Option<String>> {
+        let manifest = match fs::read_to_string(self.dir.join(MANIFEST)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let install_path = match &self.install_path {
+            Some(path) => path.clone(),
+            None => std::env::current_exe()?,
+        };
+        let manifest = match self.check(&manifest, &install_path) {
+            Ok(manifest) => manifest,
+            Err(reason) => return self.reject(reason),
+        };
+
+        #[cfg(feature = "signatures")]
+        let authenticated = match self.trust.active_keys(&Default::default())? {
+            keys if keys.is_empty() => None,
+            keys => match self.authenticate(&manifest, &keys) {
+                Ok(extracted) => Some(extracted),
+                Err(reason) => return self.reject(reason),
+            },
+        };
+        #[cfg(feature = "signatures")]
+        let binary = match &authenticated {
+            Some((_, extracted)) => extracted.clone(),
+            None => self.dir.join(BINARY),
+        };
+        #[cfg(not(feature = "signatures"))]
+        let binary = self.dir.join(BINARY);
+
+        crate::update::install_binary(&binary, &install_path, None, false)?;
+        self.discard()?;
+        if self.restart {
+            crate::restart::restart()?;
+        }
+        Ok(Some(manifest.version))
+    }
+
+    /// Parse the manifest and check the staged update against the running install, returning
+    /// why it must be rejected.
+    fn check(&self, manifest: &str, install_path: &Path) -> std::result::Result<Manifest, String> {
+        let manifest: Manifest =
+            serde_json::from_str(manifest).map_err(|e| format!("unreadable manifest: {}", e))?;
+        match crate::version::bump_is_greater(&self.current_version, &manifest.version) {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(format!(
+                    "staged version {} is not newer than the running {}",
+                    manifest.version, self.current_version
+                ));
+            }
+            Err(e) => return Err(format!("staged version {}: {}", manifest.version, e)),
+        }
+        if !crate::update::same_file(&manifest.install_path, install_path) {
+            return Err(format!(
+                "staged for {}, not {}",
+                manifest.install_path.display(),
+                install_path.display()
+            ));
+        }
+        let installed_sha256 = sha256_file_hex(install_path).ok();
+        if installed_sha256 != manifest.installed_sha256 {
+            return Err(format!(
+                "{} changed since the update was staged",
+                install_path.display()
+            ));
+        }
+        match sha256_file_hex(&self.dir.join(BINARY)) {
+            Ok(sha256) if sha256 == manifest.sha256 => Ok(manifest),
+            Ok(sha256) => Err(format!(
+                "the staged binary's SHA-256 is {}, not the staged {}",
+                sha256, manifest.sha256
+            )),
+            Err(e) => Err(format!("unreadable staged binary: {}", e)),
+        }
+    }
+
+    /// Verify the staged archive's signature against `keys` and extract the binary from it again,
+    /// returning it (in a private temp dir) or why the stage must be rejected. The archive is
+    /// copied there first, under the name its signature is bound to, so the file verified is the
+    /// file extracted.
+    #[cfg(feature = "signatures")]
+    fn authenticate(
+        &self,
+        manifest: &Manifest,
+        keys: &[crate::VerifyingKey],
+    ) -> std::result::Result<(tempfile::TempDir, PathBuf), String> {
+        let (Some(name), Some(bin_path)) = (&manifest.archive_name, &manifest.bin_path_in_archive)
+        else {
+            return Err("no signed archive was staged with it".to_string());
+        };
+        let inside = Path::new(bin_path).components().all(|c| {
+            matches!(
+                c,
+                std::path::Component::Normal(_) | std::path::Component::CurDir
+            )
+        });
+        if !crate::update::is_safe_asset_name(name) || !inside {
+            return Err(format!(
+                "unsafe archive name {:?} or binary path {:?}",
+                name, bin_path
+            ));
+        }
+        let extract = || -> Result<(tempfile::TempDir, PathBuf)> {
+            let tmp = tempfile::tempdir()?;
+            let archive = tmp.path().join(name);
+            fs::copy(self.dir.join(ARCHIVE), &archive)?;
+            let signature = self.dir.join(ARCHIVE_SIG);
+            if signature.is_file() {
+                fs::copy(&signature, crate::update::detached_signature_path(&archive))?;
+            }
+            crate::update::verify_signature(&archive, keys)?;
+            let out = tmp.path().join("extracted");
+            fs::create_dir(&out)?;
+            crate::Extract::from_source(&archive).extract_file(&out, bin_path)?;
+            let extracted = out.join(bin_path);
+            Ok((tmp, extracted))
+        };
+        extract().map_err(|e| format!("the staged archive {} does not verify: {}", name, e))
+    }
+
+    /// Discard the stage and report why it was rejected.
+    fn reject(self, reason: String) -> Result<Option<String>> {
+        self.discard()?;
+        Err(Error::StagedUpdateRejected {
+            dir: self.dir,
+            reason,
+        })
+    }
+
+    /// Remove the manifest, then the binary and the archive.
+    fn discard(&self) -> Result<()> {
+        let [manifest, rest @ ..] = staged_files(&self.dir);
+        remove_if_present(&manifest)?;
+        rest.iter().try_for_each(|path| remove_if_present(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A staging dir holding version `1.1.0` for `<dir>/app`, currently an installed `1.0.0`.
+    fn staged() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let installed = dir.path().join("app");
+        fs::write(&installed, "1.0.0").unwrap();
+        let new_exe = dir.path().join("new");
+        fs::write(&new_exe, "1.1.0").unwrap();
+        let staging = dir.path().join("staging");
+        stage(&new_exe, &staging, "1.1.0", &installed, &new_exe, "new").unwrap();
+        (dir, staging, installed)
+    }
+
+    fn rejection(res: Result<Option<String>>) -> String {
+        match res.unwrap_err() {
+            Error::StagedUpdateRejected { reason, .. } => reason,
+            other => panic!("expected Error::StagedUpdateRejected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn apply_installs_the_staged_binary_and_clears_the_stage() {
+        let (_dir, staging, installed) = staged();
+
+        let applied = ApplyStaged::new(&staging, "1.0.0")
+            .install_path(&installed)
+            .apply()
+            .unwrap();
+
+        assert_eq!(applied.as_deref(), Some("1.1.0"));
+        assert_eq!(fs::read_to_string(&installed).unwrap(), "1.1.0");
+        assert_eq!(fs::read_dir(&staging).unwrap().count(), 0);
+        let again = ApplyStaged::new(&staging, "1.1.0").install_path(&installed);
+        assert_eq!(again.apply().unwrap(), None);
+    }
+
+    #[test]
+    fn apply_rejects_a_corrupt_binary() {
+        let (_dir, staging, installed) = staged();
+        fs::write(staging.join(BINARY), "evil").unwrap();
+
+        let reason = rejection(
+            ApplyStaged::new(&staging, "1.0.0")
+                .install_path(&installed)
+                .apply(),
+        );
+
+        assert!(reason.contains("SHA-256"), "{}", reason);
+        assert_eq!(fs::read_to_string(&installed).unwrap(), "1.0.0");
+        assert_eq!(fs::read_dir(&staging).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn apply_rejects_a_stale_stage() {
+        // Already running the staged version.
+        let (_dir, staging, installed) = staged();
+        let reason = rejection(
+            ApplyStaged::new(&staging, "1.1.0")
+                .install_path(&installed)
+                .apply(),
+        );
+        assert!(reason.contains("not newer"), "{}", reason);
+
+        // The installed binary was replaced by other means since staging.
+        let (_dir, staging, installed) = staged();
+        fs::write(&installed, "1.0.1").unwrap();
+        let reason = rejection(
+            ApplyStaged::new(&staging, "1.0.1")
+                .install_path(&installed)
+                .apply(),
+        );
+        assert!(reason.contains("changed"), "{}", reason);
+        assert_eq!(fs::read_to_string(&installed).unwrap(), "1.0.1");
+
+        // Staged for another binary.
+        let (dir, staging, _) = staged();
+        let other = dir.path().join("other");
+        fs::write(&other, "1.0.0").unwrap();
+        let reason = rejection(
+            ApplyStaged::new(&staging, "1.0.0")
+                .install_path(&other)
+                .apply(),
+        );
+        assert!(reason.starts_with("staged for"), "{}", reason);
+    }
+
+    #[cfg(feature = "signatures")]
+    #[test]
+    fn apply_with_keys_rejects_an_unsigned_stage() {
+        let (_dir, staging, installed) = staged();
+        let key = zipsign_api::SigningKey::from_bytes(&[40u8; 32])
+            .verifying_key()
+            .to_bytes();
+
+        let reason = rejection(
+            ApplyStaged::new(&staging, "1.0.0")
+                .install_path(&installed)
+                .verifying_keys([key])
+                .apply(),
+        );
+
+        assert!(reason.contains("does not verify"), "{}", reason);
+        assert_eq!(fs::read_to_string(&installed).unwrap(), "1.0.0");
+        assert_eq!(fs::read_dir(&staging).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn stage_replaces_an_earlier_stage() {
+        let (dir, staging, installed) = staged();
+        let newer = dir.path().join("newer");
+        fs::write(&newer, "1.2.0").unwrap();
+        stage(&newer, &staging, "1.2.0", &installed, &newer, "newer").unwrap();
+
+        let applied = ApplyStaged::new(&staging, "1.0.0")
+            .install_path(&installed)
+            .apply()
+            .unwrap();
+
+        assert_eq!(applied.as_deref(), Some("1.2.0"));
+        assert_eq!(fs::read_to_string(&installed).unwrap(), "1.2.0");
+    }
+}
@@ -0,0 +1,622 @@
+/*! An in-process stand-in for the release hosts, for integration tests (`test-util` feature).
+
+A [`ReleaseServer`] listens on a loopback port and serves a declarative set of [`TestRelease`]s
+through the GitHub, GitLab and Gitea release APIs and an S3 `ListObjectsV2` bucket listing, plus
+the asset downloads they point at. Point a backend at it and run the real update flow, offline:
+
+```rust,no_run
+# #[cfg(feature = "github")]
+# fn run() -> self_update::Result<()> {
+use self_update::test_server::{ReleaseServer, TestAsset, TestRelease};
+
+let server = ReleaseServer::configure()
+    .release(
+        TestRelease::new("v2.0.0")
+            .with_asset(TestAsset::new("myapp-x86_64-unknown-linux-gnu", &b"new binary"[..])),
+    )
+    .release(TestRelease::new("v1.0.0"))
+    .page_size(1)
+    .build()?;
+
+let latest = self_update::backends::github::Update::configure()
+    .api_base_url(server.github_api_url())
+    .repo_owner("o")
+    .repo_name("myapp")
+    .bin_name("myapp")
+    .current_version("1.0.0")
+    .build()?
+    .get_latest_release()?;
+# let _ = latest;
+# Ok(())
+# }
+```
+
+## Routes
+
+Releases are listed in the order they were added, so add the newest first (GitHub's
+`releases/latest` serves the first one). The owner and repo name in a path are not checked.
+
+| Host | Base | Served |
+|------|------|--------|
+| GitHub | [`github_api_url`](ReleaseServer::github_api_url) | `/repos/{o}/{r}/releases`, `/releases/latest`, `/releases/tags/{tag}` |
+| GitLab | [`gitlab_host`](ReleaseServer::gitlab_host) | `/api/v4/projects/{o}%2F{r}/releases`, `/releases/{tag}` |
+| Gitea | [`gitea_host`](ReleaseServer::gitea_host) | `/api/v1/repos/{o}/{r}/releases`, `/releases/tags/{tag}` |
+| S3 | [`s3_endpoint`](ReleaseServer::s3_endpoint) | `?list-type=2` listing, `/{asset name}` objects |
+
+The forge listings are paginated with `per_page`/`page` and a `Link: rel="next"` header, the S3
+listing with `max-keys` and continuation tokens; [`page_size`](ReleaseServerBuilder::page_size)
+caps both so a handful of releases spans several pages. Forge assets download from
+`/download/{release}/{asset}`. S3 keys are the asset names, so name S3 assets after the
+`<name>-<version>-<target>` convention the backend parses.
+
+GitHub assets carry a `digest`: the one set with [`TestAsset::with_digest`] (set a wrong one to
+exercise a checksum failure), else the asset's real `sha256:` digest when the `checksums` feature
+is on.
+
+With [`require_auth_token`](ReleaseServerBuilder::require_auth_token), forge API and download
+requests without that token in `Authorization` (any scheme) get a 401. With
+[`redirect_downloads`](ReleaseServerBuilder::redirect_downloads), a download answers with a 302 to
+`/storage/{release}/{asset}`, which is served without the token check, like a pre-signed CDN URL.
+[`requests`](ReleaseServer::requests) lists what the server saw, including the `Authorization`
+header of each request.
+
+The server answers one request per connection (`Connection: close`) and stops when dropped.
+*/
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use serde_json::{Value, json};
+
+use crate::errors::*;
+
+/// The publication date given to every served release.
+const RELEASE_DATE: &str = "2020-01-01T00:00:00Z";
+
+/// Listing page size when a request does not ask for one (GitHub's default).
+const DEFAULT_PER_PAGE: usize = 30;
+
+/// A release served by a [`ReleaseServer`].
+#[derive(Clone, Debug)]
+pub struct TestRelease {
+    tag: String,
+    body: Option<String>,
+    assets: Vec<TestAsset>,
+}
+
+impl TestRelease {
+    /// A release tagged `tag` (e.g. `v1.2.3`), named after its tag, with no assets.
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            body: None,
+            assets: Vec::new(),
+        }
+    }
+
+    /// Add a downloadable asset.
+    pub fn with_asset(mut self, asset: TestAsset) -> Self {
+        self.assets.push(asset);
+        self
+    }
+
+    /// Set the release notes.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+/// A release asset served by a [`ReleaseServer`].
+#[derive(Clone, Debug)]
+pub struct TestAsset {
+    name: String,
+    content: Arc<[u8]>,
+    digest: Option<String>,
+}
+
+impl TestAsset {
+    /// An asset named `name` whose download is `content`.
+    pub fn new(name: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.into(),
+            content: content.into().into(),
+            digest: None,
+        }
+    }
+
+    /// Publish `digest` (in `algorithm:hex` form) as the asset's GitHub `digest`, instead of its
+    /// real SHA-256.
+    pub fn with_digest(mut self, digest: impl Into<String>) -> Self {
+        self.digest = Some(digest.into());
+        self
+    }
+}
+
+/// A request seen by a [`ReleaseServer`].
+#[derive(Clone, Debug)]
+pub struct ServedRequest {
+    path: String,
+    authorization: Option<String>,
+}
+
+impl ServedRequest {
+    /// The request target: the path and query, e.g. `/github/repos/o/r/releases?per_page=100`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The request's `Authorization` header, if it sent one.
+    pub fn authorization(&self) -> Option<&str> {
+        self.authorization.as_deref()
+    }
+}
+
+/// `ReleaseServer` builder
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct ReleaseServerBuilder {
+    releases: Vec<TestRelease>,
+    page_size: Option<usize>,
+    auth_token: Option<String>,
+    redirect_downloads: bool,
+}
+
+impl ReleaseServerBuilder {
+    /// Serve `release`, listed after the ones already added.
+    pub fn release(&mut self, release: TestRelease) -> &mut Self {
+        self.releases.push(release);
+        self
+    }
+
+    /// Cap every listing page at `size` entries (at least 1), whatever page size the client asks
+    /// for. Defaults to the client's `per_page` / `max-keys`.
+    pub fn page_size(&mut self, size: usize) -> &mut Self {
+        self.page_size = Some(size.max(1));
+        self
+    }
+
+    /// Reject forge API and download requests that do not carry `token` in their
+    /// `Authorization` header with a 401.
+    pub fn require_auth_token(&mut self, token: impl Into<String>) -> &mut Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Answer asset downloads with a 302 redirect to an unauthenticated storage path.
+    pub fn redirect_downloads(&mut self, redirect: bool) -> &mut Self {
+        self.redirect_downloads = redirect;
+        self
+    }
+
+    /// Bind a loopback port and start serving on a background thread.
+    pub fn build(&self) -> Result<ReleaseServer> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(State {
+            base: format!("http://{addr}"),
+            config: self.clone(),
+            requests: Mutex::new(Vec::new()),
+        });
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let (state, stop) = (state.clone(), stop.clone());
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let state = state.clone();
+                        std::thread::spawn(move || state.handle(stream));
+                    }
+                }
+            })
+        };
+        Ok(ReleaseServer {
+            addr,
+            state,
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// A local release host serving a fixed set of [`TestRelease`]s. See the
+/// [module docs](crate::test_server).
+#[derive(Debug)]
+pub struct ReleaseServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ReleaseServer {
+    /// Initialize a `ReleaseServerBuilder`
+    pub fn configure() -> ReleaseServerBuilder {
+        ReleaseServerBuilder::default()
+    }
+
+    /// The server's root URL, `http://127.0.0.1:<port>`.
+    pub fn url(&self) -> &str {
+        &self.state.base
+    }
+
+    /// The value for the github backend's `api_base_url`.
+    pub fn github_api_url(&self) -> String {
+        format!("{}/github", self.state.base)
+    }
+
+    /// The value for the gitlab backend's `host`.
+    pub fn gitlab_host(&self) -> String {
+        format!("{}/gitlab", self.state.base)
+    }
+
+    /// The value for the gitea backend's `host`.
+    pub fn gitea_host(&self) -> String {
+        format!("{}/gitea", self.state.base)
+    }
+
+    /// The value for the s3 backend's `endpoint` (the bucket name is not part of any path).
+    pub fn s3_endpoint(&self) -> String {
+        format!("{}/s3/", self.state.base)
+    }
+
+    /// Every request served so far, in arrival order.
+    pub fn requests(&self) -> Vec<ServedRequest> {
+        self.state.requests.lock().expect("requests lock").clone()
+    }
+}
+
+impl Drop for ReleaseServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// What the serving threads share.
+#[derive(Debug)]
+struct State {
+    base: String,
+    config: ReleaseServerBuilder,
+    requests: Mutex<Vec<ServedRequest>>,
+}
+
+/// A response about to be written.
+struct Response {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(value: &Value) -> Self {
+        Self {
+            status: "200 OK",
+            headers: vec![("Content-Type", "application/json".to_owned())],
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn status(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "application/json".to_owned())],
+            body: json!({ "message": message }).to_string().into_bytes(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self::status("404 Not Found", "Not Found")
+    }
+
+    fn with_header(mut self, name: &'static str, value: String) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+}
+
+/// Which forge's JSON shape a listing is rendered in.
+#[derive(Clone, Copy)]
+enum Forge {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl State {
+    fn handle(&self, mut stream: TcpStream) {
+        let Some((target, authorization)) = read_request(&mut stream) else {
+            return;
+        };
+        self.requests
+            .lock()
+            .expect("requests lock")
+            .push(ServedRequest {
+                path: target.clone(),
+                authorization: authorization.clone(),
+            });
+        let response = self.route(&target, authorization.as_deref());
+        let mut head = format!("HTTP/1.1 {}\r\n", response.status);
+        for (name, value) in &response.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            response.body.len()
+        ));
+        let _ = stream.write_all(head.as_bytes());
+        let _ = stream.write_all(&response.body);
+        let _ = stream.flush();
+    }
+
+    fn route(&self, target: &str, authorization: Option<&str>) -> Response {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        let forge = match segments.as_slice() {
+            ["github", "repos", _, _, "releases", rest @ ..] => Some((Forge::GitHub, rest)),
+            ["gitlab", "api", "v4", "projects", _, "releases", rest @ ..] => {
+                Some((Forge::GitLab, rest))
+            }
+            ["gitea", "api", "v1", "repos", _, _, "releases", rest @ ..] => {
+                Some((Forge::Gitea, rest))
+            }
+            _ => None,
+        };
+        if let Some((forge, rest)) = forge {
+            if !self.authorized(authorization) {
+                return Response::status("401 Unauthorized", "Bad credentials");
+            }
+            let index = match (forge, rest) {
+                (_, [] | [""]) => return self.listing(forge, path, query),
+                (Forge::GitHub, ["latest"]) => Some(0),
+                (Forge::GitHub | Forge::Gitea, ["tags", tag]) | (Forge::GitLab, [tag]) => {
+                    let tag = urlencoding::decode(tag).unwrap_or_default();
+                    self.config.releases.iter().position(|r| r.tag == tag)
+                }
+                _ => None,
+            };
+            return match index.filter(|i| *i < self.config.releases.len()) {
+                Some(index) => Response::json(&self.release_json(forge, index)),
+                None => Response::not_found(),
+            };
+        }
+        match segments.as_slice() {
+            ["download", release, asset] => {
+                if !self.authorized(authorization) {
+                    return Response::status("401 Unauthorized", "Bad credentials");
+                }
+                if self.config.redirect_downloads {
+                    return Response::status("302 Found", "Found").with_header(
+                        "Location",
+                        format!("{}/storage/{release}/{asset}", self.base),
+                    );
+                }
+                self.asset(release, asset)
+            }
+            ["storage", release, asset] => self.asset(release, asset),
+            ["s3", ""] => self.s3_listing(query),
+            ["s3", key @ ..] => {
+                let key = urlencoding::decode(&key.join("/"))
+                    .unwrap_or_default()
+                    .into_owned();
+                self.config
+                    .releases
+                    .iter()
+                    .flat_map(|r| &r.assets)
+                    .find(|a| a.name == key)
+                    .map_or_else(Response::not_found, octet_stream)
+            }
+            _ => Response::not_found(),
+        }
+    }
+
+    fn authorized(&self, authorization: Option<&str>) -> bool {
+        match &self.config.auth_token {
+            None => true,
+            Some(token) => authorization
+                .and_then(|value| value.split_once(' '))
+                .is_some_and(|(_, presented)| presented == token),
+        }
+    }
+
+    fn asset(&self, release: &str, asset: &str) -> Response {
+        let found = release
+            .parse::<usize>()
+            .ok()
+            .zip(asset.parse::<usize>().ok())
+            .and_then(|(r, a)| self.config.releases.get(r)?.assets.get(a));
+        found.map_or_else(Response::not_found, octet_stream)
+    }
+
+    /// One page of a forge listing, with a `Link: rel="next"` header when more remain.
+    fn listing(&self, forge: Forge, path: &str, query: &str) -> Response {
+        let per_page = query_param(query, "per_page")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .max(1);
+        let per_page = self
+            .config
+            .page_size
+            .map_or(per_page, |cap| per_page.min(cap));
+        let page = query_param(query, "page")
+            .and_then(|n| n.parse::<usize>().ok())
+            .unwrap_or(1)
+            .max(1);
+        let releases = &self.config.releases;
+        let start = (page - 1).saturating_mul(per_page).min(releases.len());
+        let end = start.saturating_add(per_page).min(releases.len());
+        let items: Vec<Value> = (start..end).map(|i| self.release_json(forge, i)).collect();
+        let response = Response::json(&Value::Array(items));
+        if end < releases.len() {
+            let next = format!(
+                "<{}{path}?per_page={per_page}&page={}>; rel=\"next\"",
+                self.base,
+                page + 1
+            );
+            response.with_header("Link", next)
+        } else {
+            response
+        }
+    }
+
+    /// One page of the S3 `ListObjectsV2` listing over every asset of every release.
+    fn s3_listing(&self, query: &str) -> Response {
+        let assets: Vec<&TestAsset> = self
+            .config
+            .releases
+            .iter()
+            .flat_map(|r| &r.assets)
+            .collect();
+        let max_keys = query_param(query, "max-keys")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(1000usize)
+            .max(1);
+        let max_keys = self
+            .config
+            .page_size
+            .map_or(max_keys, |cap| max_keys.min(cap));
+        let start = query_param(query, "continuation-token")
+            .and_then(|t| t.parse::<usize>().ok())
+            .unwrap_or(0)
+            .min(assets.len());
+        let end = start.saturating_add(max_keys).min(assets.len());
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <ListBucketResult xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\">\
+             <Name>releases</Name>",
+        );
+        if end < assets.len() {
+            xml.push_str(&format!(
+                "<IsTruncated>true</IsTruncated><NextContinuationToken>{end}</NextContinuationToken>"
+            ));
+        } else {
+            xml.push_str("<IsTruncated>false</IsTruncated>");
+        }
+        for asset in &assets[start..end] {
+            xml.push_str(&format!(
+                "<Contents><Key>{}</Key><LastModified>2020-01-01T00:00:00.000Z</LastModified>\
+                 <Size>{}</Size></Contents>",
+                xml_escape(&asset.name),
+                asset.content.len()
+            ));
+        }
+        xml.push_str("</ListBucketResult>");
+        Response {
+            status: "200 OK",
+            headers: vec![("Content-Type", "application/xml".to_owned())],
+            body: xml.into_bytes(),
+        }
+    }
+
+    /// The release at `index`, in `forge`'s JSON shape.
+    fn release_json(&self, forge: Forge, index: usize) -> Value {
+        let release = &self.config.releases[index];
+        let page_url = format!("{}/releases/{}", self.base, release.tag);
+        let assets = release.assets.iter().enumerate().map(|(i, asset)| {
+            let url = format!("{}/download/{index}/{i}", self.base);
+            match forge {
+                Forge::GitHub => {
+                    let mut json = json!({
+                        "name": asset.name,
+                        "url": url,
+                        "browser_download_url": url,
+                        "size": asset.content.len(),
+                    });
+                    if let Some(digest) = asset_digest(asset) {
+                        json["digest"] = Value::String(digest);
+                    }
+                    json
+                }
+                Forge::GitLab => json!({ "name": asset.name, "url": url }),
+                Forge::Gitea => json!({ "name": asset.name, "browser_download_url": url }),
+            }
+        });
+        let assets: Vec<Value> = assets.collect();
+        match forge {
+            Forge::GitHub | Forge::Gitea => json!({
+                "tag_name": release.tag,
+                "name": release.tag,
+                "created_at": RELEASE_DATE,
+                "body": release.body,
+                "html_url": page_url,
+                "assets": assets,
+            }),
+            Forge::GitLab => json!({
+                "tag_name": release.tag,
+                "name": release.tag,
+                "created_at": RELEASE_DATE,
+                "description": release.body,
+                "_links": { "self": page_url },
+                "assets": { "links": assets },
+            }),
+        }
+    }
+}
+
+fn octet_stream(asset: &TestAsset) -> Response {
+    Response {
+        status: "200 OK",
+        headers: vec![("Content-Type", "application/octet-stream".to_owned())],
+        body: asset.content.to_vec(),
+    }
+}
+
+/// The digest published for `asset`: the configured one, else its SHA-256 (with `checksums`).
+fn asset_digest(asset: &TestAsset) -> Option<String> {
+    if let Some(digest) = &asset.digest {
+        return Some(digest.clone());
+    }
+    #[cfg(feature = "checksums")]
+    return Some(format!(
+        "sha256:{}",
+        crate::checksum::sha256_hex(&asset.content)
+    ));
+    #[cfg(not(feature = "checksums"))]
+    None
+}
+
+/// Read a request head, returning the request target and the `Authorization` header.
+fn read_request(stream: &mut TcpStream) -> Option<(String, Option<String>)> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 4096];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).ok()?;
+        if n == 0 || head.len() > 64 * 1024 {
+            return None;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.split("\r\n");
+    let target = lines.next()?.split(' ').nth(1)?.to_owned();
+    let authorization = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .map(|(_, value)| value.trim().to_owned());
+    Some((target, authorization))
+}
+
+/// The decoded value of `name` in a query string.
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| urlencoding::decode(value).unwrap_or_default().into_owned())
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -67,6 +67,22 @@ impl std::fmt::Debug for Certificate {
     }
 }
 
+/// Which root CA set the crate-built ureq client trusts, chosen with the `tls_root_store` setter.
+///
+/// Certificates added with `add_root_certificate` are trusted on top of the chosen set. The reqwest
+/// client always verifies against the OS trust store (plus added certificates) and ignores this.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RootStore {
+    /// Mozilla's root set bundled into the binary (the default). Works the same on every machine,
+    /// but does not trust a CA installed only in the OS store, such as a corporate TLS-inspection CA.
+    #[default]
+    Bundled,
+    /// The operating system's trust store. Requires the `native-certs` feature.
+    System,
+    /// Both the bundled Mozilla set and the OS trust store. Requires the `native-certs` feature.
+    Both,
+}
+
 #[cfg(test)]
 mod tests {
     use super::Certificate;
@@ -0,0 +1,345 @@
+/*!
+Remove what the updater installed, for an `app self uninstall` command.
+
+[`Uninstall::from_updater`] gathers everything an updater built with the application's usual
+configuration may have put on disk:
+
+- the binary at `bin_install_path`, or the bundle directory in bundle mode,
+- a versioned layout's `current` link and every version under `versions/`, the current one and
+  the previous ones kept for rollback,
+- the companion `install_file`s, both as configured and as recorded in the install receipt,
+- the install receipt itself,
+- a staged update in the `staging_dir` (`stage` feature).
+
+[`check_guard`](Uninstall::check_guard) adds an [`UpdateCheckGuard`]'s stamp, and
+[`file`](Uninstall::file) any other file the application wants gone.
+
+[`plan`](Uninstall::plan) is the dry run: the paths that exist and would be deleted, in order.
+[`run`](Uninstall::run) deletes them. The running executable goes last, through
+[`self_replace::self_delete_at`](https://docs.rs/self-replace), so it works on Windows too
+(where the deletion completes once the process exits). Directories the layout or staging lived
+in are left in place.
+
+Only the bundle directory and the layout's `versions/` directory, both named by the updater's
+own configuration, are removed recursively. Every other path, including everything read from the
+receipt, is removed only when it is a file or a symlink, so a stale or edited receipt naming a
+directory cannot take a whole tree with it.
+
+```rust,no_run
+use std::time::Duration;
+use self_update::check_interval::UpdateCheckGuard;
+use self_update::uninstall::Uninstall;
+
+fn uninstall(dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let updater = self_update::backends::github::Update::configure()
+        .repo_owner("jaemk")
+        .repo_name("self_update")
+        .bin_name("myapp")
+        .current_version(self_update::cargo_crate_version!())
+        .build()?;
+    let guard = UpdateCheckGuard::new("/home/me/.cache/myapp/update-check", Duration::from_secs(86400));
+    let uninstall = Uninstall::from_updater(&updater).check_guard(&guard);
+
+    if dry_run {
+        for path in uninstall.plan()? {
+            println!("would remove {}", path.display());
+        }
+    } else {
+        uninstall.run()?;
+    }
+    Ok(())
+}
+```
+*/
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::check_interval::UpdateCheckGuard;
+use crate::errors::*;
+use crate::receipt::InstallReceipt;
+use crate::update::UpdateConfig;
+
+/// Everything to remove for an uninstall. See the [module docs](crate::uninstall).
+///
+/// Built with [`new`](Self::new) or [`from_updater`](Self::from_updater), extended with the
+/// consuming setters, then listed with [`plan`](Self::plan) or removed with [`run`](Self::run).
+#[derive(Clone, Debug, Default)]
+pub struct Uninstall {
+    binary: Option<PathBuf>,
+    bundle: Option<PathBuf>,
+    versioned_install: Option<crate::VersionedInstall>,
+    files: Vec<PathBuf>,
+    receipt: Option<PathBuf>,
+    #[cfg(feature = "stage")]
+    staging_dir: Option<PathBuf>,
+}
+
+impl Uninstall {
+    /// Remove the binary at `bin_install_path`.
+    pub fn new(bin_install_path: impl AsRef<Path>) -> Self {
+        Self {
+            binary: Some(bin_install_path.as_ref().to_path_buf()),
+            ..Self::default()
+        }
+    }
+
+    /// Remove what `updater` installs: its bundle, versioned layout or binary, its
+    /// `install_file`s, its install receipt and the companion files recorded there, and (with
+    /// `stage`) an update staged in its `staging_dir`.
+    pub fn from_updater<U: UpdateConfig + ?Sized>(updater: &U) -> Self {
+        let mut uninstall = match (updater.bundle_install_path(), updater.versioned_install()) {
+            (Some(bundle), _) => Self::default().bundle(bundle),
+            (None, Some(layout)) => Self::default().versioned_install(layout.clone()),
+            (None, None) => Self::new(updater.bin_install_path()),
+        };
+        for (_, path) in updater.install_files() {
+            uninstall = uninstall.file(path);
+        }
+        if let Some(receipt) = updater.install_receipt() {
+            uninstall = uninstall.receipt(receipt);
+        }
+        #[cfg(feature = "stage")]
+        if let Some(dir) = updater.staging_dir() {
+            uninstall = uninstall.staging_dir(dir);
+        }
+        uninstall
+    }
+
+    /// Also remove the installed bundle directory `dir`.
+    pub fn bundle(mut self, dir: impl AsRef<Path>) -> Self {
+        self.bundle = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Also remove `layout`'s `current` link and its `versions/` directory.
+    pub fn versioned_install(mut self, layout: crate::VersionedInstall) -> Self {
+        self.versioned_install = Some(layout);
+        self
+    }
+
+    /// Also remove `path`, e.g. a companion file or a config the application wrote.
+    pub fn file(mut self, path: impl AsRef<Path>) -> Self {
+        self.files.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Also remove the install receipt at `path` and what it records as installed.
+    pub fn receipt(mut self, path: impl AsRef<Path>) -> Self {
+        self.receipt = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Also remove an update staged in `dir`.
+    #[cfg(feature = "stage")]
+    pub fn staging_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.staging_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Also remove `guard`'s stamp file.
+    pub fn check_guard(self, guard: &UpdateCheckGuard) -> Self {
+        self.file(guard.stamp_path())
+    }
+
+    /// The dry run: every path [`run`](Self::run) would delete that exists now, in deletion
+    /// order. Fails only when the install receipt cannot be read.
+    pub fn plan(&self) -> Result<Vec<PathBuf>> {
+        Ok(self.entries()?.into_iter().map(|(path, _)| path).collect())
+    }
+
+    /// The plan, each path paired with whether it is a directory to remove recursively.
+    fn entries(&self) -> Result<Vec<(PathBuf, bool)>> {
+        let exe = std::env::current_exe().ok();
+        let is_exe = |path: &Path| {
+            exe.as_deref()
+                .is_some_and(|exe| crate::update::same_file(path, exe))
+        };
+
+        // `(path, recursive)`: only the bundle and `versions/` may be directories.
+        let mut candidates = Vec::new();
+        if let Some(receipt_path) = &self.receipt
+            && let Some(receipt) = InstallReceipt::read(receipt_path)?
+        {
+            candidates.extend(receipt.files().iter().map(|p| (p.clone(), false)));
+            // A receipt written for a binary that has moved since names a path this
+            // configuration would not.
+            candidates.push((receipt.install_path().to_path_buf(), false));
+        }
+        candidates.extend(self.files.iter().map(|p| (p.clone(), false)));
+        #[cfg(feature = "stage")]
+        if let Some(dir) = &self.staging_dir {
+            candidates.extend(
+                crate::staged::staged_files(dir)
+                    .into_iter()
+                    .map(|p| (p, false)),
+            );
+        }
+        if let Some(layout) = &self.versioned_install {
+            candidates.push((layout.current_link(), false));
+            candidates.push((layout.root().join("versions"), true));
+        }
+        candidates.extend(self.bundle.iter().map(|p| (p.clone(), true)));
+        candidates.extend(self.binary.iter().map(|p| (p.clone(), false)));
+        candidates.extend(self.receipt.iter().map(|p| (p.clone(), false)));
+
+        let mut plan: Vec<(PathBuf, bool)> = Vec::new();
+        for (path, recursive) in candidates {
+            let covered = plan.iter().any(|(p, _)| path.starts_with(p));
+            let removable =
+                fs::symlink_metadata(&path).is_ok_and(|meta| recursive || !meta.is_dir());
+            if !covered && removable {
+                plan.push((path, recursive));
+            }
+        }
+        // The running executable, or the directory holding it, goes last.
+        plan.sort_by_key(|(path, _)| {
+            is_exe(path) || exe.as_deref().is_some_and(|e| e.starts_with(path))
+        });
+        Ok(plan)
+    }
+
+    /// Delete everything in the [`plan`](Self::plan), returning what was deleted.
+    ///
+    /// Stops at the first failure, leaving the rest in place: a permission failure is
+    /// [`Error::InstallPathNotWritable`] naming the path, any other IO error is `Error::Io`
+    /// with the path in its message.
+    pub fn run(self) -> Result<Vec<PathBuf>> {
+        let plan = self.entries()?;
+        let exe = std::env::current_exe().ok();
+        for (path, recursive) in &plan {
+            remove(path, *recursive, exe.as_deref())
+                .map_err(|e| crate::update::map_install_io_error(e, path))?;
+        }
+        Ok(plan.into_iter().map(|(path, _)| path).collect())
+    }
+}
+
+/// Remove `path`: a directory recursively when `recursive`, the running executable `exe` through
+/// `self_replace`, anything else (including a symlink to `exe`) as a plain file.
+fn remove(path: &Path, recursive: bool, exe: Option<&Path>) -> io::Result<()> {
+    let meta = fs::symlink_metadata(path)?;
+    if recursive && meta.is_dir() {
+        if let Some(exe) = exe
+            && exe.starts_with(path)
+        {
+            self_replace::self_delete_outside_path(path)?;
+        }
+        return match fs::remove_dir_all(path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        };
+    }
+    match exe {
+        Some(exe) if !meta.is_symlink() && crate::update::same_file(path, exe) => {
+            self_replace::self_delete_at(path)
+        }
+        _ => fs::remove_file(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_lists_existing_paths_and_run_removes_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin/app");
+        fs::create_dir_all(bin.parent().unwrap()).unwrap();
+        fs::write(&bin, "app").unwrap();
+        let man = dir.path().join("man/app.1");
+        fs::create_dir_all(man.parent().unwrap()).unwrap();
+        fs::write(&man, "man").unwrap();
+        let stamp = dir.path().join("update-check");
+        let guard = UpdateCheckGuard::new(&stamp, std::time::Duration::from_secs(60));
+        guard.record_check().unwrap();
+        let receipt_path = dir.path().join("receipt.json");
+        InstallReceipt::new("github", "1.0.0", &bin, vec![man.clone()], None)
+            .write(&receipt_path)
+            .unwrap();
+
+        let uninstall = Uninstall::new(&bin)
+            .receipt(&receipt_path)
+            .check_guard(&guard)
+            .file(dir.path().join("never-installed"));
+        let plan = uninstall.plan().unwrap();
+
+        assert_eq!(
+            plan,
+            vec![
+                man.clone(),
+                bin.clone(),
+                stamp.clone(),
+                receipt_path.clone()
+            ]
+        );
+        assert!(bin.exists(), "the dry run deletes nothing");
+
+        assert_eq!(uninstall.run().unwrap(), plan);
+        for path in &plan {
+            assert!(!path.exists(), "{:?} was not removed", path);
+        }
+        assert!(dir.path().join("bin").is_dir());
+    }
+
+    #[test]
+    fn directories_named_by_the_receipt_are_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("app");
+        fs::write(&bin, "app").unwrap();
+        let tree = dir.path().join("home");
+        fs::create_dir_all(tree.join("docs")).unwrap();
+        fs::write(tree.join("docs/notes.txt"), "keep").unwrap();
+        let receipt_path = dir.path().join("receipt.json");
+        InstallReceipt::new("github", "1.0.0", &tree, vec![tree.join("docs")], None)
+            .write(&receipt_path)
+            .unwrap();
+
+        let removed = Uninstall::new(&bin).receipt(&receipt_path).run().unwrap();
+
+        assert_eq!(removed, vec![bin.clone(), receipt_path.clone()]);
+        assert!(tree.join("docs/notes.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_versioned_layout_is_removed_with_every_kept_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = crate::VersionedInstall::new(dir.path().join("app"));
+        for version in ["1.0.0", "1.1.0"] {
+            let new_exe = dir.path().join("new");
+            fs::write(&new_exe, version).unwrap();
+            layout.install(&new_exe, "app", version).unwrap();
+        }
+
+        let uninstall = Uninstall::default().versioned_install(layout.clone());
+
+        assert_eq!(
+            uninstall.plan().unwrap(),
+            vec![layout.current_link(), layout.root().join("versions")]
+        );
+        uninstall.run().unwrap();
+        assert_eq!(fs::read_dir(layout.root()).unwrap().count(), 0);
+    }
+
+    #[cfg(feature = "stage")]
+    #[test]
+    fn a_staged_update_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("app");
+        fs::write(&bin, "1.0.0").unwrap();
+        let new_exe = dir.path().join("new");
+        fs::write(&new_exe, "1.1.0").unwrap();
+        let staging = dir.path().join("staging");
+        crate::staged::stage(&new_exe, &staging, "1.1.0", &bin, &new_exe, "new").unwrap();
+
+        let removed = Uninstall::default().staging_dir(&staging).run().unwrap();
+
+        // The manifest and the binary, plus the archive kept with `signatures`.
+        let expected = if cfg!(feature = "signatures") { 3 } else { 2 };
+        assert_eq!(removed.len(), expected);
+        assert_eq!(fs::read_dir(&staging).unwrap().count(), 0);
+    }
+}
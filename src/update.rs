@@ -12,13 +12,17 @@ use crate::{Download, Extract, Move, VersionStatus, confirm, errors::*, version}
 /// through the [`name`](ReleaseAsset::name) / [`download_url`](ReleaseAsset::download_url) /
 /// [`digest`](ReleaseAsset::digest) getters, which return borrows. Build one with
 /// [`ReleaseAsset::new`] (plus [`with_digest`](ReleaseAsset::with_digest) when the forge publishes
-/// a content digest for the asset).
+/// a content digest for the asset, [`with_size`](ReleaseAsset::with_size) when it lists the
+/// asset's size, and [`with_mirrors`](ReleaseAsset::with_mirrors) when the asset is also served
+/// from alternate URLs).
 #[derive(Clone, Debug, Default)]
 #[non_exhaustive]
 pub struct ReleaseAsset {
     pub(crate) name: Arc<str>,
     pub(crate) download_url: Arc<str>,
     pub(crate) digest: Option<Arc<str>>,
+    pub(crate) size: Option<u64>,
+    pub(crate) mirrors: Arc<[String]>,
 }
 
 impl ReleaseAsset {
@@ -32,6 +36,8 @@ impl ReleaseAsset {
             name: Arc::from(name.into()),
             download_url: Arc::from(download_url.into()),
             digest: None,
+            size: None,
+            mirrors: Arc::from([]),
         }
     }
 
@@ -46,6 +52,40 @@ impl ReleaseAsset {
         self
     }
 
+    /// Attach the asset's size in bytes, as the release listing reports it.
+    ///
+    /// The backends fill this from their listings where one is given (github's and gitea's asset
+    /// `size`, the manifest's optional `size`, the S3 object `Size`). The free-space preflight
+    /// (`check_free_space` on the builders) uses it to fail before the download starts; without
+    /// it the check waits for the download's `Content-Length`.
+    pub fn with_size(mut self, bytes: u64) -> Self {
+        self.size = Some(bytes);
+        self
+    }
+
+    /// Add alternate download URLs for the asset, tried in order after
+    /// [`download_url`](ReleaseAsset::download_url).
+    ///
+    /// The download fails over to the next URL when a request cannot connect or the server answers
+    /// with a 5xx status. Any other failure (a 404, a rejected credential) is returned as is. The
+    /// manifest backend fills this from an asset's `mirrors` array; a custom [`ReleaseSource`] can
+    /// chain this onto [`new`](ReleaseAsset::new). The auth token is sent to a mirror only when its
+    /// host is authorized (see `allow_auth_host` on the builders). Appends to any mirrors already
+    /// set.
+    pub fn with_mirrors<I, S>(mut self, mirrors: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.mirrors = self
+            .mirrors
+            .iter()
+            .cloned()
+            .chain(mirrors.into_iter().map(Into::into))
+            .collect();
+        self
+    }
+
     /// The asset's file name (e.g. `app-x86_64-unknown-linux-gnu.tar.gz`).
     pub fn name(&self) -> &str {
         &self.name
@@ -66,6 +106,18 @@ impl ReleaseAsset {
     pub fn digest(&self) -> Option<&str> {
         self.digest.as_deref()
     }
+
+    /// The asset's size in bytes, when the backend lists one (see
+    /// [`with_size`](ReleaseAsset::with_size)).
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// The asset's alternate download URLs, in failover order. Empty unless the backend or
+    /// [`with_mirrors`](ReleaseAsset::with_mirrors) supplied some.
+    pub fn mirrors(&self) -> &[String] {
+        &self.mirrors
+    }
 }
 
 /// The richer result of [`update_extended`](ReleaseUpdate::update_extended) (and its async sibling
@@ -200,18 +252,21 @@ impl Release {
     /// if set); (2) it contains the arch and os tokens derived from `target` (and `identifier`);
     /// (3) it contains just the `identifier`. The arch/os fallback is derived from the `target`
     /// argument, not the build host, so an explicitly configured cross-target selects correctly.
+    ///
+    /// Assets ending in `.sig` are never selected: they are the detached signatures of the asset
+    /// they extend (`signatures` feature), and contain the same target string.
     pub fn asset_for(&self, target: &str, identifier: Option<&str>) -> Option<ReleaseAsset> {
         let has_identifier =
             |asset: &&ReleaseAsset| identifier.is_none_or(|i| asset.name.contains(i));
-        self.assets
-            .iter()
+        let candidates = || self.assets.iter().filter(|a| !a.name.ends_with(".sig"));
+        candidates()
             // first look specifically for a target with identifier
             .find(|asset| asset.name.contains(target) && has_identifier(asset))
             // otherwise look for a target for the configured arch/os with identifier
             .or_else(|| {
                 let (arch, os) = target_arch_os(target);
                 match (arch, os) {
-                    (Some(arch), Some(os)) => self.assets.iter().find(|asset| {
+                    (Some(arch), Some(os)) => candidates().find(|asset| {
                         asset.name.contains(arch)
                             && asset.name.contains(os)
                             && has_identifier(asset)
@@ -220,9 +275,7 @@ impl Release {
                 }
             })
             // otherwise just with the identifier if set
-            .or_else(|| {
-                identifier.and_then(|i| self.assets.iter().find(|asset| asset.name.contains(i)))
-            })
+            .or_else(|| identifier.and_then(|i| candidates().find(|asset| asset.name.contains(i))))
             .cloned()
     }
 
@@ -729,7 +782,25 @@ pub trait AsyncReleaseUpdate: UpdateConfig + UpdateInternals {
     where
         Self: Sized + Sync,
     {
-        update_extended_async(self)
+        update_extended_async(self, None)
+    }
+
+    /// Async sibling of [`ReleaseUpdate::stage`]: download and verify the latest release, then
+    /// store the verified binary in the `staging_dir` for
+    /// [`staged::ApplyStaged`](crate::staged::ApplyStaged) instead of installing it. Returns the
+    /// staged [`Release`], or `None` when already up to date. Requires the `stage` feature.
+    #[cfg(feature = "stage")]
+    fn stage_async(&self) -> impl std::future::Future<Output = Result<Option<Release>>> + Send + '_
+    where
+        Self: Sized + Sync,
+    {
+        async move {
+            let staging_dir = self.staging_dir().ok_or(Error::MissingField {
+                field: "staging_dir",
+            })?;
+            let status = update_extended_async(self, Some(staging_dir.to_path_buf())).await?;
+            Ok(status.into_updated_release())
+        }
     }
 }
 
@@ -804,9 +875,88 @@ pub trait UpdateConfig: sealed::Sealed {
         false
     }
 
+    /// Opt-in preflight: refuse an install path owned by a package manager before downloading
+    /// (set via `check_package_manager`). Defaults to `false`.
+    fn check_package_manager(&self) -> bool {
+        false
+    }
+
+    /// Opt-in preflight: refuse an update the temp or install filesystem has no room for (set
+    /// via `check_free_space`). Defaults to `false`.
+    fn check_free_space(&self) -> bool {
+        false
+    }
+
+    /// What the free-space preflight assumes an asset unpacks to, as a multiple of its size (set
+    /// via `free_space_expansion_factor`). Defaults to `3.0`.
+    fn free_space_expansion_factor(&self) -> f64 {
+        crate::free_space::DEFAULT_EXPANSION_FACTOR
+    }
+
+    /// Whether the download is made into a temporary directory beside the install path instead
+    /// of the system temp dir (set via `temp_dir_on_install_fs`). Defaults to `false`.
+    fn temp_dir_on_install_fs(&self) -> bool {
+        false
+    }
+
+    /// The limits extracting the downloaded archive is held to (set via `extract_limits`).
+    /// Defaults to none.
+    fn extract_limits(&self) -> crate::ExtractLimits {
+        crate::ExtractLimits::default()
+    }
+
+    /// Where an install receipt is written after a successful update (set via
+    /// `install_receipt`), if anywhere.
+    fn install_receipt(&self) -> Option<&std::path::Path> {
+        None
+    }
+
     /// Path of the binary to be extracted from release package
     fn bin_path_in_archive(&self) -> &str;
 
+    /// Bundle root directory to extract from the release package in bundle mode (set via
+    /// `bundle_root_in_archive`), or `None` for a single-binary install.
+    fn bundle_root_in_archive(&self) -> Option<&str> {
+        None
+    }
+
+    /// Installed bundle directory replaced in bundle mode; `Some` exactly when
+    /// [`bundle_root_in_archive`](Self::bundle_root_in_archive) is.
+    fn bundle_install_path(&self) -> Option<&std::path::Path> {
+        None
+    }
+
+    /// Extra `(path_in_archive, install_path)` files installed alongside the binary, in one
+    /// transaction with it (set via `install_file`).
+    fn install_files(&self) -> &[(String, std::path::PathBuf)] {
+        &[]
+    }
+
+    /// The versioned side-by-side layout installed into instead of replacing
+    /// [`bin_install_path`](Self::bin_install_path) (set via `versioned_install`).
+    fn versioned_install(&self) -> Option<&crate::VersionedInstall> {
+        None
+    }
+
+    /// Whether the installed binary's owner, mode and extended attributes are carried over to its
+    /// replacement (set via `preserve_metadata`). Defaults to `false`.
+    #[cfg(feature = "preserve-metadata")]
+    fn preserve_metadata(&self) -> bool {
+        false
+    }
+
+    /// The tool a replace refused for lack of permission is retried through (set via `elevate`).
+    #[cfg(feature = "elevate")]
+    fn elevate(&self) -> Option<crate::elevate::Elevator> {
+        None
+    }
+
+    /// The directory `stage()` stores a verified update in (set via `staging_dir`).
+    #[cfg(feature = "stage")]
+    fn staging_dir(&self) -> Option<&std::path::Path> {
+        None
+    }
+
     /// Flag indicating if progress information shall be output when downloading a release
     fn show_download_progress(&self) -> bool;
 
@@ -839,6 +989,13 @@ pub trait UpdateConfig: sealed::Sealed {
     /// Authorisation token for communicating with backend
     fn auth_token(&self) -> Option<&str>;
 
+    /// The rate-limit state (`Retry-After`, `X-RateLimit-*` / `RateLimit-*`) reported by the most
+    /// recent API response that carried it, or `None` if no request has reported one yet. Lets a
+    /// caller check its remaining quota, or when it resets, after a release lookup.
+    fn rate_limit(&self) -> Option<crate::RateLimit> {
+        None
+    }
+
     /// Construct a header with an authorisation entry if an auth token is provided.
     ///
     /// The trait default is a no-op (empty header map): the authorization scheme now lives in the
@@ -885,6 +1042,15 @@ pub(crate) trait UpdateInternals: sealed::Sealed {
     /// Optional post-update verification hook, run on the extracted binary before install.
     fn verify_callback(&self) -> Option<std::sync::Arc<crate::DynVerifyFn>>;
 
+    /// Optional built-in self-test, run on the extracted binary before the verification hook.
+    fn self_test(&self) -> Option<&crate::self_test::SelfTest> {
+        None
+    }
+
+    /// The backend's short name (`"github"`, `"s3"`, `"custom"`, ...), recorded in install
+    /// receipts.
+    fn backend(&self) -> &'static str;
+
     /// Optional custom asset matcher, overriding the built-in target/identifier selection.
     fn asset_matcher(&self) -> Option<std::sync::Arc<crate::DynAssetMatcher>> {
         None
@@ -904,6 +1070,30 @@ pub(crate) trait UpdateInternals: sealed::Sealed {
     fn verifying_keys(&self) -> &[crate::VerifyingKey] {
         &[]
     }
+
+    /// Verifying keys with validity windows, trusted alongside `verifying_keys`.
+    #[cfg(feature = "signatures")]
+    fn trusted_keys(&self) -> &[crate::keys::TrustedKey] {
+        &[]
+    }
+
+    /// Name of the signed revocation-list asset to fetch from the selected release, if any.
+    #[cfg(feature = "signatures")]
+    fn revocation_list_asset(&self) -> Option<&str> {
+        None
+    }
+
+    /// Name of the signed key-set asset to fetch from the selected release, if any.
+    #[cfg(feature = "signatures")]
+    fn key_set_asset(&self) -> Option<&str> {
+        None
+    }
+
+    /// File persisting delivered key sets and revocations between runs, if any.
+    #[cfg(feature = "signatures")]
+    fn key_store_path(&self) -> Option<&std::path::Path> {
+        None
+    }
 }
 
 /// Updates to a specified or latest release.
@@ -975,52 +1165,80 @@ pub trait ReleaseUpdate: UpdateConfig + UpdateInternals {
 
     /// Same as `update`, but returns [`ReleaseStatus`].
     fn update_extended(&self) -> Result<ReleaseStatus> {
-        let current_version = self.current_version();
-        let show_output = self.show_output();
-        print_check_header(self.target(), current_version, show_output);
-
-        let release = match self.release_tag() {
-            None => {
-                print_flush(show_output, "Checking latest released version... ")?;
-                let releases = self.get_newer_releases()?;
-                match choose_latest_release(
-                    releases.into_vec(),
-                    current_version,
-                    show_output,
-                    self.update_strategy(),
-                )? {
-                    Some(release) => release,
-                    None => return Ok(ReleaseStatus::UpToDate),
-                }
-            }
-            Some(ref ver) => {
-                println(show_output, &format!("Looking for tag: {}", ver));
-                self.get_release_version(ver)?
-            }
-        };
+        update_or_stage(self, None)
+    }
+
+    /// Download and verify the latest release like [`update_extended`](Self::update_extended),
+    /// but store the verified binary in the `staging_dir` instead of installing it, for
+    /// [`staged::ApplyStaged`](crate::staged::ApplyStaged) to install on the next start.
+    ///
+    /// Returns the staged [`Release`], or `None` when already up to date. Staging replaces
+    /// anything staged before. Fails with [`Error::MissingField`] (`"staging_dir"`) when no
+    /// `staging_dir` is set. Requires the `stage` feature.
+    #[cfg(feature = "stage")]
+    fn stage(&self) -> Result<Option<Release>> {
+        let staging_dir = self.staging_dir().ok_or(Error::MissingField {
+            field: "staging_dir",
+        })?;
+        Ok(update_or_stage(self, Some(staging_dir.to_path_buf()))?.into_updated_release())
+    }
+}
 
-        let target_asset = resolve_and_confirm(self, &release)?;
+/// The body of [`ReleaseUpdate::update_extended`], shared with `stage()`: with `stage_into` the
+/// verified binary is stored there for `staged::ApplyStaged` instead of being installed.
+fn update_or_stage<U: ReleaseUpdate + ?Sized>(
+    u: &U,
+    stage_into: Option<std::path::PathBuf>,
+) -> Result<ReleaseStatus> {
+    let current_version = u.current_version();
+    let show_output = u.show_output();
+    print_check_header(u.target(), current_version, show_output);
 
-        // Opt-in preflight: bail before downloading if the install path is definitely not writable.
-        if self.check_install_path_writable() {
-            probe_install_path_writable(self.bin_install_path())?;
+    let release = match u.release_tag() {
+        None => {
+            print_flush(show_output, "Checking latest released version... ")?;
+            let releases = u.get_newer_releases()?;
+            match choose_latest_release(
+                releases.into_vec(),
+                current_version,
+                show_output,
+                u.update_strategy(),
+            )? {
+                Some(release) => release,
+                None => return Ok(ReleaseStatus::UpToDate),
+            }
         }
+        Some(ref ver) => {
+            println(show_output, &format!("Looking for tag: {}", ver));
+            u.get_release_version(ver)?
+        }
+    };
 
-        let tmp_archive_dir = tempfile::TempDir::new()?;
-        let tmp_archive_path = tmp_archive_dir.path().join(target_asset.name());
-        let mut tmp_archive = fs::File::create(&tmp_archive_path)?;
+    let target_asset = resolve_and_confirm(u, &release)?;
 
-        println(show_output, "Downloading...");
-        build_download(self, &target_asset)?.download_to(&mut tmp_archive)?;
+    preflight(u)?;
 
-        finish_update(
-            self,
-            release,
-            &target_asset,
-            tmp_archive_dir,
-            &tmp_archive_path,
-        )
+    let install_dir = install_dir(u, stage_into.as_deref());
+    let mut ctx = FinishCtx::capture(u, release, &target_asset)?;
+    #[cfg(feature = "stage")]
+    {
+        ctx.stage_into = stage_into;
     }
+    let tmp_archive_dir = download_temp_dir(u, &install_dir)?;
+    let tmp_archive_path = tmp_archive_dir.path().join(target_asset.name());
+    let mut download = build_download(u, &target_asset)?;
+    check_free_space(
+        u,
+        &target_asset,
+        tmp_archive_dir.path(),
+        install_dir,
+        &mut download,
+    )?;
+
+    println(show_output, "Downloading...");
+    download_archive(&download, &tmp_archive_path, &mut ctx)?;
+
+    finish_update(u, ctx, &target_asset, tmp_archive_dir, &tmp_archive_path)
 }
 
 /// Print the "Checking target-arch / current version" header lines.
@@ -1172,7 +1390,7 @@ fn target_arch_os(target: &str) -> (Option<&str>, Option<&str>) {
 /// as a disk-qualified path, letting a server-supplied asset name escape the temporary directory.
 /// `\` and `:` are only special on Windows, so they are rejected explicitly rather than relying on
 /// component parsing (which does not treat them as special when the crate is built for unix).
-fn is_safe_asset_name(name: &str) -> bool {
+pub(crate) fn is_safe_asset_name(name: &str) -> bool {
     if name.contains('\\') || name.contains(':') {
         return false;
     }
@@ -1233,14 +1451,11 @@ fn resolve_and_confirm<U: UpdateConfig + UpdateInternals + ?Sized>(
     Ok(target_asset)
 }
 
-/// Build the [`Download`] for an asset, applying the auth/accept/extra headers, timeout, progress
-/// callback, and progress style from the updater. Shared by both orchestrators; the caller drives
-/// it with `download_to` (sync) or `download_to_async` (async).
-fn build_download<U: UpdateConfig + UpdateInternals + ?Sized>(
+/// The request headers for downloading an asset from `url` (the primary download URL or a mirror).
+fn download_headers<U: UpdateConfig + UpdateInternals + ?Sized>(
     u: &U,
-    target_asset: &ReleaseAsset,
-) -> Result<Download> {
-    let mut download = Download::from_url(target_asset.download_url());
+    url: &str,
+) -> Result<http_client::HeaderMap> {
     // Backend base headers (e.g. github's User-Agent). The trait default is a no-op; the auth
     // scheme/token is applied below by the shared `apply_auth` so the download honors a user
     // `request_header(AUTHORIZATION, ..)` override exactly like the listing path.
@@ -1255,8 +1470,7 @@ fn build_download<U: UpdateConfig + UpdateInternals + ?Sized>(
     // their own Authorization via `request_header`. The token is attached only when the asset
     // download URL is on the configured API host (or an allow_auth_host entry), so a server-supplied
     // download_url pointing at another host does not receive the credential.
-    u.request_config()
-        .apply_auth(target_asset.download_url(), &mut headers)?;
+    u.request_config().apply_auth(url, &mut headers)?;
     // Apply the user's extra request headers to the download too. This runs after the ACCEPT and
     // auth headers set above, so a user-supplied header of the same name overrides them here.
     //
@@ -1265,16 +1479,42 @@ fn build_download<U: UpdateConfig + UpdateInternals + ?Sized>(
     // host `auth_allowed_for` permits (the configured API host or an `allow_auth_host` entry, over
     // https / loopback). A server-chosen next-page or download host that is not authorized does not
     // receive it, so a malicious release server cannot harvest the user's Authorization.
-    let user_auth_allowed = u
-        .request_config()
-        .auth_allowed_for(target_asset.download_url());
+    let user_auth_allowed = u.request_config().auth_allowed_for(url);
     for (name, value) in u.request_headers() {
         if name == header::AUTHORIZATION && !user_auth_allowed {
             continue;
         }
         headers.insert(name.clone(), value.clone());
     }
-    download.replace_headers(headers);
+    Ok(headers)
+}
+
+/// Build the [`Download`] for an asset and its mirrors, applying the auth/accept/extra headers,
+/// timeout, progress callback, and progress style from the updater. Shared by both orchestrators;
+/// the caller drives it with `download_to` (sync) or `download_to_async` (async).
+fn build_download<U: UpdateConfig + UpdateInternals + ?Sized>(
+    u: &U,
+    target_asset: &ReleaseAsset,
+) -> Result<Download> {
+    let mut download = Download::from_url(target_asset.download_url());
+    download.replace_headers(download_headers(u, target_asset.download_url())?);
+    // Mirrors: the asset's own, then those the builder's `asset_mirror` rules derive, each with
+    // headers built for its own URL so the auth token follows the same host gate as the primary.
+    let mut mirrors: Vec<String> = Vec::new();
+    for url in target_asset
+        .mirrors()
+        .iter()
+        .cloned()
+        .chain(u.request_config().mirror_urls(target_asset.download_url()))
+    {
+        if url != target_asset.download_url() && !mirrors.contains(&url) {
+            mirrors.push(url);
+        }
+    }
+    for url in mirrors {
+        let headers = download_headers(u, &url)?;
+        download.add_mirror_with_headers(url, headers);
+    }
     // Forward any injected HTTP client so the download reuses it too.
     download.set_http_client(
         u.request_client(),
@@ -1286,6 +1526,11 @@ fn build_download<U: UpdateConfig + UpdateInternals + ?Sized>(
     for cert in &u.request_config().root_certificates {
         download.add_root_certificate(cert.clone());
     }
+    // Likewise forward the root store choice and an explicit proxy.
+    download.tls_root_store(u.request_config().root_store);
+    if let Some(proxy) = &u.request_config().proxy {
+        download.proxy(proxy.clone());
+    }
     if let Some(timeout) = u.request_timeout() {
         download.timeout(timeout);
     }
@@ -1299,6 +1544,13 @@ fn build_download<U: UpdateConfig + UpdateInternals + ?Sized>(
             request.retry_base_delay,
             request.retry_max_delay,
         );
+        download.set_max_rate_limit_wait(request.max_rate_limit_wait);
+        download.parallel_chunks(request.parallel_chunks);
+        if let Some(rate) = request.max_bandwidth {
+            download.max_bandwidth(rate);
+        }
+        download.redirect_policy(request.redirect_policy.clone());
+        download.set_redirect_callback(request.on_redirect.clone());
     }
     if let Some(callback) = u.progress_callback() {
         download.set_progress_callback_arc(callback);
@@ -1312,6 +1564,106 @@ fn build_download<U: UpdateConfig + UpdateInternals + ?Sized>(
     Ok(download)
 }
 
+/// The opt-in checks run after confirmation and before anything is downloaded: the package-manager
+/// guard, then the writability probe of every path the install will write.
+fn preflight<U: UpdateConfig + ?Sized>(u: &U) -> Result<()> {
+    if u.check_package_manager() {
+        // A versioned layout installs under its own root, never over a packaged binary.
+        let install_path = match (u.bundle_install_path(), u.versioned_install()) {
+            (Some(bundle_install_path), _) => Some(bundle_install_path),
+            (None, Some(_)) => None,
+            (None, None) => Some(u.bin_install_path()),
+        };
+        let dbs = crate::package_manager::Databases::system();
+        for path in install_path
+            .into_iter()
+            .chain(u.install_files().iter().map(|(_, p)| p.as_path()))
+        {
+            crate::package_manager::refuse_package_managed(path, &dbs)?;
+        }
+    }
+    // Bail before downloading if an install path is definitely not writable.
+    if u.check_install_path_writable() {
+        match (u.bundle_install_path(), u.versioned_install()) {
+            (Some(bundle_install_path), _) => probe_bundle_parent_writable(bundle_install_path)?,
+            (None, Some(layout)) => probe_bundle_parent_writable(&layout.current_link())?,
+            (None, None) if !elevates(u) => probe_install_path_writable(u.bin_install_path())?,
+            (None, None) => {}
+        }
+        for (_, install_path) in u.install_files() {
+            probe_install_path_writable(install_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The directory the update writes the new files into: the `staging_dir` when staging, else the
+/// bundle's or the binary's parent directory, or the root of a versioned layout.
+fn install_dir<U: UpdateConfig + ?Sized>(
+    u: &U,
+    stage_into: Option<&std::path::Path>,
+) -> std::path::PathBuf {
+    let dir = match (stage_into, u.bundle_install_path(), u.versioned_install()) {
+        (Some(staging_dir), _, _) => staging_dir,
+        (None, Some(bundle_install_path), _) => install_parent(bundle_install_path),
+        (None, None, Some(layout)) => layout.root(),
+        (None, None, None) => install_parent(u.bin_install_path()),
+    };
+    dir.to_path_buf()
+}
+
+/// The temporary directory the asset is downloaded and extracted into: a fresh one in the system
+/// temp dir, or with `temp_dir_on_install_fs` a hidden one in `install_dir` (created if missing).
+fn download_temp_dir<U: UpdateConfig + ?Sized>(
+    u: &U,
+    install_dir: &std::path::Path,
+) -> Result<tempfile::TempDir> {
+    if !u.temp_dir_on_install_fs() {
+        return Ok(tempfile::TempDir::new()?);
+    }
+    fs::create_dir_all(install_dir).map_err(|e| map_install_io_error(e, install_dir))?;
+    tempfile::Builder::new()
+        .prefix(".self_update-download")
+        .tempdir_in(install_dir)
+        .map_err(|e| map_install_io_error(e, install_dir))
+}
+
+/// The free-space preflight (`check_free_space`): check the listed asset size against
+/// `temp_dir` and `install_dir` now, or when the listing has none, have `download` check its
+/// `Content-Length` before writing anything.
+fn check_free_space<U: UpdateConfig + ?Sized>(
+    u: &U,
+    target_asset: &ReleaseAsset,
+    temp_dir: &std::path::Path,
+    install_dir: std::path::PathBuf,
+    download: &mut Download,
+) -> Result<()> {
+    if !u.check_free_space() {
+        return Ok(());
+    }
+    let check = crate::free_space::SpaceCheck {
+        temp_dir: temp_dir.to_path_buf(),
+        install_dir,
+        expansion_factor: u.free_space_expansion_factor(),
+    };
+    match target_asset.size() {
+        Some(size) => check.check(size),
+        None => {
+            download.set_space_check(check);
+            Ok(())
+        }
+    }
+}
+
+/// Whether a refused replace is retried with privileges, so refusal is no reason to stop early.
+#[cfg_attr(not(feature = "elevate"), allow(unused_variables))]
+fn elevates<U: UpdateConfig + ?Sized>(u: &U) -> bool {
+    #[cfg(feature = "elevate")]
+    return u.elevate().is_some();
+    #[cfg(not(feature = "elevate"))]
+    false
+}
+
 /// The owned, `'static` fields the blocking finish tail needs, copied out of the `&U` accessors so
 /// the tail can run inside [`tokio::task::spawn_blocking`] without borrowing the updater.
 struct FinishCtx {
@@ -1320,64 +1672,299 @@ struct FinishCtx {
     target: String,
     bin_name: String,
     bin_path_in_archive: String,
+    /// Bundle mode: the bundle root inside the archive and the installed directory it replaces.
+    bundle: Option<(String, std::path::PathBuf)>,
+    /// Extra `(path_in_archive, install_path)` files installed with the binary.
+    install_files: Vec<(String, std::path::PathBuf)>,
+    versioned_install: Option<crate::VersionedInstall>,
+    /// What extracting the archive may unpack.
+    extract_limits: crate::ExtractLimits,
+    /// The backend's short name and the receipt path, for the install receipt.
+    backend: &'static str,
+    install_receipt: Option<std::path::PathBuf>,
+    /// Carry the installed binary's owner, mode and xattrs over to its replacement.
+    #[cfg(feature = "preserve-metadata")]
+    preserve_metadata: bool,
+    /// Retry a replace refused for lack of permission through this tool.
+    #[cfg(feature = "elevate")]
+    elevate: Option<crate::elevate::Elevator>,
+    /// `stage()`: store the verified binary in this directory instead of installing it.
+    #[cfg(feature = "stage")]
+    stage_into: Option<std::path::PathBuf>,
     show_output: bool,
     verify_callback: Option<std::sync::Arc<crate::DynVerifyFn>>,
+    self_test: Option<crate::self_test::SelfTest>,
+    /// The checksums the archive must still be verified against (see [`expected_checksums`]).
+    /// Emptied by [`archive_writer`] when they are instead checked while the archive downloads.
     #[cfg(feature = "checksums")]
-    verify_checksum: Option<crate::Checksum>,
-    /// The selected asset's backend-published digest (`algorithm:hex`), if any, verified when
-    /// `verify_release_digest` is on.
-    #[cfg(feature = "checksums")]
-    asset_digest: Option<Arc<str>>,
-    #[cfg(feature = "checksums")]
-    verify_release_digest: bool,
+    checksums: Vec<crate::Checksum>,
+    /// The configured keys, validity windows, and key store.
     #[cfg(feature = "signatures")]
-    verify_keys: Vec<crate::VerifyingKey>,
+    trust: crate::keys::TrustPolicy,
+    /// The revocation list / key set fetched from the release, if configured and published.
+    #[cfg(feature = "signatures")]
+    trust_documents: crate::keys::TrustDocuments,
 }
 
 impl FinishCtx {
     /// Capture the owned fields the finish tail needs from the updater, the resolved `release`,
-    /// and the selected `target_asset` (its digest feeds the release-digest gate).
+    /// and the selected `target_asset` (its digest feeds the release-digest gate). Fails on a
+    /// release digest that cannot be parsed.
     #[cfg_attr(not(feature = "checksums"), allow(unused_variables))]
     fn capture<U: UpdateConfig + UpdateInternals + ?Sized>(
         u: &U,
         release: Release,
         target_asset: &ReleaseAsset,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        Ok(Self {
             #[cfg(feature = "checksums")]
-            asset_digest: target_asset.digest.clone(),
+            checksums: expected_checksums(u, target_asset)?,
             release,
             bin_install_path: u.bin_install_path().to_path_buf(),
             target: u.target().to_string(),
             bin_name: u.bin_name().to_string(),
             bin_path_in_archive: u.bin_path_in_archive().to_string(),
+            bundle: u
+                .bundle_root_in_archive()
+                .zip(u.bundle_install_path())
+                .map(|(root, path)| (root.to_string(), path.to_path_buf())),
+            install_files: u.install_files().to_vec(),
+            versioned_install: u.versioned_install().cloned(),
+            extract_limits: u.extract_limits(),
+            backend: u.backend(),
+            install_receipt: u.install_receipt().map(|p| p.to_path_buf()),
+            #[cfg(feature = "preserve-metadata")]
+            preserve_metadata: u.preserve_metadata(),
+            #[cfg(feature = "elevate")]
+            elevate: u.elevate(),
+            #[cfg(feature = "stage")]
+            stage_into: None,
             show_output: u.show_output(),
             verify_callback: u.verify_callback(),
-            #[cfg(feature = "checksums")]
-            verify_checksum: u.verify_checksum().cloned(),
-            #[cfg(feature = "checksums")]
-            verify_release_digest: u.verify_release_digest(),
+            self_test: u.self_test().cloned(),
             #[cfg(feature = "signatures")]
-            verify_keys: u.verifying_keys().to_vec(),
-        }
+            trust: crate::keys::TrustPolicy {
+                verifying_keys: u.verifying_keys().to_vec(),
+                trusted_keys: u.trusted_keys().to_vec(),
+                key_store_path: u.key_store_path().map(|p| p.to_path_buf()),
+            },
+            #[cfg(feature = "signatures")]
+            trust_documents: crate::keys::TrustDocuments::default(),
+        })
+    }
+}
+
+/// The checksums the downloaded archive must match, in the order they are reported: the pinned
+/// `verify_checksum`, then the selected asset's backend-published digest (github's per-asset
+/// `digest` field) when `verify_release_digest` is on. A present-but-unparseable digest is a hard
+/// error rather than a silent skip; `verify_release_digest(false)` is the escape hatch.
+#[cfg(feature = "checksums")]
+fn expected_checksums<U: UpdateInternals + ?Sized>(
+    u: &U,
+    target_asset: &ReleaseAsset,
+) -> Result<Vec<crate::Checksum>> {
+    let mut checksums: Vec<_> = u.verify_checksum().cloned().into_iter().collect();
+    if u.verify_release_digest()
+        && let Some(digest) = target_asset.digest()
+    {
+        checksums.push(crate::Checksum::parse_digest(digest)?);
+    }
+    Ok(checksums)
+}
+
+/// The writer the selected asset is downloaded through.
+#[cfg(feature = "checksums")]
+type ArchiveWriter = crate::ChecksumWriter<fs::File>;
+#[cfg(not(feature = "checksums"))]
+type ArchiveWriter = fs::File;
+
+/// Create the file at `path` the archive is downloaded into. With `checksums`, the bytes are hashed
+/// for every checksum in `ctx` as they are written, in a single pass; those checksums are moved
+/// out of `ctx`, so the finish tail does not read the archive again for them.
+#[cfg_attr(not(feature = "checksums"), allow(unused_variables))]
+fn archive_writer(path: &std::path::Path, ctx: &mut FinishCtx) -> Result<ArchiveWriter> {
+    let file = fs::File::create(path)?;
+    #[cfg(feature = "checksums")]
+    let file = crate::ChecksumWriter::new(file, std::mem::take(&mut ctx.checksums));
+    Ok(file)
+}
+
+/// Complete an [`archive_writer`] once the download has finished: with `checksums`, fail with
+/// [`Error::ChecksumMismatch`] unless every streamed digest matched.
+fn finish_archive(writer: ArchiveWriter) -> Result<()> {
+    #[cfg(feature = "checksums")]
+    writer.finish()?;
+    #[cfg(not(feature = "checksums"))]
+    drop(writer);
+    Ok(())
+}
+
+/// Download the selected asset into `path`. A single-stream download is hashed as it is written
+/// (see [`archive_writer`]). A parallel chunked download writes the file at offsets, so with
+/// `checksums` it is verified by reading the finished file back instead.
+#[cfg_attr(not(feature = "checksums"), allow(unused_variables))]
+fn download_archive(
+    download: &Download,
+    path: &std::path::Path,
+    ctx: &mut FinishCtx,
+) -> Result<()> {
+    if download.is_parallel() {
+        download.download_to_file(&fs::File::create(path)?)?;
+        #[cfg(feature = "checksums")]
+        crate::Checksum::verify_all(path, &std::mem::take(&mut ctx.checksums))?;
+        return Ok(());
+    }
+    let mut writer = archive_writer(path, ctx)?;
+    download.download_to(&mut writer)?;
+    finish_archive(writer)
+}
+
+/// Async sibling of [`download_archive`].
+#[cfg(feature = "async")]
+#[cfg_attr(not(feature = "checksums"), allow(unused_variables))]
+async fn download_archive_async(
+    download: &Download,
+    path: &std::path::Path,
+    ctx: &mut FinishCtx,
+) -> Result<()> {
+    if download.is_parallel() {
+        download
+            .download_to_file_async(&fs::File::create(path)?)
+            .await?;
+        #[cfg(feature = "checksums")]
+        crate::Checksum::verify_all(path, &std::mem::take(&mut ctx.checksums))?;
+        return Ok(());
     }
+    let mut writer = archive_writer(path, ctx)?;
+    download.download_to_async(&mut writer).await?;
+    finish_archive(writer)
 }
 
-/// Verify the downloaded archive (checksum/signature), extract the binary, and install it. This is
-/// the sync tail shared verbatim by the sync and async update flows. Builds a [`FinishCtx`] from
-/// the updater and delegates to [`finish_update_owned`] without spawning (the sync path runs it
-/// inline). Consumes `release` and returns the resulting status.
+/// Verify the downloaded archive (checksum/signature), extract the binary, and install it. Completes
+/// the [`FinishCtx`] captured from the updater (fetching any configured trust documents next to the
+/// archive) and delegates to [`finish_update_owned`] without spawning (the sync path runs it
+/// inline). Returns the resulting status.
+#[cfg_attr(not(feature = "signatures"), allow(unused_variables))]
 fn finish_update<U: UpdateConfig + UpdateInternals + ?Sized>(
     u: &U,
-    release: Release,
+    #[cfg_attr(not(feature = "signatures"), allow(unused_mut))] mut ctx: FinishCtx,
     target_asset: &ReleaseAsset,
     tmp_archive_dir: tempfile::TempDir,
     tmp_archive_path: &std::path::Path,
 ) -> Result<ReleaseStatus> {
-    let ctx = FinishCtx::capture(u, release, target_asset);
+    #[cfg(feature = "signatures")]
+    {
+        let dir = trust_document_dir(tmp_archive_dir.path())?;
+        for (asset, dest) in
+            signature_downloads(u, &ctx.release, target_asset, tmp_archive_path, &dir)?
+        {
+            build_download(u, &asset)?.download_to(&mut fs::File::create(&dest)?)?;
+        }
+        ctx.trust_documents = trust_documents_in(u, &ctx.release, &dir);
+    }
     finish_update_owned(ctx, tmp_archive_dir, tmp_archive_path)
 }
 
+/// The directory the trust documents are downloaded into: a subdirectory of the update's temp dir,
+/// so a document can never overwrite the archive whatever its name.
+#[cfg(feature = "signatures")]
+fn trust_document_dir(tmp_dir: &std::path::Path) -> Result<std::path::PathBuf> {
+    let dir = tmp_dir.join("trust");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// The configured trust documents (`revocation_list_asset` / `key_set_asset`) the release carries,
+/// each paired with the name of its detached `.sig` asset. A document the release does not
+/// publish is skipped; one published without its signature is an error.
+#[cfg(feature = "signatures")]
+fn published_trust_documents<'r, U: UpdateInternals + ?Sized>(
+    u: &U,
+    release: &'r Release,
+) -> Result<Vec<(&'r ReleaseAsset, &'r ReleaseAsset)>> {
+    let find = |name: &str| release.assets().iter().find(|a| a.name() == name);
+    let mut found = Vec::new();
+    for name in [u.revocation_list_asset(), u.key_set_asset()]
+        .into_iter()
+        .flatten()
+    {
+        let Some(doc) = find(name) else {
+            continue;
+        };
+        let sig_name = format!("{name}.sig");
+        let sig = find(&sig_name).ok_or_else(|| {
+            Error::Signature(Box::new(MessageError(format!(
+                "release asset {name:?} has no detached signature {sig_name:?}"
+            ))))
+        })?;
+        found.push((doc, sig));
+    }
+    Ok(found)
+}
+
+/// The (asset, destination) pairs to download besides the archive itself: the detached
+/// `<asset>.sig` of an archive whose format cannot embed a signature (saved next to the archive,
+/// where [`verify_signature`] looks for it), and the release's trust documents (into `dir`).
+/// Nothing is fetched when no key is configured, since verification is then disabled.
+#[cfg(feature = "signatures")]
+fn signature_downloads<U: UpdateInternals + ?Sized>(
+    u: &U,
+    release: &Release,
+    target_asset: &ReleaseAsset,
+    tmp_archive_path: &std::path::Path,
+    dir: &std::path::Path,
+) -> Result<Vec<(ReleaseAsset, std::path::PathBuf)>> {
+    let mut downloads = Vec::new();
+    if u.verifying_keys().is_empty() && u.trusted_keys().is_empty() && u.key_store_path().is_none()
+    {
+        return Ok(downloads);
+    }
+    let embedded = crate::detect_archive(std::path::Path::new(target_asset.name()))
+        .is_ok_and(|kind| carries_embedded_signature(&kind));
+    if !embedded {
+        let sig_name = format!("{}.sig", target_asset.name());
+        // A release without the `.sig` leaves verification to fail with `NoSignatures`.
+        if let Some(sig) = release.assets().iter().find(|a| a.name() == sig_name) {
+            downloads.push((sig.clone(), detached_signature_path(tmp_archive_path)));
+        }
+    }
+    for (doc, sig) in published_trust_documents(u, release)? {
+        for asset in [doc, sig] {
+            if !is_safe_asset_name(asset.name()) {
+                return Err(Error::InvalidAssetName {
+                    name: asset.name().to_string(),
+                });
+            }
+            downloads.push((asset.clone(), dir.join(asset.name())));
+        }
+    }
+    Ok(downloads)
+}
+
+/// The downloaded trust documents in `dir`, keyed by role.
+#[cfg(feature = "signatures")]
+fn trust_documents_in<U: UpdateInternals + ?Sized>(
+    u: &U,
+    release: &Release,
+    dir: &std::path::Path,
+) -> crate::keys::TrustDocuments {
+    let located = |name: Option<&str>| {
+        let name = name?;
+        release
+            .assets()
+            .iter()
+            .any(|a| a.name() == name)
+            .then(|| crate::keys::SignedDocument {
+                path: dir.join(name),
+                signature_path: dir.join(format!("{name}.sig")),
+            })
+    };
+    crate::keys::TrustDocuments {
+        revocation_list: located(u.revocation_list_asset()),
+        key_set: located(u.key_set_asset()),
+    }
+}
+
 /// The blocking finish tail over **owned** fields: verify (checksum/signature), extract, install.
 /// Takes the [`tempfile::TempDir`] by value (moved in, dropped at the end) and the owned `ctx`, so
 /// it can be run directly inside [`tokio::task::spawn_blocking`] on the async path. Returns the
@@ -1389,32 +1976,29 @@ fn finish_update_owned(
 ) -> Result<ReleaseStatus> {
     let show_output = ctx.show_output;
 
+    // Checksums not already verified while the archive streamed in, all in one read of the file.
     #[cfg(feature = "checksums")]
-    {
-        if let Some(checksum) = ctx.verify_checksum.as_ref() {
-            checksum.verify(tmp_archive_path)?;
-        }
-        // The backend-published digest of the selected asset (github's per-asset `digest` field),
-        // verified by default. A present-but-unparseable digest is a hard error rather than a
-        // silent skip; `verify_release_digest(false)` is the escape hatch.
-        if ctx.verify_release_digest
-            && let Some(digest) = ctx.asset_digest.as_deref()
-        {
-            crate::Checksum::parse_digest(digest)?.verify(tmp_archive_path)?;
-        }
-    }
+    crate::Checksum::verify_all(tmp_archive_path, &ctx.checksums)?;
 
+    // Revocations apply before the archive is checked; a delivered key set is accepted only once
+    // the archive has verified with the keys trusted so far, and saved only once it is installed.
     #[cfg(feature = "signatures")]
-    {
-        if !ctx.verify_keys.is_empty() {
+    let key_set = {
+        let keys = ctx.trust.active_keys(&ctx.trust_documents)?;
+        if !keys.is_empty() {
             println(show_output, "Verifying downloaded file...");
         }
-        verify_signature(tmp_archive_path, &ctx.verify_keys)?;
-    }
+        verify_signature(tmp_archive_path, &keys)?;
+        ctx.trust.accept_key_set(&ctx.trust_documents, &keys)?
+    };
 
     print_flush(show_output, "Extracting archive... ")?;
 
-    let bin_path_str = Cow::Borrowed(ctx.bin_path_in_archive.as_str());
+    // In bundle mode the templates apply to the bundle root rather than the binary path.
+    let bin_path_str = Cow::Borrowed(match &ctx.bundle {
+        Some((bundle_root_in_archive, _)) => bundle_root_in_archive.as_str(),
+        None => ctx.bin_path_in_archive.as_str(),
+    });
 
     // The `{{ version }}` / `{{ target }}` / `{{ bin }}` template matchers. Hoisted to `static`
     // `LazyLock<Regex>` (I6) so each is compiled once, not rebuilt from its constant pattern on
@@ -1445,33 +2029,165 @@ fn finish_update_owned(
         Ok(re.replace_all(str, regex::NoExpand(val)))
     }
 
-    let bin_path_str = substitute(&VERSION_RE, &bin_path_str, ctx.release.version())?;
-    let bin_path_str = substitute(&TARGET_RE, &bin_path_str, &ctx.target)?;
-    let bin_path_str = substitute(&BIN_RE, &bin_path_str, &ctx.bin_name)?;
-    let bin_path_str = bin_path_str.as_ref();
+    // Apply all three substitutions to an archive path.
+    let template = |path: &str| -> Result<String> {
+        let path = substitute(&VERSION_RE, path, ctx.release.version())?;
+        let path = substitute(&TARGET_RE, &path, &ctx.target)?;
+        Ok(substitute(&BIN_RE, &path, &ctx.bin_name)?.into_owned())
+    };
+    let bin_path_str = template(&bin_path_str)?;
+    let bin_path_str = bin_path_str.as_str();
+
+    if let Some((_, bundle_install_path)) = &ctx.bundle {
+        finish_bundle(&ctx, tmp_archive_path, bin_path_str, bundle_install_path)?;
+        #[cfg(feature = "signatures")]
+        ctx.trust.save_key_set(key_set)?;
+        write_receipt(&ctx, tmp_archive_path, bundle_install_path);
+        return Ok(ReleaseStatus::Updated(ctx.release));
+    }
 
-    Extract::from_source(tmp_archive_path).extract_file(tmp_archive_dir.path(), bin_path_str)?;
+    if !ctx.install_files.is_empty() {
+        let files = ctx
+            .install_files
+            .iter()
+            .map(|(path, install_path)| Ok((template(path)?, install_path.as_path())))
+            .collect::<Result<Vec<_>>>()?;
+        finish_files(&ctx, tmp_archive_path, bin_path_str, &files)?;
+        #[cfg(feature = "signatures")]
+        ctx.trust.save_key_set(key_set)?;
+        write_receipt(&ctx, tmp_archive_path, &ctx.bin_install_path);
+        return Ok(ReleaseStatus::Updated(ctx.release));
+    }
+
+    Extract::from_source(tmp_archive_path)
+        .limits(ctx.extract_limits)
+        .extract_file(tmp_archive_dir.path(), bin_path_str)?;
     let new_exe = tmp_archive_dir.path().join(bin_path_str);
 
     println(show_output, "Done");
 
+    if let Some(self_test) = ctx.self_test.as_ref() {
+        print_flush(show_output, "Testing new binary... ")?;
+        self_test.run(&new_exe, ctx.release.version())?;
+        println(show_output, "Done");
+    }
+
+    #[cfg(feature = "stage")]
+    if let Some(staging_dir) = &ctx.stage_into {
+        print_flush(show_output, "Staging new binary... ")?;
+        run_verify_hook(ctx.verify_callback.as_deref(), &new_exe)?;
+        crate::staged::stage(
+            &new_exe,
+            staging_dir,
+            ctx.release.version(),
+            &ctx.bin_install_path,
+            tmp_archive_path,
+            bin_path_str,
+        )?;
+        println(show_output, "Done");
+        // Nothing is installed yet, so a delivered key set is not saved: the stored trust stays
+        // as it was until a release delivering the set is installed.
+        return Ok(ReleaseStatus::Updated(ctx.release));
+    }
+
+    if let Some(layout) = &ctx.versioned_install {
+        print_flush(show_output, "Installing new version... ")?;
+        install_versioned(
+            &new_exe,
+            layout,
+            &ctx.bin_name,
+            ctx.release.version(),
+            ctx.verify_callback.as_deref(),
+        )?;
+        println(show_output, "Done");
+        #[cfg(feature = "signatures")]
+        ctx.trust.save_key_set(key_set)?;
+        let installed = layout.binary_path(ctx.release.version(), &ctx.bin_name);
+        write_receipt(&ctx, tmp_archive_path, &installed);
+        return Ok(ReleaseStatus::Updated(ctx.release));
+    }
+
     print_flush(show_output, "Replacing binary file... ")?;
 
-    install_binary(
+    #[cfg(feature = "preserve-metadata")]
+    let preserve_metadata = ctx.preserve_metadata;
+    #[cfg(not(feature = "preserve-metadata"))]
+    let preserve_metadata = false;
+    let installed = install_binary(
         &new_exe,
         &ctx.bin_install_path,
         ctx.verify_callback.as_deref(),
-    )?;
+        preserve_metadata,
+    );
+    #[cfg(feature = "elevate")]
+    let installed = match (installed, ctx.elevate) {
+        (Err(Error::InstallPathNotWritable { .. }), Some(elevator)) => {
+            let tool = crate::elevate::find_tool(elevator, &ctx.bin_install_path)?;
+            println(
+                show_output,
+                &format!("permission denied, retrying with {}", tool.display()),
+            );
+            crate::elevate::install_elevated(&tool, &new_exe, &ctx.bin_install_path)
+        }
+        (installed, _) => installed,
+    };
+    installed?;
     println(show_output, "Done");
+    #[cfg(feature = "signatures")]
+    ctx.trust.save_key_set(key_set)?;
+    write_receipt(&ctx, tmp_archive_path, &ctx.bin_install_path);
 
     Ok(ReleaseStatus::Updated(ctx.release))
 }
 
+/// Write the install receipt, if one is configured, for a release just installed at
+/// `install_path` from `archive`. The update has already succeeded, so a failure is only logged.
+fn write_receipt(ctx: &FinishCtx, archive: &std::path::Path, install_path: &std::path::Path) {
+    let Some(receipt_path) = &ctx.install_receipt else {
+        return;
+    };
+    #[cfg(feature = "checksums")]
+    let sha256 = match crate::checksum::sha256_file_hex(archive) {
+        Ok(sha256) => Some(sha256),
+        Err(e) => {
+            log::warn!(
+                "failed to hash {:?} for the install receipt: {}",
+                archive,
+                e
+            );
+            None
+        }
+    };
+    #[cfg(not(feature = "checksums"))]
+    let sha256 = {
+        let _ = archive;
+        None
+    };
+    let receipt = crate::receipt::InstallReceipt::new(
+        ctx.backend,
+        ctx.release.version(),
+        install_path,
+        ctx.install_files.iter().map(|(_, p)| p.clone()).collect(),
+        sha256,
+    );
+    if let Err(e) = receipt.write(receipt_path) {
+        log::warn!(
+            "failed to write the install receipt {:?}: {}",
+            receipt_path,
+            e
+        );
+    }
+}
+
 /// Async sibling of [`ReleaseUpdate::update_extended`]: identical flow with the release listing and
 /// the download done asynchronously, reusing the shared sync helpers for selection, confirmation,
-/// verification, extraction, and install.
+/// verification, extraction, and install. With `stage_into` the verified binary is staged there
+/// instead of installed, as for `stage()`.
 #[cfg(feature = "async")]
-pub(crate) async fn update_extended_async<U>(u: &U) -> Result<ReleaseStatus>
+pub(crate) async fn update_extended_async<U>(
+    u: &U,
+    stage_into: Option<std::path::PathBuf>,
+) -> Result<ReleaseStatus>
 where
     // `AsyncReleaseUpdate` is never used through a trait object (the async API hands out a concrete
     // `Update`), so `U` is always `Sized` here — unlike the shared sync helpers above. The bound is
@@ -1504,25 +2220,52 @@ where
 
     let target_asset = resolve_and_confirm(u, &release)?;
 
-    // Opt-in preflight: bail before downloading if the install path is definitely not writable.
-    // Shares the sync probe for exact parity with `update_extended`.
-    if u.check_install_path_writable() {
-        probe_install_path_writable(u.bin_install_path())?;
-    }
+    // Shares the sync preflight for exact parity with `update_extended`.
+    preflight(u)?;
 
-    let tmp_archive_dir = tempfile::TempDir::new()?;
+    let install_dir = install_dir(u, stage_into.as_deref());
+    let mut ctx = FinishCtx::capture(u, release, &target_asset)?;
+    #[cfg(feature = "stage")]
+    {
+        ctx.stage_into = stage_into;
+    }
+    let tmp_archive_dir = download_temp_dir(u, &install_dir)?;
     let tmp_archive_path = tmp_archive_dir.path().join(target_asset.name());
-    let mut tmp_archive = fs::File::create(&tmp_archive_path)?;
+    let mut download = build_download(u, &target_asset)?;
+    check_free_space(
+        u,
+        &target_asset,
+        tmp_archive_dir.path(),
+        install_dir,
+        &mut download,
+    )?;
 
     println(show_output, "Downloading...");
-    build_download(u, &target_asset)?
-        .download_to_async(&mut tmp_archive)
-        .await?;
-
-    // Run the blocking finish tail (verify/extract/install) off the async executor. Copy out the
-    // owned fields, MOVE the TempDir into the closure (it is dropped there), and `.await` the
-    // join handle, mapping a JoinError to an update error.
-    let ctx = FinishCtx::capture(u, release, &target_asset);
+    download_archive_async(&download, &tmp_archive_path, &mut ctx).await?;
+
+    #[cfg(feature = "signatures")]
+    let trust_dir = trust_document_dir(tmp_archive_dir.path())?;
+    #[cfg(feature = "signatures")]
+    for (asset, dest) in signature_downloads(
+        u,
+        &ctx.release,
+        &target_asset,
+        &tmp_archive_path,
+        &trust_dir,
+    )? {
+        build_download(u, &asset)?
+            .download_to_async(&mut fs::File::create(&dest)?)
+            .await?;
+    }
+
+    // Run the blocking finish tail (verify/extract/install) off the async executor. The owned
+    // fields were copied out into `ctx` before the download; MOVE it and the TempDir into the
+    // closure (the TempDir is dropped there), and `.await` the join handle, mapping a JoinError to
+    // an update error.
+    #[cfg(feature = "signatures")]
+    {
+        ctx.trust_documents = trust_documents_in(u, &ctx.release, &trust_dir);
+    }
     tokio::task::spawn_blocking(move || {
         finish_update_owned(ctx, tmp_archive_dir, &tmp_archive_path)
     })
@@ -1537,29 +2280,45 @@ where
 /// it — replacing the current executable in place, or moving it to `bin_install_path`. If the
 /// hook returns `Err(..)` the install is aborted (as `Error::VerificationRejected`) before
 /// anything is replaced.
-fn install_binary(
+///
+/// With `preserve_metadata` (unix, `preserve-metadata` feature) and an existing installed binary,
+/// the new binary is instead staged beside it carrying its owner, xattrs and mode (the
+/// `preserve` module), then renamed over it. That is also how `self_replace` swaps the
+/// running executable on unix, so both cases share the path.
+#[cfg_attr(not(all(unix, feature = "preserve-metadata")), allow(unused_variables))]
+pub(crate) fn install_binary(
     new_exe: &std::path::Path,
     bin_install_path: &std::path::Path,
     verify: Option<&crate::DynVerifyFn>,
+    preserve_metadata: bool,
 ) -> Result<()> {
-    if let Some(verify) = verify {
-        // A hook that returns `Err` (an explicit rejection or a hook IO error) aborts the install;
-        // its message becomes the rejection reason. An error that already is a
-        // `VerificationRejected` (e.g. built via `Error::verification_rejected`) passes through
-        // unwrapped so the reason is not nested inside another rejection message.
-        verify(new_exe).map_err(|e| match e {
-            Error::VerificationRejected { .. } => e,
-            other => Error::VerificationRejected {
-                reason: Some(other.to_string()),
-            },
+    run_verify_hook(verify, new_exe)?;
+    let current_exe = std::env::current_exe()?;
+    let is_current_exe = same_file(bin_install_path, &current_exe);
+    #[cfg(all(unix, feature = "preserve-metadata"))]
+    if preserve_metadata {
+        // Rename onto the resolved running executable, as `self_replace` would, not a symlink to it.
+        let installed = if is_current_exe {
+            current_exe.as_path()
+        } else {
+            bin_install_path
+        };
+        let staged = crate::preserve::stage_beside(new_exe, installed).map_err(|e| match e {
+            Error::Io(io) => map_install_io_error(io, bin_install_path),
+            other => other,
         })?;
+        if let Some(staged) = staged {
+            staged
+                .persist(installed)
+                .map_err(|e| map_install_io_error(e.error, bin_install_path))?;
+            return Ok(());
+        }
     }
-    let current_exe = std::env::current_exe()?;
     // Only the two install-step writes are wrapped with path context (not `current_exe()` or the
     // verify hook above): a permission failure here becomes `InstallPathNotWritable` naming the
     // path, and any other IO error is rewrapped so the path shows up while the `ErrorKind` stays
     // inspectable. This annotation is always on, independent of the opt-in preflight probe.
-    if same_file(bin_install_path, &current_exe) {
+    if is_current_exe {
         self_replace::self_replace(new_exe)
             .map_err(|e| map_install_io_error(e, bin_install_path))?;
     } else {
@@ -1573,13 +2332,319 @@ fn install_binary(
     Ok(())
 }
 
+/// The versioned-layout sibling of [`install_binary`]: run the verification hook, then install
+/// `new_exe` into `version`'s directory of `layout` and flip its `current` link (see
+/// [`VersionedInstall`](crate::VersionedInstall)). The running executable is left untouched. A
+/// permission failure becomes `InstallPathNotWritable` naming the layout root.
+fn install_versioned(
+    new_exe: &std::path::Path,
+    layout: &crate::VersionedInstall,
+    bin_name: &str,
+    version: &str,
+    verify: Option<&crate::DynVerifyFn>,
+) -> Result<()> {
+    run_verify_hook(verify, new_exe)?;
+    layout
+        .install(new_exe, bin_name, version)
+        .map_err(|e| match e {
+            Error::Io(io) => map_install_io_error(io, layout.root()),
+            other => other,
+        })
+}
+
+/// Run the post-update verification hook (if any) on `path`.
+///
+/// A hook that returns `Err` (an explicit rejection or a hook IO error) aborts the install; its
+/// message becomes the rejection reason. An error that already is a `VerificationRejected` (e.g.
+/// built via `Error::verification_rejected`) passes through unwrapped so the reason is not nested
+/// inside another rejection message.
+fn run_verify_hook(verify: Option<&crate::DynVerifyFn>, path: &std::path::Path) -> Result<()> {
+    match verify {
+        Some(verify) => verify(path).map_err(|e| match e {
+            Error::VerificationRejected { .. } => e,
+            other => Error::VerificationRejected {
+                reason: Some(other.to_string()),
+            },
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The bundle-mode finish tail: extract the whole archive into a staging dir next to
+/// `bundle_install_path`, self-test the staged tree, then swap it in with [`install_bundle`].
+///
+/// Staging and stash dirs are created in the bundle's parent directory so every rename of the
+/// swap stays on one filesystem.
+fn finish_bundle(
+    ctx: &FinishCtx,
+    archive: &std::path::Path,
+    bundle_root_in_archive: &str,
+    bundle_install_path: &std::path::Path,
+) -> Result<()> {
+    let show_output = ctx.show_output;
+    let staging = temp_dir_beside(bundle_install_path, ".self_update-staging")?;
+    let stash = temp_dir_beside(bundle_install_path, ".self_update-stash")?;
+
+    Extract::from_source(archive)
+        .limits(ctx.extract_limits)
+        .extract_into(staging.path())?;
+    let staged_root = staging.path().join(bundle_root_in_archive);
+    if !staged_root.is_dir() {
+        return Err(Error::Internal {
+            message: format!(
+                "Could not find the required directory in the archive: {:?}",
+                bundle_root_in_archive
+            ),
+            source: None,
+        });
+    }
+
+    println(show_output, "Done");
+
+    let exe_rel = exe_in_bundle(&std::env::current_exe()?, bundle_install_path);
+    if let Some(self_test) = ctx.self_test.as_ref() {
+        // The staged copy of the running exe, or `<root>/<bin_name>` when it lives elsewhere.
+        let new_exe = staged_root.join(exe_rel.as_deref().unwrap_or(ctx.bin_name.as_ref()));
+        print_flush(show_output, "Testing new binary... ")?;
+        self_test.run(&new_exe, ctx.release.version())?;
+        println(show_output, "Done");
+    }
+
+    print_flush(show_output, "Replacing bundle directory... ")?;
+    install_bundle(
+        &staged_root,
+        bundle_install_path,
+        stash.path(),
+        exe_rel.as_deref(),
+        ctx.verify_callback.as_deref(),
+    )?;
+    println(show_output, "Done");
+    Ok(())
+}
+
+/// The multi-file finish tail (`install_file`): extract the binary and every extra file into a
+/// staging dir next to `bin_install_path`, self-test the binary, then install them all with
+/// [`install_all`].
+fn finish_files(
+    ctx: &FinishCtx,
+    archive: &std::path::Path,
+    bin_path_in_archive: &str,
+    files: &[(String, &std::path::Path)],
+) -> Result<()> {
+    let show_output = ctx.show_output;
+    let staging = temp_dir_beside(&ctx.bin_install_path, ".self_update-staging")?;
+    let stash = temp_dir_beside(&ctx.bin_install_path, ".self_update-stash")?;
+
+    // Each file is extracted into its own numbered subdirectory, so two mappings of the same
+    // archive path each get a source to move.
+    let mut extract = Extract::from_source(archive);
+    extract.limits(ctx.extract_limits);
+    let mut moves = Vec::with_capacity(files.len() + 1);
+    let entries = std::iter::once((bin_path_in_archive, ctx.bin_install_path.as_path()))
+        .chain(files.iter().map(|(path, dest)| (path.as_str(), *dest)));
+    for (i, (path, dest)) in entries.enumerate() {
+        let dir = staging.path().join(i.to_string());
+        fs::create_dir(&dir)?;
+        extract.extract_file(&dir, path)?;
+        moves.push((dir.join(path), dest));
+    }
+
+    println(show_output, "Done");
+
+    if let Some(self_test) = ctx.self_test.as_ref() {
+        print_flush(show_output, "Testing new binary... ")?;
+        self_test.run(&moves[0].0, ctx.release.version())?;
+        println(show_output, "Done");
+    }
+
+    print_flush(show_output, "Replacing files... ")?;
+    install_all(&moves, stash.path(), ctx.verify_callback.as_deref())?;
+    println(show_output, "Done");
+    Ok(())
+}
+
+/// Install every staged `(source, dest)` pair in one [`MoveAll`](crate::MoveAll) transaction,
+/// stashing replaced files under `stash`. The verification hook runs on the first source (the
+/// binary) before anything is replaced. A destination that is the running executable is replaced
+/// last via `self_replace`; if that fails, every other file is rolled back.
+fn install_all(
+    moves: &[(std::path::PathBuf, &std::path::Path)],
+    stash: &std::path::Path,
+    verify: Option<&crate::DynVerifyFn>,
+) -> Result<()> {
+    if let Some((new_exe, _)) = moves.first() {
+        run_verify_hook(verify, new_exe)?;
+    }
+    let current_exe = std::env::current_exe()?;
+    let mut running = None;
+    let mut transaction = crate::MoveAll::from_temp(stash);
+    for (source, dest) in moves {
+        if let Some(parent) = dest.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(|e| map_install_io_error(e, dest))?;
+        }
+        if running.is_none() && same_file(dest, &current_exe) {
+            running = Some((source, *dest));
+        } else {
+            transaction.add(source, dest);
+        }
+    }
+    transaction.commit_then(|| match running {
+        Some((source, dest)) => {
+            self_replace::self_replace(source).map_err(|e| map_install_io_error(e, dest))
+        }
+        None => Ok(()),
+    })
+}
+
+/// Create a temp dir in the directory holding `install_path`, so renames between it and
+/// `install_path` stay on one filesystem. A failure names `install_path`.
+fn temp_dir_beside(install_path: &std::path::Path, prefix: &str) -> Result<tempfile::TempDir> {
+    tempfile::Builder::new()
+        .prefix(prefix)
+        .tempdir_in(install_parent(install_path))
+        .map_err(|e| map_install_io_error(e, install_path))
+}
+
+/// Swap the staged bundle root `staged_root` in for `bundle_install_path`, stashing the displaced
+/// tree under `stash` (same filesystem) until the swap has succeeded.
+///
+/// The verification hook runs on `staged_root` first. `exe_rel` is the running executable's path
+/// relative to the installed bundle, when it lives inside it: the staged tree must then carry a
+/// file at the same path, and the running exe is moved aside before the old tree is. A failure at
+/// any rename restores what was already moved, in reverse (best-effort, logged, as
+/// [`MoveAll`](crate::MoveAll)), and the original error is returned naming
+/// `bundle_install_path`.
+fn install_bundle(
+    staged_root: &std::path::Path,
+    bundle_install_path: &std::path::Path,
+    stash: &std::path::Path,
+    exe_rel: Option<&std::path::Path>,
+    verify: Option<&crate::DynVerifyFn>,
+) -> Result<()> {
+    run_verify_hook(verify, staged_root)?;
+    if let Some(exe_rel) = exe_rel
+        && !staged_root.join(exe_rel).is_file()
+    {
+        return Err(Error::Internal {
+            message: format!(
+                "Could not find the running executable {:?} in the new bundle",
+                exe_rel
+            ),
+            source: None,
+        });
+    }
+    let in_bundle = |e| map_install_io_error(e, bundle_install_path);
+    let old = stash.join("old");
+    let aside = stash.join("exe-aside");
+
+    // 1. Move the running image out of the old tree: renaming a running exe is allowed on unix
+    //    and windows, and is the primitive `self_replace` itself relies on.
+    let exe = exe_rel.map(|rel| bundle_install_path.join(rel));
+    if let Some(exe) = &exe {
+        fs::rename(exe, &aside).map_err(in_bundle)?;
+    }
+    let restore_exe = || {
+        if let Some(exe) = &exe
+            && let Err(e) = fs::rename(&aside, exe)
+        {
+            log::error!(
+                "failed to restore {:?} from {:?} during rollback: {}",
+                exe,
+                aside,
+                e
+            );
+        }
+    };
+
+    // 2. Stash the whole old tree.
+    let had_old = fs::symlink_metadata(bundle_install_path).is_ok();
+    if had_old && let Err(e) = fs::rename(bundle_install_path, &old) {
+        restore_exe();
+        return Err(in_bundle(e));
+    }
+
+    // 3. Move the new tree into place.
+    if let Err(e) = fs::rename(staged_root, bundle_install_path) {
+        if had_old && let Err(restore_err) = fs::rename(&old, bundle_install_path) {
+            log::error!(
+                "failed to restore {:?} from stash {:?} during rollback: {}",
+                bundle_install_path,
+                old,
+                restore_err
+            );
+        }
+        restore_exe();
+        return Err(in_bundle(e));
+    }
+
+    // Committed. The old tree goes with the stash dir; the aside exe may still be locked by this
+    // process (windows), so its deletion is left to `self_replace`.
+    if exe.is_some()
+        && let Err(e) = self_replace::self_delete_at(&aside)
+    {
+        log::warn!("failed to schedule removal of {:?}: {}", aside, e);
+    }
+    Ok(())
+}
+
+/// The running executable's path relative to `bundle`, when it lives inside it. Both paths are
+/// canonicalized first (see [`same_file`]), falling back to the raw paths when that fails.
+fn exe_in_bundle(exe: &std::path::Path, bundle: &std::path::Path) -> Option<std::path::PathBuf> {
+    let (exe, bundle) = match (std::fs::canonicalize(exe), std::fs::canonicalize(bundle)) {
+        (Ok(exe), Ok(bundle)) => (exe, bundle),
+        _ => (exe.to_path_buf(), bundle.to_path_buf()),
+    };
+    exe.strip_prefix(&bundle)
+        .ok()
+        .filter(|rel| !rel.as_os_str().is_empty())
+        .map(|rel| rel.to_path_buf())
+}
+
+/// The nearest ancestor of `exe` whose file name ends in `.app`: the macOS default
+/// `bundle_install_path`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn app_bundle_ancestor(exe: &std::path::Path) -> Option<std::path::PathBuf> {
+    exe.ancestors()
+        .skip(1)
+        .find(|p| p.extension().is_some_and(|ext| ext == "app"))
+        .map(|p| p.to_path_buf())
+}
+
+/// Resolve the `bundle_install_path` of bundle mode when none was set: the `.app` bundle holding
+/// the current executable on macOS ([`Error::NoAppBundle`] if there is none); elsewhere there is no
+/// default (`Error::MissingField { field: "bundle_install_path" }`).
+pub(crate) fn default_bundle_install_path() -> Result<std::path::PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        let exe = std::env::current_exe()?;
+        app_bundle_ancestor(&exe).ok_or(Error::NoAppBundle { exe })
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        Err(Error::MissingField {
+            field: "bundle_install_path",
+        })
+    }
+}
+
+/// The directory holding `path`; `parent()` is `Some("")` for a bare file name, which (like
+/// `None`) means the CWD.
+pub(crate) fn install_parent(path: &std::path::Path) -> &std::path::Path {
+    match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => std::path::Path::new("."),
+    }
+}
+
 /// Map an IO error from an install-step write into the crate error, always naming the install path.
 ///
 /// A `PermissionDenied` becomes [`Error::InstallPathNotWritable`] carrying the path; any other kind
 /// is rewrapped as [`Error::Io`] whose message names the path (`"installing to {path}: {orig}"`)
 /// while preserving the original [`std::io::ErrorKind`] so callers can still inspect it. Non-permission
 /// failures (e.g. a full disk) are deliberately NOT reclassified as `InstallPathNotWritable`.
-fn map_install_io_error(e: std::io::Error, bin_install_path: &std::path::Path) -> Error {
+pub(crate) fn map_install_io_error(e: std::io::Error, bin_install_path: &std::path::Path) -> Error {
     if e.kind() == std::io::ErrorKind::PermissionDenied {
         Error::InstallPathNotWritable {
             path: bin_install_path.to_path_buf(),
@@ -1610,14 +2675,9 @@ pub(crate) fn probe_install_path_writable(bin_install_path: &std::path::Path) ->
             .open(bin_install_path)
             .map(|_| ())
     } else {
-        // `parent()` is `Some("")` for a bare file name; treat that (and `None`) as the CWD.
-        let parent = match bin_install_path.parent() {
-            Some(p) if !p.as_os_str().is_empty() => p,
-            _ => std::path::Path::new("."),
-        };
         tempfile::Builder::new()
             .prefix(".self_update_writecheck")
-            .tempfile_in(parent)
+            .tempfile_in(install_parent(bin_install_path))
             .map(|_| ())
     };
     match probe {
@@ -1632,12 +2692,32 @@ pub(crate) fn probe_install_path_writable(bin_install_path: &std::path::Path) ->
     }
 }
 
+/// The bundle-mode sibling of [`probe_install_path_writable`]: the swap renames entries of the
+/// bundle's parent directory, so that directory is probed (a temporary sibling is created and
+/// removed). Only a definite `PermissionDenied` fails, as
+/// [`Error::InstallPathNotWritable`] naming the parent. A versioned install probes its layout root
+/// the same way, through the `current` link it renames over.
+pub(crate) fn probe_bundle_parent_writable(bundle_install_path: &std::path::Path) -> Result<()> {
+    let parent = install_parent(bundle_install_path);
+    match tempfile::Builder::new()
+        .prefix(".self_update_writecheck")
+        .tempdir_in(parent)
+    {
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            Err(Error::InstallPathNotWritable {
+                path: parent.to_path_buf(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Whether two paths refer to the same file. Compares canonicalized paths (resolving symlinks and
 /// `..`), falling back to a raw comparison when canonicalization fails (for example when a path does
 /// not yet exist). `current_exe()` is symlink-resolved on some platforms while a user-supplied
 /// `bin_install_path` is not, so a raw `==` can miss that both name the running executable and route
 /// a self-update through the plain `Move` path instead of `self_replace`.
-fn same_file(a: &std::path::Path, b: &std::path::Path) -> bool {
+pub(crate) fn same_file(a: &std::path::Path, b: &std::path::Path) -> bool {
     match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
         (Ok(ca), Ok(cb)) => ca == cb,
         _ => a == b,
@@ -1659,23 +2739,32 @@ fn println(show_output: bool, msg: &str) {
     }
 }
 
-/// Verify a downloaded archive's embedded signature against a set of ed25519 verifying keys, the
-/// same check [`update()`](ReleaseUpdate::update) runs internally when `verifying_keys` are set.
+/// Verify a downloaded archive's signature against a set of ed25519 verifying keys, the same check
+/// [`update()`](ReleaseUpdate::update) runs internally when `verifying_keys` are set.
 ///
 /// This is exposed so a caller that stages a download itself (for example an installer that fetches
 /// a companion binary via [`Download`](crate::Download) before the main update loop exists) can run
 /// the identical verification without reimplementing it.
 ///
 /// `keys` are raw 32-byte ed25519 public keys ([`VerifyingKey`](crate::VerifyingKey)); verification
-/// uses any-of semantics, passing as soon as one key validates a signature in the archive (so a
-/// key-rotation window can list both the old and new keys). Signatures are produced and read with
-/// [`zipsign`](zipsign_api), which supports `.tar.gz` and `.zip` archives only.
+/// uses any-of semantics, passing as soon as one key validates a signature (so a key-rotation
+/// window can list both the old and new keys). Signatures are produced and read with
+/// [`zipsign`](zipsign_api), in one of two carriages:
+///
+/// - **Embedded**, for `.tar.gz` and `.zip` archives: the signature block lives inside the archive,
+///   as written by `zipsign sign tar` / `zipsign sign zip`.
+/// - **Detached**, for every other format (`.tar.xz`, `.tar.zst`, a raw binary, ...): the same
+///   signature block over the file's raw bytes, stored next to it as `<file>.sig` (see
+///   [`keys::sign_detached`](crate::keys::sign_detached)). The update pipeline downloads the
+///   release asset `<asset>.sig` for you; a standalone caller downloads it alongside the file.
+///
+/// Either way the file name is the signing context, so the file must keep its asset name.
 ///
 /// # Errors
 ///
 /// - Returns `Ok(())` immediately when `keys` is empty (nothing to verify against).
-/// - [`Error::NoSignatures`](crate::errors::Error::NoSignatures) if the archive format is not one
-///   zipsign can carry a signature in (anything other than `.tar.gz` / `.zip`).
+/// - [`Error::NoSignatures`](crate::errors::Error::NoSignatures) if the format cannot embed a
+///   signature and no detached `<file>.sig` sits next to it.
 /// - [`Error::Signature`](crate::errors::Error::Signature) if no key validates a signature.
 /// - [`Error::SignatureNonUTF8`](crate::errors::Error::SignatureNonUTF8) if the archive's file name
 ///   is not UTF-8 (zipsign binds the signature to the file name).
@@ -1699,8 +2788,12 @@ pub fn verify_signature(
         return Ok(());
     }
 
-    let archive_kind = crate::detect_archive(archive_path)?;
+    // A kind whose archive feature is off cannot be detected, but a detached signature covers the
+    // raw bytes and needs no decoding, so the detection error only surfaces when there is none.
+    let archive_kind = crate::detect_archive(archive_path);
     #[cfg(any(feature = "archive-tar", feature = "archive-zip"))]
+    if let Ok(kind) = &archive_kind
+        && carries_embedded_signature(kind)
     {
         let context = archive_path
             .file_name()
@@ -1714,23 +2807,48 @@ pub fn verify_signature(
 
         let mut exe = std::fs::File::open(archive_path)?;
 
-        match archive_kind {
+        match kind {
             #[cfg(feature = "archive-tar")]
             crate::ArchiveKind::Tar(Some(crate::Compression::Gz)) => {
                 zipsign_api::verify::verify_tar(&mut exe, &keys, Some(context))
                     .map_err(zipsign_api::ZipsignError::from)?;
-                return Ok(());
             }
             #[cfg(feature = "archive-zip")]
             crate::ArchiveKind::Zip => {
                 zipsign_api::verify::verify_zip(&mut exe, &keys, Some(context))
                     .map_err(zipsign_api::ZipsignError::from)?;
-                return Ok(());
             }
-            _ => {}
+            _ => unreachable!("carries_embedded_signature admitted {kind}"),
         }
+        return Ok(());
     }
-    Err(Error::NoSignatures(archive_kind))
+
+    let signature_path = detached_signature_path(archive_path);
+    if signature_path.is_file() {
+        return crate::keys::verify_detached(archive_path, &signature_path, keys);
+    }
+    Err(Error::NoSignatures(archive_kind?))
+}
+
+/// Whether zipsign can embed a signature in an archive of `kind` (`.tar.gz` and `.zip`). Every
+/// other kind is signed with a detached `<file>.sig`.
+#[cfg(feature = "signatures")]
+fn carries_embedded_signature(kind: &crate::ArchiveKind) -> bool {
+    match kind {
+        #[cfg(feature = "archive-tar")]
+        crate::ArchiveKind::Tar(Some(crate::Compression::Gz)) => true,
+        #[cfg(feature = "archive-zip")]
+        crate::ArchiveKind::Zip => true,
+        _ => false,
+    }
+}
+
+/// The detached signature of `path`: the same file name with `.sig` appended.
+#[cfg(feature = "signatures")]
+pub(crate) fn detached_signature_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sig");
+    std::path::PathBuf::from(name)
 }
 
 #[cfg(test)]
@@ -2216,6 +3334,25 @@ mod tests {
         );
     }
 
+    // A detached `<asset>.sig` contains the target string too; listed first, it must still not be
+    // selected as the update asset.
+    #[test]
+    fn asset_for_skips_detached_signatures() {
+        let release = super::Release::builder()
+            .version("1.0.0")
+            .assets([
+                super::ReleaseAsset::new(
+                    "app-x86_64-unknown-linux-gnu.tar.xz.sig",
+                    "https://host/sig",
+                ),
+                super::ReleaseAsset::new("app-x86_64-unknown-linux-gnu.tar.xz", "https://host/xz"),
+            ])
+            .build()
+            .unwrap();
+        let chosen = release.asset_for("x86_64-unknown-linux-gnu", None).unwrap();
+        assert_eq!(chosen.download_url(), "https://host/xz");
+    }
+
     // `ReleaseAsset::new(name, download_url)` argument order must match the field order so the two
     // same-typed args can't be silently swapped. Pins the constructor maps arg 1 -> name, arg 2 -> url.
     #[test]
@@ -2728,7 +3865,7 @@ mod tests {
                 reason: Some("binary did not pass the smoke test".to_string()),
             })
         });
-        let res = install_binary(&new_exe, &dest, Some(&*reject));
+        let res = install_binary(&new_exe, &dest, Some(&*reject), false);
         // P4: a rejecting verify callback surfaces the dedicated `VerificationRejected` variant,
         // carrying the hook's error message as the reason.
         let err = res.expect_err("a rejecting verify hook must abort the install");
@@ -2762,7 +3899,7 @@ mod tests {
         let reject: Box<DynVerifyFn> = Box::new(|_: &std::path::Path| {
             Err(crate::errors::Error::verification_rejected("bad signature"))
         });
-        let err = install_binary(&new_exe, &dest, Some(&*reject))
+        let err = install_binary(&new_exe, &dest, Some(&*reject), false)
             .expect_err("a rejecting verify hook must abort the install");
         match err {
             crate::errors::Error::VerificationRejected { reason } => {
@@ -2792,7 +3929,7 @@ mod tests {
                 "could not run new --version",
             )))
         });
-        let err = install_binary(&new_exe, &dest, Some(&*io_failing))
+        let err = install_binary(&new_exe, &dest, Some(&*io_failing), false)
             .expect_err("a hook IO error must abort the install");
         match err {
             crate::errors::Error::VerificationRejected { reason } => {
@@ -2815,7 +3952,7 @@ mod tests {
         let dest = dir.path().join("installed");
 
         let accept: Box<DynVerifyFn> = Box::new(|_: &std::path::Path| Ok(()));
-        install_binary(&new_exe, &dest, Some(&*accept)).unwrap();
+        install_binary(&new_exe, &dest, Some(&*accept), false).unwrap();
         assert!(
             dest.exists(),
             "binary is installed when verification passes"
@@ -2823,6 +3960,28 @@ mod tests {
         assert_eq!(std::fs::read(&dest).unwrap(), b"new binary");
     }
 
+    // With `preserve_metadata` the replacement keeps the installed binary's mode (setgid included)
+    // and leaves no staged file behind.
+    #[cfg(all(unix, feature = "preserve-metadata"))]
+    #[test]
+    fn install_binary_preserves_the_installed_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let new_exe = dir.path().join("new");
+        std::fs::write(&new_exe, b"new binary").unwrap();
+        let installed = tempfile::tempdir().unwrap();
+        let dest = installed.path().join("app");
+        std::fs::write(&dest, b"old binary").unwrap();
+        std::fs::set_permissions(&dest, std::fs::Permissions::from_mode(0o2750)).unwrap();
+
+        install_binary(&new_exe, &dest, None, true).unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), b"new binary");
+        let mode = std::fs::metadata(&dest).unwrap().permissions().mode();
+        assert_eq!(mode & 0o7777, 0o2750);
+        assert_eq!(std::fs::read_dir(installed.path()).unwrap().count(), 1);
+    }
+
     // Installing into a read-only (0555) directory fails with a permission error at the move step;
     // `install_binary` must annotate it as `InstallPathNotWritable` naming the install path, not
     // leak a bare `Io(Permission denied)`. Pins the always-on install-error path context.
@@ -2839,7 +3998,7 @@ mod tests {
         std::fs::set_permissions(&ro_dir, std::fs::Permissions::from_mode(0o555)).unwrap();
         let dest = ro_dir.join("installed");
 
-        let res = install_binary(&new_exe, &dest, None);
+        let res = install_binary(&new_exe, &dest, None, false);
         // Restore write perms so the tempdir can be cleaned up regardless of the assertion outcome.
         std::fs::set_permissions(&ro_dir, std::fs::Permissions::from_mode(0o755)).unwrap();
 
@@ -2866,7 +4025,7 @@ mod tests {
         // Parent directory intentionally absent -> rename fails with NotFound, not PermissionDenied.
         let dest = dir.path().join("no-such-dir").join("installed");
 
-        let err = install_binary(&new_exe, &dest, None)
+        let err = install_binary(&new_exe, &dest, None, false)
             .expect_err("moving into a missing parent dir must fail");
         match err {
             Error::Io(io) => {
@@ -2931,81 +4090,812 @@ mod tests {
             .expect("a missing parent dir is indeterminate and must probe Ok");
     }
 
-    // Build a custom-backend `Update` carrying `checksum`, to drive `finish_update` directly.
-    #[cfg(feature = "checksums")]
-    fn update_with_checksum(checksum: crate::Checksum) -> crate::backends::custom::Update {
-        crate::backends::custom::Update::configure()
-            .source(BoundSource)
-            .bin_name("app")
-            .target("x86_64-unknown-linux-gnu")
-            .current_version("1.0.0")
-            .verify_checksum(checksum)
-            .build()
-            .unwrap()
+    // --- Bundle mode ---------------------------------------------------------------------------
+
+    // Write `files` (relative path, contents) under `root`, creating parent dirs.
+    fn write_tree(root: &std::path::Path, files: &[(&str, &str)]) {
+        for (rel, contents) in files {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
     }
 
-    // Build a custom-backend `Update` with no explicit checksum and `verify_release_digest`
-    // as given, to drive the release-digest gate of `finish_update` directly.
-    #[cfg(feature = "checksums")]
-    fn update_with_release_digest(verify: bool) -> crate::backends::custom::Update {
-        crate::backends::custom::Update::configure()
-            .source(BoundSource)
-            .bin_name("app")
-            .target("x86_64-unknown-linux-gnu")
-            .current_version("1.0.0")
-            .verify_release_digest(verify)
-            .build()
-            .unwrap()
+    fn read(path: impl AsRef<std::path::Path>) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    // Write a tar.gz at `path` holding `files` (archive path, contents) as executable entries.
+    #[cfg(all(feature = "archive-tar", feature = "compression-tar-gz"))]
+    fn write_tar_gz(path: &std::path::Path, files: &[(&str, &str)]) {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        let gz = GzEncoder::new(std::fs::File::create(path).unwrap(), Compression::default());
+        let mut tar = tar::Builder::new(gz);
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            tar.append_data(&mut header, name, contents.as_bytes())
+                .unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
     }
 
-    // A configured checksum is actually consulted by `finish_update`: a mismatch aborts the
-    // update at the checksum gate, before any extraction or install. If the checksum block were
-    // dropped, the bogus archive would instead fail later with a non-checksum error and this
-    // test would catch it.
-    #[cfg(feature = "checksums")]
     #[test]
-    fn finish_update_rejects_a_mismatched_checksum_before_extracting() {
+    fn install_bundle_installs_a_fresh_tree() {
         let dir = tempfile::tempdir().unwrap();
-        let archive_path = dir.path().join("release.tar.gz");
-        std::fs::write(&archive_path, b"hello").unwrap();
+        let staged = dir.path().join("staged");
+        write_tree(&staged, &[("bin/app", "new"), ("lib/libapp.so", "new lib")]);
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+        let dest = dir.path().join("installed");
 
-        // A valid-length but wrong SHA-256 digest (the file's real digest is 2cf24dba…).
-        let upd = update_with_checksum(crate::Checksum::Sha256("00".repeat(32)));
-        let release = Release::builder().version("1.2.3").build().unwrap();
-        let asset = ReleaseAsset::new("release.tar.gz", "https://host/release.tar.gz");
+        super::install_bundle(&staged, &dest, stash.path(), None, None).unwrap();
 
-        let err = super::finish_update(&upd, release, &asset, dir, &archive_path)
-            .expect_err("a mismatched checksum must abort the update");
-        let msg = err.to_string();
-        assert!(
-            msg.contains("checksum mismatch"),
-            "expected a checksum-mismatch abort, got: {}",
-            msg
-        );
+        assert_eq!(read(dest.join("bin/app")), "new");
+        assert_eq!(read(dest.join("lib/libapp.so")), "new lib");
+        assert!(!staged.exists(), "the staged root is renamed into place");
     }
 
-    // The complement: a *matching* checksum passes the gate, so the flow proceeds past it. Here
-    // the bogus `.tar.gz` then fails at extraction — a different error — proving the checksum was
-    // accepted rather than the update being aborted at the gate. Gated additionally on the
-    // archive features so the post-gate extraction failure is deterministic.
-    #[cfg(all(
-        feature = "checksums",
-        feature = "archive-tar",
-        feature = "compression-tar-gz"
-    ))]
+    // The whole tree is replaced: files only the old tree had are gone, and the running exe's path
+    // holds the new tree's copy.
     #[test]
-    fn finish_update_passes_a_matching_checksum_then_proceeds() {
+    fn install_bundle_replaces_the_tree_and_moves_the_exe_aside() {
         let dir = tempfile::tempdir().unwrap();
-        let archive_path = dir.path().join("release.tar.gz");
-        std::fs::write(&archive_path, b"hello").unwrap();
+        let dest = dir.path().join("installed");
+        write_tree(&dest, &[("bin/app", "old"), ("share/stale", "old only")]);
+        let staged = dir.path().join("staged");
+        write_tree(&staged, &[("bin/app", "new"), ("lib/libapp.so", "new lib")]);
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+        let exe_rel = std::path::Path::new("bin/app");
 
-        // The real SHA-256 of b"hello".
-        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        super::install_bundle(&staged, &dest, stash.path(), Some(exe_rel), None).unwrap();
+
+        assert_eq!(read(dest.join("bin/app")), "new");
+        assert_eq!(read(dest.join("lib/libapp.so")), "new lib");
+        assert!(!dest.join("share/stale").exists());
+        assert_eq!(read(stash.path().join("old/share/stale")), "old only");
+    }
+
+    // A failure moving the new tree into place restores the stashed old tree and surfaces the
+    // original error, naming the bundle path.
+    #[test]
+    fn install_bundle_rolls_back_when_the_new_tree_cannot_be_moved() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("installed");
+        write_tree(&dest, &[("bin/app", "old"), ("share/data", "old data")]);
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+        // The staged root vanished between extraction and the swap.
+        let staged = dir.path().join("missing");
+
+        let err = super::install_bundle(&staged, &dest, stash.path(), None, None).unwrap_err();
+
+        match err {
+            Error::Io(e) => {
+                assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+                assert!(e.to_string().contains(&dest.display().to_string()));
+            }
+            other => panic!("expected Error::Io, got {:?}", other),
+        }
+        assert_eq!(read(dest.join("bin/app")), "old");
+        assert_eq!(read(dest.join("share/data")), "old data");
+    }
+
+    // A failure stashing the old tree puts the already-moved exe back.
+    #[cfg(unix)]
+    #[test]
+    fn install_bundle_restores_the_exe_when_the_old_tree_cannot_be_stashed() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("installed");
+        write_tree(&dest, &[("bin/app", "old")]);
+        let staged = dir.path().join("staged");
+        write_tree(&staged, &[("bin/app", "new")]);
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+        // A non-empty `old` in the stash makes renaming the old tree onto it fail.
+        write_tree(stash.path(), &[("old/occupied", "x")]);
+
+        super::install_bundle(
+            &staged,
+            &dest,
+            stash.path(),
+            Some(std::path::Path::new("bin/app")),
+            None,
+        )
+        .expect_err("the stash rename must fail");
+
+        assert_eq!(read(dest.join("bin/app")), "old");
+        assert!(!stash.path().join("exe-aside").exists());
+        assert_eq!(read(staged.join("bin/app")), "new");
+    }
+
+    #[test]
+    fn install_bundle_refuses_a_tree_missing_the_running_exe() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("installed");
+        write_tree(&dest, &[("bin/app", "old")]);
+        let staged = dir.path().join("staged");
+        write_tree(&staged, &[("bin/other", "new")]);
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+
+        let err = super::install_bundle(
+            &staged,
+            &dest,
+            stash.path(),
+            Some(std::path::Path::new("bin/app")),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::Internal { .. }), "got {:?}", err);
+        assert_eq!(read(dest.join("bin/app")), "old", "nothing is touched");
+    }
+
+    // The verification hook sees the staged bundle root, and a rejection leaves the installed
+    // tree alone.
+    #[test]
+    fn install_bundle_aborts_when_verify_rejects() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("installed");
+        write_tree(&dest, &[("bin/app", "old")]);
+        let staged = dir.path().join("staged");
+        write_tree(&staged, &[("bin/app", "new")]);
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+
+        let expected = staged.clone();
+        let reject: Box<DynVerifyFn> = Box::new(move |path: &std::path::Path| {
+            assert_eq!(path, expected);
+            Err(Error::verification_rejected("unsigned bundle"))
+        });
+        let err =
+            super::install_bundle(&staged, &dest, stash.path(), None, Some(&*reject)).unwrap_err();
+
+        assert!(
+            matches!(err, Error::VerificationRejected { .. }),
+            "got {:?}",
+            err
+        );
+        assert_eq!(read(dest.join("bin/app")), "old");
+        assert!(staged.exists());
+    }
+
+    #[test]
+    fn exe_in_bundle_returns_the_relative_path_only_inside() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("App");
+        write_tree(&bundle, &[("bin/app", "")]);
+        write_tree(dir.path(), &[("elsewhere/app", "")]);
+
+        assert_eq!(
+            super::exe_in_bundle(&bundle.join("bin/app"), &bundle),
+            Some(std::path::PathBuf::from("bin/app"))
+        );
+        assert_eq!(
+            super::exe_in_bundle(&dir.path().join("elsewhere/app"), &bundle),
+            None
+        );
+        assert_eq!(super::exe_in_bundle(&bundle, &bundle), None);
+    }
+
+    // Like `same_file`, the check sees through a symlinked install path.
+    #[cfg(unix)]
+    #[test]
+    fn exe_in_bundle_resolves_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("App-1.0");
+        write_tree(&bundle, &[("bin/app", "")]);
+        let link = dir.path().join("App");
+        std::os::unix::fs::symlink(&bundle, &link).unwrap();
+
+        assert_eq!(
+            super::exe_in_bundle(&bundle.join("bin/app"), &link),
+            Some(std::path::PathBuf::from("bin/app"))
+        );
+    }
+
+    #[test]
+    fn app_bundle_ancestor_finds_the_nearest_app() {
+        let exe = std::path::Path::new("/Applications/My App.app/Contents/MacOS/my-app");
+        assert_eq!(
+            super::app_bundle_ancestor(exe).as_deref(),
+            Some(std::path::Path::new("/Applications/My App.app"))
+        );
+
+        let nested =
+            std::path::Path::new("/Applications/Outer.app/Contents/Helpers/Inner.app/Contents/x");
+        assert_eq!(
+            super::app_bundle_ancestor(nested).as_deref(),
+            Some(std::path::Path::new(
+                "/Applications/Outer.app/Contents/Helpers/Inner.app"
+            ))
+        );
+
+        assert_eq!(
+            super::app_bundle_ancestor(std::path::Path::new("/usr/local/bin/app")),
+            None
+        );
+        // The exe itself is never its own bundle.
+        assert_eq!(
+            super::app_bundle_ancestor(std::path::Path::new("/tmp/tool.app")),
+            None
+        );
+    }
+
+    #[test]
+    fn probe_bundle_parent_writable_ok_for_writable_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        super::probe_bundle_parent_writable(&dir.path().join("App.app"))
+            .expect("a writable parent dir must probe Ok");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    // End to end through the finish tail: the templated bundle root is extracted from a tar.gz
+    // and swapped in for the installed tree.
+    #[cfg(all(feature = "archive-tar", feature = "compression-tar-gz"))]
+    #[test]
+    fn finish_update_installs_a_bundle_from_the_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("app.tar.gz");
+        write_tar_gz(
+            &archive_path,
+            &[
+                ("app-1.2.3/bin/app", "new"),
+                ("app-1.2.3/lib/libapp.so", "lib"),
+            ],
+        );
+        let installed = tempfile::tempdir().unwrap();
+        let dest = installed.path().join("app");
+        write_tree(&dest, &[("bin/app", "old"), ("share/stale", "old")]);
+
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .bundle_root_in_archive("{{ bin }}-{{ version }}")
+            .bundle_install_path(&dest)
+            .show_output(false)
+            .build()
+            .unwrap();
+        let release = Release::builder().version("1.2.3").build().unwrap();
+        let asset = super::ReleaseAsset::new("app.tar.gz", "https://host/app.tar.gz");
+        let ctx = super::FinishCtx::capture(&upd, release, &asset).unwrap();
+
+        let status = super::finish_update_owned(ctx, dir, &archive_path).unwrap();
+
+        assert!(status.is_updated());
+        assert_eq!(read(dest.join("bin/app")), "new");
+        assert_eq!(read(dest.join("lib/libapp.so")), "lib");
+        assert!(!dest.join("share/stale").exists());
+        // Staging and stash dirs are cleaned up.
+        assert_eq!(std::fs::read_dir(installed.path()).unwrap().count(), 1);
+    }
+
+    // --- Multi-file installs ---------------------------------------------------------------------
+
+    #[test]
+    fn install_all_installs_every_file_creating_missing_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        write_tree(
+            &dir.path().join("staged"),
+            &[("app", "new"), ("app.1", "man")],
+        );
+        write_tree(&dir.path().join("installed"), &[("bin/app", "old")]);
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+        let bin = dir.path().join("installed/bin/app");
+        let man = dir.path().join("installed/share/man/man1/app.1");
+        let moves = [
+            (dir.path().join("staged/app"), bin.as_path()),
+            (dir.path().join("staged/app.1"), man.as_path()),
+        ];
+
+        super::install_all(&moves, stash.path(), None).unwrap();
+
+        assert_eq!(read(&bin), "new");
+        assert_eq!(read(&man), "man");
+    }
+
+    // The transaction is all-or-nothing: a file that cannot be installed restores the ones
+    // already moved into place.
+    #[test]
+    fn install_all_rolls_back_every_file_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        write_tree(&dir.path().join("staged"), &[("app", "new")]);
+        write_tree(
+            &dir.path().join("installed"),
+            &[("app", "old"), ("app.bash", "old completion")],
+        );
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+        let bin = dir.path().join("installed/app");
+        let completion = dir.path().join("installed/app.bash");
+        let moves = [
+            (dir.path().join("staged/app"), bin.as_path()),
+            (dir.path().join("staged/missing"), completion.as_path()),
+        ];
+
+        super::install_all(&moves, stash.path(), None).expect_err("the missing source must fail");
+
+        assert_eq!(read(&bin), "old");
+        assert_eq!(read(&completion), "old completion");
+    }
+
+    #[test]
+    fn install_all_aborts_when_verify_rejects() {
+        let dir = tempfile::tempdir().unwrap();
+        write_tree(
+            &dir.path().join("staged"),
+            &[("app", "new"), ("app.1", "man")],
+        );
+        let stash = tempfile::tempdir_in(dir.path()).unwrap();
+        let bin = dir.path().join("installed/app");
+        let man = dir.path().join("installed/app.1");
+        let moves = [
+            (dir.path().join("staged/app"), bin.as_path()),
+            (dir.path().join("staged/app.1"), man.as_path()),
+        ];
+        let reject: Box<DynVerifyFn> =
+            Box::new(|_: &std::path::Path| Err(Error::verification_rejected("nope")));
+
+        let err = super::install_all(&moves, stash.path(), Some(&*reject)).unwrap_err();
+
+        assert!(
+            matches!(err, Error::VerificationRejected { .. }),
+            "got {:?}",
+            err
+        );
+        assert!(!bin.exists() && !man.exists(), "nothing is installed");
+    }
+
+    // End to end through the finish tail: the binary and templated companion files come out of a
+    // tar.gz and land at their install paths together.
+    #[cfg(all(feature = "archive-tar", feature = "compression-tar-gz"))]
+    #[test]
+    fn finish_update_installs_extra_files_from_the_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("app.tar.gz");
+        write_tar_gz(
+            &archive_path,
+            &[
+                ("app-1.2.3/app", "new"),
+                ("app-1.2.3/app-helper", "helper"),
+                ("app-1.2.3/doc/app.1", "man"),
+            ],
+        );
+        let installed = tempfile::tempdir().unwrap();
+        let bin = installed.path().join("bin/app");
+        write_tree(installed.path(), &[("bin/app", "old")]);
+
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .bin_install_path(&bin)
+            .bin_path_in_archive("{{ bin }}-{{ version }}/{{ bin }}")
+            .install_file(
+                "{{ bin }}-{{ version }}/{{ bin }}-helper",
+                installed.path().join("bin/app-helper"),
+            )
+            .install_file(
+                "{{ bin }}-{{ version }}/doc/{{ bin }}.1",
+                installed.path().join("share/man/man1/app.1"),
+            )
+            .show_output(false)
+            .build()
+            .unwrap();
+        let release = Release::builder().version("1.2.3").build().unwrap();
+        let asset = super::ReleaseAsset::new("app.tar.gz", "https://host/app.tar.gz");
+        let ctx = super::FinishCtx::capture(&upd, release, &asset).unwrap();
+
+        super::finish_update_owned(ctx, dir, &archive_path).unwrap();
+
+        assert_eq!(read(&bin), "new");
+        assert_eq!(read(installed.path().join("bin/app-helper")), "helper");
+        assert_eq!(read(installed.path().join("share/man/man1/app.1")), "man");
+        assert_eq!(
+            std::fs::read_dir(installed.path().join("bin"))
+                .unwrap()
+                .count(),
+            2,
+            "staging and stash dirs are cleaned up"
+        );
+    }
+
+    // A successful install writes the receipt, naming the backend and the installed binary.
+    #[cfg(all(feature = "archive-tar", feature = "compression-tar-gz"))]
+    #[test]
+    fn finish_update_writes_the_install_receipt() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("app.tar.gz");
+        write_tar_gz(&archive_path, &[("app", "new")]);
+        let installed = tempfile::tempdir().unwrap();
+        let bin = installed.path().join("app");
+        let receipt_path = installed.path().join("state/receipt.json");
+
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .bin_install_path(&bin)
+            .install_receipt(&receipt_path)
+            .show_output(false)
+            .build()
+            .unwrap();
+        let release = Release::builder().version("1.2.3").build().unwrap();
+        let asset = super::ReleaseAsset::new("app.tar.gz", "https://host/app.tar.gz");
+        let ctx = super::FinishCtx::capture(&upd, release, &asset).unwrap();
+        #[cfg(feature = "checksums")]
+        let archive_sha256 = crate::checksum::sha256_file_hex(&archive_path).unwrap();
+
+        super::finish_update_owned(ctx, dir, &archive_path).unwrap();
+
+        let receipt = crate::receipt::InstallReceipt::read(&receipt_path)
+            .unwrap()
+            .expect("the receipt is written");
+        assert_eq!(receipt.backend(), "custom");
+        assert_eq!(receipt.version(), "1.2.3");
+        assert_eq!(receipt.install_path(), bin);
+        #[cfg(feature = "checksums")]
+        assert_eq!(receipt.sha256(), Some(archive_sha256.as_str()));
+    }
+
+    // Staging runs the verification hook, then stores the binary instead of installing it;
+    // `ApplyStaged` installs it later.
+    #[cfg(all(
+        feature = "stage",
+        feature = "archive-tar",
+        feature = "compression-tar-gz"
+    ))]
+    #[test]
+    fn finish_update_stages_the_verified_binary_instead_of_installing_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("app.tar.gz");
+        write_tar_gz(&archive_path, &[("app", "new")]);
+        let installed = tempfile::tempdir().unwrap();
+        let bin = installed.path().join("app");
+        std::fs::write(&bin, "old").unwrap();
+        let staging = installed.path().join("staging");
+        let verified = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let seen = verified.clone();
+
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .bin_install_path(&bin)
+            .staging_dir(&staging)
+            .verify_binary(move |_| {
+                seen.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            })
+            .show_output(false)
+            .build()
+            .unwrap();
+        let release = Release::builder().version("1.2.3").build().unwrap();
+        let asset = super::ReleaseAsset::new("app.tar.gz", "https://host/app.tar.gz");
+        let mut ctx = super::FinishCtx::capture(&upd, release, &asset).unwrap();
+        ctx.stage_into = Some(staging.clone());
+
+        let status = super::finish_update_owned(ctx, dir, &archive_path).unwrap();
+
+        assert_eq!(status.version(), Some("1.2.3"));
+        assert!(verified.load(std::sync::atomic::Ordering::SeqCst));
+        assert_eq!(std::fs::read_to_string(&bin).unwrap(), "old");
+        let applied = crate::staged::ApplyStaged::new(&staging, "1.0.0")
+            .install_path(&bin)
+            .apply()
+            .unwrap();
+        assert_eq!(applied.as_deref(), Some("1.2.3"));
+        assert_eq!(std::fs::read_to_string(&bin).unwrap(), "new");
+    }
+
+    // Stage a signed `hello.txt` archive for `<dir>/app` (currently `old`), returning the dir,
+    // the staging dir, the install path and the key that verifies it.
+    #[cfg(all(
+        feature = "stage",
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz"
+    ))]
+    fn signed_stage() -> (
+        tempfile::TempDir,
+        std::path::PathBuf,
+        std::path::PathBuf,
+        crate::VerifyingKey,
+    ) {
+        let key = zipsign_api::SigningKey::from_bytes(&[41u8; 32]);
+        let signed = sign_tar_gz(&make_tar_gz().unwrap(), std::slice::from_ref(&key)).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join(signed.path().file_name().unwrap());
+        std::fs::copy(signed.path(), &archive_path).unwrap();
+        let installed = tempfile::tempdir().unwrap();
+        let bin = installed.path().join("app");
+        std::fs::write(&bin, "old").unwrap();
+        let staging = installed.path().join("staging");
+
+        let mut ctx = traversal_ctx("hello.txt", "1.2.3");
+        ctx.bin_install_path = bin.clone();
+        ctx.stage_into = Some(staging.clone());
+        ctx.trust.verifying_keys = vec![key.verifying_key().to_bytes()];
+        super::finish_update_owned(ctx, dir, &archive_path).unwrap();
+        (installed, staging, bin, key.verifying_key().to_bytes())
+    }
+
+    // A staged binary swapped out together with its manifest's checksum passes the corruption
+    // check, but with the keys configured `apply` installs the binary from the signed archive.
+    #[cfg(all(
+        feature = "stage",
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz"
+    ))]
+    #[test]
+    fn apply_staged_with_keys_installs_from_the_signed_archive() {
+        let (_installed, staging, bin, vkey) = signed_stage();
+        let binary = staging.join("binary");
+        std::fs::write(&binary, "evil").unwrap();
+        let manifest_path = staging.join("staged.json");
+        let mut manifest: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&manifest_path).unwrap()).unwrap();
+        manifest["sha256"] = crate::checksum::sha256_file_hex(&binary).unwrap().into();
+        std::fs::write(&manifest_path, manifest.to_string()).unwrap();
+
+        let applied = crate::staged::ApplyStaged::new(&staging, "1.0.0")
+            .install_path(&bin)
+            .verifying_keys([vkey])
+            .apply()
+            .unwrap();
+
+        assert_eq!(applied.as_deref(), Some("1.2.3"));
+        assert_eq!(std::fs::read_to_string(&bin).unwrap(), "hello");
+        assert_eq!(std::fs::read_dir(&staging).unwrap().count(), 0);
+    }
+
+    // A staged archive that no longer verifies, or one checked against another key, is rejected
+    // and discarded; the installed binary is untouched.
+    #[cfg(all(
+        feature = "stage",
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz"
+    ))]
+    #[test]
+    fn apply_staged_with_keys_rejects_a_tampered_or_foreign_archive() {
+        let (_installed, staging, bin, vkey) = signed_stage();
+        let archive = staging.join("archive");
+        let mut bytes = std::fs::read(&archive).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        std::fs::write(&archive, bytes).unwrap();
+        let res = crate::staged::ApplyStaged::new(&staging, "1.0.0")
+            .install_path(&bin)
+            .verifying_keys([vkey])
+            .apply();
+        assert!(
+            matches!(&res, Err(Error::StagedUpdateRejected { reason, .. }) if reason.contains("does not verify")),
+            "{:?}",
+            res
+        );
+        assert_eq!(std::fs::read_to_string(&bin).unwrap(), "old");
+        assert_eq!(std::fs::read_dir(&staging).unwrap().count(), 0);
+
+        let (_installed, staging, bin, _) = signed_stage();
+        let other = zipsign_api::SigningKey::from_bytes(&[42u8; 32])
+            .verifying_key()
+            .to_bytes();
+        let res = crate::staged::ApplyStaged::new(&staging, "1.0.0")
+            .install_path(&bin)
+            .verifying_keys([other])
+            .apply();
+        assert!(
+            matches!(res, Err(Error::StagedUpdateRejected { .. })),
+            "{:?}",
+            res
+        );
+        assert_eq!(std::fs::read_to_string(&bin).unwrap(), "old");
+    }
+
+    #[cfg(feature = "stage")]
+    #[test]
+    fn stage_requires_a_staging_dir() {
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .build()
+            .unwrap();
+        assert!(matches!(
+            upd.stage(),
+            Err(Error::MissingField {
+                field: "staging_dir"
+            })
+        ));
+    }
+
+    // The package-manager guard refuses before anything is downloaded.
+    #[test]
+    fn preflight_refuses_a_package_managed_install_path() {
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .bin_install_path("/nix/store/abc123-app-1.0.0/bin/app")
+            .check_package_manager(true)
+            .build()
+            .unwrap();
+        match super::preflight(&upd).unwrap_err() {
+            Error::PackageManaged { manager, .. } => assert_eq!(manager, "Nix"),
+            other => panic!("expected Error::PackageManaged, got {:?}", other),
+        }
+    }
+
+    // The free-space preflight checks a listed size at once and otherwise defers to the download.
+    #[test]
+    fn check_free_space_uses_the_listed_size_or_defers_to_the_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .bin_install_path(dir.path().join("app"))
+            .check_free_space(true)
+            .free_space_expansion_factor(0.5)
+            .build()
+            .unwrap();
+        assert_eq!(upd.free_space_expansion_factor(), 1.0);
+        let install_dir = super::install_dir(&upd, None);
+        assert_eq!(install_dir, dir.path());
+
+        let huge = super::ReleaseAsset::new("app", "https://host/app").with_size(u64::MAX / 4);
+        let mut download = Download::from_url(huge.download_url());
+        match super::check_free_space(&upd, &huge, dir.path(), install_dir.clone(), &mut download)
+            .unwrap_err()
+        {
+            Error::InsufficientSpace { path, .. } => assert_eq!(path, dir.path()),
+            other => panic!("expected Error::InsufficientSpace, got {:?}", other),
+        }
+
+        let unlisted = super::ReleaseAsset::new("app", "https://host/app");
+        super::check_free_space(&upd, &unlisted, dir.path(), install_dir, &mut download).unwrap();
+        assert!(download.space_check.is_some());
+    }
+
+    // `temp_dir_on_install_fs` downloads into a hidden directory beside the install path.
+    #[test]
+    fn download_temp_dir_moves_beside_the_install_path_on_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let install_dir = dir.path().join("bin");
+        let mut builder = crate::backends::custom::Update::configure();
+        builder
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .bin_install_path(install_dir.join("app"));
+
+        let system = super::download_temp_dir(&builder.build().unwrap(), &install_dir).unwrap();
+        assert!(!system.path().starts_with(dir.path()));
+
+        let upd = builder.temp_dir_on_install_fs(true).build().unwrap();
+        let beside = super::download_temp_dir(&upd, &install_dir).unwrap();
+        assert_eq!(beside.path().parent(), Some(install_dir.as_path()));
+    }
+
+    // A versioned layout takes the new binary into its own version directory and flips `current`.
+    #[cfg(all(unix, feature = "archive-tar", feature = "compression-tar-gz"))]
+    #[test]
+    fn finish_update_installs_a_new_version_into_a_versioned_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("app.tar.gz");
+        write_tar_gz(&archive_path, &[("app", "new")]);
+        let installed = tempfile::tempdir().unwrap();
+        let layout = crate::VersionedInstall::new(installed.path());
+
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .versioned_install(layout.clone())
+            .show_output(false)
+            .build()
+            .unwrap();
+        let release = Release::builder().version("1.2.3").build().unwrap();
+        let asset = super::ReleaseAsset::new("app.tar.gz", "https://host/app.tar.gz");
+        let ctx = super::FinishCtx::capture(&upd, release, &asset).unwrap();
+
+        super::finish_update_owned(ctx, dir, &archive_path).unwrap();
+
+        assert_eq!(read(layout.binary_path("1.2.3", "app")), "new");
+        assert_eq!(layout.current_version().unwrap().as_deref(), Some("1.2.3"));
+    }
+
+    // Capture a `FinishCtx` from `upd` and run `finish_update` over the archive already at
+    // `archive_path`, as the update flows do after downloading it.
+    #[cfg(feature = "checksums")]
+    fn finish_update(
+        upd: &crate::backends::custom::Update,
+        release: Release,
+        asset: &ReleaseAsset,
+        dir: tempfile::TempDir,
+        archive_path: &std::path::Path,
+    ) -> Result<super::ReleaseStatus> {
+        let ctx = super::FinishCtx::capture(upd, release, asset)?;
+        super::finish_update(upd, ctx, asset, dir, archive_path)
+    }
+
+    // Build a custom-backend `Update` carrying `checksum`, to drive `finish_update` directly.
+    #[cfg(feature = "checksums")]
+    fn update_with_checksum(checksum: crate::Checksum) -> crate::backends::custom::Update {
+        crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .target("x86_64-unknown-linux-gnu")
+            .current_version("1.0.0")
+            .verify_checksum(checksum)
+            .build()
+            .unwrap()
+    }
+
+    // Build a custom-backend `Update` with no explicit checksum and `verify_release_digest`
+    // as given, to drive the release-digest gate of `finish_update` directly.
+    #[cfg(feature = "checksums")]
+    fn update_with_release_digest(verify: bool) -> crate::backends::custom::Update {
+        crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .target("x86_64-unknown-linux-gnu")
+            .current_version("1.0.0")
+            .verify_release_digest(verify)
+            .build()
+            .unwrap()
+    }
+
+    // A configured checksum is actually consulted by `finish_update`: a mismatch aborts the
+    // update at the checksum gate, before any extraction or install. If the checksum block were
+    // dropped, the bogus archive would instead fail later with a non-checksum error and this
+    // test would catch it.
+    #[cfg(feature = "checksums")]
+    #[test]
+    fn finish_update_rejects_a_mismatched_checksum_before_extracting() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("release.tar.gz");
+        std::fs::write(&archive_path, b"hello").unwrap();
+
+        // A valid-length but wrong SHA-256 digest (the file's real digest is 2cf24dba…).
+        let upd = update_with_checksum(crate::Checksum::Sha256("00".repeat(32)));
+        let release = Release::builder().version("1.2.3").build().unwrap();
+        let asset = ReleaseAsset::new("release.tar.gz", "https://host/release.tar.gz");
+
+        let err = finish_update(&upd, release, &asset, dir, &archive_path)
+            .expect_err("a mismatched checksum must abort the update");
+        let msg = err.to_string();
+        assert!(
+            msg.contains("checksum mismatch"),
+            "expected a checksum-mismatch abort, got: {}",
+            msg
+        );
+    }
+
+    // The complement: a *matching* checksum passes the gate, so the flow proceeds past it. Here
+    // the bogus `.tar.gz` then fails at extraction — a different error — proving the checksum was
+    // accepted rather than the update being aborted at the gate. Gated additionally on the
+    // archive features so the post-gate extraction failure is deterministic.
+    #[cfg(all(
+        feature = "checksums",
+        feature = "archive-tar",
+        feature = "compression-tar-gz"
+    ))]
+    #[test]
+    fn finish_update_passes_a_matching_checksum_then_proceeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("release.tar.gz");
+        std::fs::write(&archive_path, b"hello").unwrap();
+
+        // The real SHA-256 of b"hello".
+        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
         let upd = update_with_checksum(crate::Checksum::Sha256(digest.to_string()));
         let release = Release::builder().version("1.2.3").build().unwrap();
         let asset = ReleaseAsset::new("release.tar.gz", "https://host/release.tar.gz");
 
-        let err = super::finish_update(&upd, release, &asset, dir, &archive_path)
+        let err = finish_update(&upd, release, &asset, dir, &archive_path)
             .expect_err("the bytes are not a real archive, so extraction must fail");
         let msg = err.to_string();
         assert!(
@@ -3032,7 +4922,7 @@ mod tests {
         let asset = ReleaseAsset::new("release.tar.gz", "https://host/release.tar.gz")
             .with_digest(format!("sha256:{}", "00".repeat(32)));
 
-        let err = super::finish_update(&upd, release, &asset, dir, &archive_path)
+        let err = finish_update(&upd, release, &asset, dir, &archive_path)
             .expect_err("a mismatched release digest must abort the update");
         let msg = err.to_string();
         assert!(
@@ -3061,7 +4951,7 @@ mod tests {
         let asset = ReleaseAsset::new("release.tar.gz", "https://host/release.tar.gz")
             .with_digest("sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824");
 
-        let err = super::finish_update(&upd, release, &asset, dir, &archive_path)
+        let err = finish_update(&upd, release, &asset, dir, &archive_path)
             .expect_err("the bytes are not a real archive, so extraction must fail");
         let msg = err.to_string();
         assert!(
@@ -3090,7 +4980,7 @@ mod tests {
         let asset = ReleaseAsset::new("release.tar.gz", "https://host/release.tar.gz")
             .with_digest(format!("sha256:{}", "00".repeat(32)));
 
-        let err = super::finish_update(&upd, release, &asset, dir, &archive_path)
+        let err = finish_update(&upd, release, &asset, dir, &archive_path)
             .expect_err("the bytes are not a real archive, so extraction must fail");
         let msg = err.to_string();
         assert!(
@@ -3115,7 +5005,7 @@ mod tests {
         let asset = ReleaseAsset::new("release.tar.gz", "https://host/release.tar.gz")
             .with_digest("md5:abc123");
 
-        let err = super::finish_update(&upd, release, &asset, dir, &archive_path)
+        let err = finish_update(&upd, release, &asset, dir, &archive_path)
             .expect_err("an unsupported digest must abort the update");
         assert!(
             matches!(err, crate::errors::Error::InvalidResponse { .. }),
@@ -3130,6 +5020,96 @@ mod tests {
         );
     }
 
+    // End to end through `update_extended`: the pinned checksum and the release digest are both
+    // checked against the bytes as they download. A mismatch in either aborts the update before
+    // anything is installed, reporting the first failing checksum (pinned, then release digest).
+    #[cfg(feature = "checksums")]
+    #[test]
+    fn update_extended_verifies_checksums_while_downloading() {
+        use crate::http_client::{self, header::HeaderMap};
+
+        struct Served;
+        struct ServedResponse(HeaderMap);
+        impl http_client::HttpResponse for ServedResponse {
+            fn headers(&self) -> &HeaderMap {
+                &self.0
+            }
+            fn body(self: Box<Self>) -> Box<dyn std::io::Read> {
+                Box::new(&b"hello"[..])
+            }
+        }
+        impl http_client::HttpClient for Served {
+            fn get(
+                &self,
+                _url: &str,
+                _headers: &HeaderMap,
+                _timeout: Option<std::time::Duration>,
+            ) -> Result<Box<dyn http_client::HttpResponse>> {
+                Ok(Box::new(ServedResponse(HeaderMap::new())))
+            }
+        }
+
+        struct DigestSource(String);
+        impl crate::update::ReleaseSource for DigestSource {
+            fn get_latest_release(&self) -> Result<Release> {
+                Release::builder()
+                    .version("2.0.0")
+                    .asset(
+                        ReleaseAsset::new("app-linux", "https://host/app-linux")
+                            .with_digest(self.0.clone()),
+                    )
+                    .build()
+            }
+            fn get_releases(&self) -> Result<Vec<Release>> {
+                Ok(vec![self.get_latest_release()?])
+            }
+            fn get_release_version(&self, _: &str) -> Result<Release> {
+                self.get_latest_release()
+            }
+        }
+
+        let sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let sha512 = "9b71d224bd62f3785d96d46ad3ea3d73319bfbc2890caadae2dff72519673ca72323c3d99ba5c11d7c7acc6e14b8c5da0c4663475c2e5c3adef46f73bcdec043";
+        let dir = tempfile::tempdir().unwrap();
+        let install_path = dir.path().join("app");
+        let update = |digest: String, pinned: &str| {
+            crate::backends::custom::Update::configure()
+                .source(DigestSource(digest))
+                .bin_name("app")
+                .target("x86_64-unknown-linux-gnu")
+                .current_version("1.0.0")
+                .bin_install_path(&install_path)
+                .asset_matcher(|assets| assets.first().cloned())
+                .no_confirm(true)
+                .show_output(false)
+                .http_client(std::sync::Arc::new(Served))
+                .verify_checksum(crate::Checksum::Sha256(pinned.to_string()))
+                .build()
+                .unwrap()
+                .update_extended()
+        };
+
+        match update(format!("sha512:{}", "00".repeat(64)), sha256) {
+            Err(Error::ChecksumMismatch { expected, computed }) => {
+                assert_eq!(expected, "00".repeat(64));
+                assert_eq!(computed, sha512);
+            }
+            other => panic!("a mismatched release digest must abort, got {other:?}"),
+        }
+        match update(format!("sha512:{sha512}"), &"00".repeat(32)) {
+            Err(Error::ChecksumMismatch { computed, .. }) => assert_eq!(computed, sha256),
+            other => panic!("a mismatched pinned checksum must abort, got {other:?}"),
+        }
+        assert!(
+            !install_path.exists(),
+            "nothing may be installed on a mismatch"
+        );
+
+        let status = update(format!("sha512:{sha512}"), sha256).unwrap();
+        assert!(status.updated_release().is_some(), "got {status:?}");
+        assert_eq!(std::fs::read(&install_path).unwrap(), b"hello");
+    }
+
     // the async finish tail (`finish_update_async`, ~update.rs:1022) runs the
     // verify/extract/install tail under `tokio::task::spawn_blocking` and maps a `JoinError` (e.g.
     // a panic in that tail) to `Error::Internal { source: Some(Box::new(join_err)) }`. That site is
@@ -3387,6 +5367,29 @@ mod tests {
     // updater with `.retries(2)` must produce a download that retries. We prove the wiring by
     // checking `build_download` carries the budget through to a re-established request via an
     // injected flaky client.
+    #[test]
+    fn build_download_forwards_parallel_chunks() {
+        let mut builder = crate::backends::custom::Update::configure();
+        builder
+            .source(BoundSource)
+            .bin_name("app")
+            .target("x86_64-unknown-linux-gnu")
+            .current_version("1.0.0");
+        let asset = super::ReleaseAsset::new("app.bin", "https://nonroutable.invalid/app.bin");
+        let single = builder.build().unwrap();
+        assert!(
+            !super::build_download(&single, &asset)
+                .unwrap()
+                .is_parallel()
+        );
+        let chunked = builder.parallel_chunks(4).build().unwrap();
+        assert!(
+            super::build_download(&chunked, &asset)
+                .unwrap()
+                .is_parallel()
+        );
+    }
+
     #[test]
     fn build_download_forwards_configured_retry_budget() {
         use crate::http_client::header::HeaderMap;
@@ -3467,11 +5470,11 @@ mod tests {
         );
     }
 
-    // CORP-1: a custom-backend updater configured with `root_certificate`(s) must forward them onto
-    // the `Download` built by `build_download`, so the download materializes a client that trusts
-    // them. We assert the count of forwarded certs matches what was configured on the builder.
+    // CORP-1 / CORP-3: a custom-backend updater configured with `root_certificate`(s) and a `proxy`
+    // must forward them onto the `Download` built by `build_download`, so the download materializes
+    // a client that applies them. We assert the forwarded certs and proxy match the builder's.
     #[test]
-    fn build_download_forwards_certs_to_download() {
+    fn build_download_forwards_certs_and_proxy_to_download() {
         use crate::http_client::header::HeaderMap;
         use std::sync::Arc;
 
@@ -3515,7 +5518,8 @@ mod tests {
             .current_version("1.0.0")
             .http_client(Arc::new(NoopClient))
             .add_root_certificate(crate::Certificate::from_pem(b"pem-bytes".to_vec()))
-            .add_root_certificate(crate::Certificate::from_der(b"der-bytes".to_vec()));
+            .add_root_certificate(crate::Certificate::from_der(b"der-bytes".to_vec()))
+            .proxy(crate::Proxy::new("http://proxy.invalid:3128").basic_auth("bot", "pw"));
         #[cfg(feature = "async")]
         builder.http_client_async(Arc::new(NoopAsyncClient));
         let upd = builder.build().unwrap();
@@ -3527,6 +5531,114 @@ mod tests {
             2,
             "both configured root certificates must be forwarded onto the Download"
         );
+        let proxy = download
+            .proxy_config()
+            .expect("the configured proxy must be forwarded onto the Download");
+        assert_eq!(proxy.url(), "http://proxy.invalid:3128");
+        assert_eq!(proxy.credentials(), Some(("bot", "pw")));
+    }
+
+    /// A client answering per URL host: `primary.invalid` with a 503, `down.invalid` with a
+    /// connection error, `gone.invalid` with a 404, and any other host with `body`. Records each
+    /// request's URL and whether it carried an `Authorization` header.
+    struct MirrorClient {
+        body: Vec<u8>,
+        requests: std::sync::Mutex<Vec<(String, bool)>>,
+    }
+    impl crate::http_client::HttpClient for MirrorClient {
+        fn get(
+            &self,
+            url: &str,
+            headers: &crate::http_client::HeaderMap,
+            _timeout: Option<std::time::Duration>,
+        ) -> Result<Box<dyn crate::http_client::HttpResponse>> {
+            self.requests.lock().unwrap().push((
+                url.to_string(),
+                headers.contains_key(crate::http_client::header::AUTHORIZATION),
+            ));
+            let status = |status| Error::HttpStatus {
+                status,
+                url: url.to_string(),
+            };
+            match crate::backends::common::host_of(url).as_deref() {
+                Some("primary.invalid") => Err(status(503)),
+                Some("down.invalid") => Err(Error::transport(std::io::Error::other("refused"))),
+                Some("gone.invalid") => Err(status(404)),
+                _ => Ok(Box::new(MirrorResponse(self.body.clone()))),
+            }
+        }
+    }
+    struct MirrorResponse(Vec<u8>);
+    impl crate::http_client::HttpResponse for MirrorResponse {
+        fn headers(&self) -> &crate::http_client::HeaderMap {
+            static EMPTY: std::sync::OnceLock<crate::http_client::HeaderMap> =
+                std::sync::OnceLock::new();
+            EMPTY.get_or_init(crate::http_client::HeaderMap::new)
+        }
+        fn body(self: Box<Self>) -> Box<dyn std::io::Read> {
+            Box::new(std::io::Cursor::new(self.0))
+        }
+    }
+
+    #[test]
+    fn download_fails_over_through_mirrors_with_per_mirror_auth() {
+        use std::sync::Arc;
+
+        let client = Arc::new(MirrorClient {
+            body: b"from-mirror".to_vec(),
+            requests: Default::default(),
+        });
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .target("x86_64-unknown-linux-gnu")
+            .current_version("1.0.0")
+            .request_header(crate::http_client::header::AUTHORIZATION, "Bearer secret")
+            .allow_auth_host("mirror.invalid")
+            .asset_mirror("https://primary.invalid/", "https://mirror.invalid/cache/")
+            .http_client(client.clone())
+            .build()
+            .unwrap();
+
+        let asset = super::ReleaseAsset::new("app.bin", "https://primary.invalid/v1/app.bin")
+            .with_mirrors(["https://down.invalid/v1/app.bin"]);
+        let download = super::build_download(&upd, &asset).unwrap();
+        let mut out = Vec::new();
+        download.download_to(&mut out).unwrap();
+        assert_eq!(out, b"from-mirror");
+        assert_eq!(
+            *client.requests.lock().unwrap(),
+            [
+                ("https://primary.invalid/v1/app.bin".to_string(), false),
+                ("https://down.invalid/v1/app.bin".to_string(), false),
+                ("https://mirror.invalid/cache/v1/app.bin".to_string(), true),
+            ],
+            "a 5xx and a connection error fail over in order; only the authorized mirror gets the \
+             Authorization header"
+        );
+    }
+
+    #[test]
+    fn download_does_not_fail_over_on_a_client_error() {
+        let client = std::sync::Arc::new(MirrorClient {
+            body: b"unused".to_vec(),
+            requests: Default::default(),
+        });
+        let mut download = crate::Download::from_url("https://gone.invalid/app.bin");
+        download
+            .mirror("https://mirror.invalid/app.bin")
+            .set_http_client(
+                Some(client.clone()),
+                #[cfg(feature = "async")]
+                None,
+            );
+        let err = download.download_to(Vec::new()).unwrap_err();
+        assert_eq!(err.http_status(), Some(404));
+        assert_eq!(
+            client.requests.lock().unwrap().len(),
+            1,
+            "a 404 is returned as is, not retried against a mirror"
+        );
     }
 
     // download retry only covers the request-ESTABLISHMENT phase. A failure that occurs
@@ -3873,6 +5985,116 @@ mod tests {
         );
     }
 
+    // --- Detached signatures (formats zipsign cannot embed into) --------------------------------
+
+    /// Write `data` as `name` in a fresh temp dir, with a detached `name.sig` by `signer` unless
+    /// `signer` is `None`. Returns the dir (keep it alive) and the file path.
+    #[cfg(feature = "signatures")]
+    fn detached_file(
+        name: &str,
+        data: &[u8],
+        signer: Option<&zipsign_api::SigningKey>,
+    ) -> Result<(tempfile::TempDir, std::path::PathBuf)> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(name);
+        std::fs::write(&path, data)?;
+        if let Some(signer) = signer {
+            let sig = crate::keys::sign_detached(data, std::slice::from_ref(signer), name)?;
+            std::fs::write(dir.path().join(format!("{name}.sig")), sig)?;
+        }
+        Ok((dir, path))
+    }
+
+    /// A `.tar.xz` and a raw binary verify through their detached `.sig`, with the same keys and
+    /// any-of semantics as an embedded signature.
+    #[test]
+    #[cfg(feature = "signatures")]
+    fn detached_signature_verifies_non_embeddable_formats() -> Result<()> {
+        let key = zipsign_api::SigningKey::from_bytes(&[31u8; 32]);
+        let other: crate::VerifyingKey = zipsign_api::SigningKey::from_bytes(&[32u8; 32])
+            .verifying_key()
+            .to_bytes();
+        let vkey = key.verifying_key().to_bytes();
+
+        for name in ["app-1.2.3.tar.xz", "app-1.2.3.tar.zst", "app"] {
+            let (_dir, path) = detached_file(name, b"raw release bytes", Some(&key))?;
+            super::verify_signature(&path, &[other, vkey])?;
+            let err = super::verify_signature(&path, &[other]).unwrap_err();
+            assert!(matches!(err, Error::Signature(_)), "{name}: got {err}");
+        }
+        Ok(())
+    }
+
+    /// A detached signature covers the exact bytes: a tampered file fails, and a file without any
+    /// `.sig` still reports `NoSignatures`.
+    #[test]
+    #[cfg(feature = "signatures")]
+    fn detached_signature_rejects_tampering_and_reports_missing_sig() -> Result<()> {
+        let key = zipsign_api::SigningKey::from_bytes(&[33u8; 32]);
+        let vkey = key.verifying_key().to_bytes();
+
+        let (_dir, path) = detached_file("app", b"original", Some(&key))?;
+        std::fs::write(&path, b"tampered")?;
+        assert!(matches!(
+            super::verify_signature(&path, &[vkey]),
+            Err(Error::Signature(_))
+        ));
+
+        let (_dir, path) = detached_file("app", b"original", None)?;
+        assert!(matches!(
+            super::verify_signature(&path, &[vkey]),
+            Err(Error::NoSignatures(_))
+        ));
+        Ok(())
+    }
+
+    /// The pipeline fetches `<asset>.sig` next to the archive only when verification is on and the
+    /// format cannot embed a signature.
+    #[test]
+    #[cfg(all(
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz",
+    ))]
+    fn signature_downloads_fetch_detached_sig_only_when_needed() {
+        let key: crate::VerifyingKey = [0u8; 32];
+        let configured = |keys: Vec<crate::VerifyingKey>| {
+            crate::backends::custom::Update::configure()
+                .source(BoundSource)
+                .bin_name("app")
+                .current_version("1.0.0")
+                .verifying_keys(keys)
+                .build()
+                .unwrap()
+        };
+        let release = Release::builder()
+            .version("1.2.3")
+            .assets([
+                crate::ReleaseAsset::new("app.tar.xz", "https://host/app.tar.xz"),
+                crate::ReleaseAsset::new("app.tar.xz.sig", "https://host/app.tar.xz.sig"),
+                crate::ReleaseAsset::new("app.tar.gz", "https://host/app.tar.gz"),
+                crate::ReleaseAsset::new("app.tar.gz.sig", "https://host/app.tar.gz.sig"),
+            ])
+            .build()
+            .unwrap();
+        let dir = std::path::Path::new("/tmp/unused");
+        let downloads = |upd: &crate::backends::custom::Update, name: &str| {
+            let asset = crate::ReleaseAsset::new(name, "https://host/asset");
+            super::signature_downloads(upd, &release, &asset, &dir.join(name), dir).unwrap()
+        };
+
+        let on = configured(vec![key]);
+        let xz = downloads(&on, "app.tar.xz");
+        assert_eq!(xz.len(), 1);
+        assert_eq!(xz[0].0.download_url(), "https://host/app.tar.xz.sig");
+        assert_eq!(xz[0].1, dir.join("app.tar.xz.sig"));
+        assert!(downloads(&on, "app.tar.gz").is_empty(), "embedded: no .sig");
+        assert!(
+            downloads(&configured(vec![]), "app.tar.xz").is_empty(),
+            "verification off: nothing to fetch"
+        );
+    }
+
     // --- ZIP signature verification ------------------------------------------------------------
 
     /// Build a minimal in-memory `.zip` (stored, no compression) suitable for signing.
@@ -3958,6 +6180,247 @@ mod tests {
         Ok(())
     }
 
+    // --- Key revocation and rotation (keys.rs) in the finish tail -----------------------------
+
+    /// A [`FinishCtx`] verifying against `keys` with the key store at `store`.
+    #[cfg(all(
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz",
+    ))]
+    fn trust_ctx(
+        keys: Vec<crate::VerifyingKey>,
+        store: &std::path::Path,
+        docs: crate::keys::TrustDocuments,
+    ) -> super::FinishCtx {
+        let mut ctx = traversal_ctx("app", "1.2.3");
+        ctx.trust = crate::keys::TrustPolicy {
+            verifying_keys: keys,
+            trusted_keys: vec![],
+            key_store_path: Some(store.to_path_buf()),
+        };
+        ctx.trust_documents = docs;
+        ctx
+    }
+
+    /// Write `body` and its detached signature by `signer` into `dir` as `name` / `name.sig`.
+    #[cfg(all(
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz",
+    ))]
+    fn signed_document(
+        dir: &std::path::Path,
+        name: &str,
+        body: &str,
+        signer: &zipsign_api::SigningKey,
+    ) -> crate::keys::SignedDocument {
+        let path = dir.join(name);
+        let signature_path = dir.join(format!("{name}.sig"));
+        std::fs::write(&path, body).unwrap();
+        let sig = crate::keys::sign_detached(body.as_bytes(), std::slice::from_ref(signer), name)
+            .unwrap();
+        std::fs::write(&signature_path, sig).unwrap();
+        crate::keys::SignedDocument {
+            path,
+            signature_path,
+        }
+    }
+
+    /// A revocation list fetched with the release revokes the only key the archive is signed with:
+    /// the update is refused with `NoTrustedKeys` instead of verifying (or skipping verification).
+    #[test]
+    #[cfg(all(
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz",
+    ))]
+    fn finish_update_refuses_archive_signed_by_revoked_key() -> Result<()> {
+        let key = zipsign_api::SigningKey::from_bytes(&[21u8; 32]);
+        let vkey = key.verifying_key().to_bytes();
+        let signed_file = sign_tar_gz(&make_tar_gz()?, std::slice::from_ref(&key))?;
+
+        let dir = tempfile::tempdir()?;
+        let store = dir.path().join("trust.json");
+        let list = crate::keys::RevocationList::new(vec![vkey]).to_json();
+        let docs = crate::keys::TrustDocuments {
+            revocation_list: Some(signed_document(dir.path(), "revoked.json", &list, &key)),
+            key_set: None,
+        };
+
+        let ctx = trust_ctx(vec![vkey], &store, docs);
+        let err = super::finish_update_owned(ctx, tempfile::tempdir()?, signed_file.path())
+            .expect_err("an archive signed by a revoked key must not verify");
+        assert!(matches!(err, Error::NoTrustedKeys), "got: {err}");
+        Ok(())
+    }
+
+    /// Rotation through the finish tail: an archive signed by the old key delivers a key set; it is
+    /// persisted once the update is installed, so the next run trusts the new key.
+    #[test]
+    #[cfg(all(
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz",
+    ))]
+    fn finish_update_persists_key_set_delivered_by_trusted_release() -> Result<()> {
+        let old = zipsign_api::SigningKey::from_bytes(&[22u8; 32]);
+        let new = zipsign_api::SigningKey::from_bytes(&[23u8; 32]);
+        let old_vkey = old.verifying_key().to_bytes();
+        let new_vkey = new.verifying_key().to_bytes();
+        let signed_file = sign_tar_gz(&make_tar_gz()?, std::slice::from_ref(&old))?;
+
+        let dir = tempfile::tempdir()?;
+        let store = dir.path().join("trust.json");
+        let set = crate::keys::KeySet::new(vec![crate::keys::TrustedKey::new(new_vkey)]).to_json();
+        let docs = crate::keys::TrustDocuments {
+            revocation_list: None,
+            key_set: Some(signed_document(dir.path(), "keys.json", &set, &old)),
+        };
+
+        let mut ctx = trust_ctx(vec![old_vkey], &store, docs);
+        ctx.bin_path_in_archive = "hello.txt".to_string();
+        ctx.bin_install_path = dir.path().join("app");
+        super::finish_update_owned(ctx, tempfile::tempdir()?, signed_file.path())?;
+        assert_eq!(std::fs::read(dir.path().join("app"))?, b"hello");
+
+        let next = trust_ctx(vec![], &store, Default::default());
+        assert_eq!(
+            next.trust.active_keys(&next.trust_documents)?,
+            vec![new_vkey]
+        );
+        Ok(())
+    }
+
+    /// An update whose install fails after the archive verified does not save the key set it
+    /// delivered: the stored set and serial are unchanged, and the retry is not taken for a replay.
+    #[test]
+    #[cfg(all(
+        feature = "signatures",
+        feature = "archive-tar",
+        feature = "compression-tar-gz",
+    ))]
+    fn finish_update_keeps_key_set_unsaved_when_install_fails() -> Result<()> {
+        let old = zipsign_api::SigningKey::from_bytes(&[24u8; 32]);
+        let new = zipsign_api::SigningKey::from_bytes(&[25u8; 32]);
+        let old_vkey = old.verifying_key().to_bytes();
+        let new_vkey = new.verifying_key().to_bytes();
+        let signed_file = sign_tar_gz(&make_tar_gz()?, std::slice::from_ref(&old))?;
+
+        let dir = tempfile::tempdir()?;
+        let store = dir.path().join("trust.json");
+        let stored = crate::keys::KeySet::new(vec![crate::keys::TrustedKey::new(old_vkey)])
+            .with_serial(1)
+            .to_json();
+        let stored = crate::keys::TrustDocuments {
+            revocation_list: None,
+            key_set: Some(signed_document(dir.path(), "stored.json", &stored, &old)),
+        };
+        let seed = trust_ctx(vec![old_vkey], &store, Default::default());
+        seed.trust
+            .save_key_set(seed.trust.accept_key_set(&stored, &[old_vkey])?)?;
+
+        let set = crate::keys::KeySet::new(vec![crate::keys::TrustedKey::new(new_vkey)])
+            .with_serial(2)
+            .to_json();
+        let docs = || crate::keys::TrustDocuments {
+            revocation_list: None,
+            key_set: Some(signed_document(dir.path(), "keys.json", &set, &old)),
+        };
+        let mut ctx = trust_ctx(vec![old_vkey], &store, docs());
+        ctx.bin_path_in_archive = "hello.txt".to_string();
+        ctx.bin_install_path = dir.path().join("app");
+        ctx.verify_callback = Some(std::sync::Arc::new(|_: &std::path::Path| {
+            Err(Error::VerificationRejected { reason: None })
+        }));
+        super::finish_update_owned(ctx, tempfile::tempdir()?, signed_file.path())
+            .expect_err("the verify hook fails the install");
+        assert!(!dir.path().join("app").exists());
+
+        let next = trust_ctx(vec![old_vkey], &store, docs());
+        assert_eq!(
+            next.trust.active_keys(&Default::default())?,
+            vec![old_vkey],
+            "the stored set (serial 1) is still the trust root"
+        );
+        assert!(
+            next.trust
+                .accept_key_set(&next.trust_documents, &[old_vkey])?
+                .is_some(),
+            "the retry delivering serial 2 is not a replay"
+        );
+        Ok(())
+    }
+
+    /// A configured trust document the release does not publish is skipped; one published without
+    /// its `.sig` companion fails the update.
+    #[test]
+    #[cfg(feature = "signatures")]
+    fn trust_documents_require_a_detached_signature() {
+        let upd = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .revocation_list_asset("revoked.json")
+            .build()
+            .unwrap();
+
+        let bare = Release::builder().version("1.2.3").build().unwrap();
+        assert!(
+            super::published_trust_documents(&upd, &bare)
+                .unwrap()
+                .is_empty()
+        );
+
+        let unsigned = Release::builder()
+            .version("1.2.3")
+            .assets([crate::ReleaseAsset::new(
+                "revoked.json",
+                "https://host/revoked.json",
+            )])
+            .build()
+            .unwrap();
+        let err = super::published_trust_documents(&upd, &unsigned).unwrap_err();
+        assert!(matches!(err, Error::Signature(_)), "got: {err}");
+
+        let signed = Release::builder()
+            .version("1.2.3")
+            .assets([
+                crate::ReleaseAsset::new("revoked.json", "https://host/revoked.json"),
+                crate::ReleaseAsset::new("revoked.json.sig", "https://host/revoked.json.sig"),
+            ])
+            .build()
+            .unwrap();
+        assert_eq!(
+            super::published_trust_documents(&upd, &signed)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    /// A key-set asset without a key store to persist it into is a config error at `build()`.
+    #[test]
+    #[cfg(feature = "signatures")]
+    fn key_set_asset_requires_key_store_path() {
+        let err = crate::backends::custom::Update::configure()
+            .source(BoundSource)
+            .bin_name("app")
+            .current_version("1.0.0")
+            .key_set_asset("keys.json")
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                Error::MissingField {
+                    field: "key_store_path"
+                }
+            ),
+            "got: {err}"
+        );
+    }
+
     // --- S6: template-substitution path-traversal guard --------------------------------------
 
     /// Build a [`FinishCtx`] for the substitution guard tests. The archive is never read (the guard
@@ -3970,16 +6433,27 @@ mod tests {
             target: "x86_64-unknown-linux-gnu".to_string(),
             bin_name: "app".to_string(),
             bin_path_in_archive: bin_path_in_archive.to_string(),
+            bundle: None,
+            install_files: vec![],
+            versioned_install: None,
+            extract_limits: crate::ExtractLimits::default(),
+            backend: "custom",
+            install_receipt: None,
+            #[cfg(feature = "preserve-metadata")]
+            preserve_metadata: false,
+            #[cfg(feature = "elevate")]
+            elevate: None,
+            #[cfg(feature = "stage")]
+            stage_into: None,
             show_output: false,
             verify_callback: None,
+            self_test: None,
             #[cfg(feature = "checksums")]
-            verify_checksum: None,
-            #[cfg(feature = "checksums")]
-            asset_digest: None,
-            #[cfg(feature = "checksums")]
-            verify_release_digest: true,
+            checksums: Vec::new(),
             #[cfg(feature = "signatures")]
-            verify_keys: vec![],
+            trust: Default::default(),
+            #[cfg(feature = "signatures")]
+            trust_documents: Default::default(),
         }
     }
 
@@ -4000,6 +6474,25 @@ mod tests {
         }
     }
 
+    // In bundle mode the same guard covers the templated bundle root.
+    #[test]
+    fn finish_update_rejects_traversal_in_substituted_bundle_root() {
+        let mut ctx = traversal_ctx("unused", "../evil");
+        ctx.bundle = Some((
+            "app-{{ version }}".to_string(),
+            std::path::PathBuf::from("unused"),
+        ));
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("archive.tar.gz");
+        assert!(
+            matches!(
+                super::finish_update_owned(ctx, dir, &archive),
+                Err(super::Error::InvalidAssetName { .. })
+            ),
+            "a traversal version must not reach the bundle root"
+        );
+    }
+
     // A separator injected through the substituted value (e.g. `sub/evil`) is likewise rejected.
     #[test]
     fn finish_update_rejects_separator_in_substituted_version() {
@@ -4031,6 +6524,39 @@ mod tests {
         );
     }
 
+    // The built-in self-test runs on the extracted binary inside the finish tail: a raw asset that
+    // reports the wrong version is rejected with nothing installed; the right one is installed.
+    #[cfg(unix)]
+    #[test]
+    fn finish_update_runs_self_test_before_installing() {
+        for printed in ["1.2.2", "1.2.3"] {
+            let dir = tempfile::tempdir().unwrap();
+            let archive = dir.path().join("app-linux");
+            std::fs::write(&archive, format!("#!/bin/sh\necho app {printed}\n")).unwrap();
+            let install = tempfile::tempdir().unwrap();
+            let install_path = install.path().join("app");
+
+            let mut ctx = traversal_ctx("app", "1.2.3");
+            ctx.bin_install_path = install_path.clone();
+            ctx.self_test = Some(crate::self_test::SelfTest::new());
+            let res = super::finish_update_owned(ctx, dir, &archive);
+
+            if printed == "1.2.3" {
+                assert!(res.is_ok(), "a passing self-test must install, got {res:?}");
+                assert!(install_path.exists());
+            } else {
+                assert!(
+                    matches!(res, Err(super::Error::VerificationRejected { .. })),
+                    "got {res:?}"
+                );
+                assert!(
+                    !install_path.exists(),
+                    "a rejected binary must not be installed"
+                );
+            }
+        }
+    }
+
     // --- S2: credential host-gate parity (RequestConfig::auth_allowed_for) --------------------
 
     // The host-gate mirrors `RequestConfig::auth_allowed_for`: a matching host over https is
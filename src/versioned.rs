@@ -0,0 +1,467 @@
+/*!
+Versioned side-by-side installs.
+
+Instead of overwriting the installed binary, a [`VersionedInstall`] keeps every version in its own
+directory under a root and points a `current` symlink at the active one:
+
+```text
+~/.local/share/app/
+├── current -> versions/1.2.0
+└── versions/
+    ├── 1.1.0/app
+    └── 1.2.0/app
+```
+
+An update installs the new binary into `versions/<version>/` and only then flips `current`, after
+every verification step has passed, so a launcher on `PATH` (a symlink to `current/app`, or a
+shim script that runs it) never sees a half-installed version. On unix the flip is a single
+`rename` of a fresh symlink over the old one. Earlier versions stay on disk:
+[`switch_to`](VersionedInstall::switch_to) rolls back instantly, any installed version can be run
+directly from [`binary_path`](VersionedInstall::binary_path), and after each update all but the
+newest [`keep`](VersionedInstall::keep) versions are removed (never the current one).
+
+The running executable is never replaced, so there is no `self_replace` step: the process keeps
+running its own version directory, and the next launch through `current` picks up the new one.
+
+On Windows `current` is a directory symlink, which needs Developer Mode or the
+`SeCreateSymbolicLinkPrivilege`, and the flip removes the old link before renaming the new one
+into place, so it is not atomic there.
+*/
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+use crate::errors::Error;
+
+/// Versions kept by [`VersionedInstall::new`] when pruning after an update.
+const DEFAULT_KEEP: usize = 3;
+
+/// The directory under the root holding one subdirectory per installed version.
+const VERSIONS_DIR: &str = "versions";
+
+/// The symlink under the root naming the active version.
+const CURRENT_LINK: &str = "current";
+
+/// A side-by-side install layout: each version in `<root>/versions/<version>/`, with a
+/// `<root>/current` symlink naming the active one.
+///
+/// Supplied to a backend builder via the `versioned_install` setter, which makes the update
+/// install into this layout instead of replacing `bin_install_path`:
+///
+/// ```
+/// let layout = self_update::VersionedInstall::new("/home/me/.local/share/app").keep(5);
+/// assert_eq!(
+///     layout.binary_path("1.2.0", "app"),
+///     std::path::Path::new("/home/me/.local/share/app/versions/1.2.0/app")
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionedInstall {
+    root: PathBuf,
+    keep: usize,
+}
+
+impl VersionedInstall {
+    /// A layout under `root`, keeping the newest 3 versions.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            keep: DEFAULT_KEEP,
+        }
+    }
+
+    /// Keep the newest `versions` installed versions (by semver) when pruning after an update.
+    /// The current version is always kept, even when it is older. At least 1.
+    pub fn keep(mut self, versions: usize) -> Self {
+        self.keep = versions.max(1);
+        self
+    }
+
+    /// The layout root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The `current` symlink, naming the active version's directory.
+    pub fn current_link(&self) -> PathBuf {
+        self.root.join(CURRENT_LINK)
+    }
+
+    /// The directory holding `version`.
+    pub fn version_dir(&self, version: &str) -> PathBuf {
+        self.root.join(VERSIONS_DIR).join(version)
+    }
+
+    /// The path of `bin_name` in `version`'s directory, to run that version explicitly.
+    pub fn binary_path(&self, version: &str, bin_name: &str) -> PathBuf {
+        self.version_dir(version).join(bin_name)
+    }
+
+    /// The version `current` points at, or `None` before the first install.
+    pub fn current_version(&self) -> Result<Option<String>> {
+        match fs::read_link(self.current_link()) {
+            Ok(target) => Ok(target
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The installed versions, newest first. Directories under `versions/` whose names are not
+    /// semver versions are ignored.
+    pub fn installed_versions(&self) -> Result<Vec<String>> {
+        let entries = match fs::read_dir(self.root.join(VERSIONS_DIR)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut versions = vec![];
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if let Ok(version) = semver::Version::parse(&name) {
+                versions.push((version, name));
+            }
+        }
+        versions.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(versions.into_iter().map(|(_, name)| name).collect())
+    }
+
+    /// Point `current` at an already-installed `version`: an instant rollback (or roll forward)
+    /// that downloads nothing. Fails with an [`Io`](Error::Io) `NotFound` error when `version` is
+    /// not installed.
+    pub fn switch_to(&self, version: &str) -> Result<()> {
+        check_version(version)?;
+        if !self.version_dir(version).is_dir() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "version {} is not installed under {}",
+                    version,
+                    self.root.display()
+                ),
+            )));
+        }
+        self.point_current_at(version)
+    }
+
+    /// Install `new_exe` as `bin_name` in `version`'s directory, flip `current` to it, then prune
+    /// old versions.
+    ///
+    /// The version directory is assembled in a staging directory under `versions/` and renamed into
+    /// place, so `versions/<version>` is either absent or complete. Reinstalling an existing
+    /// version replaces its directory, which is put back if the new one cannot be moved in.
+    pub(crate) fn install(&self, new_exe: &Path, bin_name: &str, version: &str) -> Result<()> {
+        check_version(version)?;
+        let versions = self.root.join(VERSIONS_DIR);
+        fs::create_dir_all(&versions)?;
+        let staging = tempfile::Builder::new()
+            .prefix(".staging")
+            .tempdir_in(&versions)?;
+        let staged = staging.path().join("version");
+        fs::create_dir(&staged)?;
+        // `new_exe` usually lives on another filesystem (the system temp dir), so it is copied,
+        // which also carries its permissions over.
+        fs::copy(new_exe, staged.join(bin_name))?;
+
+        replace_dir(
+            &staged,
+            &self.version_dir(version),
+            &staging.path().join("replaced"),
+        )?;
+
+        self.point_current_at(version)?;
+        self.prune();
+        Ok(())
+    }
+
+    /// Atomically repoint `current` at `versions/<version>`: a new symlink is created beside it
+    /// and renamed over it. The target is relative, so the whole root can be moved.
+    fn point_current_at(&self, version: &str) -> Result<()> {
+        let target = Path::new(VERSIONS_DIR).join(version);
+        let link = self.current_link();
+        let new_link = self.root.join(format!(".{}.new", CURRENT_LINK));
+        match fs::symlink_metadata(&new_link) {
+            Ok(_) => remove_link(&new_link)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        symlink_dir(&target, &new_link)?;
+        #[cfg(windows)]
+        if fs::symlink_metadata(&link).is_ok() {
+            remove_link(&link)?;
+        }
+        if let Err(e) = fs::rename(&new_link, &link) {
+            let _ = remove_link(&new_link);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Remove installed versions beyond the newest [`keep`](Self::keep), sparing the current one.
+    /// Best-effort: failures are logged, never returned, since the update itself has succeeded.
+    fn prune(&self) {
+        let installed = match self.installed_versions() {
+            Ok(installed) => installed,
+            Err(e) => {
+                log::warn!("failed to list installed versions to prune: {}", e);
+                return;
+            }
+        };
+        let current = self.current_version().ok().flatten();
+        for version in installed.iter().skip(self.keep) {
+            if current.as_deref() == Some(version.as_str()) {
+                continue;
+            }
+            let dir = self.version_dir(version);
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                log::warn!("failed to remove old version {:?}: {}", dir, e);
+            }
+        }
+    }
+}
+
+/// Rename `staged` to `dest`, first moving an existing `dest` aside to `stash` (in the staging
+/// directory, deleted with it). If the rename fails, the stashed directory is moved back, so a
+/// failed reinstall keeps the version it was replacing.
+fn replace_dir(staged: &Path, dest: &Path, stash: &Path) -> Result<()> {
+    let replacing = fs::symlink_metadata(dest).is_ok();
+    if replacing {
+        fs::rename(dest, stash)?;
+    }
+    if let Err(e) = fs::rename(staged, dest) {
+        if replacing && let Err(restore_err) = fs::rename(stash, dest) {
+            log::error!(
+                "failed to restore {:?} from stash {:?} after a failed install: {}",
+                dest,
+                stash,
+                restore_err
+            );
+        }
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Reject a version that is not a single safe path component (the version comes from the release
+/// server, so `../evil` must not reach the path).
+fn check_version(version: &str) -> Result<()> {
+    if crate::update::is_safe_asset_name(version) {
+        Ok(())
+    } else {
+        Err(Error::InvalidAssetName {
+            name: version.to_string(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+/// Remove a symlink (a directory symlink is removed with `remove_dir` on Windows).
+fn remove_link(link: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        fs::remove_dir(link)
+    }
+    #[cfg(not(windows))]
+    {
+        fs::remove_file(link)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn new_exe(dir: &Path, contents: &str) -> PathBuf {
+        let path = dir.join("downloaded");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn install_creates_the_version_dir_and_points_current_at_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path().join("app"));
+
+        layout
+            .install(&new_exe(dir.path(), "v1"), "app", "1.0.0")
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(layout.binary_path("1.0.0", "app")).unwrap(),
+            "v1"
+        );
+        assert_eq!(layout.current_version().unwrap().as_deref(), Some("1.0.0"));
+        assert_eq!(
+            fs::read_link(layout.current_link()).unwrap(),
+            Path::new("versions/1.0.0"),
+            "the link target is relative"
+        );
+        assert_eq!(
+            fs::read_to_string(layout.current_link().join("app")).unwrap(),
+            "v1"
+        );
+    }
+
+    #[test]
+    fn a_second_install_flips_current_and_switch_to_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path().join("app"));
+        layout
+            .install(&new_exe(dir.path(), "v1"), "app", "1.0.0")
+            .unwrap();
+        layout
+            .install(&new_exe(dir.path(), "v2"), "app", "1.1.0")
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(layout.current_link().join("app")).unwrap(),
+            "v2"
+        );
+        assert_eq!(layout.installed_versions().unwrap(), ["1.1.0", "1.0.0"]);
+
+        layout.switch_to("1.0.0").unwrap();
+        assert_eq!(
+            fs::read_to_string(layout.current_link().join("app")).unwrap(),
+            "v1"
+        );
+        assert!(!layout.root().join(".current.new").exists());
+    }
+
+    #[test]
+    fn switch_to_an_uninstalled_version_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path());
+        match layout.switch_to("9.9.9").unwrap_err() {
+            Error::Io(e) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            other => panic!("expected Error::Io, got {:?}", other),
+        }
+        assert_eq!(layout.current_version().unwrap(), None);
+    }
+
+    // Pruning keeps the newest `keep` versions by semver, plus the current one when older.
+    #[test]
+    fn install_prunes_old_versions_but_never_the_current_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path().join("app")).keep(2);
+        for version in ["1.0.0", "1.1.0", "1.2.0"] {
+            layout
+                .install(&new_exe(dir.path(), version), "app", version)
+                .unwrap();
+        }
+        assert_eq!(layout.installed_versions().unwrap(), ["1.2.0", "1.1.0"]);
+
+        layout.switch_to("1.1.0").unwrap();
+        layout
+            .install(&new_exe(dir.path(), "old"), "app", "0.9.0")
+            .unwrap();
+        layout.switch_to("0.9.0").unwrap();
+        layout
+            .install(&new_exe(dir.path(), "new"), "app", "1.3.0")
+            .unwrap();
+        assert_eq!(layout.installed_versions().unwrap(), ["1.3.0", "1.2.0"]);
+
+        // Installing an older version than the newest two leaves it current and kept.
+        layout
+            .install(&new_exe(dir.path(), "old"), "app", "0.8.0")
+            .unwrap();
+        assert_eq!(
+            layout.installed_versions().unwrap(),
+            ["1.3.0", "1.2.0", "0.8.0"]
+        );
+    }
+
+    #[test]
+    fn reinstalling_a_version_replaces_its_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path().join("app"));
+        layout
+            .install(&new_exe(dir.path(), "first"), "app", "1.0.0")
+            .unwrap();
+        fs::write(layout.version_dir("1.0.0").join("stale"), "x").unwrap();
+        layout
+            .install(&new_exe(dir.path(), "second"), "app", "1.0.0")
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(layout.binary_path("1.0.0", "app")).unwrap(),
+            "second"
+        );
+        assert!(!layout.version_dir("1.0.0").join("stale").exists());
+        assert_eq!(
+            fs::read_dir(layout.root().join("versions"))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn a_failed_reinstall_puts_the_replaced_version_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path().join("app"));
+        layout
+            .install(&new_exe(dir.path(), "v1"), "app", "1.0.0")
+            .unwrap();
+
+        // A staged directory that is gone makes the rename into place fail after the installed
+        // version was moved aside.
+        let staging = tempfile::tempdir_in(layout.root().join("versions")).unwrap();
+        let stash = staging.path().join("replaced");
+        let err = replace_dir(
+            &staging.path().join("missing"),
+            &layout.version_dir("1.0.0"),
+            &stash,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::Io(_)), "got {:?}", err);
+        drop(staging);
+
+        assert_eq!(
+            fs::read_to_string(layout.binary_path("1.0.0", "app")).unwrap(),
+            "v1"
+        );
+        assert_eq!(layout.current_version().unwrap().as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn install_rejects_a_traversal_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path().join("app"));
+        assert!(matches!(
+            layout.install(&new_exe(dir.path(), "x"), "app", "../evil"),
+            Err(Error::InvalidAssetName { .. })
+        ));
+        assert!(!dir.path().join("evil").exists());
+    }
+
+    #[test]
+    fn installed_versions_ignores_non_version_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = VersionedInstall::new(dir.path());
+        assert!(layout.installed_versions().unwrap().is_empty());
+        for name in ["1.0.0", "2.0.0-beta.1", "notes", ".staging1"] {
+            fs::create_dir_all(layout.version_dir(name)).unwrap();
+        }
+        fs::write(layout.root().join("versions/3.0.0"), "a file").unwrap();
+        assert_eq!(
+            layout.installed_versions().unwrap(),
+            ["2.0.0-beta.1", "1.0.0"]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! The local release-server stand-in drives each backend end to end over real HTTP on loopback:
+//! paginated listings, auth gating, redirected downloads and a full github update (including a
+//! checksum failure) without touching the network.
+
+#![cfg(feature = "test-util")]
+
+use self_update::test_server::{ReleaseServer, ReleaseServerBuilder, TestAsset, TestRelease};
+
+const TARGET: &str = "x86_64-unknown-linux-gnu";
+
+/// Three releases, newest first, each with one plain-binary asset for [`TARGET`].
+fn three_releases() -> ReleaseServerBuilder {
+    let mut builder = ReleaseServer::configure();
+    for version in ["3.0.0", "2.0.0", "1.0.0"] {
+        builder.release(
+            TestRelease::new(format!("v{version}")).with_asset(TestAsset::new(
+                format!("myapp-{version}-{TARGET}"),
+                format!("myapp {version}").into_bytes(),
+            )),
+        );
+    }
+    builder
+}
+
+fn versions(releases: self_update::update::Releases) -> Vec<String> {
+    releases
+        .into_vec()
+        .iter()
+        .map(|r| r.version().to_owned())
+        .collect()
+}
+
+#[cfg(feature = "github")]
+#[test]
+fn github_listing_follows_every_page() {
+    let server = three_releases().page_size(1).build().unwrap();
+    let releases = self_update::backends::github::ReleaseList::configure()
+        .api_base_url(server.github_api_url())
+        .repo_owner("o")
+        .repo_name("myapp")
+        .build()
+        .unwrap()
+        .fetch()
+        .unwrap();
+    assert_eq!(versions(releases), ["3.0.0", "2.0.0", "1.0.0"]);
+    assert_eq!(server.requests().len(), 3, "one request per page");
+}
+
+#[cfg(feature = "gitlab")]
+#[test]
+fn gitlab_listing_follows_every_page() {
+    let server = three_releases().page_size(2).build().unwrap();
+    let releases = self_update::backends::gitlab::ReleaseList::configure()
+        .host(server.gitlab_host())
+        .repo_owner("o")
+        .repo_name("myapp")
+        .build()
+        .unwrap()
+        .fetch()
+        .unwrap();
+    assert_eq!(versions(releases), ["3.0.0", "2.0.0", "1.0.0"]);
+}
+
+#[cfg(feature = "gitea")]
+#[test]
+fn gitea_listing_follows_every_page() {
+    let server = three_releases().page_size(2).build().unwrap();
+    let releases = self_update::backends::gitea::ReleaseList::configure()
+        .host(server.gitea_host())
+        .repo_owner("o")
+        .repo_name("myapp")
+        .build()
+        .unwrap()
+        .fetch()
+        .unwrap();
+    assert_eq!(versions(releases), ["3.0.0", "2.0.0", "1.0.0"]);
+}
+
+#[cfg(feature = "s3")]
+#[test]
+fn s3_listing_follows_continuation_tokens() {
+    let server = three_releases().page_size(2).build().unwrap();
+    let releases = self_update::backends::s3::ReleaseList::configure()
+        .endpoint(server.s3_endpoint())
+        .bucket_name("releases")
+        .build()
+        .unwrap()
+        .fetch()
+        .unwrap();
+    let mut versions = versions(releases);
+    versions.sort();
+    assert_eq!(versions, ["1.0.0", "2.0.0", "3.0.0"]);
+    assert_eq!(server.requests().len(), 2, "one request per listing page");
+}
+
+#[cfg(feature = "github")]
+#[test]
+fn github_listing_is_gated_on_the_auth_token() {
+    let server = three_releases()
+        .require_auth_token("secret")
+        .build()
+        .unwrap();
+    let list = |token: Option<&str>| {
+        let mut builder = self_update::backends::github::ReleaseList::configure();
+        builder
+            .api_base_url(server.github_api_url())
+            .repo_owner("o")
+            .repo_name("myapp");
+        if let Some(token) = token {
+            builder.auth_token(token);
+        }
+        builder.build().unwrap().fetch()
+    };
+
+    let err = list(None).unwrap_err();
+    assert_eq!(err.http_status(), Some(401), "got {err:?}");
+    let err = list(Some("wrong")).unwrap_err();
+    assert_eq!(err.http_status(), Some(401), "got {err:?}");
+    assert_eq!(versions(list(Some("secret")).unwrap()).len(), 3);
+}
+
+#[cfg(feature = "github")]
+fn github_update(
+    server: &ReleaseServer,
+    install_path: &std::path::Path,
+) -> self_update::backends::github::UpdateBuilder {
+    let mut builder = self_update::backends::github::Update::configure();
+    builder
+        .api_base_url(server.github_api_url())
+        .repo_owner("o")
+        .repo_name("myapp")
+        .bin_name("myapp")
+        .target(TARGET)
+        .current_version("1.0.0")
+        .bin_install_path(install_path)
+        .no_confirm(true)
+        .show_output(false)
+        .show_download_progress(false);
+    builder
+}
+
+#[cfg(feature = "github")]
+#[test]
+fn github_update_installs_through_a_redirected_authenticated_download() {
+    let server = three_releases()
+        .require_auth_token("secret")
+        .redirect_downloads(true)
+        .build()
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let install_path = dir.path().join("myapp");
+
+    let status = github_update(&server, &install_path)
+        .auth_token("secret")
+        .build()
+        .unwrap()
+        .update_extended()
+        .unwrap();
+    assert_eq!(
+        status.updated_release().map(|r| r.version().to_owned()),
+        Some("3.0.0".to_owned())
+    );
+    assert_eq!(std::fs::read(&install_path).unwrap(), b"myapp 3.0.0");
+
+    let requests = server.requests();
+    let download = requests
+        .iter()
+        .find(|r| r.path().starts_with("/download/"))
+        .expect("the asset was requested");
+    assert_eq!(download.authorization(), Some("token secret"));
+    assert!(
+        requests.iter().any(|r| r.path() == "/storage/0/0"),
+        "the download followed the redirect, saw {requests:?}"
+    );
+}
+
+#[cfg(all(feature = "github", feature = "checksums"))]
+#[test]
+fn github_update_rejects_an_asset_that_does_not_match_its_digest() {
+    let server = ReleaseServer::configure()
+        .release(
+            TestRelease::new("v2.0.0").with_asset(
+                TestAsset::new(format!("myapp-2.0.0-{TARGET}"), &b"tampered"[..])
+                    .with_digest(format!("sha256:{}", "00".repeat(32))),
+            ),
+        )
+        .build()
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let install_path = dir.path().join("myapp");
+
+    let err = github_update(&server, &install_path)
+        .build()
+        .unwrap()
+        .update()
+        .unwrap_err();
+    assert!(
+        matches!(err, self_update::Error::ChecksumMismatch { .. }),
+        "got {err:?}"
+    );
+    assert!(!install_path.exists(), "nothing is installed on a mismatch");
+}
+
+#[cfg(feature = "github")]
+#[test]
+fn github_update_reports_the_redirected_download_url() {
+    let server = three_releases().redirect_downloads(true).build().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let install_path = dir.path().join("myapp");
+    let hops = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = hops.clone();
+
+    github_update(&server, &install_path)
+        .redirect_callback(move |hop| seen.lock().unwrap().push(hop.clone()))
+        .build()
+        .unwrap()
+        .update()
+        .unwrap();
+
+    let hops = hops.lock().unwrap();
+    assert_eq!(hops.len(), 1, "one redirect, saw {hops:?}");
+    assert_eq!(hops[0].status(), 302);
+    assert!(hops[0].from().ends_with("/download/0/0"), "{hops:?}");
+    assert_eq!(hops[0].to(), format!("{}/storage/0/0", server.url()));
+}
+
+#[cfg(feature = "github")]
+#[test]
+fn github_update_fails_when_the_redirect_policy_refuses_the_download() {
+    let server = three_releases().redirect_downloads(true).build().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let install_path = dir.path().join("myapp");
+
+    let err = github_update(&server, &install_path)
+        .redirect_policy(self_update::RedirectPolicy::none())
+        .build()
+        .unwrap()
+        .update()
+        .unwrap_err();
+    assert!(
+        matches!(err, self_update::Error::RedirectRejected { .. }),
+        "got {err:?}"
+    );
+    assert!(!install_path.exists());
+}
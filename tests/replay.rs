@@ -0,0 +1,90 @@
+//! The record/replay fixture transport, used the way a downstream test would: record a backend's
+//! responses through the public `http_client::replay` API once, then drive the same backend from
+//! the fixtures alone.
+
+#![cfg(all(feature = "test-util", feature = "github"))]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use self_update::http_client::replay::{RecordingClient, ReplayClient};
+use self_update::http_client::{HeaderMap, HttpClient, HttpResponse};
+
+/// A response backed by a canned body.
+struct CannedResponse {
+    body: String,
+    headers: HeaderMap,
+}
+
+impl HttpResponse for CannedResponse {
+    fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+    fn body(self: Box<Self>) -> Box<dyn std::io::Read> {
+        Box::new(std::io::Cursor::new(self.body.into_bytes()))
+    }
+}
+
+/// Stands in for the live forge: counts requests and answers with a canned listing.
+struct CannedClient {
+    body: String,
+    requested: Arc<Mutex<Vec<String>>>,
+}
+
+impl HttpClient for CannedClient {
+    fn get(
+        &self,
+        url: &str,
+        _headers: &HeaderMap,
+        _timeout: Option<Duration>,
+    ) -> self_update::Result<Box<dyn HttpResponse>> {
+        self.requested.lock().unwrap().push(url.to_string());
+        Ok(Box::new(CannedResponse {
+            body: self.body.clone(),
+            headers: HeaderMap::new(),
+        }))
+    }
+}
+
+fn fetch(client: Arc<dyn HttpClient>) -> self_update::Result<Vec<self_update::update::Release>> {
+    Ok(self_update::backends::github::ReleaseList::configure()
+        .repo_owner("o")
+        .repo_name("r")
+        .http_client(client)
+        .build()?
+        .fetch()?
+        .into_vec())
+}
+
+#[test]
+fn a_recorded_listing_replays_offline() {
+    let fixtures = tempfile::TempDir::new().unwrap();
+    let requested = Arc::new(Mutex::new(Vec::new()));
+    let live = Arc::new(CannedClient {
+        body: r#"[{"tag_name":"v4.5.6","created_at":"2020-01-01T00:00:00Z","name":"v4.5.6","assets":[]}]"#.to_string(),
+        requested: requested.clone(),
+    });
+
+    let recorded = fetch(Arc::new(RecordingClient::new(live, fixtures.path()))).unwrap();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(requested.lock().unwrap().len(), 1);
+
+    let replayed = fetch(Arc::new(ReplayClient::new(fixtures.path()))).unwrap();
+    assert_eq!(
+        requested.lock().unwrap().len(),
+        1,
+        "replay never reaches the live client"
+    );
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].version(), "4.5.6");
+}
+
+#[test]
+fn replay_without_a_fixture_fails_with_a_transport_error() {
+    let fixtures = tempfile::TempDir::new().unwrap();
+    let err = fetch(Arc::new(ReplayClient::new(fixtures.path()))).unwrap_err();
+    assert!(
+        matches!(err, self_update::Error::Transport(_)),
+        "got {err:?}"
+    );
+}